@@ -0,0 +1,139 @@
+//! `dexhub` — command-line companion for the running DexHub app.
+//!
+//! Talks to the app over `<app data>/dexhub.sock` (see `src/ipc.rs` in the
+//! app crate). Set `DEXHUB_SOCKET` to override the socket location. The
+//! socket is Unix-only, so on other platforms the CLI just says so.
+
+#[cfg(unix)]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[cfg(unix)]
+const APP_IDENTIFIER: &str = "com.dexhub.client";
+
+#[cfg(unix)]
+const USAGE: &str = "\
+Usage: dexhub <command> [args]
+
+Commands:
+  status                     List projects with state, port, URL and uptime
+  start <name>               Start a server
+  stop <name>                Stop a server
+  restart <name>             Restart a server
+  logs <name> [-f] [-n N]    Print the last N log lines (default 100); -f follows";
+
+#[cfg(unix)]
+fn socket_path() -> PathBuf {
+    if let Ok(p) = std::env::var("DEXHUB_SOCKET") {
+        return PathBuf::from(p);
+    }
+    let home = std::env::var("HOME").unwrap_or_default();
+    let data_dir = if cfg!(target_os = "macos") {
+        PathBuf::from(&home).join("Library/Application Support")
+    } else {
+        std::env::var("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(&home).join(".local/share"))
+    };
+    data_dir.join(APP_IDENTIFIER).join("dexhub.sock")
+}
+
+#[cfg(unix)]
+fn format_uptime(secs: u64) -> String {
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m{:02}s", s / 60, s % 60),
+        s => format!("{}h{:02}m", s / 3600, (s % 3600) / 60),
+    }
+}
+
+#[cfg(unix)]
+fn print_status(data: &serde_json::Value) {
+    let servers = data.as_array().cloned().unwrap_or_default();
+    let width = servers.iter().filter_map(|s| s["name"].as_str()).map(str::len).max().unwrap_or(4).max(4);
+    println!("{:<width$}  {:<7}  {:>5}  {:<8}  URL", "NAME", "STATE", "PORT", "UPTIME", width = width);
+    for s in &servers {
+        let running = s["running"].as_bool().unwrap_or(false);
+        println!(
+            "{:<width$}  {:<7}  {:>5}  {:<8}  {}",
+            s["name"].as_str().unwrap_or(""),
+            if running { "running" } else { "stopped" },
+            s["port"].as_u64().unwrap_or(0),
+            s["uptime_secs"].as_u64().map(format_uptime).unwrap_or_else(|| "-".to_string()),
+            if running { s["url"].as_str().unwrap_or("") } else { "" },
+            width = width,
+        );
+    }
+}
+
+#[cfg(unix)]
+fn run(args: &[String]) -> Result<(), String> {
+    let cmd = args.first().ok_or(USAGE)?;
+    let mut request = serde_json::json!({ "cmd": cmd });
+    match cmd.as_str() {
+        "status" => {}
+        "start" | "stop" | "restart" | "logs" => {
+            let name = args.get(1).ok_or(USAGE)?;
+            request["name"] = serde_json::json!(name);
+            let mut rest = args[2..].iter();
+            while let Some(flag) = rest.next() {
+                match flag.as_str() {
+                    "-f" | "--follow" if cmd == "logs" => request["follow"] = serde_json::json!(true),
+                    "-n" | "--lines" if cmd == "logs" => {
+                        let n: usize = rest.next().and_then(|v| v.parse().ok()).ok_or("-n expects a number")?;
+                        request["lines"] = serde_json::json!(n);
+                    }
+                    other => return Err(format!("Unexpected argument '{}'\n\n{}", other, USAGE)),
+                }
+            }
+        }
+        "-h" | "--help" | "help" => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        other => return Err(format!("Unknown command '{}'\n\n{}", other, USAGE)),
+    }
+
+    let path = socket_path();
+    let mut stream = UnixStream::connect(&path)
+        .map_err(|e| format!("Can't reach DexHub at {} ({}). Is the app running?", path.display(), e))?;
+    writeln!(stream, "{}", request).map_err(|e| e.to_string())?;
+
+    for line in BufReader::new(stream).lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() { continue; } // follow-mode heartbeat
+        let val: serde_json::Value = serde_json::from_str(&line).map_err(|e| e.to_string())?;
+        if let Some(l) = val["line"].as_str() {
+            println!("{}", l);
+            continue;
+        }
+        if val["ok"].as_bool() != Some(true) {
+            return Err(val["error"].as_str().unwrap_or("Unknown error").to_string());
+        }
+        if cmd == "status" { print_status(&val["data"]); }
+        break;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("dexhub: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn main() -> ExitCode {
+    eprintln!("dexhub: the command-line client is unsupported on this platform; DexHub only serves it over a Unix socket");
+    ExitCode::FAILURE
+}
@@ -0,0 +1,83 @@
+//! The signing and pairing protocol, server side. Canonicalization and
+//! signature checks live in `dexhub-proto`; this adds the hub's freshness,
+//! nonce and pairing-code state.
+
+use dexhub_proto::{pairing_message, verify_ed25519, PairConfirm, PairConfirmed, PairingCodes, SignedHeaders, TIMESTAMP_WINDOW_MS};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::http::HttpRequest;
+use crate::store::{now_ms, Device, Store};
+
+pub type AuthError = (u16, &'static str);
+
+#[derive(Default)]
+pub struct Auth {
+    nonces:  Mutex<HashMap<String, HashMap<String, u128>>>, // device → nonce → expiry (ms)
+    pairing: Mutex<PairingCodes>,
+}
+
+impl Auth {
+    /// Checks the signed headers of a request and returns the device id.
+    pub fn verify_request(&self, store: &Store, req: &HttpRequest) -> Result<String, AuthError> {
+        let Some(signed) = SignedHeaders::from_lookup(|k| req.headers.get(k).cloned()) else {
+            return Err((401, "Missing Auth Headers"));
+        };
+
+        let now = now_ms();
+        let req_ts = signed.timestamp_ms().ok_or((401u16, "Invalid Timestamp"))?;
+        if now.abs_diff(req_ts) > TIMESTAMP_WINDOW_MS {
+            return Err((401, "Request Expired"));
+        }
+
+        let public_key = match store.device(&signed.device_id) {
+            Some(d) if d.enabled => d.public_key,
+            _ => return Err((403, "Device Unauthorized or Unknown")),
+        };
+        signed.verify(&public_key, &req.method, &req.path, &req.body)?;
+        let SignedHeaders { device_id: dev_id, nonce, .. } = signed;
+
+        // Record the nonce only after the signature checks out, so forged
+        // requests can't burn a legitimate device's nonces.
+        let mut nonces = self.nonces.lock().unwrap();
+        let seen = nonces.entry(dev_id.clone()).or_default();
+        seen.retain(|_, exp| *exp > now);
+        if seen.contains_key(&nonce) {
+            return Err((409, "Nonce Replay Detected"));
+        }
+        seen.insert(nonce, now + TIMESTAMP_WINDOW_MS + 5_000);
+        Ok(dev_id)
+    }
+
+    /// Issues a one-time pairing code; only callable from the hub's own machine.
+    pub fn start_pairing(&self) -> Result<String, String> {
+        self.pairing.lock().unwrap().issue(Instant::now())
+    }
+
+    /// Registers the device if the code is live and the key signed
+    /// `PAIR:<code>`. Wrong codes and proofs count towards the lockout.
+    pub fn confirm_pairing(&self, store: &Store, body: &[u8]) -> Result<serde_json::Value, AuthError> {
+        let payload: PairConfirm = serde_json::from_slice(body).map_err(|_| (400u16, "Invalid JSON"))?;
+        let PairConfirm { code, public_key, signature, role } = payload;
+
+        {
+            let mut pairing = self.pairing.lock().unwrap();
+            pairing.check(&code, Instant::now())?;
+            if !verify_ed25519(&public_key, pairing_message(&code).as_bytes(), &signature) {
+                pairing.fail();
+                return Err((401, "Proof of Possession Failed"));
+            }
+            pairing.consume(&code);
+        }
+
+        let dev_id = dexhub_proto::device_id(&public_key);
+        store.put_device(&dev_id, Device {
+            public_key,
+            role,
+            enabled: true,
+            created_at: now_ms() as f64 / 1000.0,
+        });
+        Ok(serde_json::to_value(PairConfirmed { device_id: dev_id, status: "Paired".to_string() }).unwrap_or_default())
+    }
+}
@@ -0,0 +1,38 @@
+//! HTTP responses — one request per connection. Parsing is shared with the
+//! desktop app in `dexhub_proto::http`.
+
+use std::io::Write;
+use std::net::TcpStream;
+
+pub use dexhub_proto::http::{percent_decode, HttpRequest};
+
+const MAX_BODY_BYTES: usize = 4 << 20;
+
+/// Reads one request, refusing bodies over `MAX_BODY_BYTES`.
+pub fn read_request(stream: &mut TcpStream) -> Option<HttpRequest> {
+    dexhub_proto::http::read_request(stream, MAX_BODY_BYTES)
+}
+
+pub fn write_json(stream: &mut TcpStream, status: u16, value: &serde_json::Value) {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        429 => "Too Many Requests",
+        _ => "Error",
+    };
+    let body = value.to_string();
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status, reason, body.len()
+    );
+    let _ = stream.write_all(head.as_bytes());
+    let _ = stream.write_all(body.as_bytes());
+}
+
+pub fn write_error(stream: &mut TcpStream, status: u16, detail: &str) {
+    write_json(stream, status, &serde_json::json!({ "detail": detail }));
+}
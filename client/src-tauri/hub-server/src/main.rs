@@ -0,0 +1,165 @@
+//! `dexhub-hub` — self-hostable hub for DexHub clients.
+//!
+//! The other end of the client's signing/pairing protocol:
+//! - `POST /pair/request` (local only) prints a six-digit pairing code
+//! - `POST /pair/confirm` registers a device that signed `PAIR:<code>`
+//! - `GET /admin/devices`, `POST /admin/devices/revoke` (local only)
+//! - `POST /cards/sync` — two-way card sync, last writer wins
+//! - `POST /relay/commands` queues a command for another device,
+//!   `GET /relay/commands` collects the caller's pending ones,
+//!   `POST /relay/commands/<id>/result` answers one and
+//!   `GET /relay/commands/<id>` lets the sender read the answer
+//!
+//! Every route except pairing and admin requires a signed request.
+
+mod auth;
+mod http;
+mod store;
+
+use std::net::{IpAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use auth::Auth;
+use http::{percent_decode, read_request, write_error, write_json, HttpRequest};
+use dexhub_proto::{QueueCommand, SyncRequest, SyncResponse};
+use store::Store;
+
+const USAGE: &str = "\
+Usage: dexhub-hub [--bind ADDR] [--port PORT] [--data-dir DIR]
+
+Options:
+  --bind ADDR       Address to listen on (default 127.0.0.1; use 0.0.0.0 for LAN/tailnet)
+  --port PORT       Port to listen on (default 5050)
+  --data-dir DIR    Where the device registry and cards live (default ./hub-data)";
+
+struct Hub {
+    store: Store,
+    auth:  Auth,
+}
+
+fn is_local(stream: &TcpStream) -> bool {
+    stream.peer_addr().map(|a| match a.ip() {
+        IpAddr::V4(ip) => ip.is_loopback(),
+        IpAddr::V6(ip) => ip.is_loopback(),
+    }).unwrap_or(false)
+}
+
+fn handle_admin(hub: &Hub, stream: &mut TcpStream, req: &HttpRequest) {
+    if !is_local(stream) {
+        return write_error(stream, 403, "Admin actions must be local");
+    }
+    match (req.method.as_str(), req.path.as_str()) {
+        ("POST", "/pair/request") => {
+            match hub.auth.start_pairing() {
+                Ok(code) => {
+                    println!("*** PAIRING CODE: {} ***", code);
+                    write_json(stream, 200, &serde_json::json!({ "status": "Code generated", "code": code }));
+                }
+                Err(e) => write_error(stream, 500, &e),
+            }
+        }
+        ("GET", "/admin/devices") => {
+            write_json(stream, 200, &serde_json::to_value(hub.store.devices()).unwrap_or_default());
+        }
+        ("POST", "/admin/devices/revoke") => {
+            let payload: serde_json::Value = serde_json::from_slice(&req.body).unwrap_or_default();
+            let status = if hub.store.revoke_device(payload["device_id"].as_str().unwrap_or("")) { "Revoked" } else { "Not Found" };
+            write_json(stream, 200, &serde_json::json!({ "status": status }));
+        }
+        _ => write_error(stream, 404, "Not Found"),
+    }
+}
+
+fn handle_signed(hub: &Hub, stream: &mut TcpStream, req: &HttpRequest, dev_id: &str) {
+    let segments: Vec<String> = req.path.trim_matches('/').split('/').map(percent_decode).collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap_or_default();
+
+    match (req.method.as_str(), segments.as_slice()) {
+        ("POST", ["cards", "sync"]) => {
+            let SyncRequest { cards, since } = serde_json::from_slice(&req.body).unwrap_or_default();
+            let (cards, cursor) = hub.store.sync_cards(dev_id, cards, since);
+            write_json(stream, 200, &serde_json::to_value(SyncResponse { cards, cursor }).unwrap_or_default());
+        }
+        ("POST", ["relay", "commands"]) => {
+            let Ok(QueueCommand { to, command }) = serde_json::from_slice(&req.body) else {
+                return write_error(stream, 400, "Invalid JSON");
+            };
+            if !hub.store.device(&to).is_some_and(|d| d.enabled) {
+                return write_error(stream, 404, "Target Device Unknown");
+            }
+            let id = hub.store.queue_command(dev_id, &to, command);
+            write_json(stream, 200, &serde_json::json!({ "id": id }));
+        }
+        ("GET", ["relay", "commands"]) => {
+            write_json(stream, 200, &serde_json::json!({ "commands": hub.store.take_commands(dev_id) }));
+        }
+        ("GET", ["relay", "commands", id]) => match hub.store.command(dev_id, id) {
+            Some(c) => write_json(stream, 200, &serde_json::to_value(c).unwrap_or_default()),
+            None => write_error(stream, 404, "Command Not Found"),
+        },
+        ("POST", ["relay", "commands", id, "result"]) => {
+            if hub.store.complete_command(dev_id, id, body["result"].clone()) {
+                write_json(stream, 200, &serde_json::json!({ "status": "ok" }));
+            } else {
+                write_error(stream, 404, "Command Not Found");
+            }
+        }
+        _ => write_error(stream, 404, "Not Found"),
+    }
+}
+
+fn handle_connection(hub: &Hub, mut stream: TcpStream) {
+    let Some(req) = read_request(&mut stream) else { return };
+    if req.method == "POST" && req.path == "/pair/confirm" {
+        return match hub.auth.confirm_pairing(&hub.store, &req.body) {
+            Ok(v) => write_json(&mut stream, 200, &v),
+            Err((status, detail)) => write_error(&mut stream, status, detail),
+        };
+    }
+    if req.path == "/pair/request" || req.path.starts_with("/admin/") {
+        return handle_admin(hub, &mut stream, &req);
+    }
+    match hub.auth.verify_request(&hub.store, &req) {
+        Ok(dev_id) => handle_signed(hub, &mut stream, &req, &dev_id),
+        Err((status, detail)) => write_error(&mut stream, status, detail),
+    }
+}
+
+fn main() -> ExitCode {
+    let mut bind = "127.0.0.1".to_string();
+    let mut port: u16 = 5050;
+    let mut data_dir = PathBuf::from("hub-data");
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--bind", Some(v)) => bind = v,
+            ("--port", Some(v)) => match v.parse() {
+                Ok(p) => port = p,
+                Err(_) => { eprintln!("Invalid port '{}'", v); return ExitCode::FAILURE; }
+            },
+            ("--data-dir", Some(v)) => data_dir = PathBuf::from(v),
+            _ => { eprintln!("{}", USAGE); return ExitCode::FAILURE; }
+        }
+    }
+
+    let store = match Store::open(&data_dir) {
+        Ok(s) => s,
+        Err(e) => { eprintln!("Can't open {}: {}", data_dir.display(), e); return ExitCode::FAILURE; }
+    };
+    let listener = match TcpListener::bind((bind.as_str(), port)) {
+        Ok(l) => l,
+        Err(e) => { eprintln!("Can't listen on {}:{}: {}", bind, port, e); return ExitCode::FAILURE; }
+    };
+    println!("DexHub hub listening on {}:{} (data in {})", bind, port, data_dir.display());
+
+    let hub = Arc::new(Hub { store, auth: Auth::default() });
+    for stream in listener.incoming().flatten() {
+        let hub = Arc::clone(&hub);
+        std::thread::spawn(move || handle_connection(&hub, stream));
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,169 @@
+//! Hub state: the device registry and synced cards, persisted as JSON in the
+//! data directory, plus the in-memory remote-command relay.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub use dexhub_proto::{Card, Device, RelayCommand};
+
+/// Relayed commands nobody collects are dropped after this long.
+const RELAY_TTL_MS: u128 = 10 * 60 * 1000;
+
+pub fn now_ms() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+pub struct Store {
+    dir:     PathBuf,
+    devices: Mutex<HashMap<String, Device>>,
+    cards:   Mutex<HashMap<String, Card>>,
+    relay:   Mutex<Vec<RelayCommand>>,
+    next_id: Mutex<u64>,
+}
+
+fn load_json<T: serde::de::DeserializeOwned + Default>(path: &Path) -> T {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_json<T: serde::Serialize>(path: &Path, value: &T) {
+    if let Ok(json) = serde_json::to_string_pretty(value) {
+        let tmp = path.with_extension("json.tmp");
+        if std::fs::write(&tmp, json).is_ok() {
+            let _ = std::fs::rename(&tmp, path);
+        }
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600));
+    }
+}
+
+impl Store {
+    pub fn open(dir: &Path) -> std::io::Result<Store> {
+        std::fs::create_dir_all(dir)?;
+        Ok(Store {
+            devices: Mutex::new(load_json(&dir.join("device_registry.json"))),
+            cards:   Mutex::new(load_json(&dir.join("cards.json"))),
+            relay:   Mutex::new(Vec::new()),
+            next_id: Mutex::new(0),
+            dir:     dir.to_path_buf(),
+        })
+    }
+
+    // ─── Devices ──────────────────────────────────────────────────────────────
+
+    pub fn device(&self, id: &str) -> Option<Device> {
+        self.devices.lock().unwrap().get(id).cloned()
+    }
+
+    pub fn devices(&self) -> HashMap<String, Device> {
+        self.devices.lock().unwrap().clone()
+    }
+
+    pub fn put_device(&self, id: &str, device: Device) {
+        let mut devices = self.devices.lock().unwrap();
+        devices.insert(id.to_string(), device);
+        save_json(&self.dir.join("device_registry.json"), &*devices);
+    }
+
+    /// Disables rather than deletes, matching the sensory server. False if unknown.
+    pub fn revoke_device(&self, id: &str) -> bool {
+        let mut devices = self.devices.lock().unwrap();
+        let Some(device) = devices.get_mut(id) else { return false };
+        device.enabled = false;
+        save_json(&self.dir.join("device_registry.json"), &*devices);
+        true
+    }
+
+    // ─── Cards ────────────────────────────────────────────────────────────────
+
+    /// Applies the device's changes, then returns every card accepted after
+    /// `since` (including the ones just sent) and the new cursor.
+    ///
+    /// Each sync that writes stamps its cards strictly after every earlier
+    /// stamp — the clock in ms, bumped past the last one if needed — so a
+    /// write in the same millisecond as someone's cursor is still newer.
+    pub fn sync_cards(&self, device_id: &str, incoming: Vec<Card>, since: u64) -> (Vec<Card>, u64) {
+        let mut cards = self.cards.lock().unwrap();
+        let last = cards.values().map(|c| c.synced_at).max().unwrap_or(0);
+        let stamp = (now_ms() as u64).max(last + 1);
+        let mut changed = false;
+        for mut card in incoming {
+            if card.id.is_empty() { continue; }
+            let newer = cards.get(&card.id).is_none_or(|existing| card.updated_at > existing.updated_at);
+            if newer {
+                card.updated_by = device_id.to_string();
+                card.synced_at = stamp;
+                cards.insert(card.id.clone(), card);
+                changed = true;
+            }
+        }
+        if changed {
+            save_json(&self.dir.join("cards.json"), &*cards);
+        }
+        let mut out: Vec<Card> = cards.values().filter(|c| c.synced_at > since).cloned().collect();
+        out.sort_by_key(|c| c.synced_at);
+        (out, if changed { stamp } else { last.max(since) })
+    }
+
+    // ─── Relay ────────────────────────────────────────────────────────────────
+
+    fn prune_relay(relay: &mut Vec<RelayCommand>) {
+        let now = now_ms();
+        relay.retain(|c| now.saturating_sub(c.created_at as u128) < RELAY_TTL_MS);
+    }
+
+    pub fn queue_command(&self, from: &str, to: &str, command: serde_json::Value) -> String {
+        let id = {
+            let mut next = self.next_id.lock().unwrap();
+            *next += 1;
+            format!("{:x}-{}", now_ms(), *next)
+        };
+        let mut relay = self.relay.lock().unwrap();
+        Self::prune_relay(&mut relay);
+        relay.push(RelayCommand {
+            id: id.clone(),
+            from: from.to_string(),
+            to: to.to_string(),
+            command,
+            created_at: now_ms() as u64,
+            delivered: false,
+            result: None,
+        });
+        id
+    }
+
+    /// Commands waiting for `device_id`; each is handed out once.
+    pub fn take_commands(&self, device_id: &str) -> Vec<RelayCommand> {
+        let mut relay = self.relay.lock().unwrap();
+        Self::prune_relay(&mut relay);
+        relay.iter_mut()
+            .filter(|c| c.to == device_id && !c.delivered)
+            .map(|c| { c.delivered = true; c.clone() })
+            .collect()
+    }
+
+    /// Only the target device may answer a command.
+    pub fn complete_command(&self, device_id: &str, id: &str, result: serde_json::Value) -> bool {
+        let mut relay = self.relay.lock().unwrap();
+        match relay.iter_mut().find(|c| c.id == id && c.to == device_id) {
+            Some(c) => { c.result = Some(result); true }
+            None => false,
+        }
+    }
+
+    /// Only the sender or the target may look a command up.
+    pub fn command(&self, device_id: &str, id: &str) -> Option<RelayCommand> {
+        self.relay.lock().unwrap().iter()
+            .find(|c| c.id == id && (c.from == device_id || c.to == device_id))
+            .cloned()
+    }
+}
@@ -0,0 +1,110 @@
+//! Minimal HTTP/1.1 request parsing — one request per connection — shared by
+//! the desktop remote API and the hub so the two listeners parse alike.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::net::TcpStream;
+use std::time::Duration;
+
+pub struct HttpRequest {
+    pub method:  String,
+    pub path:    String,
+    pub query:   HashMap<String, String>,
+    pub headers: HashMap<String, String>, // keys lower-cased
+    pub body:    Vec<u8>,
+}
+
+pub fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(b) => { out.push(b); i += 3; }
+                    None => { out.push(b'%'); i += 1; }
+                }
+            }
+            b'+' => { out.push(b' '); i += 1; }
+            b => { out.push(b); i += 1; }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Parses one request; bodies over `max_body` bytes are refused.
+pub fn parse_request(reader: &mut impl BufRead, max_body: usize) -> Option<HttpRequest> {
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).ok()?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?;
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).ok()? == 0 { break; }
+        let line = line.trim_end();
+        if line.is_empty() { break; }
+        if let Some((k, v)) = line.split_once(':') {
+            headers.insert(k.trim().to_ascii_lowercase(), v.trim().to_string());
+        }
+    }
+
+    let len: usize = headers.get("content-length").and_then(|v| v.parse().ok()).unwrap_or(0);
+    if len > max_body { return None; }
+    let mut body = vec![0u8; len];
+    reader.read_exact(&mut body).ok()?;
+
+    let (path, query_str) = target.split_once('?').unwrap_or((target, ""));
+    let query = query_str
+        .split('&')
+        .filter(|kv| !kv.is_empty())
+        .map(|kv| {
+            let (k, v) = kv.split_once('=').unwrap_or((kv, ""));
+            (percent_decode(k), percent_decode(v))
+        })
+        .collect();
+
+    Some(HttpRequest { method, path: path.to_string(), query, headers, body })
+}
+
+/// Reads one request off `stream`, giving up after ten idle seconds.
+pub fn read_request(stream: &mut TcpStream, max_body: usize) -> Option<HttpRequest> {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(10)));
+    let mut reader = BufReader::new(stream.try_clone().ok()?);
+    parse_request(&mut reader, max_body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_decoding() {
+        assert_eq!(percent_decode("my%20app+x"), "my app x");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+    }
+
+    #[test]
+    fn parses_request_line_headers_query_and_body() {
+        let raw = b"POST /cards/sync?since=5&name=a%2Fb HTTP/1.1\r\nContent-Length: 2\r\nX-DEX-Nonce: n1\r\n\r\n{}";
+        let req = parse_request(&mut &raw[..], 1024).unwrap();
+        assert_eq!(req.method, "POST");
+        assert_eq!(req.path, "/cards/sync");
+        assert_eq!(req.query["since"], "5");
+        assert_eq!(req.query["name"], "a/b");
+        assert_eq!(req.headers["x-dex-nonce"], "n1");
+        assert_eq!(req.body, b"{}");
+    }
+
+    #[test]
+    fn oversized_bodies_are_refused() {
+        let raw = b"POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\n0123456789";
+        assert!(parse_request(&mut &raw[..], 9).is_none());
+        assert!(parse_request(&mut &raw[..], 10).is_some());
+    }
+}
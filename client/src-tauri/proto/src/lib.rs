@@ -0,0 +1,516 @@
+//! The DexHub wire protocol, shared by the desktop client, the hub and any
+//! third-party client so they can't drift apart.
+//!
+//! Signed requests carry `X-DEX-DeviceId`, `X-DEX-Timestamp` (ms),
+//! `X-DEX-Nonce`, `X-DEX-BodySha256` and `X-DEX-Signature` — a base64 Ed25519
+//! signature over `METHOD\nPATH\nTIMESTAMP\nNONCE\nBODY_SHA256`. Pairing proves
+//! possession of the key by signing `PAIR:<code>`.
+
+use base64::{engine::general_purpose, Engine as _};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+pub mod http;
+
+// ─── Headers ──────────────────────────────────────────────────────────────────
+
+pub const HEADER_DEVICE_ID:   &str = "X-DEX-DeviceId";
+pub const HEADER_TIMESTAMP:   &str = "X-DEX-Timestamp";
+pub const HEADER_NONCE:       &str = "X-DEX-Nonce";
+pub const HEADER_BODY_SHA256: &str = "X-DEX-BodySha256";
+pub const HEADER_SIGNATURE:   &str = "X-DEX-Signature";
+
+/// Every signed-request header, lower-cased as HTTP servers usually store them.
+pub const SIGNED_HEADERS: [&str; 5] = [
+    "x-dex-deviceid",
+    "x-dex-timestamp",
+    "x-dex-nonce",
+    "x-dex-bodysha256",
+    "x-dex-signature",
+];
+
+/// Signed requests older or newer than this are rejected.
+pub const TIMESTAMP_WINDOW_MS: u128 = 60_000;
+
+// ─── Canonicalization ─────────────────────────────────────────────────────────
+
+pub fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) { return None; }
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok()).collect()
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    encode_hex(&Sha256::digest(data))
+}
+
+/// The exact bytes a request signature covers. `path` excludes the query string.
+pub fn canonical_request(method: &str, path: &str, timestamp: &str, nonce: &str, body_sha256: &str) -> String {
+    format!("{}\n{}\n{}\n{}\n{}", method, path, timestamp, nonce, body_sha256)
+}
+
+/// The message a device signs to prove it holds the key it's pairing with.
+pub fn pairing_message(code: &str) -> String {
+    format!("PAIR:{}", code)
+}
+
+/// Devices are identified by the first 12 hex digits of SHA-256(public key).
+pub fn device_id(public_key_hex: &str) -> String {
+    let pub_bytes = decode_hex(public_key_hex).unwrap_or_default();
+    sha256_hex(&pub_bytes).chars().take(12).collect()
+}
+
+pub fn verify_ed25519(public_key_hex: &str, message: &[u8], signature_b64: &str) -> bool {
+    let Some(key_bytes) = decode_hex(public_key_hex) else { return false };
+    let Ok(key_bytes) = <[u8; 32]>::try_from(key_bytes.as_slice()) else { return false };
+    let Ok(key) = VerifyingKey::from_bytes(&key_bytes) else { return false };
+    let Ok(sig_bytes) = general_purpose::STANDARD.decode(signature_b64) else { return false };
+    let Ok(signature) = Signature::from_slice(&sig_bytes) else { return false };
+    key.verify(message, &signature).is_ok()
+}
+
+pub fn sign_ed25519(key: &SigningKey, message: &[u8]) -> String {
+    general_purpose::STANDARD.encode(key.sign(message).to_bytes())
+}
+
+// ─── Signed Requests ──────────────────────────────────────────────────────────
+
+/// The five auth headers of one signed request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedHeaders {
+    pub device_id:   String,
+    pub timestamp:   String,
+    pub nonce:       String,
+    pub body_sha256: String,
+    pub signature:   String,
+}
+
+impl SignedHeaders {
+    /// Signs a request the way a client does.
+    pub fn sign(key: &SigningKey, device_id: &str, method: &str, path: &str, body: &[u8], timestamp_ms: u128, nonce: &str) -> SignedHeaders {
+        let timestamp = timestamp_ms.to_string();
+        let body_sha256 = sha256_hex(body);
+        let canonical = canonical_request(method, path, &timestamp, nonce, &body_sha256);
+        SignedHeaders {
+            device_id: device_id.to_string(),
+            timestamp,
+            nonce: nonce.to_string(),
+            body_sha256,
+            signature: sign_ed25519(key, canonical.as_bytes()),
+        }
+    }
+
+    /// Reads the headers through `lookup`, which is given the lower-cased name.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Option<SignedHeaders> {
+        Some(SignedHeaders {
+            device_id:   lookup(SIGNED_HEADERS[0])?,
+            timestamp:   lookup(SIGNED_HEADERS[1])?,
+            nonce:       lookup(SIGNED_HEADERS[2])?,
+            body_sha256: lookup(SIGNED_HEADERS[3])?,
+            signature:   lookup(SIGNED_HEADERS[4])?,
+        })
+    }
+
+    /// Header name/value pairs, in the canonical casing, for sending.
+    pub fn to_pairs(&self) -> [(&'static str, &str); 5] {
+        [
+            (HEADER_DEVICE_ID, &self.device_id),
+            (HEADER_TIMESTAMP, &self.timestamp),
+            (HEADER_NONCE, &self.nonce),
+            (HEADER_BODY_SHA256, &self.body_sha256),
+            (HEADER_SIGNATURE, &self.signature),
+        ]
+    }
+
+    /// The timestamp in ms, if it parses. Python clients send it as a float.
+    pub fn timestamp_ms(&self) -> Option<u128> {
+        self.timestamp.parse::<f64>().ok().map(|t| t as u128)
+    }
+
+    /// Checks the body hash and signature, not freshness or nonce reuse —
+    /// those need server state.
+    pub fn verify(&self, public_key_hex: &str, method: &str, path: &str, body: &[u8]) -> Result<(), (u16, &'static str)> {
+        if sha256_hex(body) != self.body_sha256 {
+            return Err((400, "Body Integrity Check Failed"));
+        }
+        let canonical = canonical_request(method, path, &self.timestamp, &self.nonce, &self.body_sha256);
+        if !verify_ed25519(public_key_hex, canonical.as_bytes(), &self.signature) {
+            return Err((401, "Invalid Signature"));
+        }
+        Ok(())
+    }
+}
+
+// ─── Pairing Codes ────────────────────────────────────────────────────────────
+
+/// Pairing codes are valid for five minutes.
+pub const PAIRING_CODE_TTL: Duration = Duration::from_secs(300);
+/// Wrong codes or proofs tolerated before every outstanding code is dropped.
+pub const MAX_PAIRING_FAILURES: u32 = 5;
+/// Confirm attempts checked per `PAIRING_RATE_WINDOW`; the rest get a 429.
+pub const PAIRING_RATE_LIMIT: usize = 10;
+pub const PAIRING_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Fills `buf` from the OS CSPRNG. There is no fallback on purpose: a
+/// predictable code or token is worse than an error.
+pub fn random_bytes(buf: &mut [u8]) -> Result<(), String> {
+    use std::io::Read;
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut f| f.read_exact(buf))
+        .map_err(|e| format!("OS random source unavailable: {}", e))
+}
+
+/// 256 bits from the OS RNG, hex-encoded.
+pub fn random_token() -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    random_bytes(&mut bytes)?;
+    Ok(encode_hex(&bytes))
+}
+
+/// A uniformly distributed 6-digit code.
+pub fn random_pairing_code() -> Result<String, String> {
+    // Redraw the top sliver of u32 so no code is likelier than another
+    const LIMIT: u32 = u32::MAX - u32::MAX % 1_000_000;
+    loop {
+        let mut bytes = [0u8; 4];
+        random_bytes(&mut bytes)?;
+        let n = u32::from_le_bytes(bytes);
+        if n < LIMIT { return Ok(format!("{:06}", n % 1_000_000)); }
+    }
+}
+
+/// Outstanding pairing codes, plus the lockout and rate limit that keep a
+/// 6-digit code from being brute-forced over the network.
+#[derive(Default)]
+pub struct PairingCodes {
+    codes:    HashMap<String, Instant>, // code → issued at
+    failures: u32,
+    attempts: VecDeque<Instant>,        // confirm attempts inside the rate window
+}
+
+impl PairingCodes {
+    /// Issues a new code alongside any live ones and resets the lockout.
+    pub fn issue(&mut self, now: Instant) -> Result<String, String> {
+        self.codes.retain(|_, issued| now.duration_since(*issued) <= PAIRING_CODE_TTL);
+        let code = random_pairing_code()?;
+        self.codes.insert(code.clone(), now);
+        self.failures = 0;
+        Ok(code)
+    }
+
+    pub fn clear(&mut self) {
+        self.codes.clear();
+    }
+
+    /// Checks a confirm attempt against the rate limit and the live codes.
+    /// A wrong code counts as a failure.
+    pub fn check(&mut self, code: &str, now: Instant) -> Result<(), (u16, &'static str)> {
+        while self.attempts.front().is_some_and(|t| now.duration_since(*t) > PAIRING_RATE_WINDOW) {
+            self.attempts.pop_front();
+        }
+        if self.attempts.len() >= PAIRING_RATE_LIMIT {
+            return Err((429, "Too Many Attempts"));
+        }
+        self.attempts.push_back(now);
+
+        match self.codes.get(code) {
+            Some(issued) if now.duration_since(*issued) <= PAIRING_CODE_TTL => Ok(()),
+            Some(_) => {
+                self.codes.remove(code);
+                Err((403, "Code Expired"))
+            }
+            None => {
+                self.fail();
+                Err((403, "Invalid Code"))
+            }
+        }
+    }
+
+    /// Counts a failed attempt, e.g. a bad proof of possession for a live
+    /// code. Once the limit is hit every code is dropped, so pairing has to
+    /// be restarted from the host.
+    pub fn fail(&mut self) {
+        self.failures += 1;
+        if self.failures >= MAX_PAIRING_FAILURES {
+            self.codes.clear();
+        }
+    }
+
+    /// Retires a code once its device has paired.
+    pub fn consume(&mut self, code: &str) {
+        self.codes.remove(code);
+        self.failures = 0;
+    }
+}
+
+// ─── Payloads ─────────────────────────────────────────────────────────────────
+
+/// A paired device, as kept in device_registry.json.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Device {
+    pub public_key: String, // hex-encoded Ed25519 public key
+    pub role:       String,
+    pub enabled:    bool,
+    pub created_at: f64,
+}
+
+/// Body of `POST /pair/confirm`.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PairConfirm {
+    #[serde(default)]
+    pub code:       String,
+    #[serde(default)]
+    pub public_key: String,
+    #[serde(default)]
+    pub signature:  String, // base64 signature over `pairing_message(code)`
+    #[serde(default = "default_role")]
+    pub role:       String,
+}
+
+fn default_role() -> String {
+    "client".to_string()
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PairConfirmed {
+    pub device_id: String,
+    pub status:    String,
+}
+
+/// A synced card. Conflicts resolve last-writer-wins on `updated_at`;
+/// deletions are kept as tombstones so they propagate.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Card {
+    pub id:         String,
+    pub updated_at: f64, // client clock, seconds
+    #[serde(default)]
+    pub deleted:    bool,
+    #[serde(default)]
+    pub data:       serde_json::Value,
+    #[serde(default)]
+    pub updated_by: String, // device id, filled in by the hub
+    #[serde(default)]
+    pub synced_at:  u64,    // hub stamp when accepted, ms and strictly increasing; the sync cursor
+}
+
+/// Body of `POST /cards/sync`.
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SyncRequest {
+    #[serde(default)]
+    pub cards: Vec<Card>,
+    #[serde(default)]
+    pub since: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SyncResponse {
+    pub cards:  Vec<Card>,
+    pub cursor: u64,
+}
+
+/// Body of `POST /relay/commands`.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct QueueCommand {
+    pub to:      String,
+    pub command: serde_json::Value,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RelayCommand {
+    pub id:         String,
+    pub from:       String,
+    pub to:         String,
+    pub command:    serde_json::Value,
+    pub created_at: u64,
+    pub delivered:  bool,
+    pub result:     Option<serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    fn public_hex(key: &SigningKey) -> String {
+        encode_hex(key.verifying_key().as_bytes())
+    }
+
+    #[test]
+    fn canonical_request_layout() {
+        assert_eq!(
+            canonical_request("POST", "/cards/sync", "1700000000000", "abc", "deadbeef"),
+            "POST\n/cards/sync\n1700000000000\nabc\ndeadbeef"
+        );
+        assert_eq!(pairing_message("012345"), "PAIR:012345");
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        let bytes = [0u8, 1, 0x7f, 0xff];
+        assert_eq!(decode_hex(&encode_hex(&bytes)).unwrap(), bytes);
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("zz"), None);
+    }
+
+    #[test]
+    fn sha256_of_empty_body() {
+        assert_eq!(sha256_hex(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+    }
+
+    #[test]
+    fn signed_request_round_trip() {
+        let key = key();
+        let body = br#"{"since":0}"#;
+        let headers = SignedHeaders::sign(&key, "dev", "POST", "/cards/sync", body, 1_700_000_000_000, "n1");
+
+        let pairs = headers.to_pairs();
+        let parsed = SignedHeaders::from_lookup(|name| {
+            pairs.iter().find(|(k, _)| k.to_ascii_lowercase() == name).map(|(_, v)| v.to_string())
+        }).unwrap();
+        assert_eq!(parsed, headers);
+        assert_eq!(parsed.timestamp_ms(), Some(1_700_000_000_000));
+        assert_eq!(parsed.verify(&public_hex(&key), "POST", "/cards/sync", body), Ok(()));
+    }
+
+    #[test]
+    fn tampering_is_detected() {
+        let key = key();
+        let public = public_hex(&key);
+        let headers = SignedHeaders::sign(&key, "dev", "POST", "/cards/sync", b"{}", 1, "n1");
+
+        assert_eq!(headers.verify(&public, "POST", "/cards/sync", b"{ }").unwrap_err().0, 400);
+        assert_eq!(headers.verify(&public, "GET", "/cards/sync", b"{}").unwrap_err().0, 401);
+        assert_eq!(headers.verify(&public, "POST", "/relay/commands", b"{}").unwrap_err().0, 401);
+        let other = public_hex(&SigningKey::from_bytes(&[8u8; 32]));
+        assert_eq!(headers.verify(&other, "POST", "/cards/sync", b"{}").unwrap_err().0, 401);
+    }
+
+    #[test]
+    fn python_float_timestamps_parse() {
+        let headers = SignedHeaders::from_lookup(|name| Some(match name {
+            "x-dex-timestamp" => "1700000000000.5".to_string(),
+            _ => String::new(),
+        })).unwrap();
+        assert_eq!(headers.timestamp_ms(), Some(1_700_000_000_000));
+    }
+
+    #[test]
+    fn pairing_proof_round_trip() {
+        let key = key();
+        let public = public_hex(&key);
+        let signature = sign_ed25519(&key, pairing_message("123456").as_bytes());
+        assert!(verify_ed25519(&public, pairing_message("123456").as_bytes(), &signature));
+        assert!(!verify_ed25519(&public, pairing_message("654321").as_bytes(), &signature));
+        assert_eq!(device_id(&public).len(), 12);
+    }
+
+    #[test]
+    fn pair_confirm_defaults() {
+        let confirm: PairConfirm = serde_json::from_str(r#"{"code":"1","public_key":"ab","signature":"s"}"#).unwrap();
+        assert_eq!(confirm.role, "client");
+        let back: PairConfirm = serde_json::from_value(serde_json::to_value(&confirm).unwrap()).unwrap();
+        assert_eq!(back, confirm);
+    }
+
+    #[test]
+    fn sync_payloads_round_trip() {
+        let card = Card {
+            id: "c1".to_string(),
+            updated_at: 12.5,
+            deleted: false,
+            data: serde_json::json!({ "title": "Demo" }),
+            updated_by: "dev".to_string(),
+            synced_at: 99,
+        };
+        let request = SyncRequest { cards: vec![card.clone()], since: 42 };
+        let back: SyncRequest = serde_json::from_str(&serde_json::to_string(&request).unwrap()).unwrap();
+        assert_eq!(back, request);
+
+        let response = SyncResponse { cards: vec![card], cursor: 100 };
+        let back: SyncResponse = serde_json::from_str(&serde_json::to_string(&response).unwrap()).unwrap();
+        assert_eq!(back, response);
+
+        // Clients may send only what they know about.
+        let minimal: Card = serde_json::from_str(r#"{"id":"c2","updated_at":1.0}"#).unwrap();
+        assert!(!minimal.deleted && minimal.updated_by.is_empty() && minimal.synced_at == 0);
+        assert_eq!(serde_json::from_str::<SyncRequest>("{}").unwrap(), SyncRequest::default());
+    }
+
+    #[test]
+    fn relay_payloads_round_trip() {
+        let queue = QueueCommand { to: "dev2".to_string(), command: serde_json::json!({ "action": "start" }) };
+        let back: QueueCommand = serde_json::from_str(&serde_json::to_string(&queue).unwrap()).unwrap();
+        assert_eq!(back, queue);
+
+        let command = RelayCommand {
+            id: "1-1".to_string(),
+            from: "dev1".to_string(),
+            to: "dev2".to_string(),
+            command: queue.command,
+            created_at: 5,
+            delivered: true,
+            result: Some(serde_json::json!({ "ok": true })),
+        };
+        let back: RelayCommand = serde_json::from_str(&serde_json::to_string(&command).unwrap()).unwrap();
+        assert_eq!(back, command);
+    }
+
+    #[test]
+    fn pairing_codes_are_six_digits() {
+        for _ in 0..100 {
+            let code = random_pairing_code().unwrap();
+            assert_eq!(code.len(), 6);
+            assert!(code.chars().all(|c| c.is_ascii_digit()));
+        }
+        assert_eq!(random_token().unwrap().len(), 64);
+    }
+
+    #[test]
+    fn pairing_code_expires() {
+        let mut codes = PairingCodes::default();
+        let issued = Instant::now();
+        let code = codes.issue(issued).unwrap();
+        assert_eq!(codes.check(&code, issued + Duration::from_secs(10)), Ok(()));
+        assert_eq!(codes.check(&code, issued + PAIRING_CODE_TTL + Duration::from_secs(1)), Err((403, "Code Expired")));
+        assert_eq!(codes.check(&code, issued + PAIRING_CODE_TTL + Duration::from_secs(2)), Err((403, "Invalid Code")));
+    }
+
+    #[test]
+    fn pairing_locks_out_after_repeated_failures() {
+        let mut codes = PairingCodes::default();
+        let now = Instant::now();
+        let code = codes.issue(now).unwrap();
+        let wrong = if code == "000000" { "000001" } else { "000000" };
+        for _ in 0..MAX_PAIRING_FAILURES - 1 {
+            assert_eq!(codes.check(wrong, now), Err((403, "Invalid Code")));
+        }
+        codes.fail(); // a bad proof counts too
+        assert_eq!(codes.check(&code, now), Err((403, "Invalid Code")));
+
+        // A fresh code from the host starts over
+        let code = codes.issue(now).unwrap();
+        assert_eq!(codes.check(&code, now), Ok(()));
+        codes.consume(&code);
+        assert_eq!(codes.check(&code, now), Err((403, "Invalid Code")));
+    }
+
+    #[test]
+    fn pairing_confirms_are_rate_limited() {
+        let mut codes = PairingCodes::default();
+        let now = Instant::now();
+        let code = codes.issue(now).unwrap();
+        for _ in 0..PAIRING_RATE_LIMIT {
+            assert_eq!(codes.check(&code, now), Ok(()));
+        }
+        assert_eq!(codes.check(&code, now), Err((429, "Too Many Attempts")));
+        assert_eq!(codes.check(&code, now + PAIRING_RATE_WINDOW + Duration::from_secs(1)), Ok(()));
+    }
+}
@@ -0,0 +1,105 @@
+//! Homebrew services (`brew services`): Postgres, Redis, nginx and the like
+//! run outside DexHub but belong to the same local stack, so they're listed
+//! in their own tray section with start/stop actions.
+//!
+//! `brew` takes a second or so to answer, so the list is cached in state and
+//! refreshed off the main thread: at launch, on Refresh, and after an action.
+
+use std::path::PathBuf;
+use tauri::Manager;
+
+use crate::{ensure_not_presenting, rebuild_tray, record_event, ServerState};
+
+const BREW_CANDIDATES: &[&str] = &["/opt/homebrew/bin/brew", "/usr/local/bin/brew", "/home/linuxbrew/.linuxbrew/bin/brew"];
+
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BrewService {
+    pub name:      String,
+    pub status:    String, // "started", "stopped", "none", "error", "scheduled", …
+    #[serde(default)]
+    pub user:      Option<String>,
+    #[serde(default)]
+    pub exit_code: Option<i64>,
+}
+
+impl BrewService {
+    pub fn is_running(&self) -> bool {
+        self.status == "started" || self.status == "scheduled"
+    }
+}
+
+fn brew_binary() -> Option<PathBuf> {
+    BREW_CANDIDATES.iter().map(PathBuf::from).find(|p| p.exists())
+}
+
+/// Empty when Homebrew isn't installed or has no services.
+fn list_services() -> Vec<BrewService> {
+    let Some(brew) = brew_binary() else { return Vec::new() };
+    let Ok(output) = std::process::Command::new(brew).args(["services", "list", "--json"]).output() else { return Vec::new() };
+    let mut services: Vec<BrewService> = serde_json::from_slice(&output.stdout).unwrap_or_default();
+    services.sort_by(|a, b| a.name.cmp(&b.name));
+    services
+}
+
+/// Re-reads `brew services` and rebuilds the tray if anything changed.
+pub fn refresh_brew_services(app: &tauri::AppHandle) -> Vec<BrewService> {
+    let services = list_services();
+    let changed = {
+        let state = app.state::<ServerState>();
+        let mut cached = state.brew_services.lock().unwrap();
+        let changed = *cached != services;
+        *cached = services.clone();
+        changed
+    };
+    if changed { rebuild_tray(app); }
+    services
+}
+
+pub fn spawn_brew_refresh(app: &tauri::AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || { refresh_brew_services(&app); });
+}
+
+pub fn run_brew_action(app: &tauri::AppHandle, name: &str, action: &str) -> Result<(), String> {
+    if !["start", "stop", "restart"].contains(&action) {
+        return Err(format!("Unknown brew services action '{}'", action));
+    }
+    if action != "start" { ensure_not_presenting(app)?; }
+    let brew = brew_binary().ok_or("Homebrew isn't installed")?;
+    let output = std::process::Command::new(brew)
+        .args(["services", action, name])
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    record_event(app, "brew-service", Some(name), serde_json::json!({ "action": action }));
+    refresh_brew_services(app);
+    Ok(())
+}
+
+/// Tray click: start a stopped service, stop a running one.
+pub fn toggle_brew_service(app: &tauri::AppHandle, name: &str) {
+    let running = app.state::<ServerState>().brew_services.lock().unwrap()
+        .iter()
+        .any(|s| s.name == name && s.is_running());
+    let (app, name) = (app.clone(), name.to_string());
+    std::thread::spawn(move || {
+        if let Err(e) = run_brew_action(&app, &name, if running { "stop" } else { "start" }) {
+            eprintln!("[DexHub] brew services failed for '{}': {}", name, e);
+        }
+    });
+}
+
+// ─── Tauri Commands ───────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn list_brew_services(app: tauri::AppHandle) -> Vec<BrewService> {
+    refresh_brew_services(&app)
+}
+
+/// `action` is "start", "stop" or "restart".
+#[tauri::command]
+pub fn brew_service_action(app: tauri::AppHandle, name: String, action: String) -> Result<(), String> {
+    run_brew_action(&app, &name, &action)
+}
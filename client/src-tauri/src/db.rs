@@ -0,0 +1,398 @@
+//! The encrypted local database, `dexhub.db` (SQLCipher). Its 256-bit key is
+//! generated on first use and kept in the system keyring as `dexhub_db_key`;
+//! where there's no keyring it falls back to a 0600 key file beside the DB.
+//!
+//! Holds the run history: one row per server run, opened on start and closed
+//! on stop, crash or exit, so "crashed 6 times today" is a query away. Each
+//! run also sums the latencies its health checks measured. A run is open
+//! while its outcome is `running`; one a previous session never closed
+//! (DexHub was killed) is marked `unknown` once its process is gone, keeps
+//! no end time, and is left out of the stats. While its process lives on,
+//! the run stays open and is where that server's uptime comes from.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::Manager;
+
+const DB_KEY_ACCOUNT: &str = "dexhub_db_key";
+
+/// None in safe mode, or if the database couldn't be opened; history is
+/// then simply not recorded.
+pub struct HistoryDb(pub Mutex<Option<Connection>>);
+
+#[derive(Clone, serde::Serialize)]
+pub struct RunRecord {
+    pub id:            i64,
+    pub project:       String,
+    pub started_at:    u64,         // unix seconds
+    pub ended_at:      Option<u64>, // None while running, or when unknown
+    pub duration_secs: Option<u64>,
+    pub outcome:       String,      // "running" | "stopped" | "exited" | "crashed" | "unknown"
+    pub exit_code:     Option<i32>,
+    pub port:          Option<u16>,
+}
+
+fn valid_key(key: &str) -> bool {
+    key.len() == 64 && key.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// The keyring copy wins, then the key file. A new key is only generated
+/// while there's no dexhub.db it would lock out: a keyring that couldn't
+/// be read (locked, no secret-tool, D-Bus timeout) is an error instead,
+/// and history stays off for the session.
+fn load_or_create_key(app_data_dir: &Path) -> Result<String, String> {
+    let keyring = crate::platform::keyring_lookup(DB_KEY_ACCOUNT);
+    if let Ok(Some(key)) = &keyring {
+        if valid_key(key) { return Ok(key.clone()); }
+    }
+    let key_file = app_data_dir.join("dexhub.db.key");
+    if let Some(key) = std::fs::read_to_string(&key_file).ok().map(|k| k.trim().to_string()).filter(|k| valid_key(k)) {
+        return Ok(key);
+    }
+    if app_data_dir.join("dexhub.db").exists() {
+        return Err(match keyring {
+            Err(e) => format!("Can't read the dexhub.db key from the keyring: {}", e),
+            Ok(_) => "dexhub.db exists but its key is in neither the keyring nor dexhub.db.key".to_string(),
+        });
+    }
+    let key = dexhub_proto::random_token()?;
+    if crate::platform::keyring_set(DB_KEY_ACCOUNT, &key).is_err() {
+        std::fs::write(&key_file, &key).map_err(|e| e.to_string())?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&key_file, std::fs::Permissions::from_mode(0o600));
+        }
+    }
+    Ok(key)
+}
+
+/// A keyed connection, without the schema setup `open` does.
+fn connect(app_data_dir: &Path) -> Result<Connection, String> {
+    let key = load_or_create_key(app_data_dir)?;
+    let conn = Connection::open(app_data_dir.join("dexhub.db")).map_err(|e| e.to_string())?;
+    // Raw key format, so SQLCipher skips its passphrase KDF
+    conn.execute_batch(&format!("PRAGMA key = \"x'{}'\";", key)).map_err(|e| e.to_string())?;
+    Ok(conn)
+}
+
+pub fn open(app_data_dir: &Path) -> Result<Connection, String> {
+    std::fs::create_dir_all(app_data_dir).map_err(|e| e.to_string())?;
+    let conn = connect(app_data_dir)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS run_history (
+            id         INTEGER PRIMARY KEY AUTOINCREMENT,
+            project    TEXT    NOT NULL,
+            started_at INTEGER NOT NULL,
+            ended_at   INTEGER,
+            outcome    TEXT    NOT NULL DEFAULT 'running',
+            exit_code  INTEGER,
+            port       INTEGER
+        );
+        CREATE INDEX IF NOT EXISTS run_history_project ON run_history (project, started_at);",
+    ).map_err(|e| format!("Can't open dexhub.db (wrong key?): {}", e))?;
+    // Added after the table shipped
+    if conn.prepare("SELECT latency_samples FROM run_history LIMIT 0").is_err() {
+        conn.execute_batch(
+            "ALTER TABLE run_history ADD COLUMN latency_samples  INTEGER NOT NULL DEFAULT 0;
+             ALTER TABLE run_history ADD COLUMN latency_total_ms INTEGER NOT NULL DEFAULT 0;",
+        ).map_err(|e| e.to_string())?;
+    }
+    if conn.prepare("SELECT pid FROM run_history LIMIT 0").is_err() {
+        conn.execute_batch("ALTER TABLE run_history ADD COLUMN pid INTEGER;").map_err(|e| e.to_string())?;
+    }
+    mark_stale_runs(&conn, process_alive).map_err(|e| e.to_string())?;
+    Ok(conn)
+}
+
+/// Whether a process with this pid is still around.
+fn process_alive(pid: u32) -> bool {
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// Runs still open from a previous session whose process is gone ended at
+/// some point nobody recorded; guessing an end time would make up uptime.
+/// Runs whose process outlived that session stay open.
+fn mark_stale_runs(conn: &Connection, alive: impl Fn(u32) -> bool) -> rusqlite::Result<usize> {
+    let open: Vec<(i64, Option<i64>)> = {
+        let mut stmt = conn.prepare("SELECT id, pid FROM run_history WHERE outcome = 'running'")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+    let mut marked = 0;
+    for (id, pid) in open {
+        if pid.is_some_and(|pid| alive(pid as u32)) { continue; }
+        marked += conn.execute("UPDATE run_history SET outcome = 'unknown' WHERE id = ?1", params![id])?;
+    }
+    Ok(marked)
+}
+
+/// When the project's open run started, and its process.
+fn open_run(conn: &Connection, project: &str) -> rusqlite::Result<Option<(u64, Option<u32>)>> {
+    conn.query_row(
+        "SELECT started_at, pid FROM run_history WHERE project = ?1 AND outcome = 'running' ORDER BY id DESC LIMIT 1",
+        params![project],
+        |row| Ok((row.get::<_, i64>(0)? as u64, row.get::<_, Option<i64>>(1)?.map(|p| p as u32))),
+    ).optional()
+}
+
+fn rename_runs(conn: &Connection, old: &str, new: &str) -> rusqlite::Result<usize> {
+    conn.execute("UPDATE run_history SET project = ?1 WHERE project = ?2", params![new, old])
+}
+
+/// Moves a renamed project's run history to its new name. Runs during a
+/// scan, which at startup comes before the shared connection is opened, so
+/// this uses its own.
+pub fn rename_project(app_data_dir: &Path, old: &str, new: &str) {
+    if !app_data_dir.join("dexhub.db").exists() { return; }
+    let result = connect(app_data_dir).and_then(|conn| {
+        conn.busy_timeout(std::time::Duration::from_secs(2)).map_err(|e| e.to_string())?;
+        rename_runs(&conn, old, new).map_err(|e| e.to_string())
+    });
+    if let Err(e) = result { eprintln!("[DexHub] Run history rename '{}' → '{}': {}", old, new, e); }
+}
+
+fn with_db<T>(app: &tauri::AppHandle, f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Option<T> {
+    let db = app.try_state::<HistoryDb>()?;
+    let guard = db.0.lock().unwrap();
+    let conn = guard.as_ref()?;
+    match f(conn) {
+        Ok(v) => Some(v),
+        Err(e) => { eprintln!("[DexHub] Run history: {}", e); None }
+    }
+}
+
+/// Opens a run started at `started_at` (unix seconds). A run still open for
+/// the project (a canary restart's old instance) is closed as stopped first.
+pub fn record_run_start(app: &tauri::AppHandle, project: &str, port: u16, started_at: u64, pid: u32) {
+    let now = started_at as i64;
+    with_db(app, |conn| {
+        conn.execute(
+            "UPDATE run_history SET ended_at = ?1, outcome = 'stopped' WHERE project = ?2 AND outcome = 'running'",
+            params![now, project],
+        )?;
+        conn.execute(
+            "INSERT INTO run_history (project, started_at, port, pid) VALUES (?1, ?2, ?3, ?4)",
+            params![project, now, port, pid],
+        )
+    });
+}
+
+/// Closes the project's open run. `outcome` is "stopped", "exited" or "crashed".
+pub fn record_run_end(app: &tauri::AppHandle, project: &str, outcome: &str, exit_code: Option<i32>) {
+    with_db(app, |conn| conn.execute(
+        "UPDATE run_history SET ended_at = ?1, outcome = ?2, exit_code = ?3
+         WHERE id = (SELECT MAX(id) FROM run_history WHERE project = ?4 AND outcome = 'running')",
+        params![crate::unix_now() as i64, outcome, exit_code, project],
+    ));
+}
+
+/// Adds a health check's latency to the project's open run.
+pub fn record_latency(app: &tauri::AppHandle, project: &str, latency_ms: u64) {
+    with_db(app, |conn| conn.execute(
+        "UPDATE run_history SET latency_samples = latency_samples + 1, latency_total_ms = latency_total_ms + ?1
+         WHERE id = (SELECT MAX(id) FROM run_history WHERE project = ?2 AND outcome = 'running')",
+        params![latency_ms as i64, project],
+    ));
+}
+
+/// Closes every open run, e.g. when DexHub quits and takes its servers along.
+pub fn close_open_runs(app: &tauri::AppHandle) {
+    with_db(app, |conn| conn.execute(
+        "UPDATE run_history SET ended_at = ?1, outcome = 'stopped' WHERE outcome = 'running'",
+        params![crate::unix_now() as i64],
+    ));
+}
+
+/// When the project's open run started, in unix seconds, while its process
+/// is alive; the source of uptime when `start_times` has no entry, as for
+/// a server a previous session started.
+pub fn open_run_started(app: &tauri::AppHandle, project: &str) -> Option<u64> {
+    let (started_at, pid) = with_db(app, |conn| open_run(conn, project)).flatten()?;
+    pid.is_some_and(process_alive).then_some(started_at)
+}
+
+/// When each project's most recent run started, in unix seconds.
+pub fn last_started(app: &tauri::AppHandle) -> HashMap<String, u64> {
+    with_db(app, |conn| {
+        let mut stmt = conn.prepare("SELECT project, MAX(started_at) FROM run_history GROUP BY project")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64)))?;
+        rows.collect()
+    }).unwrap_or_default()
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct ProjectUsage {
+    pub project:        String,
+    pub starts:         u64,
+    pub crashes:        u64,
+    pub uptime_secs:    u64,         // time running inside the window
+    pub avg_latency_ms: Option<f64>, // over the window's runs; None without health checks
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct DashboardStats {
+    pub since:             u64, // unix seconds; the window runs from here to now
+    pub total_uptime_secs: u64,
+    pub total_starts:      u64,
+    pub total_crashes:     u64,
+    pub projects:          Vec<ProjectUsage>, // most uptime first
+}
+
+fn run_from_row(row: &rusqlite::Row) -> rusqlite::Result<RunRecord> {
+    let started_at: i64 = row.get(2)?;
+    let ended_at: Option<i64> = row.get(3)?;
+    Ok(RunRecord {
+        id:            row.get(0)?,
+        project:       row.get(1)?,
+        started_at:    started_at as u64,
+        ended_at:      ended_at.map(|t| t as u64),
+        duration_secs: ended_at.map(|t| t.saturating_sub(started_at) as u64),
+        outcome:       row.get(4)?,
+        exit_code:     row.get(5)?,
+        port:          row.get::<_, Option<i64>>(6)?.map(|p| p as u16),
+    })
+}
+
+// ─── Tauri Commands ───────────────────────────────────────────────────────────
+
+/// Most recent runs first; all projects when `name` is None.
+#[tauri::command]
+pub fn get_run_history(app: tauri::AppHandle, name: Option<String>, limit: Option<usize>) -> Vec<RunRecord> {
+    let limit = limit.unwrap_or(100) as i64;
+    with_db(&app, |conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, project, started_at, ended_at, outcome, exit_code, port FROM run_history
+             WHERE ?1 IS NULL OR project = ?1 ORDER BY id DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![name, limit], run_from_row)?;
+        rows.collect()
+    }).unwrap_or_default()
+}
+
+/// Per-project usage between `since` and `now`; runs of unknown length
+/// are left out.
+fn project_usage(conn: &Connection, since: u64, now: u64) -> rusqlite::Result<Vec<ProjectUsage>> {
+    let mut stmt = conn.prepare(
+        "SELECT project,
+                SUM(started_at >= ?1),
+                SUM(started_at >= ?1 AND outcome = 'crashed'),
+                SUM(MAX(0, COALESCE(ended_at, ?2) - MAX(started_at, ?1))),
+                SUM(latency_samples),
+                SUM(latency_total_ms)
+         FROM run_history WHERE outcome != 'unknown' AND COALESCE(ended_at, ?2) >= ?1 GROUP BY project",
+    )?;
+    let rows = stmt.query_map(params![since as i64, now as i64], |row| {
+        let samples: i64 = row.get(4)?;
+        let total_ms: i64 = row.get(5)?;
+        Ok(ProjectUsage {
+            project:        row.get(0)?,
+            starts:         row.get::<_, i64>(1)? as u64,
+            crashes:        row.get::<_, i64>(2)? as u64,
+            uptime_secs:    row.get::<_, i64>(3)? as u64,
+            avg_latency_ms: (samples > 0).then(|| total_ms as f64 / samples as f64),
+        })
+    })?;
+    rows.collect()
+}
+
+/// Usage over the last `days` days (7 by default): uptime, starts, crashes
+/// and average latency per project, for the dashboard. Runs that began
+/// before the window count only the part inside it.
+#[tauri::command]
+pub fn get_dashboard_stats(app: tauri::AppHandle, days: Option<u64>) -> DashboardStats {
+    let now = crate::unix_now();
+    let since = now.saturating_sub(days.unwrap_or(7).max(1) * 86_400);
+    let mut projects = with_db(&app, |conn| project_usage(conn, since, now)).unwrap_or_default();
+    projects.sort_by(|a, b| b.uptime_secs.cmp(&a.uptime_secs).then_with(|| a.project.cmp(&b.project)));
+    DashboardStats {
+        since,
+        total_uptime_secs: projects.iter().map(|p| p.uptime_secs).sum(),
+        total_starts:      projects.iter().map(|p| p.starts).sum(),
+        total_crashes:     projects.iter().map(|p| p.crashes).sum(),
+        projects,
+    }
+}
+
+/// How many runs of `name` crashed since `since` (unix seconds).
+#[tauri::command]
+pub fn count_crashes(app: tauri::AppHandle, name: String, since: u64) -> u64 {
+    with_db(&app, |conn| conn.query_row(
+        "SELECT COUNT(*) FROM run_history WHERE project = ?1 AND outcome = 'crashed' AND started_at >= ?2",
+        params![name, since as i64],
+        |row| row.get::<_, i64>(0),
+    )).unwrap_or(0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renaming_moves_only_that_projects_runs() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE run_history (id INTEGER PRIMARY KEY, project TEXT NOT NULL, started_at INTEGER NOT NULL);
+             INSERT INTO run_history (project, started_at) VALUES ('web', 1), ('web', 2), ('api', 3);",
+        ).unwrap();
+
+        assert_eq!(rename_runs(&conn, "web", "storefront").unwrap(), 2);
+
+        let count = |project: &str| conn.query_row(
+            "SELECT COUNT(*) FROM run_history WHERE project = ?1", params![project], |row| row.get::<_, i64>(0),
+        ).unwrap();
+        assert_eq!(count("web"), 0);
+        assert_eq!(count("storefront"), 2);
+        assert_eq!(count("api"), 1);
+    }
+
+    #[test]
+    fn runs_left_open_by_a_previous_session_are_unknown_and_uncounted() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE run_history (id INTEGER PRIMARY KEY, project TEXT NOT NULL, started_at INTEGER NOT NULL,
+                 ended_at INTEGER, outcome TEXT NOT NULL DEFAULT 'running', exit_code INTEGER, port INTEGER,
+                 latency_samples INTEGER NOT NULL DEFAULT 0, latency_total_ms INTEGER NOT NULL DEFAULT 0, pid INTEGER);
+             INSERT INTO run_history (project, started_at, ended_at, outcome) VALUES ('web', 100, 160, 'crashed');
+             INSERT INTO run_history (project, started_at) VALUES ('web', 200), ('api', 300);",
+        ).unwrap();
+
+        assert_eq!(mark_stale_runs(&conn, |_| false).unwrap(), 2);
+
+        let unknown: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM run_history WHERE outcome = 'unknown' AND ended_at IS NULL", [], |row| row.get(0),
+        ).unwrap();
+        assert_eq!(unknown, 2);
+        let usage = project_usage(&conn, 0, 1_000).unwrap();
+        assert_eq!(usage.len(), 1);
+        assert_eq!((usage[0].project.as_str(), usage[0].starts, usage[0].crashes, usage[0].uptime_secs), ("web", 1, 1, 60));
+    }
+
+    #[test]
+    fn a_run_whose_process_outlived_the_session_keeps_its_start_after_reopening() {
+        let path = std::env::temp_dir().join(format!("dexhub-db-{}-reopen.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE run_history (id INTEGER PRIMARY KEY, project TEXT NOT NULL, started_at INTEGER NOT NULL,
+                     ended_at INTEGER, outcome TEXT NOT NULL DEFAULT 'running', pid INTEGER);
+                 INSERT INTO run_history (project, started_at, pid) VALUES ('web', 100, 4242), ('api', 200, 4343);",
+            ).unwrap();
+        }
+
+        // DexHub was killed; on reopening, only web's process is still up
+        let conn = Connection::open(&path).unwrap();
+        assert_eq!(mark_stale_runs(&conn, |pid| pid == 4242).unwrap(), 1);
+        assert_eq!(open_run(&conn, "web").unwrap(), Some((100, Some(4242))));
+        assert_eq!(open_run(&conn, "api").unwrap(), None);
+        drop(conn);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,150 @@
+//! Docker containers: databases, caches and queues often run in containers
+//! next to the dev servers, so they're listed in their own tray section with
+//! their published ports and start/stop actions.
+//!
+//! Uses the `docker` CLI rather than the socket so Docker Desktop, Colima and
+//! OrbStack all work through whichever context is active. Like the Homebrew
+//! list, it's cached in state and refreshed off the main thread.
+
+use std::path::PathBuf;
+use tauri::Manager;
+
+use crate::{ensure_not_presenting, rebuild_tray, record_event, ServerState};
+
+const DOCKER_CANDIDATES: &[&str] = &[
+    "/usr/local/bin/docker",
+    "/opt/homebrew/bin/docker",
+    "/usr/bin/docker",
+    "/Applications/Docker.app/Contents/Resources/bin/docker",
+];
+
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Container {
+    pub id:      String, // short id
+    pub name:    String,
+    pub image:   String,
+    pub state:   String, // "running", "exited", "paused", "created", …
+    pub status:  String, // docker's own "Up 3 hours" / "Exited (0) 2 days ago"
+    pub ports:   Vec<u16>, // published host ports
+    pub compose_project: Option<String>, // set for containers started by compose
+}
+
+impl Container {
+    pub fn is_running(&self) -> bool {
+        self.state == "running"
+    }
+}
+
+fn docker_binary() -> Option<PathBuf> {
+    DOCKER_CANDIDATES.iter().map(PathBuf::from).find(|p| p.exists())
+}
+
+/// Host ports from `docker ps`'s Ports column, e.g.
+/// `0.0.0.0:5432->5432/tcp, :::5432->5432/tcp, 6379/tcp`.
+fn parse_published_ports(ports: &str) -> Vec<u16> {
+    let mut out: Vec<u16> = ports.split(", ")
+        .filter_map(|mapping| mapping.split_once("->"))
+        .filter_map(|(host, _)| host.rsplit(':').next()?.split('-').next()?.parse().ok())
+        .collect();
+    out.sort_unstable();
+    out.dedup();
+    out
+}
+
+fn label(labels: &str, key: &str) -> Option<String> {
+    labels.split(',').find_map(|kv| kv.strip_prefix(key)?.strip_prefix('=')).map(str::to_string)
+}
+
+/// Empty when Docker isn't installed or the daemon isn't running.
+fn list_containers() -> Vec<Container> {
+    let Some(docker) = docker_binary() else { return Vec::new() };
+    let Ok(output) = std::process::Command::new(docker)
+        .args(["ps", "--all", "--format", "{{json .}}"])
+        .output() else { return Vec::new() };
+    if !output.status.success() { return Vec::new(); }
+    let mut containers: Vec<Container> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .map(|c| {
+            let field = |k: &str| c[k].as_str().unwrap_or_default().to_string();
+            Container {
+                id: field("ID"),
+                name: field("Names"),
+                image: field("Image"),
+                state: field("State"),
+                status: field("Status"),
+                ports: parse_published_ports(&field("Ports")),
+                compose_project: label(&field("Labels"), "com.docker.compose.project"),
+            }
+        })
+        .collect();
+    // Running first, then by name
+    containers.sort_by(|a, b| b.is_running().cmp(&a.is_running()).then_with(|| a.name.cmp(&b.name)));
+    containers
+}
+
+/// Re-reads `docker ps` and rebuilds the tray if anything changed.
+pub fn refresh_containers(app: &tauri::AppHandle) -> Vec<Container> {
+    let containers = list_containers();
+    let changed = {
+        let state = app.state::<ServerState>();
+        let mut cached = state.containers.lock().unwrap();
+        let changed = *cached != containers;
+        *cached = containers.clone();
+        changed
+    };
+    if changed { rebuild_tray(app); }
+    containers
+}
+
+pub fn spawn_container_refresh(app: &tauri::AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || { refresh_containers(&app); });
+}
+
+pub fn run_container_action(app: &tauri::AppHandle, id: &str, action: &str) -> Result<(), String> {
+    if !["start", "stop", "restart"].contains(&action) {
+        return Err(format!("Unknown container action '{}'", action));
+    }
+    if action != "start" { ensure_not_presenting(app)?; }
+    let docker = docker_binary().ok_or("Docker isn't installed")?;
+    let output = std::process::Command::new(docker)
+        .args([action, id])
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    let name = app.state::<ServerState>().containers.lock().unwrap()
+        .iter().find(|c| c.id == id).map(|c| c.name.clone())
+        .unwrap_or_else(|| id.to_string());
+    record_event(app, "container", Some(&name), serde_json::json!({ "action": action, "id": id }));
+    refresh_containers(app);
+    Ok(())
+}
+
+/// Tray click: start a stopped container, stop a running one.
+pub fn toggle_container(app: &tauri::AppHandle, id: &str) {
+    let running = app.state::<ServerState>().containers.lock().unwrap()
+        .iter()
+        .any(|c| c.id == id && c.is_running());
+    let (app, id) = (app.clone(), id.to_string());
+    std::thread::spawn(move || {
+        if let Err(e) = run_container_action(&app, &id, if running { "stop" } else { "start" }) {
+            eprintln!("[DexHub] docker {} failed: {}", id, e);
+        }
+    });
+}
+
+// ─── Tauri Commands ───────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn list_docker_containers(app: tauri::AppHandle) -> Vec<Container> {
+    refresh_containers(&app)
+}
+
+/// `action` is "start", "stop" or "restart".
+#[tauri::command]
+pub fn docker_container_action(app: tauri::AppHandle, id: String, action: String) -> Result<(), String> {
+    run_container_action(&app, &id, &action)
+}
@@ -0,0 +1,294 @@
+//! A project's own `.env` files, as the dev tooling (Vite, Next, dotenv)
+//! would load them, next to what DexHub injects on top.
+//!
+//! Files are only ever written by an explicit save from the UI. A save keeps
+//! comments, blank lines and the order of untouched keys, so the diff a user
+//! commits afterwards is just the values they changed.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use tauri::Manager;
+
+use crate::{ensure_not_presenting, presenting, project_key, record_event, ServerState, REDACTED};
+
+/// In load order; later files win, as in Vite and Next in development.
+const ENV_FILES: &[&str] = &[".env", ".env.local", ".env.development", ".env.development.local"];
+
+#[derive(Clone, serde::Serialize)]
+pub struct EnvFile {
+    pub file:   String,
+    pub exists: bool,
+    pub vars:   BTreeMap<String, String>,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct EnvChange {
+    pub key:    String,
+    pub before: Option<String>, // None when added
+    pub after:  Option<String>, // None when removed
+}
+
+/// One variable as the dev server will see it, and where it came from.
+#[derive(Clone, serde::Serialize)]
+pub struct EffectiveVar {
+    pub key:      String,
+    pub value:    String,
+    pub source:   String,      // an env file name, "override" or "profile:<name>"
+    pub shadowed: Vec<String>, // earlier sources that set it too
+}
+
+/// Something in the env sources that's probably a mistake.
+#[derive(Clone, serde::Serialize)]
+pub struct EnvIssue {
+    pub source:  String,
+    pub line:    Option<usize>, // 1-based, for env files
+    pub key:     Option<String>,
+    pub message: String,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct EffectiveEnv {
+    pub vars:   Vec<EffectiveVar>,
+    pub issues: Vec<EnvIssue>,
+}
+
+fn project_cwd(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, String> {
+    app.state::<ServerState>().projects.lock().unwrap()
+        .iter().find(|p| p.name == name).map(|p| PathBuf::from(&p.cwd))
+        .ok_or_else(|| format!("Project '{}' not found", name))
+}
+
+/// Only the known env file names; never a path.
+fn env_file_path(app: &tauri::AppHandle, name: &str, file: &str) -> Result<PathBuf, String> {
+    if !ENV_FILES.contains(&file) {
+        return Err(format!("'{}' isn't an env file DexHub manages ({})", file, ENV_FILES.join(", ")));
+    }
+    Ok(project_cwd(app, name)?.join(file))
+}
+
+fn valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_blank_or_comment(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#')
+}
+
+/// `KEY=value`, `export KEY=value`, with single, double or no quotes.
+fn parse_line(line: &str) -> Option<(String, String)> {
+    if is_blank_or_comment(line) { return None; }
+    let line = line.trim();
+    let line = line.strip_prefix("export ").unwrap_or(line);
+    let (key, value) = line.split_once('=')?;
+    let key = key.trim();
+    if !valid_key(key) { return None; }
+    Some((key.to_string(), unquote(value.trim())))
+}
+
+/// Lines that aren't `KEY=value` and keys set more than once.
+fn check_env_file(file: &str, content: &str) -> Vec<EnvIssue> {
+    let mut issues = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for (index, line) in content.lines().enumerate() {
+        let number = index + 1;
+        if is_blank_or_comment(line) { continue; }
+        match parse_line(line) {
+            None => issues.push(EnvIssue {
+                source: file.to_string(), line: Some(number), key: None,
+                message: "Not a KEY=value line; it's ignored".to_string(),
+            }),
+            Some((key, _)) => {
+                if let Some(first) = seen.insert(key.clone(), number) {
+                    issues.push(EnvIssue {
+                        source: file.to_string(), line: Some(number), key: Some(key),
+                        message: format!("Also set on line {}; this one wins", first),
+                    });
+                }
+            }
+        }
+    }
+    issues
+}
+
+/// Invalid names, and secrets whose keyring entry is gone.
+fn check_dexhub_vars(project: &str, source: &str, vars: &HashMap<String, String>) -> Vec<EnvIssue> {
+    let mut keys: Vec<&String> = vars.keys().collect();
+    keys.sort();
+    keys.into_iter().filter_map(|key| {
+        let message = if !valid_key(key) {
+            "Not a valid variable name; the shell may drop it"
+        } else if vars[key] == crate::SECRET_PLACEHOLDER
+            && crate::platform::keyring_get(&crate::env_secret_account(project, key)).is_none()
+        {
+            "Secret is missing from the keyring; the server won't start"
+        } else {
+            return None;
+        };
+        Some(EnvIssue { source: source.to_string(), line: None, key: Some(key.clone()), message: message.to_string() })
+    }).collect()
+}
+
+fn unquote(value: &str) -> String {
+    if let Some(inner) = value.strip_prefix('"').and_then(|v| v.rsplit_once('"')).map(|(i, _)| i) {
+        return inner.replace("\\n", "\n").replace("\\\"", "\"").replace("\\\\", "\\");
+    }
+    if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.rsplit_once('\'')).map(|(i, _)| i) {
+        return inner.to_string();
+    }
+    // Unquoted values end at an inline comment
+    value.split_once(" #").map_or(value, |(v, _)| v).trim_end().to_string()
+}
+
+fn format_value(value: &str) -> String {
+    let plain = !value.is_empty() && value.chars().all(|c| !c.is_whitespace() && !matches!(c, '#' | '"' | '\'' | '\\' | '$' | '`'));
+    if plain {
+        value.to_string()
+    } else {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+    }
+}
+
+fn parse_env(content: &str) -> BTreeMap<String, String> {
+    content.lines().filter_map(parse_line).collect()
+}
+
+fn read_env_file(path: &std::path::Path) -> Option<BTreeMap<String, String>> {
+    std::fs::read_to_string(path).ok().map(|c| parse_env(&c))
+}
+
+fn diff(before: &BTreeMap<String, String>, after: &BTreeMap<String, String>) -> Vec<EnvChange> {
+    let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|k| before.get(*k) != after.get(*k))
+        .map(|k| EnvChange { key: k.clone(), before: before.get(k).cloned(), after: after.get(k).cloned() })
+        .collect()
+}
+
+/// Rewrites `content` to hold exactly `vars`: changed keys are updated in
+/// place, removed keys dropped, new keys appended at the end.
+fn rewrite(content: &str, vars: &BTreeMap<String, String>) -> String {
+    let mut out = String::new();
+    let mut written: Vec<&str> = Vec::new();
+    for line in content.lines() {
+        match parse_line(line) {
+            Some((key, value)) => {
+                let Some((key, new_value)) = vars.get_key_value(&key) else { continue };
+                if written.contains(&key.as_str()) { continue; }
+                written.push(key);
+                if *new_value == value {
+                    out.push_str(line);
+                } else {
+                    out.push_str(&format!("{}={}", key, format_value(new_value)));
+                }
+            }
+            None => out.push_str(line),
+        }
+        out.push('\n');
+    }
+    for (key, value) in vars {
+        if !written.contains(&key.as_str()) {
+            out.push_str(&format!("{}={}\n", key, format_value(value)));
+        }
+    }
+    out
+}
+
+fn redact(vars: &mut BTreeMap<String, String>) {
+    for value in vars.values_mut() { *value = REDACTED.to_string(); }
+}
+
+// ─── Tauri Commands ───────────────────────────────────────────────────────────
+
+/// Every known env file, in load order, whether or not it exists.
+#[tauri::command]
+pub fn read_env_files(app: tauri::AppHandle, name: String) -> Result<Vec<EnvFile>, String> {
+    let cwd = project_cwd(&app, &name)?;
+    let masked = presenting(&app);
+    Ok(ENV_FILES.iter().map(|file| {
+        let parsed = read_env_file(&cwd.join(file));
+        let mut vars = parsed.clone().unwrap_or_default();
+        if masked { redact(&mut vars); }
+        EnvFile { file: file.to_string(), exists: parsed.is_some(), vars }
+    }).collect())
+}
+
+/// What saving `vars` to `file` would change, without touching it.
+#[tauri::command]
+pub fn diff_env_file(app: tauri::AppHandle, name: String, file: String, vars: BTreeMap<String, String>) -> Result<Vec<EnvChange>, String> {
+    ensure_not_presenting(&app)?;
+    let current = read_env_file(&env_file_path(&app, &name, &file)?).unwrap_or_default();
+    Ok(diff(&current, &vars))
+}
+
+/// Replaces the file's variables with `vars`, creating it if needed, and
+/// returns what changed. Takes effect on the server's next start.
+#[tauri::command]
+pub fn write_env_file(app: tauri::AppHandle, name: String, file: String, vars: BTreeMap<String, String>) -> Result<Vec<EnvChange>, String> {
+    ensure_not_presenting(&app)?;
+    if let Some(bad) = vars.keys().find(|k| !valid_key(k)) {
+        return Err(format!("'{}' isn't a valid variable name", bad));
+    }
+    let path = env_file_path(&app, &name, &file)?;
+    let content = std::fs::read_to_string(&path).unwrap_or_default();
+    let changes = diff(&parse_env(&content), &vars);
+    if changes.is_empty() { return Ok(changes); }
+    std::fs::write(&path, rewrite(&content, &vars)).map_err(|e| e.to_string())?;
+    // Keys only; values may be secrets
+    let keys: Vec<&str> = changes.iter().map(|c| c.key.as_str()).collect();
+    record_event(&app, "env-file-written", Some(&name), serde_json::json!({ "file": file, "keys": keys }));
+    Ok(changes)
+}
+
+/// The env the dev server would launch with, plus anything in its sources
+/// that looks wrong. Lowest precedence first: the env files in load order,
+/// DexHub's own process env, DexHub's overrides, the active env profile.
+/// dotenv loaders don't replace variables that are already set, so
+/// everything on the process beats the files. Secrets show as the keyring
+/// placeholder.
+#[tauri::command]
+pub fn get_effective_env(app: tauri::AppHandle, name: String) -> Result<EffectiveEnv, String> {
+    let cwd = project_cwd(&app, &name)?;
+    let state = app.state::<ServerState>();
+    let mut layers: Vec<(String, HashMap<String, String>)> = Vec::new();
+    let mut issues: Vec<EnvIssue> = Vec::new();
+    for file in ENV_FILES {
+        let Ok(content) = std::fs::read_to_string(cwd.join(file)) else { continue };
+        issues.extend(check_env_file(file, &content));
+        layers.push((file.to_string(), parse_env(&content).into_iter().collect()));
+    }
+    layers.push(("process".to_string(), std::env::vars().collect()));
+    let key = project_key(&state, &name);
+    let overrides = state.env_overrides.lock().unwrap().get(&key).cloned().unwrap_or_default();
+    issues.extend(check_dexhub_vars(&name, "override", &overrides));
+    layers.push(("override".to_string(), overrides));
+    let profile = state.env_profiles.lock().unwrap().get(&name)
+        .and_then(|p| Some((p.active.clone()?, p.active_vars())));
+    if let Some((active, vars)) = profile {
+        let source = format!("profile:{}", active);
+        issues.extend(check_dexhub_vars(&name, &source, &vars));
+        layers.push((source, vars));
+    }
+
+    let mut merged: BTreeMap<String, EffectiveVar> = BTreeMap::new();
+    for (source, vars) in layers {
+        for (key, value) in vars {
+            let entry = merged.entry(key.clone()).or_insert_with(|| EffectiveVar {
+                key, value: String::new(), source: String::new(), shadowed: Vec::new(),
+            });
+            if !entry.source.is_empty() { entry.shadowed.push(std::mem::take(&mut entry.source)); }
+            entry.value = value;
+            entry.source = source.clone();
+        }
+    }
+    let masked = presenting(&app);
+    let vars = merged.into_values().map(|mut v| {
+        if masked { v.value = REDACTED.to_string(); }
+        v
+    }).collect();
+    Ok(EffectiveEnv { vars, issues })
+}
@@ -0,0 +1,108 @@
+//! The durable event log: every `record_event` is appended to
+//! `events.jsonl` as one JSON object per line, so scripts and other tools
+//! can follow DexHub's state changes without the app open. The in-memory
+//! log in `ServerState` stays the fast path for the UI.
+//!
+//! With `general.otlp_endpoint` set, each event is also sent as an
+//! OpenTelemetry log record (OTLP/HTTP JSON), through curl like the GitHub
+//! client.
+
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tauri::Manager;
+
+use crate::{EventRecord, ServerState};
+
+/// Past this the file is rotated to `events.jsonl.1`, replacing the last one.
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+const OTLP_TIMEOUT_SECS: &str = "5";
+
+fn events_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("events.jsonl")
+}
+
+/// Appends one line. Callers serialize appends (`record_event` holds the
+/// event log lock), so lines never interleave.
+pub fn append(app_data_dir: &Path, record: &EventRecord) {
+    let path = events_path(app_data_dir);
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() > MAX_FILE_BYTES) {
+        let _ = std::fs::rename(&path, app_data_dir.join("events.jsonl.1"));
+    }
+    let Ok(line) = serde_json::to_string(record) else { return };
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(&path) {
+        let _ = writeln!(file, "{}", line);
+    }
+}
+
+fn otlp_string(key: &str, value: &str) -> serde_json::Value {
+    serde_json::json!({ "key": key, "value": { "stringValue": value } })
+}
+
+/// One event as an OTLP `ExportLogsServiceRequest`.
+fn otlp_payload(record: &EventRecord) -> serde_json::Value {
+    let mut attributes = vec![otlp_string("dexhub.event", &record.kind), otlp_string("dexhub.detail", &record.detail.to_string())];
+    if let Some(project) = &record.project { attributes.push(otlp_string("dexhub.project", project)); }
+    serde_json::json!({
+        "resourceLogs": [{
+            "resource": { "attributes": [otlp_string("service.name", "dexhub")] },
+            "scopeLogs": [{
+                "scope": { "name": "dexhub" },
+                "logRecords": [{
+                    "timeUnixNano": (record.ts as u128 * 1_000_000_000).to_string(),
+                    "severityText": "INFO",
+                    "body": { "stringValue": record.kind },
+                    "attributes": attributes,
+                }],
+            }],
+        }],
+    })
+}
+
+/// Posts the event to the collector in the background; failures are only logged.
+pub fn export_otlp(endpoint: &str, record: &EventRecord) {
+    let endpoint = endpoint.trim_end_matches('/');
+    let url = if endpoint.ends_with("/v1/logs") { endpoint.to_string() } else { format!("{}/v1/logs", endpoint) };
+    let body = otlp_payload(record).to_string();
+    std::thread::spawn(move || {
+        let child = Command::new("curl")
+            .args(["-sS", "--fail", "--max-time", OTLP_TIMEOUT_SECS, "-X", "POST", "-H", "Content-Type: application/json", "--data-binary", "@-"])
+            .arg(&url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn();
+        let Ok(mut child) = child else { return };
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(body.as_bytes());
+        }
+        if let Ok(output) = child.wait_with_output() {
+            if !output.status.success() {
+                eprintln!("[DexHub] OTLP export to {} failed: {}", url, String::from_utf8_lossy(&output.stderr).trim());
+            }
+        }
+    });
+}
+
+// ─── Tauri Commands ───────────────────────────────────────────────────────────
+
+/// Events at or after `since` (unix seconds) from `events.jsonl`, oldest
+/// first, at most `limit` of the newest (1000 by default). Unlike
+/// `get_event_log` this reaches back past the current session.
+#[tauri::command]
+pub fn get_events(app: tauri::AppHandle, since: u64, limit: Option<usize>) -> Result<Vec<EventRecord>, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    // Holding the log lock keeps a half-written line out of the read
+    let _appending = app.state::<ServerState>().event_log.lock().unwrap();
+    let mut events: Vec<EventRecord> = Vec::new();
+    for path in [app_data_dir.join("events.jsonl.1"), events_path(&app_data_dir)] {
+        let Ok(file) = std::fs::File::open(&path) else { continue };
+        events.extend(BufReader::new(file).lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str::<EventRecord>(&line).ok())
+            .filter(|e| e.ts >= since));
+    }
+    let skip = events.len().saturating_sub(limit.unwrap_or(1000));
+    Ok(events.split_off(skip))
+}
@@ -0,0 +1,156 @@
+//! macOS Focus mode integration.
+//!
+//! The active Focus is read from the Do Not Disturb database
+//! (`~/Library/DoNotDisturb/DB/{Assertions,ModeConfigurations}.json`, which
+//! needs Full Disk Access). When that's unreadable, a user-created Shortcut
+//! named `DexHub Focus` that outputs the current Focus name is used instead.
+//!
+//! Each Focus can map to a rule: servers to start when it turns on, and
+//! servers whose notifications go silent while it's active.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::{ServerState, record_event, running_names, start_server};
+
+const FOCUS_POLL_INTERVAL: Duration = Duration::from_secs(20);
+const SHORTCUT_NAME: &str = "DexHub Focus";
+
+#[derive(Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct FocusRule {
+    pub start:   Vec<String>,
+    pub silence: Vec<String>,
+}
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct FocusConfig {
+    pub enabled: bool,
+    pub rules:   HashMap<String, FocusRule>, // Focus name → rule
+}
+
+fn focus_config_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("focus.json")
+}
+
+pub fn load_focus_config(app_data_dir: &Path) -> FocusConfig {
+    std::fs::read_to_string(focus_config_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_focus_config_to_disk(app_data_dir: &Path, config: &FocusConfig) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(config) {
+        let _ = std::fs::write(focus_config_path(app_data_dir), json);
+    }
+}
+
+fn dnd_db_dir() -> PathBuf {
+    PathBuf::from(std::env::var("HOME").unwrap_or_default()).join("Library/DoNotDisturb/DB")
+}
+
+/// `Ok(None)` means "readable, no Focus active"; `Err` means the DB isn't accessible.
+fn focus_from_db() -> Result<Option<String>, ()> {
+    let read = |file: &str| -> Result<serde_json::Value, ()> {
+        let content = std::fs::read_to_string(dnd_db_dir().join(file)).map_err(|_| ())?;
+        serde_json::from_str(&content).map_err(|_| ())
+    };
+    let assertions = read("Assertions.json")?;
+    let Some(mode_id) = assertions["data"][0]["storeAssertionRecords"]
+        .as_array()
+        .and_then(|records| records.last())
+        .and_then(|r| r["assertionDetails"]["assertionDetailsModeIdentifier"].as_str())
+        .map(str::to_string)
+    else {
+        return Ok(None);
+    };
+    let configs = read("ModeConfigurations.json")?;
+    let name = configs["data"][0]["modeConfigurations"][&mode_id]["mode"]["name"]
+        .as_str()
+        .map(str::to_string);
+    Ok(name.or(Some(mode_id)))
+}
+
+fn focus_from_shortcut() -> Option<String> {
+    let output = std::process::Command::new("shortcuts").args(["run", SHORTCUT_NAME]).output().ok()?;
+    if !output.status.success() { return None; }
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+pub fn detect_focus() -> Option<String> {
+    if !cfg!(target_os = "macos") { return None; }
+    focus_from_db().unwrap_or_else(|_| focus_from_shortcut())
+}
+
+/// True when the active Focus' rule silences this project's notifications.
+pub fn is_silenced(app: &tauri::AppHandle, name: &str) -> bool {
+    let state = app.state::<ServerState>();
+    let Some(focus) = state.active_focus.lock().unwrap().clone() else { return false };
+    let config = state.focus.lock().unwrap();
+    config.enabled && config.rules.get(&focus).is_some_and(|r| r.silence.iter().any(|n| n == name))
+}
+
+fn on_focus_changed(app: &tauri::AppHandle, focus: Option<String>) {
+    let _ = app.emit("focus-changed", &focus);
+    record_event(app, "focus-changed", None, serde_json::json!({ "focus": focus }));
+    let Some(focus) = focus else { return };
+    let rule = {
+        let state = app.state::<ServerState>();
+        let config = state.focus.lock().unwrap();
+        if !config.enabled { return; }
+        config.rules.get(&focus).cloned()
+    };
+    let Some(rule) = rule else { return };
+    let running = running_names(&app.state::<ServerState>());
+    for name in rule.start.iter().filter(|n| !running.contains(n)) {
+        if let Err(e) = start_server(app, name.clone()) {
+            eprintln!("[DexHub] Focus '{}' failed to start '{}': {}", focus, name, e);
+        }
+    }
+}
+
+pub fn spawn_focus_watcher(app: tauri::AppHandle) {
+    if !cfg!(target_os = "macos") { return; }
+    std::thread::spawn(move || loop {
+        let enabled = app.state::<ServerState>().focus.lock().unwrap().enabled;
+        if enabled {
+            let current = detect_focus();
+            let changed = {
+                let state = app.state::<ServerState>();
+                let mut active = state.active_focus.lock().unwrap();
+                let changed = *active != current;
+                *active = current.clone();
+                changed
+            };
+            if changed { on_focus_changed(&app, current); }
+        }
+        std::thread::sleep(FOCUS_POLL_INTERVAL);
+    });
+}
+
+// ─── Tauri Commands ───────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_focus_config(state: tauri::State<'_, ServerState>) -> FocusConfig {
+    state.focus.lock().unwrap().clone()
+}
+
+#[tauri::command]
+pub fn set_focus_config(app: tauri::AppHandle, config: FocusConfig) -> Result<(), String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    save_focus_config_to_disk(&app_data_dir, &config);
+    *app.state::<ServerState>().focus.lock().unwrap() = config;
+    Ok(())
+}
+
+/// The Focus the watcher last saw (`None` when off or undetectable).
+#[tauri::command]
+pub fn get_active_focus(state: tauri::State<'_, ServerState>) -> Option<String> {
+    state.active_focus.lock().unwrap().clone()
+}
@@ -0,0 +1,166 @@
+//! GitHub pull request and CI status for a project's current branch.
+//!
+//! The repository comes from the `origin` remote and the token from the
+//! system keyring. Requests go through `curl` with the token on stdin, so it
+//! never shows up in the process list. Answers are cached briefly; the UI
+//! polls every card and the API allows 5,000 requests an hour.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::ServerState;
+
+/// Keyring account the personal access token is stored under.
+const TOKEN_ACCOUNT: &str = "github-token";
+const CACHE_TTL: Duration = Duration::from_secs(60);
+const API_TIMEOUT_SECS: &str = "10";
+
+#[derive(Clone, serde::Serialize)]
+pub struct PullRequest {
+    pub number: u64,
+    pub title:  String,
+    pub url:    String,
+    pub draft:  bool,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct GithubStatus {
+    pub repo:    String, // owner/name
+    pub branch:  String,
+    pub pr:      Option<PullRequest>,
+    pub checks:  String, // "success" | "failure" | "pending" | "none"
+    pub failing: Vec<String>, // names of failed check runs
+}
+
+/// Last answer per project, keyed by name.
+#[derive(Default)]
+pub struct GithubCache(Mutex<HashMap<String, (Instant, GithubStatus)>>);
+
+/// `owner/repo` from an https, ssh or scp-style GitHub remote URL.
+fn parse_github_remote(url: &str) -> Option<String> {
+    let url = url.trim();
+    let path = url.strip_prefix("git@github.com:")
+        .or_else(|| url.strip_prefix("ssh://git@github.com/"))
+        .or_else(|| url.strip_prefix("https://github.com/"))
+        .or_else(|| url.strip_prefix("http://github.com/"))
+        .or_else(|| url.split_once("@github.com/").map(|(_, p)| p))?; // https://user@github.com/…
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    let mut parts = path.split('/');
+    let (owner, repo) = (parts.next()?, parts.next()?);
+    (!owner.is_empty() && !repo.is_empty() && parts.next().is_none()).then(|| format!("{}/{}", owner, repo))
+}
+
+/// Percent-encodes a branch name for use in a URL path or query.
+fn encode_component(s: &str) -> String {
+    s.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+        _ => format!("%{:02X}", b),
+    }).collect()
+}
+
+fn api_get(token: &str, path: &str) -> Result<serde_json::Value, String> {
+    let mut child = Command::new("curl")
+        .args(["-sS", "--fail-with-body", "--max-time", API_TIMEOUT_SECS, "-H", "@-"])
+        .args(["-H", "Accept: application/vnd.github+json", "-H", "X-GitHub-Api-Version: 2022-11-28"])
+        .arg(format!("https://api.github.com{}", path))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Couldn't run curl: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = writeln!(stdin, "Authorization: Bearer {}", token);
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    let body: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap_or_default();
+    if !output.status.success() {
+        let message = body["message"].as_str().map(str::to_string)
+            .unwrap_or_else(|| String::from_utf8_lossy(&output.stderr).trim().to_string());
+        return Err(format!("GitHub: {}", message));
+    }
+    Ok(body)
+}
+
+/// Rolls check runs up into one state, listing the failures.
+fn summarize_checks(runs: &[serde_json::Value]) -> (String, Vec<String>) {
+    let failing: Vec<String> = runs.iter()
+        .filter(|r| matches!(r["conclusion"].as_str(), Some("failure" | "timed_out" | "cancelled" | "action_required")))
+        .filter_map(|r| r["name"].as_str().map(str::to_string))
+        .collect();
+    let state = if runs.is_empty() {
+        "none"
+    } else if !failing.is_empty() {
+        "failure"
+    } else if runs.iter().any(|r| r["status"].as_str() != Some("completed")) {
+        "pending"
+    } else {
+        "success"
+    };
+    (state.to_string(), failing)
+}
+
+fn fetch_status(token: &str, repo: &str, branch: &str) -> Result<GithubStatus, String> {
+    let owner = repo.split('/').next().unwrap_or_default();
+    let pulls = api_get(token, &format!(
+        "/repos/{}/pulls?state=open&head={}",
+        repo, encode_component(&format!("{}:{}", owner, branch))
+    ))?;
+    let pr = pulls.as_array().and_then(|a| a.first()).map(|p| PullRequest {
+        number: p["number"].as_u64().unwrap_or(0),
+        title:  p["title"].as_str().unwrap_or_default().to_string(),
+        url:    p["html_url"].as_str().unwrap_or_default().to_string(),
+        draft:  p["draft"].as_bool().unwrap_or(false),
+    });
+    let checks = api_get(token, &format!("/repos/{}/commits/{}/check-runs?per_page=100", repo, encode_component(branch)))?;
+    let runs = checks["check_runs"].as_array().cloned().unwrap_or_default();
+    let (checks, failing) = summarize_checks(&runs);
+    Ok(GithubStatus { repo: repo.to_string(), branch: branch.to_string(), pr, checks, failing })
+}
+
+// ─── Tauri Commands ───────────────────────────────────────────────────────────
+
+/// PR and CI status for the project's current branch. `Ok(None)` when the
+/// project isn't a GitHub checkout or HEAD is detached.
+#[tauri::command]
+pub fn get_github_status(app: tauri::AppHandle, name: String, refresh: Option<bool>) -> Result<Option<GithubStatus>, String> {
+    let cache = app.state::<GithubCache>();
+    if !refresh.unwrap_or(false) {
+        if let Some((at, status)) = cache.0.lock().unwrap().get(&name) {
+            if at.elapsed() < CACHE_TTL { return Ok(Some(status.clone())); }
+        }
+    }
+
+    let cwd = app.state::<ServerState>().projects.lock().unwrap()
+        .iter().find(|p| p.name == name).map(|p| p.cwd.clone())
+        .ok_or_else(|| format!("Project '{}' not found", name))?;
+    let Some(repo) = crate::git_output(&cwd, &["remote", "get-url", "origin"]).and_then(|u| parse_github_remote(&u)) else {
+        return Ok(None);
+    };
+    let Some(branch) = crate::git_output(&cwd, &["symbolic-ref", "--short", "HEAD"]).map(|b| b.trim().to_string()) else {
+        return Ok(None);
+    };
+    let token = crate::platform::keyring_get(TOKEN_ACCOUNT).ok_or("No GitHub token set")?;
+
+    let status = fetch_status(&token, &repo, &branch)?;
+    cache.0.lock().unwrap().insert(name, (Instant::now(), status.clone()));
+    Ok(Some(status))
+}
+
+#[tauri::command]
+pub fn has_github_token() -> bool {
+    crate::platform::keyring_get(TOKEN_ACCOUNT).is_some()
+}
+
+/// Stores the token in the keyring; an empty or missing token removes it.
+#[tauri::command]
+pub fn set_github_token(cache: tauri::State<'_, GithubCache>, token: Option<String>) -> Result<(), String> {
+    cache.0.lock().unwrap().clear();
+    match token.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) {
+        Some(token) => crate::platform::keyring_set(TOKEN_ACCOUNT, &token),
+        None => crate::platform::keyring_delete(TOKEN_ACCOUNT),
+    }
+}
@@ -0,0 +1,295 @@
+//! Stable project IDs.
+//!
+//! Most settings files are keyed by project name, which comes from
+//! package.json and can change under them. Each project also gets an ID
+//! hashed from its folder path, and `project_ids.json` remembers the name
+//! last seen for each ID. When a scan finds an ID under a new name, every
+//! name-keyed file is migrated to the new name, so a renamed package keeps
+//! its favorites, launch commands and the rest, its run history in
+//! dexhub.db and its env secrets in the keyring. Port and env overrides
+//! are keyed by the ID itself.
+
+use sha1::{Digest, Sha1};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::ProjectConfig;
+
+/// Files that are one JSON object keyed by project name.
+const NAME_KEYED_FILES: &[&str] = &[
+    "env_profiles.json",
+    "launch_commands.json",
+    "lifecycle_hooks.json",
+    "url_hosts.json",
+    "health_warmup.json",
+    "health_checks.json",
+    "tags.json",
+    "resource_limits.json",
+    "server_schedules.json",
+    "canary.json",
+    "port_registry.json",
+];
+/// Files that are one JSON object keyed by project ID.
+const ID_KEYED_FILES: &[&str] = &["port_overrides.json", "env_overrides.json"];
+/// Files that are a JSON list of project names.
+const NAME_LIST_FILES: &[&str] = &["pull_before_start.json", "recent.json"];
+
+/// 12 hex digits of the SHA-1 of the project folder's path.
+pub fn project_id(cwd: &str) -> String {
+    let digest = Sha1::digest(cwd.trim_end_matches('/').as_bytes());
+    digest.iter().take(6).map(|b| format!("{:02x}", b)).collect()
+}
+
+fn registry_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("project_ids.json")
+}
+
+/// ID → the name the project had at the last scan.
+fn load_registry(app_data_dir: &Path) -> HashMap<String, String> {
+    std::fs::read_to_string(registry_path(app_data_dir)).ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_registry(app_data_dir: &Path, registry: &HashMap<String, String>) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(registry) {
+        let _ = std::fs::write(registry_path(app_data_dir), json);
+    }
+}
+
+fn rename_key(map: Option<&mut serde_json::Value>, old: &str, new: &str) -> bool {
+    let Some(map) = map.and_then(|m| m.as_object_mut()) else { return false };
+    if map.contains_key(new) { return false; } // never clobber the new name's own settings
+    match map.remove(old) {
+        Some(value) => { map.insert(new.to_string(), value); true }
+        None => false,
+    }
+}
+
+fn rename_in_list(list: Option<&mut serde_json::Value>, old: &str, new: &str) -> bool {
+    let Some(list) = list.and_then(|l| l.as_array_mut()) else { return false };
+    let mut changed = false;
+    for item in list.iter_mut().filter(|i| i.as_str() == Some(old)) {
+        *item = serde_json::json!(new);
+        changed = true;
+    }
+    changed
+}
+
+/// Rewrites one file in place, if `edit` changed anything.
+fn migrate_file(path: &Path, mut edit: impl FnMut(&mut serde_json::Value) -> bool) {
+    let Some(mut doc) = std::fs::read_to_string(path).ok().and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok()) else { return };
+    if !edit(&mut doc) { return; }
+    if let Ok(json) = serde_json::to_string_pretty(&doc) {
+        let _ = std::fs::write(path, json);
+    }
+}
+
+fn migrate_name(app_data_dir: &Path, old: &str, new: &str) {
+    for file in NAME_KEYED_FILES {
+        migrate_file(&app_data_dir.join(file), |doc| rename_key(Some(doc), old, new));
+    }
+    for file in NAME_LIST_FILES {
+        migrate_file(&app_data_dir.join(file), |doc| rename_in_list(Some(doc), old, new));
+    }
+    // Entries are { "project": name, … }, or bare names in the original format
+    migrate_file(&app_data_dir.join("favorites.json"), |doc| {
+        let mut changed = rename_in_list(Some(&mut *doc), old, new);
+        for entry in doc.as_array_mut().into_iter().flatten().filter(|e| e["project"].as_str() == Some(old)) {
+            entry["project"] = serde_json::json!(new);
+            changed = true;
+        }
+        changed
+    });
+    migrate_file(&app_data_dir.join("focus.json"), |doc| {
+        let mut changed = false;
+        for rule in doc.get_mut("rules").and_then(|r| r.as_object_mut()).into_iter().flat_map(|r| r.values_mut()) {
+            changed |= rename_in_list(rule.get_mut("start"), old, new);
+            changed |= rename_in_list(rule.get_mut("silence"), old, new);
+        }
+        changed
+    });
+    migrate_file(&app_data_dir.join("settings.json"), |doc| {
+        let mut changed = rename_key(doc.pointer_mut("/general/project_browsers"), old, new);
+        changed |= rename_key(doc.pointer_mut("/general/project_health"), old, new);
+        changed |= rename_key(doc.pointer_mut("/notifications/projects"), old, new);
+        changed
+    });
+    migrate_file(&app_data_dir.join("proxy.json"), |doc| rename_in_list(doc.get_mut("lazy"), old, new));
+    crate::db::rename_project(app_data_dir, old, new);
+}
+
+/// Moves the keyring secrets among the project's env overrides, filed
+/// under `env:{name}:{key}`, to the new name. `move_account` does the
+/// keyring work.
+fn migrate_secrets(app_data_dir: &Path, id: &str, old: &str, new: &str, mut move_account: impl FnMut(&str, &str) -> Result<(), String>) {
+    let overrides: HashMap<String, HashMap<String, String>> = std::fs::read_to_string(app_data_dir.join("env_overrides.json")).ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default();
+    let mut keys: Vec<&String> = overrides.get(id).into_iter().flatten()
+        .filter(|(_, value)| *value == crate::SECRET_PLACEHOLDER)
+        .map(|(key, _)| key)
+        .collect();
+    keys.sort();
+    for key in keys {
+        if let Err(e) = move_account(&crate::env_secret_account(old, key), &crate::env_secret_account(new, key)) {
+            eprintln!("[DexHub] Couldn't move secret {} from '{}' to '{}': {}", key, old, new, e);
+        }
+    }
+}
+
+/// Re-keys the entries of the ID-keyed files that still carry a project
+/// name (written before they were ID-keyed): by the current name, else by
+/// the name last recorded for an ID. Entries matching neither stay put.
+/// Run before `migrate_renames` records the new names. Returns whether
+/// anything was re-keyed.
+pub fn key_by_id(app_data_dir: &Path, projects: &[ProjectConfig]) -> bool {
+    let registry = load_registry(app_data_dir);
+    let ids: HashSet<&str> = projects.iter().map(|p| p.id.as_str()).collect();
+    let id_for = |name: &str| -> Option<String> {
+        projects.iter().find(|p| p.name == name).map(|p| p.id.clone())
+            .or_else(|| registry.iter().find(|(_, n)| *n == name).map(|(id, _)| id.clone()))
+    };
+    let mut changed = false;
+    for file in ID_KEYED_FILES {
+        migrate_file(&app_data_dir.join(file), |doc| {
+            let Some(map) = doc.as_object() else { return false };
+            let names: Vec<(String, String)> = map.keys()
+                .filter(|k| !ids.contains(k.as_str()) && !registry.contains_key(k.as_str()))
+                .filter_map(|k| id_for(k).map(|id| (k.clone(), id)))
+                .collect();
+            let mut moved = false;
+            for (name, id) in names { moved |= rename_key(Some(&mut *doc), &name, &id); }
+            changed |= moved;
+            moved
+        });
+    }
+    changed
+}
+
+/// Moves the settings of every project whose name changed since the last
+/// scan to its new name, and records the current names. Returns the
+/// `(old, new)` pairs that were migrated.
+pub fn migrate_renames(app_data_dir: &Path, projects: &[ProjectConfig]) -> Vec<(String, String)> {
+    let mut registry = load_registry(app_data_dir);
+    let current: HashSet<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+    let mut renames = Vec::new();
+    for p in projects {
+        let Some(old) = registry.get(&p.id) else { continue };
+        // A name still in use belongs to another project now; leave its settings be
+        if *old == p.name || current.contains(old.as_str()) { continue; }
+        migrate_name(app_data_dir, old, &p.name);
+        migrate_secrets(app_data_dir, &p.id, old, &p.name, crate::platform::keyring_move);
+        renames.push((old.clone(), p.name.clone()));
+    }
+    let mut changed = false;
+    for p in projects {
+        changed |= registry.insert(p.id.clone(), p.name.clone()).as_deref() != Some(p.name.as_str());
+    }
+    if changed { save_registry(app_data_dir, &registry); }
+    renames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("dexhub-ids-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn read(dir: &Path, file: &str) -> serde_json::Value {
+        serde_json::from_str(&std::fs::read_to_string(dir.join(file)).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn project_ids_ignore_a_trailing_slash() {
+        assert_eq!(project_id("/src/web"), project_id("/src/web/"));
+        assert_ne!(project_id("/src/web"), project_id("/src/admin"));
+        assert_eq!(project_id("/src/web").len(), 12);
+    }
+
+    #[test]
+    fn port_registry_and_wake_on_demand_follow_a_rename() {
+        let dir = temp_dir("rename");
+        std::fs::write(dir.join("port_registry.json"), r#"{ "web": { "declared": [3000] }, "api": { "declared": [4000] } }"#).unwrap();
+        std::fs::write(dir.join("proxy.json"), r#"{ "enabled": true, "port": 8088, "domain": "dex.test", "lazy": ["web", "api"] }"#).unwrap();
+
+        migrate_name(&dir, "web", "storefront");
+
+        let registry = read(&dir, "port_registry.json");
+        assert!(registry.get("web").is_none());
+        assert_eq!(registry["storefront"]["declared"], serde_json::json!([3000]));
+        assert_eq!(registry["api"]["declared"], serde_json::json!([4000]));
+        let proxy = read(&dir, "proxy.json");
+        assert_eq!(proxy["lazy"], serde_json::json!(["storefront", "api"]));
+        assert_eq!(proxy["port"], 8088);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    fn project(name: &str, cwd: &str) -> ProjectConfig {
+        serde_json::from_value(serde_json::json!({
+            "id": project_id(cwd), "name": name, "cwd": cwd, "command": "npm",
+            "args": ["run", "dev"], "port": 3000, "default_port": 3000, "extra_ports": [], "workspace": "",
+        })).unwrap()
+    }
+
+    #[test]
+    fn overrides_are_rekeyed_by_id_once() {
+        let dir = temp_dir("key-by-id");
+        let (web, old) = (project("web", "/src/web"), project("storefront", "/src/shop"));
+        // `shop` was last seen as `store`, before its package was renamed
+        save_registry(&dir, &HashMap::from([(old.id.clone(), "store".to_string())]));
+        std::fs::write(dir.join("port_overrides.json"), r#"{ "web": 3100, "store": 3200, "gone": 3300 }"#).unwrap();
+        std::fs::write(dir.join("env_overrides.json"), r#"{ "web": { "DEBUG": "1" } }"#).unwrap();
+
+        assert!(key_by_id(&dir, &[web.clone(), old.clone()]));
+        let ports = read(&dir, "port_overrides.json");
+        assert_eq!(ports[&web.id], 3100);
+        assert_eq!(ports[&old.id], 3200);
+        assert_eq!(ports["gone"], 3300);
+        assert_eq!(read(&dir, "env_overrides.json")[&web.id]["DEBUG"], "1");
+
+        assert!(!key_by_id(&dir, &[web, old]));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn keyring_secrets_follow_a_rename() {
+        let dir = temp_dir("secrets");
+        let web = project("web", "/src/web");
+        std::fs::write(dir.join("env_overrides.json"), format!(
+            r#"{{ "{}": {{ "API_KEY": "@keyring", "DEBUG": "1", "TOKEN": "@keyring" }}, "0123456789ab": {{ "OTHER": "@keyring" }} }}"#,
+            web.id,
+        )).unwrap();
+
+        let mut moved = Vec::new();
+        migrate_secrets(&dir, &web.id, "web", "storefront", |from, to| {
+            moved.push((from.to_string(), to.to_string()));
+            Ok(())
+        });
+
+        assert_eq!(moved, vec![
+            ("env:web:API_KEY".to_string(), "env:storefront:API_KEY".to_string()),
+            ("env:web:TOKEN".to_string(), "env:storefront:TOKEN".to_string()),
+        ]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn a_rename_never_clobbers_the_new_names_settings() {
+        let dir = temp_dir("clobber");
+        std::fs::write(dir.join("port_registry.json"), r#"{ "web": { "declared": [3000] }, "storefront": { "declared": [5000] } }"#).unwrap();
+
+        migrate_name(&dir, "web", "storefront");
+
+        let registry = read(&dir, "port_registry.json");
+        assert_eq!(registry["storefront"]["declared"], serde_json::json!([5000]));
+        assert_eq!(registry["web"]["declared"], serde_json::json!([3000]));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
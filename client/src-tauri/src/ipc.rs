@@ -0,0 +1,127 @@
+//! Local control socket for the `dexhub` CLI.
+//!
+//! The socket lives at `<app data>/dexhub.sock` and speaks newline-delimited
+//! JSON: one request per connection, e.g. `{"cmd":"start","name":"web"}`,
+//! answered with `{"ok":true,"data":...}` or `{"ok":false,"error":"..."}`.
+//! `logs` with `"follow":true` keeps the connection open and streams
+//! `{"line":"..."}` objects (and blank heartbeat lines) until the client hangs up.
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::{ServerState, effective_port, ensure_not_presenting, get_running_servers, project_url, restart_server, server_log_lines, start_server, stop_server, uptime_secs};
+
+pub fn socket_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("dexhub.sock")
+}
+
+#[derive(serde::Deserialize)]
+struct IpcRequest {
+    cmd:    String,
+    #[serde(default)]
+    name:   Option<String>,
+    #[serde(default)]
+    follow: bool,
+    #[serde(default)]
+    lines:  Option<usize>,
+}
+
+fn reply(stream: &mut UnixStream, value: serde_json::Value) -> bool {
+    writeln!(stream, "{}", value).is_ok()
+}
+
+fn reply_result(stream: &mut UnixStream, result: Result<serde_json::Value, String>) {
+    let value = match result {
+        Ok(data) => serde_json::json!({ "ok": true, "data": data }),
+        Err(error) => serde_json::json!({ "ok": false, "error": error }),
+    };
+    reply(stream, value);
+}
+
+pub fn status(app: &tauri::AppHandle) -> serde_json::Value {
+    let running = get_running_servers(app.clone());
+    let state = app.state::<ServerState>();
+    let projects = state.projects.lock().unwrap().clone();
+    projects.iter().map(|p| serde_json::json!({
+        "name": p.name,
+        "port": effective_port(&state, p),
+        "running": running.contains(&p.name),
+        "url": project_url(&state, p),
+        "uptime_secs": uptime_secs(&state, &p.name),
+    })).collect()
+}
+
+fn follow_logs(app: &tauri::AppHandle, stream: &mut UnixStream, name: &str) {
+    let Some(rx) = crate::remote::subscribe(app, true) else { return };
+    loop {
+        match rx.recv_timeout(Duration::from_secs(1)) {
+            Ok(msg) => {
+                let Ok(val) = serde_json::from_str::<serde_json::Value>(&msg) else { continue };
+                if val["type"] != "log" || val["project"] != name { continue; }
+                if !reply(stream, serde_json::json!({ "line": val["line"] })) { return; }
+            }
+            // Heartbeat so a Ctrl-C'd client is noticed even when the server is quiet
+            Err(RecvTimeoutError::Timeout) => {
+                if stream.write_all(b"\n").is_err() { return; }
+            }
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+fn handle_client(app: &tauri::AppHandle, mut stream: UnixStream) {
+    let mut line = String::new();
+    {
+        let Ok(reader_stream) = stream.try_clone() else { return };
+        if BufReader::new(reader_stream).read_line(&mut line).is_err() { return; }
+    }
+    let req: IpcRequest = match serde_json::from_str(&line) {
+        Ok(r) => r,
+        Err(e) => return reply_result(&mut stream, Err(format!("Bad request: {}", e))),
+    };
+    let name = req.name.clone().unwrap_or_default();
+    let exists = app.state::<ServerState>().projects.lock().unwrap().iter().any(|p| p.name == name);
+
+    match req.cmd.as_str() {
+        "status" => reply_result(&mut stream, Ok(status(app))),
+        "start" | "stop" | "restart" | "logs" if !exists => {
+            reply_result(&mut stream, Err(format!("Project '{}' not found", name)))
+        }
+        "start" => reply_result(&mut stream, start_server(app, name).map(|_| serde_json::json!(null))),
+        // Same guard as the stop/restart commands: nothing destructive while presenting
+        "stop" => reply_result(&mut stream, ensure_not_presenting(app).and_then(|_| stop_server(app, name)).map(|_| serde_json::json!(null))),
+        "restart" => reply_result(&mut stream, ensure_not_presenting(app).and_then(|_| restart_server(app, name)).map(|_| serde_json::json!(null))),
+        "logs" => {
+            let logs = crate::redact_log_lines(app, server_log_lines(app, &name, None));
+            let skip = logs.len().saturating_sub(req.lines.unwrap_or(100));
+            for l in &logs[skip..] {
+                if !reply(&mut stream, serde_json::json!({ "line": l })) { return; }
+            }
+            if req.follow {
+                follow_logs(app, &mut stream, &name);
+            } else {
+                reply_result(&mut stream, Ok(serde_json::json!(null)));
+            }
+        }
+        other => reply_result(&mut stream, Err(format!("Unknown command '{}'", other))),
+    }
+}
+
+/// Binds the control socket, replacing a stale one left by a previous run.
+pub fn spawn_ipc_server(app: tauri::AppHandle, app_data_dir: &Path) -> Result<(), String> {
+    let path = socket_path(app_data_dir);
+    let _ = std::fs::create_dir_all(app_data_dir);
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).map_err(|e| e.to_string())?;
+    std::thread::spawn(move || {
+        for stream in listener.incoming().filter_map(|s| s.ok()) {
+            let app = app.clone();
+            std::thread::spawn(move || handle_client(&app, stream));
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,151 @@
+//! Per-project dependency license inventory.
+//!
+//! Reads installed packages rather than shelling out to `license-checker`, so
+//! it works offline and for Python virtualenvs too:
+//! - `node_modules/**/package.json` (`license`, or legacy `licenses[].type`)
+//! - `.venv|venv/lib/python*/site-packages/*.dist-info/METADATA` (`License:` /
+//!   `License-Expression:` / license classifiers)
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// SPDX ids flagged unless the user overrides the policy.
+const DEFAULT_DISALLOWED: &[&str] = &["AGPL-3.0", "AGPL-3.0-only", "AGPL-3.0-or-later", "GPL-3.0", "GPL-3.0-only", "GPL-3.0-or-later", "SSPL-1.0"];
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct LicensePolicy {
+    pub disallowed: Vec<String>,
+}
+
+impl Default for LicensePolicy {
+    fn default() -> Self {
+        LicensePolicy { disallowed: DEFAULT_DISALLOWED.iter().map(|s| s.to_string()).collect() }
+    }
+}
+
+fn license_policy_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("license_policy.json")
+}
+
+/// Only read to seed settings.json, which holds the policy now.
+pub fn load_license_policy(app_data_dir: &Path) -> LicensePolicy {
+    std::fs::read_to_string(license_policy_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct PackageLicense {
+    pub name:       String,
+    pub version:    String,
+    pub license:    String,
+    pub disallowed: bool,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct LicenseReport {
+    pub project:      String,
+    pub generated_at: u64,
+    pub packages:     Vec<PackageLicense>,
+    pub counts:       BTreeMap<String, usize>,
+    pub disallowed:   Vec<PackageLicense>,
+}
+
+fn node_license(pkg: &serde_json::Value) -> String {
+    match &pkg["license"] {
+        serde_json::Value::String(s) => return s.clone(),
+        serde_json::Value::Object(o) => {
+            if let Some(t) = o.get("type").and_then(|t| t.as_str()) { return t.to_string(); }
+        }
+        _ => {}
+    }
+    let legacy: Vec<&str> = pkg["licenses"].as_array()
+        .map(|a| a.iter().filter_map(|l| l["type"].as_str()).collect())
+        .unwrap_or_default();
+    if legacy.is_empty() { "UNKNOWN".to_string() } else { legacy.join(" OR ") }
+}
+
+/// Walks `node_modules`, including scoped and nested installs.
+fn collect_node(dir: &Path, seen: &mut HashSet<(String, String)>, out: &mut Vec<(String, String, String)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if file_name.starts_with('.') || !path.is_dir() { continue; }
+        if file_name.starts_with('@') {
+            collect_node(&path, seen, out);
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(path.join("package.json")) else { continue };
+        let Ok(pkg) = serde_json::from_str::<serde_json::Value>(&content) else { continue };
+        let name = pkg["name"].as_str().unwrap_or(&file_name).to_string();
+        let version = pkg["version"].as_str().unwrap_or("").to_string();
+        if seen.insert((name.clone(), version.clone())) {
+            out.push((name, version, node_license(&pkg)));
+        }
+        collect_node(&path.join("node_modules"), seen, out);
+    }
+}
+
+fn python_license(metadata: &str) -> String {
+    let mut license = None;
+    let mut classifiers = Vec::new();
+    for line in metadata.lines() {
+        if line.is_empty() { break; } // headers end at the first blank line
+        if let Some(v) = line.strip_prefix("License-Expression: ") {
+            return v.trim().to_string();
+        } else if let Some(v) = line.strip_prefix("License: ") {
+            let v = v.trim();
+            if !v.is_empty() && v != "UNKNOWN" && v.len() < 80 { license = Some(v.to_string()); }
+        } else if let Some(v) = line.strip_prefix("Classifier: License :: OSI Approved :: ") {
+            classifiers.push(v.trim().to_string());
+        }
+    }
+    license.or_else(|| (!classifiers.is_empty()).then(|| classifiers.join(" OR "))).unwrap_or_else(|| "UNKNOWN".to_string())
+}
+
+fn collect_python(project_dir: &Path, out: &mut Vec<(String, String, String)>) {
+    for venv in [".venv", "venv"] {
+        let Ok(libs) = std::fs::read_dir(project_dir.join(venv).join("lib")) else { continue };
+        for lib in libs.filter_map(|e| e.ok()) {
+            let Ok(entries) = std::fs::read_dir(lib.path().join("site-packages")) else { continue };
+            for entry in entries.filter_map(|e| e.ok()) {
+                let dir_name = entry.file_name().to_string_lossy().into_owned();
+                let Some(stem) = dir_name.strip_suffix(".dist-info") else { continue };
+                let Ok(metadata) = std::fs::read_to_string(entry.path().join("METADATA")) else { continue };
+                let (name, version) = stem.rsplit_once('-').unwrap_or((stem, ""));
+                out.push((name.to_string(), version.to_string(), python_license(&metadata)));
+            }
+        }
+    }
+}
+
+/// True when any alternative in an SPDX expression is disallowed and none is acceptable.
+fn is_disallowed(license: &str, policy: &LicensePolicy) -> bool {
+    let options: Vec<&str> = license
+        .trim_matches(|c| c == '(' || c == ')')
+        .split(" OR ")
+        .map(str::trim)
+        .collect();
+    options.iter().all(|opt| {
+        opt.split(" AND ").any(|part| policy.disallowed.iter().any(|d| d.eq_ignore_ascii_case(part.trim_matches(|c| c == '(' || c == ')'))))
+    })
+}
+
+pub fn collect_license_report(project: &str, project_dir: &Path, policy: &LicensePolicy, generated_at: u64) -> LicenseReport {
+    let mut raw = Vec::new();
+    collect_node(&project_dir.join("node_modules"), &mut HashSet::new(), &mut raw);
+    collect_python(project_dir, &mut raw);
+    raw.sort();
+
+    let mut counts = BTreeMap::new();
+    let packages: Vec<PackageLicense> = raw.into_iter().map(|(name, version, license)| {
+        *counts.entry(license.clone()).or_insert(0) += 1;
+        let disallowed = is_disallowed(&license, policy);
+        PackageLicense { name, version, license, disallowed }
+    }).collect();
+    let disallowed = packages.iter().filter(|p| p.disallowed).cloned().collect();
+    LicenseReport { project: project.to_string(), generated_at, packages, counts, disallowed }
+}
@@ -1,55 +1,403 @@
 use base64::{engine::general_purpose, Engine as _};
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::io::{BufRead, BufReader};
 use std::net::TcpStream;
 use std::path::Path;
 use std::process::Child;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tauri::{
-    menu::{IconMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
+    menu::{IconMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, TrayIconBuilder, TrayIconEvent},
-    Manager,
+    Emitter, Manager,
 };
 use tauri_plugin_positioner::Position;
 use walkdir::WalkDir;
 
+use notifications::{send_notification, CrashInfo, NotificationConfig};
+
+mod brew;
+mod db;
+mod docker;
+mod envfiles;
+mod events;
+mod focus;
+mod github;
+mod ids;
+#[cfg(unix)]
+mod ipc;
+mod licenses;
+#[cfg(unix)]
+mod mcp;
+mod notifications;
+mod platform;
+#[cfg(feature = "wasm-plugins")]
+mod plugins;
+mod preview;
+mod proxy;
+mod readme;
+mod remote;
+mod settings;
+mod telemetry;
+
 const PROJECTS_DIR: &str = "/Users/andrew/Projects";
 
 // ─── Types ────────────────────────────────────────────────────────────────────
 
-type LogBuffer = Arc<Mutex<VecDeque<String>>>;
+type LogBuffer = Arc<Mutex<VecDeque<LogLine>>>;
+type LastOutput = Arc<Mutex<std::time::Instant>>;
+
+/// One captured output line, when it arrived and how severe it looks.
+#[derive(Clone, serde::Serialize)]
+struct LogLine {
+    ts_ms: u64, // unix milliseconds
+    level: LogLevel,
+    text:  String,
+}
+
+/// Ordered, so "at least warn" is `level >= LogLevel::Warn`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum LogLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+/// Words that mark a line as an error or a warning, matched lowercase.
+const ERROR_PATTERNS: &[&str] = &["error", "err!", "failed", "exception", "panic", "fatal", "uncaught", "✘", "✖"];
+const WARN_PATTERNS: &[&str] = &["warn", "deprecat", "⚠"];
+
+/// By what the line says, then where it came from: stderr lines that say
+/// nothing alarming are warnings, since tools print progress there too.
+fn classify_line(text: &str) -> LogLevel {
+    let lower = strip_ansi(text).to_lowercase();
+    // "0 errors", "no errors found" are good news
+    let benign = ["0 error", "no error", "without error"].iter().any(|b| lower.contains(b));
+    if !benign && ERROR_PATTERNS.iter().any(|p| lower.contains(p)) { return LogLevel::Error; }
+    if WARN_PATTERNS.iter().any(|p| lower.contains(p)) { return LogLevel::Warn; }
+    if text.starts_with("[err] ") { LogLevel::Warn } else { LogLevel::Info }
+}
+
+/// The text of each line, for everything that doesn't care when it came.
+fn log_texts(buf: &VecDeque<LogLine>) -> Vec<String> {
+    buf.iter().map(|l| l.text.clone()).collect()
+}
 
 // ─── Project / Server State ───────────────────────────────────────────────────
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
 struct ProjectConfig {
+    #[serde(default)]
+    id: String,              // stable across package renames, see ids.rs
     name: String,
     cwd: String,
     command: String,
     args: Vec<String>,
     port: u16,
     default_port: u16,       // original scanned port before any user override
-    extra_ports: Vec<u16>,   // additional ports from dexhub.ports in package.json
+    extra_ports: Vec<ExtraPort>, // additional ports from dexhub.ports in package.json
     icon_path: Option<String>,
     icon_data: Option<String>,
+    accent_color: Option<String>, // dominant icon colour as #rrggbb, for UI colour-coding
     workspace: String,
+    #[serde(default)]
+    git: Option<GitInfo>,    // None outside a git work tree
+    #[serde(default)]
+    compose_file: Option<String>, // compose.yaml / docker-compose.yml in the project root
+    #[serde(default)]
+    deps: DepsStatus,
+    #[serde(default)]
+    package_manager: Option<String>, // npm / pnpm / yarn / bun; None for plugin-detected projects
+    #[serde(default)]
+    open_path: Option<String>, // dexhub.openPath, e.g. "/admin"; where the browser lands
+}
+
+/// A port a project serves besides its main one, e.g. Storybook on 6006.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct ExtraPort {
+    port:  u16,
+    label: Option<String>,
+}
+
+impl ExtraPort {
+    /// "Storybook (:6006)", or ":6006" without a label.
+    fn display(&self) -> String {
+        match &self.label {
+            Some(label) => format!("{} (:{})", label, self.port),
+            None => format!(":{}", self.port),
+        }
+    }
+}
+
+impl ProjectConfig {
+    /// The package manager to install and run scripts with.
+    fn manager(&self) -> &str {
+        self.package_manager.as_deref().unwrap_or("npm")
+    }
+}
+
+/// A public tunnel (cloudflared / ngrok) exposing one project outside the tailnet.
+struct Tunnel {
+    child:      Child,
+    provider:   String,
+    public_url: Arc<Mutex<Option<String>>>,
+    expires_at: Option<std::time::SystemTime>, // set for time-boxed share links
+    passcode:   bool,
+    policy_file: Option<std::path::PathBuf>,    // ngrok's basic-auth credentials, removed with the tunnel
 }
 
+/// `processes`, `start_times`, `served_ports`, `last_output`, a server's
+/// `log_buffers` entry and the env overrides are keyed by project ID (see
+/// `project_key`), so a running server keeps its state when its display
+/// name changes. Compose, script and preview logs keep `name:suffix` keys.
 struct ServerState {
     processes:     Mutex<HashMap<String, Child>>,
-    start_times:   Mutex<HashMap<String, std::time::Instant>>,
+    starting:      Mutex<HashSet<String>>,      // starts in flight, see claim_start
+    start_times:   Mutex<HashMap<String, u64>>, // unix secs, as recorded in run_history; wall clock so sleep counts
+    served_ports:  Mutex<HashMap<String, u16>>, // announced in startup logs when not the configured port, see note_served_port
     log_buffers:   Mutex<HashMap<String, LogBuffer>>,
     latency_cache: Mutex<HashMap<String, u64>>,
     projects:      Mutex<Vec<ProjectConfig>>,
-    tailscale_host: String,
+    tailscale_host: Mutex<String>,  // refreshed in the background by spawn_tailscale_monitor
+    tailscale_online: AtomicBool,
+    lan_ip:        Mutex<Option<String>>,
+    url_hosts:     Mutex<HashMap<String, String>>, // per-project host choice, see resolve_url_host
     env_overrides: Mutex<HashMap<String, HashMap<String, String>>>,
+    env_profiles:  Mutex<HashMap<String, EnvProfiles>>,
+    launch_commands: Mutex<HashMap<String, LaunchCommand>>, // used instead of the scanned script
+    lifecycle_hooks: Mutex<HashMap<String, LifecycleHooks>>,
+    script_runs:   Mutex<HashMap<String, ScriptRun>>, // latest one-off script run per project
+    folder_stats:  Mutex<HashMap<String, (std::time::Instant, u64, Option<u64>)>>, // cached folder_stats per project
+    tunnels:       Mutex<HashMap<String, Tunnel>>,
+    tray_suspended: AtomicBool, // set while a bulk action runs; one rebuild happens at the end
+    last_output:   Mutex<HashMap<String, LastOutput>>, // time of the most recent log line
+    last_success:  Mutex<HashMap<String, std::time::Instant>>, // last passing health check or proxied request, see note_success
+    cpu_samples:   Mutex<HashMap<String, f32>>,        // latest %CPU of each process tree
+    rss_samples:   Mutex<HashMap<String, u64>>,        // latest resident MB of each process tree
+    resource_limits: Mutex<HashMap<String, ResourceLimits>>,
+    memory_restarts: Mutex<HashSet<String>>, // restarts over the memory ceiling still in flight
+    general:       Mutex<settings::GeneralSettings>, // the rest of settings.json lives in the fields it configures
+    watchdog:      Mutex<WatchdogConfig>,
+    crash_policy:  Mutex<CrashPolicy>,
+    crash_times:   Mutex<HashMap<String, VecDeque<u64>>>, // unix secs of recent crashes
+    flapping:      Mutex<HashSet<String>>,                // cleared only by hand
+    sleep_policy:  Mutex<SleepPolicy>,
+    battery_policy: Mutex<BatteryPolicy>,
+    server_schedules: Mutex<HashMap<String, ServerSchedule>>,
+    low_power:     AtomicBool, // on battery below the threshold, see update_power_state
+    stopped_for_sleep: Mutex<Vec<String>>, // restarted on wake if the policy says so
+    runaway:       Mutex<HashSet<String>>,              // flagged by the CPU watchdog
+    event_log:     Mutex<VecDeque<EventRecord>>,
+    last_health:   Mutex<HashMap<String, bool>>, // for emitting health-changed on transitions
+    health_details: Mutex<HashMap<String, HealthDetails>>, // latest check per project, see get_health_details
+    crashed:       Mutex<HashSet<String>>,       // exited on their own; logs kept until the next start
+    notifications: Mutex<NotificationConfig>,
+    warmup_secs:   Mutex<HashMap<String, u64>>, // per-project health warm-up overrides
+    health_checks: Mutex<HashMap<String, HealthCheck>>,
+    license_reports: Mutex<HashMap<String, licenses::LicenseReport>>,
+    license_policy: Mutex<licenses::LicensePolicy>,
+    editor:        Mutex<EditorConfig>,
+    notification_queue: notifications::NotificationQueue,
+    presentation_mode: AtomicBool, // hide paths/hosts/env values, block destructive actions
+    canary:        Mutex<HashMap<String, CanaryConfig>>,
+    disk_guard:    Mutex<DiskGuardConfig>,
+    brew_services: Mutex<Vec<brew::BrewService>>, // cached; `brew` is slow to answer
+    containers:    Mutex<Vec<docker::Container>>,  // cached `docker ps --all`
+    scheduled_scan: Mutex<ScheduledScanConfig>,
+    focus:         Mutex<focus::FocusConfig>,
+    pull_before_start: Mutex<HashSet<String>>, // git pull --ff-only before each start
+    compose:       Mutex<HashMap<String, Child>>, // attached `docker compose up` per project
+    active_focus:  Mutex<Option<String>>,
+    safe_mode:     bool,
+}
+
+/// Everything `build_tray_menu` needs besides the project list.
+#[derive(Default)]
+struct TrayView {
+    running:        Vec<String>,
+    urls:           HashMap<String, String>, // resolved URL per running project
+    tunnel_urls:    HashMap<String, Option<String>>,
+    runaway:        HashSet<String>,
+    favorites:      Vec<String>,             // pinned at the top, in saved order
+    action_favorites: Vec<(usize, Favorite)>, // index into favorites.json, entry
+    recent:         Vec<String>,             // most recently started first
+    presenting:     bool,
+    healthy:        HashSet<String>,         // running and passing health checks
+    crashed:        HashSet<String>,
+    flapping:       HashSet<String>,
+    low_power_stop: usize,                   // non-favorites offered up in low-power mode
+    compose:        HashSet<String>,         // projects whose compose services are up
+    previews:       HashSet<String>,         // projects with a production preview building or serving
+    script_runs:    HashMap<String, ScriptRun>,
+    brew:           Vec<brew::BrewService>,
+    containers:     Vec<docker::Container>,
+    safe_mode:      bool,
 }
 
 struct TrayHandle(Mutex<Option<tauri::tray::TrayIcon<tauri::Wry>>>);
 
+/// The "Up … · … ms" row of each running server's submenu, kept so the
+/// refresher can update text in place instead of rebuilding the menu.
+#[derive(Default)]
+struct TrayInfoRows(Mutex<HashMap<String, MenuItem<tauri::Wry>>>);
+
+/// How often the tray's uptime/latency rows are refreshed.
+const TRAY_INFO_INTERVAL: Duration = Duration::from_secs(15);
+
 // ─── Tailscale Detection ──────────────────────────────────────────────────────
 
+#[derive(Clone, PartialEq, serde::Serialize)]
+struct TailscaleStatus {
+    host:   String,          // tailnet host when online, else the LAN IP, else localhost
+    online: bool,
+    lan_ip: Option<String>,
+}
+
+/// Resolves the tailnet host and whether the local node is connected.
+/// When the backend isn't in the Running state the host falls back to the
+/// primary LAN IP, and to `localhost` only if there is no network at all.
+fn get_tailscale_status() -> TailscaleStatus {
+    let online = std::process::Command::new("tailscale")
+        .args(["status", "--json"])
+        .output()
+        .ok()
+        .and_then(|o| serde_json::from_slice::<serde_json::Value>(&o.stdout).ok())
+        .map(|v| v["BackendState"].as_str() == Some("Running"))
+        .unwrap_or(false);
+    let lan_ip = get_lan_ip();
+    let host = if online {
+        get_tailscale_host()
+    } else {
+        lan_ip.clone().unwrap_or_else(|| "localhost".to_string())
+    };
+    TailscaleStatus { host, online, lan_ip }
+}
+
+/// IP of the interface that carries the default route. Connecting a UDP
+/// socket sends no packets; it only makes the OS pick a source address.
+fn get_lan_ip() -> Option<String> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    if ip.is_loopback() || ip.is_unspecified() { None } else { Some(ip.to_string()) }
+}
+
+/// An IPv4 address bound to a local interface, as listed by `ifconfig`.
+#[derive(Clone, serde::Serialize)]
+struct NetworkAddress {
+    interface: String,
+    ip:        String,
+}
+
+fn list_local_addresses() -> Vec<NetworkAddress> {
+    let output = match std::process::Command::new("ifconfig").output() {
+        Ok(o) => o,
+        Err(_) => return Vec::new(),
+    };
+    let mut addresses = Vec::new();
+    let mut interface = String::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if !line.starts_with(char::is_whitespace) {
+            interface = line.split(':').next().unwrap_or("").to_string();
+        } else if let Some(rest) = line.trim_start().strip_prefix("inet ") {
+            let ip = rest.split_whitespace().next().unwrap_or("").trim_start_matches("addr:");
+            if !ip.is_empty() && !ip.starts_with("127.") {
+                addresses.push(NetworkAddress { interface: interface.clone(), ip: ip.to_string() });
+            }
+        }
+    }
+    addresses
+}
+
+/// Maps a project's stored host choice to a concrete host:
+/// `auto` (default) follows `tailscale_host`, `lan` the primary LAN IP,
+/// `localhost` is literal, and anything else is used as a custom hostname/IP.
+fn resolve_url_host(state: &ServerState, name: &str) -> String {
+    if state.presentation_mode.load(Ordering::SeqCst) { return "localhost".to_string(); }
+    let choice = state.url_hosts.lock().unwrap().get(name).cloned();
+    match choice.as_deref() {
+        None | Some("auto") => state.tailscale_host.lock().unwrap().clone(),
+        Some("lan") => state.lan_ip.lock().unwrap().clone().unwrap_or_else(|| "localhost".to_string()),
+        Some(custom) => custom.to_string(),
+    }
+}
+
+/// The port a running server actually serves on: the one it announced at
+/// startup if the configured one was taken, else the configured one.
+fn effective_port(state: &ServerState, project: &ProjectConfig) -> u16 {
+    state.served_ports.lock().unwrap().get(&project.id).copied().unwrap_or(project.port)
+}
+
+/// The ID-keyed state's key for the project called `name`. Names change
+/// (a second `web` turns the first into `web (client/web)`), IDs don't;
+/// commands still take names. Unknown names are their own key.
+fn project_key(state: &ServerState, name: &str) -> String {
+    state.projects.lock().unwrap().iter().find(|p| p.name == name).map(|p| p.id.clone())
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// The current name of the project keyed `key`, for display.
+fn project_name(state: &ServerState, key: &str) -> String {
+    state.projects.lock().unwrap().iter().find(|p| p.id == key).map(|p| p.name.clone())
+        .unwrap_or_else(|| key.to_string())
+}
+
+fn is_running(state: &ServerState, name: &str) -> bool {
+    let key = project_key(state, name);
+    state.processes.lock().unwrap().contains_key(&key)
+}
+
+/// Names of the projects with a live server process.
+fn running_names(state: &ServerState) -> Vec<String> {
+    let keys: Vec<String> = state.processes.lock().unwrap().keys().cloned().collect();
+    keys.iter().map(|k| project_name(state, k)).collect()
+}
+
+fn project_url(state: &ServerState, project: &ProjectConfig) -> String {
+    format!("http://{}:{}", resolve_url_host(state, &project.name), effective_port(state, project))
+}
+
+/// An extra port's URL, on the same host as the project's main one.
+fn extra_port_url(state: &ServerState, project: &ProjectConfig, port: u16) -> String {
+    format!("http://{}:{}", resolve_url_host(state, &project.name), port)
+}
+
+/// `project_url` plus the project's `openPath`: where the browser goes.
+fn browser_url(state: &ServerState, project: &ProjectConfig) -> String {
+    format!("{}{}", project_url(state, project), project.open_path.as_deref().unwrap_or(""))
+}
+
+/// Re-reads the Tailscale state, emitting `tailscale-changed` if it moved.
+fn refresh_tailscale(app: &tauri::AppHandle) {
+    let status = get_tailscale_status();
+    let state = app.state::<ServerState>();
+    let changed = {
+        let mut host = state.tailscale_host.lock().unwrap();
+        let was_online = state.tailscale_online.swap(status.online, Ordering::SeqCst);
+        let mut lan_ip = state.lan_ip.lock().unwrap();
+        let changed = *host != status.host || was_online != status.online || *lan_ip != status.lan_ip;
+        *host = status.host.clone();
+        *lan_ip = status.lan_ip.clone();
+        changed
+    };
+    if changed {
+        let _ = app.emit("tailscale-changed", &status);
+        rebuild_tray(app);
+    }
+}
+
+/// Polls Tailscale so URLs follow the tailnet when it connects, disconnects,
+/// or the machine is renamed. Emits `tailscale-changed` on every transition.
+fn spawn_tailscale_monitor(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        let interval = app.state::<ServerState>().general.lock().unwrap().tailscale_interval();
+        std::thread::sleep(interval);
+        refresh_tailscale(&app);
+    });
+}
+
 fn get_tailscale_host() -> String {
     if let Ok(output) = std::process::Command::new("tailscale")
         .args(["status", "--json"])
@@ -78,6 +426,58 @@ fn get_tailscale_host() -> String {
     "localhost".to_string()
 }
 
+/// A node on the tailnet as reported by `tailscale status --json`.
+#[derive(Clone, serde::Serialize)]
+struct TailnetDevice {
+    host_name: String,
+    dns_name:  String,
+    os:        String,
+    ips:       Vec<String>,
+    online:    bool,
+    is_self:   bool,
+    last_seen: Option<String>,
+}
+
+fn parse_tailnet_device(val: &serde_json::Value, is_self: bool) -> Option<TailnetDevice> {
+    let host_name = val["HostName"].as_str()?.to_string();
+    let ips = val["TailscaleIPs"]
+        .as_array()
+        .map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+    Some(TailnetDevice {
+        host_name,
+        dns_name:  val["DNSName"].as_str().unwrap_or("").trim_end_matches('.').to_string(),
+        os:        val["OS"].as_str().unwrap_or("").to_string(),
+        ips,
+        online:    is_self || val["Online"].as_bool().unwrap_or(false),
+        is_self,
+        last_seen: val["LastSeen"].as_str().map(String::from),
+    })
+}
+
+fn get_tailnet_devices() -> Vec<TailnetDevice> {
+    let output = match std::process::Command::new("tailscale").args(["status", "--json"]).output() {
+        Ok(o) => o,
+        Err(_) => return Vec::new(),
+    };
+    let val: serde_json::Value = match serde_json::from_slice(&output.stdout) {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+    let mut devices: Vec<TailnetDevice> = Vec::new();
+    if let Some(me) = parse_tailnet_device(&val["Self"], true) { devices.push(me); }
+    if let Some(peers) = val["Peer"].as_object() {
+        devices.extend(peers.values().filter_map(|p| parse_tailnet_device(p, false)));
+    }
+    // Self first, then online peers, then alphabetical
+    devices.sort_by(|a, b| {
+        b.is_self.cmp(&a.is_self)
+            .then(b.online.cmp(&a.online))
+            .then(a.host_name.to_lowercase().cmp(&b.host_name.to_lowercase()))
+    });
+    devices
+}
+
 // ─── Port Extraction ──────────────────────────────────────────────────────────
 
 fn extract_port_after(text: &str, key: &str) -> Option<u16> {
@@ -91,6 +491,101 @@ fn extract_port_after(text: &str, key: &str) -> Option<u16> {
     after[..end].parse().ok()
 }
 
+/// Splits a shell line into words, honouring single and double quotes and
+/// treating `&&`, `||`, `;` and `|` as word boundaries of their own.
+fn shell_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '\\') => { if let Some(n) = chars.next() { word.push(n); } }
+            (None, c) if c.is_whitespace() => {
+                if !word.is_empty() { words.push(std::mem::take(&mut word)); }
+            }
+            (None, ';' | '&' | '|') => {
+                if !word.is_empty() { words.push(std::mem::take(&mut word)); }
+                let mut op = c.to_string();
+                if chars.peek() == Some(&c) { op.push(chars.next().unwrap_or(c)); }
+                words.push(op);
+            }
+            (None, c) => word.push(c),
+        }
+    }
+    if !word.is_empty() { words.push(word); }
+    words
+}
+
+/// The port a dev script sets: `--port 3000`, `--port=3000`, `-p 3000`,
+/// `-p3000`, or `PORT=3000` in front of a command (also via `cross-env` or
+/// `export`). Quoted sub-commands, as `concurrently` takes them, are
+/// searched too.
+fn script_port(script: &str) -> Option<u16> {
+    let words = shell_words(script);
+    let mut iter = words.iter().peekable();
+    while let Some(word) = iter.next() {
+        let numeric = |s: &str| s.parse::<u16>().ok().filter(|p| *p > 0);
+        let found = if let Some(value) = word.strip_prefix("--port=").or_else(|| word.strip_prefix("PORT=")) {
+            numeric(value)
+        } else if word == "--port" || word == "-p" {
+            iter.peek().and_then(|next| numeric(next))
+        } else if let Some(value) = word.strip_prefix("-p").filter(|v| !v.is_empty() && !v.starts_with('-')) {
+            numeric(value)
+        } else if word.contains(char::is_whitespace) {
+            script_port(word)
+        } else {
+            None
+        };
+        if found.is_some() { return found; }
+    }
+    None
+}
+
+/// Where the dev tools listen when nothing sets a port.
+const TOOL_DEFAULT_PORTS: &[(&str, u16)] = &[
+    ("next", 3000),
+    ("nuxt", 3000),
+    ("nuxi", 3000),
+    ("react-scripts", 3000),
+    ("remix", 3000),
+    ("astro", 4321),
+    ("ng", 4200),
+    ("gatsby", 8000),
+    ("vite", 5173),
+];
+
+fn tool_default_port(script: &str) -> Option<u16> {
+    shell_words(script).iter().find_map(|w| {
+        if w.contains(char::is_whitespace) { return tool_default_port(w); }
+        TOOL_DEFAULT_PORTS.iter().find(|(tool, _)| tool == w).map(|(_, p)| *p)
+    })
+}
+
+/// The flags that make each dev tool bind exactly the given port; tools not
+/// listed (react-scripts, remix, plain node servers) only get `PORT`.
+/// `--strictPort` stops Vite from quietly moving to the next free port.
+const TOOL_PORT_FLAGS: &[(&str, &[&str])] = &[
+    ("vite", &["--port", "{}", "--strictPort"]),
+    ("next", &["-p", "{}"]),
+    ("nuxt", &["--port", "{}"]),
+    ("nuxi", &["--port", "{}"]),
+    ("astro", &["--port", "{}"]),
+    ("ng", &["--port", "{}"]),
+    ("gatsby", &["-p", "{}"]),
+];
+
+fn tool_port_flags(script: &str, port: u16) -> Option<Vec<String>> {
+    shell_words(script).iter().find_map(|w| {
+        if w.contains(char::is_whitespace) { return tool_port_flags(w, port); }
+        TOOL_PORT_FLAGS.iter().find(|(tool, _)| tool == w)
+            .map(|(_, flags)| flags.iter().map(|f| f.replace("{}", &port.to_string())).collect())
+    })
+}
+
 fn extract_port(project_dir: &Path) -> u16 {
     for cfg in &["vite.config.ts", "vite.config.js", "vite.config.mts"] {
         if let Ok(content) = std::fs::read_to_string(project_dir.join(cfg)) {
@@ -102,7 +597,7 @@ fn extract_port(project_dir: &Path) -> u16 {
     if let Ok(content) = std::fs::read_to_string(project_dir.join("package.json")) {
         if let Ok(val) = serde_json::from_str::<serde_json::Value>(&content) {
             if let Some(script) = val["scripts"]["dev"].as_str() {
-                if let Some(p) = extract_port_after(script, "--port") {
+                if let Some(p) = script_port(script).or_else(|| tool_default_port(script)) {
                     return p;
                 }
             }
@@ -113,8 +608,9 @@ fn extract_port(project_dir: &Path) -> u16 {
 
 // ─── Workspace Extraction ─────────────────────────────────────────────────────
 
-fn extract_workspace(cwd: &str) -> String {
-    let base = PROJECTS_DIR.trim_end_matches('/');
+fn extract_workspace(base_dir: &Path, cwd: &str) -> String {
+    let base = base_dir.to_string_lossy();
+    let base = base.trim_end_matches('/');
     let rest = cwd
         .strip_prefix(base)
         .unwrap_or("")
@@ -127,6 +623,268 @@ fn extract_workspace(cwd: &str) -> String {
     }
 }
 
+// ─── Git Metadata ─────────────────────────────────────────────────────────────
+
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct GitInfo {
+    branch:      Option<String>, // None when HEAD is detached
+    dirty:       bool,
+    ahead:       u32,
+    behind:      u32,            // both 0 without an upstream
+    last_commit: Option<String>, // "<short sha> <subject>"
+}
+
+fn git_output(cwd: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new("git").arg("-C").arg(cwd).args(args).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Branch, dirtiness and ahead/behind from one `git status --porcelain=v2 --branch`.
+fn read_git_info(cwd: &str) -> Option<GitInfo> {
+    let status = git_output(cwd, &["status", "--porcelain=v2", "--branch"])?;
+    let mut info = GitInfo { branch: None, dirty: false, ahead: 0, behind: 0, last_commit: None };
+    for line in status.lines() {
+        if let Some(head) = line.strip_prefix("# branch.head ") {
+            if head != "(detached)" { info.branch = Some(head.to_string()); }
+        } else if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            for part in ab.split_whitespace() {
+                if let Some(n) = part.strip_prefix('+') { info.ahead = n.parse().unwrap_or(0); }
+                if let Some(n) = part.strip_prefix('-') { info.behind = n.parse().unwrap_or(0); }
+            }
+        } else if !line.starts_with('#') && !line.is_empty() {
+            info.dirty = true;
+        }
+    }
+    info.last_commit = git_output(cwd, &["log", "-1", "--format=%h %s"])
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    Some(info)
+}
+
+/// Reads git metadata for every project in parallel; a few dozen
+/// sequential `git status` calls would noticeably slow down a scan.
+fn fill_git_info(projects: &mut [ProjectConfig]) {
+    std::thread::scope(|scope| {
+        for project in projects.iter_mut() {
+            scope.spawn(move || { project.git = read_git_info(&project.cwd); });
+        }
+    });
+}
+
+/// Re-reads one project's git metadata into state and returns it.
+fn refresh_project_git(app: &tauri::AppHandle, name: &str) -> Result<Option<GitInfo>, String> {
+    let state = app.state::<ServerState>();
+    let cwd = state.projects.lock().unwrap().iter().find(|p| p.name == name).map(|p| p.cwd.clone())
+        .ok_or_else(|| format!("Project '{}' not found", name))?;
+    let git = read_git_info(&cwd);
+    if let Some(p) = state.projects.lock().unwrap().iter_mut().find(|p| p.name == name) { p.git = git.clone(); }
+    Ok(git)
+}
+
+fn pull_before_start_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("pull_before_start.json")
+}
+
+fn load_pull_before_start(app_data_dir: &Path) -> HashSet<String> {
+    std::fs::read_to_string(pull_before_start_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_pull_before_start_to_disk(app_data_dir: &Path, names: &HashSet<String>) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    let mut sorted: Vec<&String> = names.iter().collect();
+    sorted.sort();
+    if let Ok(json) = serde_json::to_string_pretty(&sorted) {
+        let _ = std::fs::write(pull_before_start_path(app_data_dir), json);
+    }
+}
+
+/// Appends a line to a server's log ring buffer, as the output readers do.
+fn push_log_line(app: &tauri::AppHandle, name: &str, buf: &LogBuffer, line: String) {
+    remote::broadcast_log(app, name, &line);
+    let mut b = buf.lock().unwrap();
+    if b.len() >= 500 { b.pop_front(); }
+    b.push_back(LogLine { ts_ms: unix_now_ms(), level: classify_line(&line), text: line });
+}
+
+/// The port in a dev server's "Local: http://localhost:5174/" banner line
+/// (Vite, Next and CRA all print one). Only a line labelled `Local:` counts,
+/// so app logs that merely mention a local URL are ignored.
+fn announced_port(line: &str) -> Option<u16> {
+    let line = strip_ansi(line);
+    // Skip the bullet before the label: Vite's "➜", Next's "-"
+    let line = line.trim().trim_start_matches(|c: char| !c.is_alphanumeric());
+    if !line.get(..6)?.eq_ignore_ascii_case("local:") { return None; }
+    let url = line[6..].trim();
+    let rest = url.strip_prefix("http://").or_else(|| url.strip_prefix("https://"))?;
+    let host_port = rest.split(['/', ' ']).next()?;
+    let (host, port) = host_port.rsplit_once(':')?;
+    matches!(host, "localhost" | "127.0.0.1" | "0.0.0.0" | "[::1]" | "[::]").then_some(())?;
+    port.parse().ok()
+}
+
+/// Records the port a server says it's on, so its URL follows a dev server
+/// that moved off a taken port instead of pointing at a dead one. True once
+/// a banner was accepted; later lines of the run needn't be parsed.
+fn note_served_port(app: &tauri::AppHandle, key: &str, line: &str) -> bool {
+    let Some(port) = announced_port(line) else { return false };
+    let state = app.state::<ServerState>();
+    let Some((name, configured)) = state.projects.lock().unwrap().iter().find(|p| p.id == key).map(|p| (p.name.clone(), p.port)) else { return false };
+    let previous = {
+        let mut served = state.served_ports.lock().unwrap();
+        if port == configured { served.remove(key) } else { served.insert(key.to_string(), port) }
+    };
+    if previous.unwrap_or(configured) != port {
+        record_event(app, "port-changed", Some(&name), serde_json::json!({ "configured": configured, "port": port }));
+        rebuild_tray(app);
+    }
+    true
+}
+
+/// Copies a child's stdout and stderr into `buf` as `<prefix> …` lines until
+/// it exits. Both pipes are drained so neither fills up and blocks it.
+fn stream_to_log(app: &tauri::AppHandle, name: &str, child: &mut Child, buf: &LogBuffer, prefix: &str) -> Result<std::process::ExitStatus, String> {
+    let stderr_reader = child.stderr.take().map(|stderr| {
+        let (app, name, buf, prefix) = (app.clone(), name.to_string(), Arc::clone(buf), prefix.to_string());
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                push_log_line(&app, &name, &buf, format!("{} {}", prefix, line));
+            }
+        })
+    });
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            push_log_line(app, name, buf, format!("{} {}", prefix, line));
+        }
+    }
+    if let Some(handle) = stderr_reader { let _ = handle.join(); }
+    child.wait().map_err(|e| e.to_string())
+}
+
+/// Runs `git <args>` in `cwd`, streaming its output into `buf` as `[git] …`
+/// lines while it runs. Prompts are disabled so a missing credential fails
+/// instead of hanging.
+fn run_git_streamed(app: &tauri::AppHandle, name: &str, cwd: &str, args: &[&str], buf: &LogBuffer) -> Result<(), String> {
+    push_log_line(app, name, buf, format!("[git] $ git {}", args.join(" ")));
+    let mut child = std::process::Command::new("git")
+        .arg("-C").arg(cwd)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Couldn't run git: {}", e))?;
+
+    // git reports progress on stderr
+    let status = stream_to_log(app, name, &mut child, buf, "[git]")?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("git {} exited with {}", args[0], status.code().map_or("a signal".to_string(), |c| c.to_string())))
+    }
+}
+
+/// Runs a git command for a project into its current log buffer (created if
+/// the project hasn't run yet), then refreshes its git metadata.
+fn run_git_action(app: &tauri::AppHandle, name: &str, args: &[&str]) -> Result<Option<GitInfo>, String> {
+    let state = app.state::<ServerState>();
+    let cwd = state.projects.lock().unwrap().iter().find(|p| p.name == name).map(|p| p.cwd.clone())
+        .ok_or_else(|| format!("Project '{}' not found", name))?;
+    let key = project_key(&state, name);
+    let buf = Arc::clone(
+        state.log_buffers.lock().unwrap()
+            .entry(key)
+            .or_insert_with(|| Arc::new(Mutex::new(VecDeque::with_capacity(500)))),
+    );
+    let result = run_git_streamed(app, name, &cwd, args, &buf);
+    let git = refresh_project_git(app, name)?;
+    record_event(app, "git", Some(name), serde_json::json!({ "command": args[0], "ok": result.is_ok() }));
+    result.map(|_| git)
+}
+
+// ─── Lifecycle Hooks ──────────────────────────────────────────────────────────
+
+/// Shell lines run around a project's server, e.g. `docker compose up -d db`
+/// before it starts and `docker compose down` after it's stopped. Output
+/// goes to the server's log as `[hook] …` lines. A failing pre-start line
+/// aborts the start; post-stop lines all run regardless.
+#[derive(Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct LifecycleHooks {
+    pre_start: Vec<String>,
+    post_stop: Vec<String>,
+}
+
+fn lifecycle_hooks_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("lifecycle_hooks.json")
+}
+
+fn load_lifecycle_hooks(app_data_dir: &Path) -> HashMap<String, LifecycleHooks> {
+    std::fs::read_to_string(lifecycle_hooks_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_lifecycle_hooks_to_disk(app_data_dir: &Path, hooks: &HashMap<String, LifecycleHooks>) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(hooks) {
+        let _ = std::fs::write(lifecycle_hooks_path(app_data_dir), json);
+    }
+}
+
+/// Runs one hook line through the user's shell in `cwd`, into `buf`.
+fn run_lifecycle_hook(
+    app: &tauri::AppHandle,
+    name: &str,
+    cwd: &str,
+    line: &str,
+    env: &HashMap<String, String>,
+    buf: &LogBuffer,
+) -> Result<(), String> {
+    push_log_line(app, name, buf, format!("[hook] $ {}", line));
+    let shell = app.state::<ServerState>().general.lock().unwrap().shell.clone();
+    let mut child = std::process::Command::new(shell)
+        .args(["-lc", line])
+        .current_dir(cwd)
+        .envs(env)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Couldn't run hook `{}`: {}", line, e))?;
+    let status = stream_to_log(app, name, &mut child, buf, "[hook]")?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("Hook `{}` exited with {}", line, status.code().map_or("a signal".to_string(), |c| c.to_string())))
+    }
+}
+
+/// Runs a stopped project's post-stop lines into its kept log buffer.
+fn run_post_stop_hooks(app: &tauri::AppHandle, name: &str) {
+    let state = app.state::<ServerState>();
+    let Some(hooks) = state.lifecycle_hooks.lock().unwrap().get(name).map(|h| h.post_stop.clone()) else { return };
+    if hooks.is_empty() { return; }
+    let Some(cwd) = state.projects.lock().unwrap().iter().find(|p| p.name == name).map(|p| p.cwd.clone()) else { return };
+    let key = project_key(&state, name);
+    let buf = Arc::clone(
+        state.log_buffers.lock().unwrap()
+            .entry(key.clone())
+            .or_insert_with(|| Arc::new(Mutex::new(VecDeque::with_capacity(500)))),
+    );
+    let env = state.env_overrides.lock().unwrap().get(&key).cloned().unwrap_or_default();
+    for line in &hooks {
+        if let Err(e) = run_lifecycle_hook(app, name, &cwd, line, &env, &buf) {
+            push_log_line(app, name, &buf, format!("[hook] {}", e));
+            record_event(app, "hook-failed", Some(name), serde_json::json!({ "stage": "post-stop", "hook": line, "error": e }));
+        }
+    }
+}
+
 // ─── Icon Helpers ─────────────────────────────────────────────────────────────
 
 fn load_icon_image(path: &str) -> Option<tauri::image::Image<'static>> {
@@ -143,6 +901,30 @@ fn icon_to_base64(path: &str) -> Option<String> {
     ))
 }
 
+/// Picks the dominant colour of an icon as a `#rrggbb` string.
+///
+/// Pixels are bucketed into a coarse 4-bit-per-channel histogram after skipping
+/// transparent, near-white, near-black and greyscale pixels (backgrounds and
+/// outlines), and the average of the most populated bucket is returned.
+fn icon_accent_color(path: &str) -> Option<String> {
+    let img = image::open(path).ok()?.thumbnail(32, 32).to_rgba8();
+    let mut buckets: HashMap<(u8, u8, u8), (u32, u32, u32, u32)> = HashMap::new();
+    for px in img.pixels() {
+        let [r, g, b, a] = px.0;
+        if a < 128 { continue; }
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        if max < 32 || min > 224 || max - min < 24 { continue; }
+        let entry = buckets.entry((r >> 4, g >> 4, b >> 4)).or_insert((0, 0, 0, 0));
+        entry.0 += r as u32;
+        entry.1 += g as u32;
+        entry.2 += b as u32;
+        entry.3 += 1;
+    }
+    let (r, g, b, n) = buckets.into_values().max_by_key(|&(_, _, _, n)| n)?;
+    Some(format!("#{:02x}{:02x}{:02x}", r / n, g / n, b / n))
+}
+
 fn find_icon(project_dir: &Path) -> Option<String> {
     let candidates = [
         "public/icon.png",
@@ -197,492 +979,5299 @@ fn save_port_overrides(app_data_dir: &Path, overrides: &HashMap<String, u16>) {
     }
 }
 
-// ─── Favorites Helpers ────────────────────────────────────────────────────────
+// ─── Port Registry ────────────────────────────────────────────────────────────
 
-fn favorites_path(app_data_dir: &Path) -> std::path::PathBuf {
-    app_data_dir.join("favorites.json")
+/// Every port a project has been seen with, plus any port DexHub assigned it
+/// to resolve a collision. Assignments stay stable across scans.
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct PortHistory {
+    declared:   BTreeSet<u16>,
+    overridden: BTreeSet<u16>,
+    observed:   BTreeSet<u16>,
+    assigned:   Option<u16>,
 }
 
-fn load_favorites_from_disk(app_data_dir: &Path) -> Vec<String> {
-    let path = favorites_path(app_data_dir);
-    if let Ok(content) = std::fs::read_to_string(&path) {
-        if let Ok(list) = serde_json::from_str::<Vec<String>>(&content) {
-            return list;
-        }
-    }
-    Vec::new()
+#[derive(Clone, serde::Serialize)]
+struct PortConflict {
+    port:     u16,
+    projects: Vec<String>,
+    kept_by:  String,                  // project that keeps the declared port
+    assigned: HashMap<String, u16>,    // everyone else → their new port
 }
 
-fn save_favorites_to_disk(app_data_dir: &Path, names: &[String]) {
+fn port_registry_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("port_registry.json")
+}
+
+fn load_port_registry(app_data_dir: &Path) -> BTreeMap<String, PortHistory> {
+    std::fs::read_to_string(port_registry_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_port_registry_to_disk(app_data_dir: &Path, registry: &BTreeMap<String, PortHistory>) {
     let _ = std::fs::create_dir_all(app_data_dir);
-    if let Ok(json) = serde_json::to_string_pretty(names) {
-        let _ = std::fs::write(favorites_path(app_data_dir), json);
+    if let Ok(json) = serde_json::to_string_pretty(registry) {
+        let _ = std::fs::write(port_registry_path(app_data_dir), json);
     }
 }
 
-// ─── Env Override Helpers ─────────────────────────────────────────────────────
+fn is_port_free(port: u16) -> bool {
+    std::net::TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
 
-fn env_overrides_path(app_data_dir: &Path) -> std::path::PathBuf {
-    app_data_dir.join("env_overrides.json")
+fn record_observed_port(app_data_dir: &Path, name: &str, port: u16) {
+    let mut registry = load_port_registry(app_data_dir);
+    if registry.entry(name.to_string()).or_default().observed.insert(port) {
+        save_port_registry_to_disk(app_data_dir, &registry);
+    }
 }
 
-fn load_env_overrides(app_data_dir: &Path) -> HashMap<String, HashMap<String, String>> {
-    let path = env_overrides_path(app_data_dir);
-    if let Ok(content) = std::fs::read_to_string(&path) {
-        if let Ok(map) = serde_json::from_str(&content) {
-            return map;
+/// Most settings are keyed by project name, so two repos with the same
+/// package.json `name` would share them (and look alike in every list).
+/// Each of them gets the shortest tail of its path that tells them apart:
+/// `web (client/web)`, `web (admin/web)`.
+fn dedupe_names(projects: &mut [ProjectConfig]) {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for p in projects.iter() { *counts.entry(p.name.clone()).or_default() += 1; }
+    let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, p) in projects.iter().enumerate() {
+        if counts[&p.name] > 1 { groups.entry(p.name.clone()).or_default().push(i); }
+    }
+    let tail = |cwd: &str, n: usize| -> String {
+        let parts: Vec<&str> = cwd.trim_end_matches('/').split('/').filter(|s| !s.is_empty()).collect();
+        parts[parts.len().saturating_sub(n)..].join("/")
+    };
+    for (name, members) in groups {
+        let longest = members.iter().map(|&i| projects[i].cwd.split('/').count()).max().unwrap_or(1);
+        let n = (1..=longest)
+            .find(|&n| members.iter().map(|&i| tail(&projects[i].cwd, n)).collect::<HashSet<_>>().len() == members.len())
+            .unwrap_or(longest);
+        for &i in &members {
+            projects[i].name = format!("{} ({})", name, tail(&projects[i].cwd, n));
         }
     }
-    HashMap::new()
 }
 
-fn save_env_overrides_to_disk(
+/// Records declared/overridden ports, then gives every project that shares a
+/// declared port with another (and has no manual override) a port nobody has
+/// ever used. The project that has actually run on the port keeps it.
+fn resolve_port_conflicts(
+    projects: &mut [ProjectConfig],
+    port_overrides: &HashMap<String, u16>,
     app_data_dir: &Path,
-    overrides: &HashMap<String, HashMap<String, String>>,
 ) {
-    let _ = std::fs::create_dir_all(app_data_dir);
-    if let Ok(json) = serde_json::to_string_pretty(overrides) {
-        let _ = std::fs::write(env_overrides_path(app_data_dir), json);
+    let mut registry = load_port_registry(app_data_dir);
+    for p in projects.iter() {
+        let entry = registry.entry(p.name.clone()).or_default();
+        entry.declared.insert(p.default_port);
+        if let Some(&o) = port_overrides.get(&p.id) { entry.overridden.insert(o); }
     }
-}
-
-// ─── Crash Notification ───────────────────────────────────────────────────────
-
-fn notify_crash(name: &str) {
-    let script = format!(
-        "display notification \"Server '{}' stopped unexpectedly.\" \
-         with title \"DexHub\" sound name \"Basso\"",
-        name
-    );
-    let _ = std::process::Command::new("osascript")
-        .args(["-e", &script])
-        .spawn();
-}
 
-// ─── Project Scanner ──────────────────────────────────────────────────────────
+    let mut by_port: BTreeMap<u16, Vec<usize>> = BTreeMap::new();
+    for (i, p) in projects.iter().enumerate() {
+        if !port_overrides.contains_key(&p.id) {
+            by_port.entry(p.default_port).or_default().push(i);
+        }
+    }
 
-fn scan_projects(base_dir: &Path, port_overrides: &HashMap<String, u16>) -> Vec<ProjectConfig> {
-    let mut projects = Vec::new();
+    let mut taken: HashSet<u16> = registry.values()
+        .flat_map(|h| h.declared.iter().chain(&h.overridden).chain(&h.observed).chain(&h.assigned).copied())
+        .chain(projects.iter().map(|p| p.port))
+        .collect();
+    let mut still_assigned = HashSet::new();
 
-    let walker = WalkDir::new(base_dir)
-        .min_depth(1)
-        .max_depth(4)
-        .follow_links(false)
-        .into_iter()
-        .filter_entry(|e| {
-            let s = e.path().to_string_lossy();
-            !s.contains("node_modules")
-                && !s.contains("/.git")
-                && !s.contains("/.cache")
-                && !s.contains("/.claude")
-                && !s.contains("/dist/")
-                && !s.contains("/build/")
-                && !s.contains("/.next")
-                && !s.contains("/target/")
+    for (port, members) in by_port.into_iter().filter(|(_, m)| m.len() > 1) {
+        let mut members = members;
+        members.sort_by_key(|&i| {
+            let ran_here = registry.get(&projects[i].name).is_some_and(|h| h.observed.contains(&port));
+            (!ran_here, projects[i].name.clone())
         });
+        for &i in &members[1..] {
+            let entry = registry.entry(projects[i].name.clone()).or_default();
+            let new_port = match entry.assigned {
+                Some(a) => a,
+                None => {
+                    let mut candidate = port.saturating_add(1);
+                    while candidate < u16::MAX && (taken.contains(&candidate) || !is_port_free(candidate)) {
+                        candidate += 1;
+                    }
+                    taken.insert(candidate);
+                    entry.assigned = Some(candidate);
+                    candidate
+                }
+            };
+            projects[i].port = new_port;
+            still_assigned.insert(projects[i].name.clone());
+        }
+    }
 
-    for entry in walker.filter_map(|e| e.ok()) {
-        if entry.file_name() != "package.json" { continue; }
+    // A conflict that went away (project removed, port changed) releases its assignment
+    for (name, history) in registry.iter_mut() {
+        if !still_assigned.contains(name) { history.assigned = None; }
+    }
+    save_port_registry_to_disk(app_data_dir, &registry);
+}
 
-        let pkg_path = entry.path();
-        let project_dir = match pkg_path.parent() { Some(d) => d, None => continue };
+// ─── Favorites Helpers ────────────────────────────────────────────────────────
 
-        // Skip Tauri apps — launching them would conflict with the host
-        if project_dir.join("src-tauri").join("tauri.conf.json").exists() { continue; }
+fn favorites_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("favorites.json")
+}
 
-        let content = match std::fs::read_to_string(pkg_path) { Ok(c) => c, Err(_) => continue };
-        let val: serde_json::Value = match serde_json::from_str(&content) { Ok(v) => v, Err(_) => continue };
+/// One pinned entry: a whole project (`action: None`) or a specific,
+/// possibly parameterised, action on it ("web → Start with mock env").
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Favorite {
+    project: String,
+    #[serde(default)]
+    action:  Option<FavoriteAction>,
+    #[serde(default)]
+    label:   Option<String>, // shown instead of the generated "project → action"
+}
 
-        let dev_script = match val["scripts"]["dev"].as_str() {
-            Some(s) if !s.trim().is_empty() => s.to_string(),
-            _ => continue,
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum FavoriteAction {
+    Start {
+        #[serde(default)]
+        env: HashMap<String, String>, // applied on top of the project's env overrides
+    },
+    Restart,
+    RestartClean,
+    OpenBrowser,
+    OpenLogs,
+    OpenTerminal,
+    CopyUrl,
+}
+
+impl Favorite {
+    fn display_label(&self) -> String {
+        if let Some(label) = &self.label { return label.clone(); }
+        let action = match &self.action {
+            None => return self.project.clone(),
+            Some(FavoriteAction::Start { env }) if env.is_empty() => "Start".to_string(),
+            Some(FavoriteAction::Start { env }) => {
+                let mut keys: Vec<&str> = env.keys().map(String::as_str).collect();
+                keys.sort();
+                format!("Start with {}", keys.join(", "))
+            }
+            Some(FavoriteAction::Restart) => "Restart".to_string(),
+            Some(FavoriteAction::RestartClean) => "Restart with Cleared Cache".to_string(),
+            Some(FavoriteAction::OpenBrowser) => "Open in Browser".to_string(),
+            Some(FavoriteAction::OpenLogs) => "View Logs".to_string(),
+            Some(FavoriteAction::OpenTerminal) => "Open Terminal".to_string(),
+            Some(FavoriteAction::CopyUrl) => "Copy URL".to_string(),
         };
+        format!("{} → {}", self.project, action)
+    }
+}
 
-        let name = val["name"]
-            .as_str()
-            .unwrap_or_else(|| {
-                project_dir.file_name().and_then(|n| n.to_str()).unwrap_or("unknown")
-            })
-            .to_string();
-        if name.trim().is_empty() { continue; }
+/// Reads favorites.json, upgrading the original plain list of project names.
+fn load_favorite_entries(app_data_dir: &Path) -> Vec<Favorite> {
+    let Ok(content) = std::fs::read_to_string(favorites_path(app_data_dir)) else { return Vec::new() };
+    if let Ok(entries) = serde_json::from_str::<Vec<Favorite>>(&content) {
+        return entries;
+    }
+    match serde_json::from_str::<Vec<String>>(&content) {
+        Ok(names) => {
+            let entries: Vec<Favorite> = names.into_iter()
+                .map(|project| Favorite { project, action: None, label: None })
+                .collect();
+            save_favorite_entries_to_disk(app_data_dir, &entries);
+            entries
+        }
+        Err(_) => Vec::new(),
+    }
+}
 
-        let (command, args) = if dev_script.trim_start().starts_with("pnpm") {
-            let rest = dev_script.trim_start_matches("pnpm").trim().to_string();
-            let pnpm_args: Vec<String> = if rest.is_empty() {
-                vec!["dev".to_string()]
-            } else {
-                rest.split_whitespace().map(|s| s.to_string()).collect()
-            };
-            ("pnpm".to_string(), pnpm_args)
-        } else {
-            ("npm".to_string(), vec!["run".to_string(), "dev".to_string()])
-        };
+fn save_favorite_entries_to_disk(app_data_dir: &Path, entries: &[Favorite]) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(entries) {
+        let _ = std::fs::write(favorites_path(app_data_dir), json);
+    }
+}
 
-        // default_port = what the project declares; port = after override
-        let default_port = extract_port(project_dir);
-        let mut port = default_port;
-        if let Some(&override_port) = port_overrides.get(&name) { port = override_port; }
+/// Whole-project favorites, in saved order.
+fn load_favorites_from_disk(app_data_dir: &Path) -> Vec<String> {
+    load_favorite_entries(app_data_dir)
+        .into_iter()
+        .filter(|f| f.action.is_none())
+        .map(|f| f.project)
+        .collect()
+}
 
-        // Extra ports declared via  "dexhub": { "ports": [3000, 5173] }  in package.json
-        let extra_ports: Vec<u16> = val["dexhub"]["ports"]
-            .as_array()
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|v| v.as_u64().map(|p| p as u16))
-                    .filter(|&p| p != port)
-                    .collect()
-            })
-            .unwrap_or_default();
+/// Replaces the whole-project favorites; action favorites are kept.
+fn save_favorites_to_disk(app_data_dir: &Path, names: &[String]) {
+    let actions = load_favorite_entries(app_data_dir).into_iter().filter(|f| f.action.is_some());
+    let entries: Vec<Favorite> = names.iter()
+        .map(|n| Favorite { project: n.clone(), action: None, label: None })
+        .chain(actions)
+        .collect();
+    save_favorite_entries_to_disk(app_data_dir, &entries);
+}
 
-        let icon_path = find_icon(project_dir);
-        let icon_data = icon_path.as_ref().and_then(|p| icon_to_base64(p));
-        let workspace = extract_workspace(&project_dir.to_string_lossy());
+/// Action favorites only; the tray lists them under the pinned projects.
+fn load_action_favorites(app_data_dir: &Path) -> Vec<(usize, Favorite)> {
+    load_favorite_entries(app_data_dir)
+        .into_iter()
+        .enumerate()
+        .filter(|(_, f)| f.action.is_some())
+        .collect()
+}
 
-        projects.push(ProjectConfig {
-            name, cwd: project_dir.to_string_lossy().into_owned(),
-            command, args, port, default_port, extra_ports,
-            icon_path, icon_data, workspace,
-        });
+fn run_favorite(app: &tauri::AppHandle, favorite: &Favorite) -> Result<(), String> {
+    let name = favorite.project.clone();
+    match &favorite.action {
+        None => {
+            let running = is_running(&app.state::<ServerState>(), &name);
+            if running { open_in_browser(app, name); Ok(()) } else { start_server(app, name) }
+        }
+        Some(FavoriteAction::Start { env }) => start_server_on(app, name, None, env),
+        Some(FavoriteAction::Restart) => { ensure_not_presenting(app)?; restart_server(app, name).map(|_| ()) }
+        Some(FavoriteAction::RestartClean) => { ensure_not_presenting(app)?; restart_server_clean(app, name) }
+        Some(FavoriteAction::OpenBrowser) => { open_in_browser(app, name); Ok(()) }
+        Some(FavoriteAction::OpenLogs) => open_log_window(app, &name),
+        Some(FavoriteAction::OpenTerminal) => open_terminal_here(app.clone(), name),
+        Some(FavoriteAction::CopyUrl) => { copy_url(app, name); Ok(()) }
     }
+}
 
-    projects.sort_by(|a, b| a.name.cmp(&b.name));
-    projects
+// ─── Recent Starts Helpers ────────────────────────────────────────────────────
+
+/// How many recently started projects the tray remembers.
+const RECENT_LIMIT: usize = 5;
+
+fn recent_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("recent.json")
 }
 
-// ─── Tray Menu Builder ────────────────────────────────────────────────────────
+/// Most recently started first.
+fn load_recent_from_disk(app_data_dir: &Path) -> Vec<String> {
+    std::fs::read_to_string(recent_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
 
-fn build_tray_menu<M: tauri::Manager<tauri::Wry>>(
-    manager: &M,
-    projects: &[ProjectConfig],
-    running_names: &[String],
-    tailscale_host: &str,
-) -> Menu<tauri::Wry> {
-    let menu = Menu::new(manager).expect("menu");
-    menu.append(&PredefinedMenuItem::separator(manager).expect("sep")).ok();
-    menu.append(
-        &MenuItem::with_id(manager, "_header_", "─── Servers ───", false, None::<&str>).expect("header"),
-    ).ok();
+fn record_recent_start(app_data_dir: &Path, name: &str) {
+    let mut recent = load_recent_from_disk(app_data_dir);
+    recent.retain(|n| n != name);
+    recent.insert(0, name.to_string());
+    recent.truncate(RECENT_LIMIT);
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(&recent) {
+        let _ = std::fs::write(recent_path(app_data_dir), json);
+    }
+}
 
-    for project in projects {
-        let is_running = running_names.iter().any(|n| n == &project.name);
-        if is_running {
-            let url   = format!("http://{}:{}", tailscale_host, project.port);
-            let label = format!("● {}", project.name);
-            let sub   = Submenu::new(manager, &label, true).expect("submenu");
-            sub.append(&MenuItem::with_id(manager, format!("open__{}", project.name), "Open in Browser", true, None::<&str>).expect("open")).ok();
-            sub.append(&MenuItem::with_id(manager, format!("url__{}", project.name), &url, true, None::<&str>).expect("url")).ok();
-            sub.append(&MenuItem::with_id(manager, format!("stop__{}", project.name), "Stop", true, None::<&str>).expect("stop")).ok();
-            menu.append(&sub).ok();
-        } else {
-            let start_id = format!("start__{}", project.name);
-            let mut added = false;
-            if let Some(icon_path) = &project.icon_path {
-                if let Some(icon) = load_icon_image(icon_path) {
-                    if let Ok(item) = IconMenuItem::with_id(manager, &start_id, &project.name, true, Some(icon), None::<&str>) {
-                        menu.append(&item).ok();
-                        added = true;
-                    }
-                }
-            }
-            if !added {
-                menu.append(&MenuItem::with_id(manager, &start_id, &project.name, true, None::<&str>).expect("start")).ok();
+// ─── URL Host Helpers ─────────────────────────────────────────────────────────
+
+fn url_hosts_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("url_hosts.json")
+}
+
+fn load_url_hosts(app_data_dir: &Path) -> HashMap<String, String> {
+    let path = url_hosts_path(app_data_dir);
+    if let Ok(content) = std::fs::read_to_string(&path) {
+        if let Ok(map) = serde_json::from_str(&content) {
+            return map;
+        }
+    }
+    HashMap::new()
+}
+
+fn save_url_hosts_to_disk(app_data_dir: &Path, hosts: &HashMap<String, String>) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(hosts) {
+        let _ = std::fs::write(url_hosts_path(app_data_dir), json);
+    }
+}
+
+// ─── Health Warm-up Helpers ───────────────────────────────────────────────────
+
+/// Seconds after a start/restart during which failed health checks don't alert.
+const DEFAULT_HEALTH_WARMUP_SECS: u64 = 30;
+
+fn warmup_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("health_warmup.json")
+}
+
+fn load_warmup_overrides(app_data_dir: &Path) -> HashMap<String, u64> {
+    let path = warmup_path(app_data_dir);
+    if let Ok(content) = std::fs::read_to_string(&path) {
+        if let Ok(map) = serde_json::from_str(&content) {
+            return map;
+        }
+    }
+    HashMap::new()
+}
+
+fn save_warmup_overrides_to_disk(app_data_dir: &Path, overrides: &HashMap<String, u64>) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(overrides) {
+        let _ = std::fs::write(warmup_path(app_data_dir), json);
+    }
+}
+
+/// Seconds since the server started, by the wall clock.
+fn uptime_secs(state: &ServerState, name: &str) -> Option<u64> {
+    let key = project_key(state, name);
+    state.start_times.lock().unwrap().get(&key).map(|t| unix_now().saturating_sub(*t))
+}
+
+fn in_health_warmup(state: &ServerState, name: &str) -> bool {
+    let window = state.warmup_secs.lock().unwrap().get(name).copied().unwrap_or(DEFAULT_HEALTH_WARMUP_SECS);
+    uptime_secs(state, name).is_some_and(|up| up < window)
+}
+
+// ─── HTTP Health Check Helpers ────────────────────────────────────────────────
+
+/// Optional HTTP probe for a project. Without one, health is a bare TCP connect.
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct HealthCheck {
+    path:          String,
+    expect_status: Option<u16>,               // any 2xx/3xx when unset
+    body_contains: Option<String>,
+    json_field:    Option<String>,            // dot path, e.g. "status" or "db.ok"
+    json_equals:   Option<serde_json::Value>,
+}
+
+impl Default for HealthCheck {
+    fn default() -> Self {
+        HealthCheck { path: "/".to_string(), expect_status: None, body_contains: None, json_field: None, json_equals: None }
+    }
+}
+
+fn health_checks_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("health_checks.json")
+}
+
+fn load_health_checks(app_data_dir: &Path) -> HashMap<String, HealthCheck> {
+    std::fs::read_to_string(health_checks_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_health_checks_to_disk(app_data_dir: &Path, checks: &HashMap<String, HealthCheck>) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(checks) {
+        let _ = std::fs::write(health_checks_path(app_data_dir), json);
+    }
+}
+
+/// Minimal HTTP/1.0 GET against localhost; returns (status, body).
+fn http_get_local(port: u16, path: &str, timeout: Duration) -> Option<(u16, String)> {
+    use std::io::{Read, Write};
+    let mut stream = TcpStream::connect_timeout(&std::net::SocketAddr::from(([127, 0, 0, 1], port)), timeout).ok()?;
+    stream.set_read_timeout(Some(timeout)).ok()?;
+    stream.set_write_timeout(Some(timeout)).ok()?;
+    write!(stream, "GET {} HTTP/1.0\r\nHost: localhost:{}\r\nUser-Agent: DexHub\r\nConnection: close\r\n\r\n", path, port).ok()?;
+    let mut raw = Vec::new();
+    stream.take(1 << 20).read_to_end(&mut raw).ok()?;
+    let text = String::from_utf8_lossy(&raw);
+    let (head, body) = text.split_once("\r\n\r\n").unwrap_or((&text, ""));
+    let status = head.split_whitespace().nth(1)?.parse().ok()?;
+    Some((status, body.to_string()))
+}
+
+/// Milliseconds from sending a GET to the first byte of the response, or
+/// None when nothing answers in time (or the port doesn't speak HTTP).
+fn http_ttfb(port: u16, path: &str, timeout: Duration) -> Option<u64> {
+    use std::io::{Read, Write};
+    let mut stream = TcpStream::connect_timeout(&std::net::SocketAddr::from(([127, 0, 0, 1], port)), timeout).ok()?;
+    stream.set_read_timeout(Some(timeout)).ok()?;
+    stream.set_write_timeout(Some(timeout)).ok()?;
+    let start = std::time::Instant::now();
+    write!(stream, "GET {} HTTP/1.0\r\nHost: localhost:{}\r\nUser-Agent: DexHub\r\nConnection: close\r\n\r\n", path, port).ok()?;
+    let mut first = [0u8; 1];
+    match stream.read(&mut first) {
+        Ok(1) => Some(start.elapsed().as_millis() as u64),
+        _ => None,
+    }
+}
+
+/// Walks `a.b.c` through nested JSON objects (numeric segments index arrays).
+fn json_lookup<'a>(value: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    path.split('.').filter(|s| !s.is_empty()).try_fold(value, |v, key| match key.parse::<usize>() {
+        Ok(i) if v.is_array() => v.get(i),
+        _ => v.get(key),
+    })
+}
+
+/// Runs an HTTP health check; `Err` explains which assertion failed.
+fn run_health_check(port: u16, check: &HealthCheck) -> Result<(), String> {
+    let path = if check.path.starts_with('/') { check.path.clone() } else { format!("/{}", check.path) };
+    let (status, body) = http_get_local(port, &path, Duration::from_secs(2)).ok_or("no HTTP response")?;
+    match check.expect_status {
+        Some(expected) if status != expected => return Err(format!("status {} (expected {})", status, expected)),
+        None if !(200..400).contains(&status) => return Err(format!("status {}", status)),
+        _ => {}
+    }
+    if let Some(needle) = &check.body_contains {
+        if !body.contains(needle.as_str()) {
+            return Err(format!("body does not contain \"{}\"", needle));
+        }
+    }
+    if let Some(field) = &check.json_field {
+        let json: serde_json::Value = serde_json::from_str(&body).map_err(|_| "body is not JSON".to_string())?;
+        let actual = json_lookup(&json, field).ok_or_else(|| format!("JSON field '{}' missing", field))?;
+        if let Some(expected) = &check.json_equals {
+            if actual != expected {
+                return Err(format!("JSON field '{}' is {} (expected {})", field, actual, expected));
             }
         }
     }
+    Ok(())
+}
 
-    menu.append(&PredefinedMenuItem::separator(manager).expect("sep")).ok();
-    menu.append(&MenuItem::with_id(manager, "refresh", "Refresh", true, None::<&str>).expect("refresh")).ok();
-    menu.append(&PredefinedMenuItem::separator(manager).expect("sep")).ok();
-    menu.append(&MenuItem::with_id(manager, "quit", "Quit DexHub", true, None::<&str>).expect("quit")).ok();
-    menu
+// ─── Disk Space Guard ─────────────────────────────────────────────────────────
+
+/// Dependencies whose dev servers write large on-disk caches.
+const CACHE_HEAVY_DEPS: &[&str] = &["next", "turbo", "vite"];
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct DiskGuardConfig {
+    enabled:      bool,
+    min_free_mb:  u64,
+    refuse_below: bool, // refuse to start instead of warning
 }
 
-fn rebuild_tray(app: &tauri::AppHandle) {
-    let server_state = app.state::<ServerState>();
-    let tray_handle  = app.state::<TrayHandle>();
-    let running: Vec<String> = server_state.processes.lock().unwrap().keys().cloned().collect();
-    let projects: Vec<ProjectConfig> = server_state.projects.lock().unwrap().clone();
-    let ts_host = server_state.tailscale_host.clone();
-    let new_menu = build_tray_menu(app, &projects, &running, &ts_host);
-    let guard = tray_handle.0.lock().unwrap();
-    if let Some(tray) = guard.as_ref() { let _ = tray.set_menu(Some(new_menu)); }
+impl Default for DiskGuardConfig {
+    fn default() -> Self {
+        DiskGuardConfig { enabled: true, min_free_mb: 2048, refuse_below: false }
+    }
 }
 
-// ─── Menu Event Handler ───────────────────────────────────────────────────────
+fn disk_guard_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("disk_guard.json")
+}
 
-fn handle_menu_event(app: &tauri::AppHandle, id: &str) {
-    if id == "quit" {
-        let state = app.state::<ServerState>();
-        let mut procs = state.processes.lock().unwrap();
-        for (_, child) in procs.iter_mut() { let _ = child.kill(); }
-        drop(procs);
-        app.exit(0);
-    } else if id == "refresh" {
-        let app_data_dir = app.path().app_data_dir().unwrap_or_else(|_| std::path::PathBuf::from("/tmp"));
-        let overrides = load_port_overrides(&app_data_dir);
-        let state = app.state::<ServerState>();
-        *state.projects.lock().unwrap() = scan_projects(Path::new(PROJECTS_DIR), &overrides);
-        rebuild_tray(app);
-    } else if let Some(name) = id.strip_prefix("start__") {
-        start_server(app, name.to_string());
-    } else if let Some(name) = id.strip_prefix("stop__") {
-        stop_server(app, name.to_string());
-    } else if let Some(name) = id.strip_prefix("open__") {
-        open_in_browser(app, name.to_string());
-    } else if let Some(name) = id.strip_prefix("url__") {
-        copy_url(app, name.to_string());
+fn load_disk_guard_config(app_data_dir: &Path) -> DiskGuardConfig {
+    std::fs::read_to_string(disk_guard_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+/// True when package.json lists Next, Turbo or Vite.
+fn writes_large_caches(cwd: &str) -> bool {
+    let Ok(content) = std::fs::read_to_string(Path::new(cwd).join("package.json")) else { return false };
+    let Ok(pkg) = serde_json::from_str::<serde_json::Value>(&content) else { return false };
+    ["dependencies", "devDependencies"].iter().any(|section| {
+        pkg[section].as_object().is_some_and(|deps| CACHE_HEAVY_DEPS.iter().any(|d| deps.contains_key(*d)))
+    })
+}
+
+/// Free space on the volume holding `path`, in MB, from `df -Pk`.
+fn free_disk_mb(path: &str) -> Option<u64> {
+    let output = std::process::Command::new("df").args(["-Pk", path]).output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let available_kb: u64 = text.lines().nth(1)?.split_whitespace().nth(3)?.parse().ok()?;
+    Some(available_kb / 1024)
+}
+
+/// Warns (or refuses, if configured) before starting a cache-heavy server on a nearly full disk.
+fn check_disk_space(app: &tauri::AppHandle, project: &ProjectConfig) -> Result<(), String> {
+    let config = app.state::<ServerState>().disk_guard.lock().unwrap().clone();
+    if !config.enabled || !writes_large_caches(&project.cwd) { return Ok(()); }
+    let Some(free_mb) = free_disk_mb(&project.cwd) else { return Ok(()) };
+    if free_mb >= config.min_free_mb { return Ok(()); }
+
+    let message = format!("Only {} MB free — {} may fail writing its build cache", free_mb, project.name);
+    record_event(app, "low-disk", Some(&project.name), serde_json::json!({ "free_mb": free_mb, "refused": config.refuse_below }));
+    if config.refuse_below {
+        return Err(format!("{} (need {} MB; change the threshold in disk guard settings)", message, config.min_free_mb));
     }
+    notify(app, "low on disk", &project.name, message, "Basso");
+    Ok(())
 }
 
-// ─── Server Lifecycle ────────────────────────────────────────────────────────
+// ─── Safe Mode ────────────────────────────────────────────────────────────────
 
-fn start_server(app: &tauri::AppHandle, name: String) {
-    let state = app.state::<ServerState>();
+/// `--safe-mode`, `DEXHUB_SAFE_MODE=1`, or Shift held while launching (macOS).
+/// Brings up only the tray and diagnostics: no project scan, no background
+/// integrations, no local API/proxy/CLI socket, no deep links.
+fn safe_mode_requested() -> bool {
+    std::env::args().any(|a| a == "--safe-mode")
+        || std::env::var("DEXHUB_SAFE_MODE").is_ok_and(|v| v == "1")
+        || platform::shift_held()
+}
 
-    // Gather env overrides before locking projects
-    let env_vars: HashMap<String, String> = state
-        .env_overrides
-        .lock()
-        .unwrap()
-        .get(&name)
-        .cloned()
-        .unwrap_or_default();
+/// Relaunches without `--safe-mode` and exits this instance.
+fn restart_normally(app: &tauri::AppHandle) {
+    let Ok(exe) = std::env::current_exe() else { return };
+    let args: Vec<String> = std::env::args().skip(1).filter(|a| a != "--safe-mode").collect();
+    match std::process::Command::new(exe).args(args).env_remove("DEXHUB_SAFE_MODE").spawn() {
+        Ok(_) => app.exit(0),
+        Err(e) => eprintln!("[DexHub] Failed to relaunch: {}", e),
+    }
+}
 
-    let project = {
-        let projects = state.projects.lock().unwrap();
-        match projects.iter().find(|p| p.name == name) {
-            Some(p) => p.clone(),
-            None => return,
+// ─── Presentation Mode ────────────────────────────────────────────────────────
+
+const PRESENTATION_BLOCKED: &str = "Disabled in presentation mode";
+const REDACTED: &str = "••••••";
+
+fn presenting(app: &tauri::AppHandle) -> bool {
+    app.state::<ServerState>().presentation_mode.load(Ordering::SeqCst)
+}
+
+fn ensure_not_presenting(app: &tauri::AppHandle) -> Result<(), String> {
+    if presenting(app) { Err(PRESENTATION_BLOCKED.to_string()) } else { Ok(()) }
+}
+
+/// Strips filesystem paths from projects before they leave the backend.
+fn redact_projects(app: &tauri::AppHandle, mut projects: Vec<ProjectConfig>) -> Vec<ProjectConfig> {
+    if presenting(app) {
+        for p in &mut projects {
+            p.cwd = format!("…/{}", p.name);
+            p.icon_path = None;
         }
-    };
+    }
+    projects
+}
 
-    let cmd_str = format!("{} {}", project.command, project.args.join(" "));
-    let mut cmd = std::process::Command::new("/bin/zsh");
-    cmd.args(["-lc", &cmd_str])
-        .current_dir(&project.cwd)
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped());
-    for (k, v) in &env_vars { cmd.env(k, v); }
+/// Masks what presentation mode hides elsewhere — project paths, the home
+/// folder, tailnet and LAN addresses, env override values — in log lines,
+/// wherever they're shown. A no-op outside presentation mode.
+fn redact_log_lines(app: &tauri::AppHandle, lines: Vec<String>) -> Vec<String> {
+    if !presenting(app) { return lines; }
+    let state = app.state::<ServerState>();
+    let mut masks: Vec<(String, String)> = state.projects.lock().unwrap().iter()
+        .map(|p| (p.cwd.clone(), format!("…/{}", p.name)))
+        .collect();
+    if let Some(home) = std::env::var_os("HOME") {
+        masks.push((home.to_string_lossy().into_owned(), "~".to_string()));
+    }
+    masks.push((state.tailscale_host.lock().unwrap().clone(), REDACTED.to_string()));
+    if let Some(ip) = state.lan_ip.lock().unwrap().clone() { masks.push((ip, REDACTED.to_string())); }
+    for env in state.env_overrides.lock().unwrap().values() {
+        masks.extend(env.values().map(|v| (v.clone(), REDACTED.to_string())));
+    }
+    // Short values like "1" or "dev" would mangle unrelated text
+    masks.retain(|(from, _)| from.len() >= 4 && from != "localhost");
+    masks.sort_by_key(|(from, _)| std::cmp::Reverse(from.len()));
+    lines.into_iter()
+        .map(|line| masks.iter().fold(line, |line, (from, to)| line.replace(from.as_str(), to)))
+        .collect()
+}
 
-    match cmd.spawn() {
-        Ok(mut child) => {
-            // Create a per-server log buffer (ring buffer, max 500 lines)
-            let log_buf: LogBuffer = Arc::new(Mutex::new(VecDeque::with_capacity(500)));
+// ─── Tag Helpers ──────────────────────────────────────────────────────────────
 
-            // Stdout reader thread
-            if let Some(stdout) = child.stdout.take() {
-                let buf = Arc::clone(&log_buf);
-                std::thread::spawn(move || {
-                    for line in BufReader::new(stdout).lines() {
-                        if let Ok(l) = line {
-                            let mut b = buf.lock().unwrap();
-                            if b.len() >= 500 { b.pop_front(); }
-                            b.push_back(l);
-                        }
-                    }
-                });
-            }
-            // Stderr reader thread
-            if let Some(stderr) = child.stderr.take() {
-                let buf = Arc::clone(&log_buf);
-                std::thread::spawn(move || {
-                    for line in BufReader::new(stderr).lines() {
-                        if let Ok(l) = line {
-                            let mut b = buf.lock().unwrap();
-                            if b.len() >= 500 { b.pop_front(); }
-                            b.push_back(format!("[err] {}", l));
-                        }
-                    }
-                });
-            }
+fn tags_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("tags.json")
+}
 
-            let now = std::time::Instant::now();
-            state.processes.lock().unwrap().insert(name.clone(), child);
-            state.start_times.lock().unwrap().insert(name.clone(), now);
-            state.log_buffers.lock().unwrap().insert(name, log_buf);
-            rebuild_tray(app);
+fn load_tags(app_data_dir: &Path) -> HashMap<String, Vec<String>> {
+    let path = tags_path(app_data_dir);
+    if let Ok(content) = std::fs::read_to_string(&path) {
+        if let Ok(map) = serde_json::from_str(&content) {
+            return map;
         }
-        Err(e) => eprintln!("[DexHub] Failed to start '{}': {}", name, e),
     }
+    HashMap::new()
+}
+
+fn save_tags_to_disk(app_data_dir: &Path, tags: &HashMap<String, Vec<String>>) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(tags) {
+        let _ = std::fs::write(tags_path(app_data_dir), json);
+    }
+}
+
+// ─── Env Override Helpers ─────────────────────────────────────────────────────
+
+/// Stands in for a secret's value in env_overrides.json; the value itself
+/// lives in the keyring and is only read when the server is spawned.
+const SECRET_PLACEHOLDER: &str = "@keyring";
+
+fn env_secret_account(project: &str, key: &str) -> String {
+    format!("env:{}:{}", project, key)
+}
+
+/// Swaps secret placeholders for their keyring values.
+fn resolve_env_secrets(project: &str, vars: &mut HashMap<String, String>) -> Result<(), String> {
+    for (key, value) in vars.iter_mut() {
+        if value != SECRET_PLACEHOLDER { continue; }
+        *value = platform::keyring_get(&env_secret_account(project, key))
+            .ok_or_else(|| format!("Secret {} for '{}' is missing from the keyring; set it again", key, project))?;
+    }
+    Ok(())
+}
+
+fn env_overrides_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("env_overrides.json")
+}
+
+fn load_env_overrides(app_data_dir: &Path) -> HashMap<String, HashMap<String, String>> {
+    let path = env_overrides_path(app_data_dir);
+    if let Ok(content) = std::fs::read_to_string(&path) {
+        if let Ok(map) = serde_json::from_str(&content) {
+            return map;
+        }
+    }
+    HashMap::new()
+}
+
+fn save_env_overrides_to_disk(
+    app_data_dir: &Path,
+    overrides: &HashMap<String, HashMap<String, String>>,
+) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(overrides) {
+        let _ = std::fs::write(env_overrides_path(app_data_dir), json);
+    }
+}
+
+/// Named sets of env vars per project ("local-db", "staging-api"). The
+/// active profile is layered over the plain env overrides at start, so
+/// flipping a frontend between backends is one selection, not an edit.
+#[derive(Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct EnvProfiles {
+    active:   Option<String>,
+    profiles: BTreeMap<String, HashMap<String, String>>,
+}
+
+impl EnvProfiles {
+    fn active_vars(&self) -> HashMap<String, String> {
+        self.active.as_ref().and_then(|a| self.profiles.get(a)).cloned().unwrap_or_default()
+    }
+}
+
+fn env_profiles_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("env_profiles.json")
+}
+
+fn load_env_profiles(app_data_dir: &Path) -> HashMap<String, EnvProfiles> {
+    std::fs::read_to_string(env_profiles_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_env_profiles_to_disk(app_data_dir: &Path, profiles: &HashMap<String, EnvProfiles>) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(profiles) {
+        let _ = std::fs::write(env_profiles_path(app_data_dir), json);
+    }
+}
+
+// ─── Launch Commands ──────────────────────────────────────────────────────────
+
+/// Replaces the scanned dev script for one project, for setups where the
+/// right command isn't in package.json (`make dev`, `docker compose up web`).
+/// Run through the shell like the scanned command, so anything the shell
+/// understands works.
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct LaunchCommand {
+    command: String,
+    #[serde(default)]
+    args:    Vec<String>,
+}
+
+fn launch_commands_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("launch_commands.json")
+}
+
+fn load_launch_commands(app_data_dir: &Path) -> HashMap<String, LaunchCommand> {
+    std::fs::read_to_string(launch_commands_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_launch_commands_to_disk(app_data_dir: &Path, commands: &HashMap<String, LaunchCommand>) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(commands) {
+        let _ = std::fs::write(launch_commands_path(app_data_dir), json);
+    }
+}
+
+// ─── CPU Watchdog ─────────────────────────────────────────────────────────────
+
+/// Flags a server as runaway when its process tree stays above `cpu_threshold`
+/// for `sustain_secs` without answering a single request — the
+/// infinite-rebuild loop. A passing health check or a proxied request counts
+/// as an answer (see `note_success`). `quiet_logs` is a secondary heuristic
+/// for servers nothing has reached since they started: stdout silence over
+/// the window stands in for the missing request signal.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct WatchdogConfig {
+    enabled:       bool,
+    cpu_threshold: f32,
+    sustain_secs:  u64,
+    quiet_logs:    bool,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        WatchdogConfig { enabled: true, cpu_threshold: 90.0, sustain_secs: 120, quiet_logs: true }
+    }
+}
+
+fn watchdog_config_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("watchdog.json")
+}
+
+fn load_watchdog_config(app_data_dir: &Path) -> WatchdogConfig {
+    std::fs::read_to_string(watchdog_config_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+#[derive(Clone, Copy, Default)]
+struct TreeSample {
+    cpu:    f32, // %CPU
+    rss_mb: u64,
+}
+
+/// Sums %CPU and resident memory over each root pid and all of its
+/// descendants with a single `ps`. Dev servers run under `zsh -lc`, so the
+/// busy node process is a grandchild.
+fn sample_process_trees(roots: &HashMap<String, u32>) -> HashMap<String, TreeSample> {
+    let output = match std::process::Command::new("ps").args(["-A", "-o", "pid=,ppid=,%cpu=,rss="]).output() {
+        Ok(o) => o,
+        Err(_) => return HashMap::new(),
+    };
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    let mut usage: HashMap<u32, (f32, u64)> = HashMap::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        if cols.len() < 4 { continue; }
+        let (Ok(pid), Ok(ppid), Ok(pct), Ok(rss_kb)) = (cols[0].parse::<u32>(), cols[1].parse::<u32>(), cols[2].parse::<f32>(), cols[3].parse::<u64>()) else { continue };
+        children.entry(ppid).or_default().push(pid);
+        usage.insert(pid, (pct, rss_kb));
+    }
+    roots.iter().map(|(name, &root)| {
+        let (mut cpu, mut rss_kb) = (0.0, 0);
+        let mut stack = vec![root];
+        while let Some(pid) = stack.pop() {
+            let (pct, kb) = usage.get(&pid).copied().unwrap_or_default();
+            cpu += pct;
+            rss_kb += kb;
+            if let Some(kids) = children.get(&pid) { stack.extend(kids); }
+        }
+        (name.clone(), TreeSample { cpu, rss_mb: rss_kb / 1024 })
+    }).collect()
+}
+
+/// Records that a server answered a request, which clears it of being a
+/// runaway in the watchdog's eyes.
+fn note_success(state: &ServerState, name: &str) {
+    let key = project_key(state, name);
+    state.last_success.lock().unwrap().insert(key, std::time::Instant::now());
+}
+
+fn spawn_cpu_watchdog(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut hot_since: HashMap<String, std::time::Instant> = HashMap::new();
+        loop {
+            let interval = app.state::<ServerState>().general.lock().unwrap().watchdog_interval();
+            std::thread::sleep(interval);
+            let state = app.state::<ServerState>();
+            let config = state.watchdog.lock().unwrap().clone();
+            let pids: Vec<(String, u32)> = state.processes.lock().unwrap()
+                .iter()
+                .map(|(k, c)| (k.clone(), c.id()))
+                .collect();
+            let roots: HashMap<String, u32> = pids.into_iter().map(|(k, pid)| (project_name(&state, &k), pid)).collect();
+            let trees = sample_process_trees(&roots);
+            let samples: HashMap<String, f32> = trees.iter().map(|(n, s)| (n.clone(), s.cpu)).collect();
+            let rss: HashMap<String, u64> = trees.iter().map(|(n, s)| (n.clone(), s.rss_mb)).collect();
+            *state.cpu_samples.lock().unwrap() = samples.clone();
+            *state.rss_samples.lock().unwrap() = rss.clone();
+            enforce_memory_limits(&app, &rss);
+            hot_since.retain(|n, _| roots.contains_key(n));
+
+            let mut changed = false;
+            let now = std::time::Instant::now();
+            let sustain = Duration::from_secs(config.sustain_secs);
+            for (name, pct) in &samples {
+                let hot = config.enabled && *pct >= config.cpu_threshold;
+                if !hot {
+                    hot_since.remove(name);
+                    changed |= state.runaway.lock().unwrap().remove(name);
+                    continue;
+                }
+                let since = *hot_since.entry(name.clone()).or_insert(now);
+                let key = project_key(&state, name);
+                let last_success = state.last_success.lock().unwrap().get(&key).copied();
+                let idle = match last_success {
+                    Some(t) => now.duration_since(t) >= sustain,
+                    None if config.quiet_logs => state.last_output.lock().unwrap()
+                        .get(&key)
+                        .map(|t| t.lock().unwrap().elapsed() >= sustain)
+                        .unwrap_or(true),
+                    None => false,
+                };
+                if now.duration_since(since) >= sustain && idle
+                    && state.runaway.lock().unwrap().insert(name.clone())
+                {
+                    changed = true;
+                    notify(
+                        &app,
+                        "flagged as runaway",
+                        name,
+                        format!("'{}' has used {:.0}% CPU for {}s without answering a request — restart it from the tray.", name, pct, config.sustain_secs),
+                        "Funk",
+                    );
+                    let _ = app.emit("watchdog-alert", serde_json::json!({ "name": name, "cpu": pct }));
+                }
+            }
+            state.runaway.lock().unwrap().retain(|n| roots.contains_key(n));
+            if changed { rebuild_tray(&app); }
+        }
+    });
+}
+
+// ─── Resource Limits ──────────────────────────────────────────────────────────
+
+/// Per-project limits. The niceness applies from the next start; the memory
+/// ceiling is checked against the process tree's RSS on every watchdog
+/// sample, and a server over it is restarted.
+#[derive(Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct ResourceLimits {
+    nice:      Option<i32>, // 0–19; raising priority would need root
+    memory_mb: Option<u64>,
+}
+
+fn resource_limits_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("resource_limits.json")
+}
+
+fn load_resource_limits(app_data_dir: &Path) -> HashMap<String, ResourceLimits> {
+    std::fs::read_to_string(resource_limits_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_resource_limits_to_disk(app_data_dir: &Path, limits: &HashMap<String, ResourceLimits>) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(limits) {
+        let _ = std::fs::write(resource_limits_path(app_data_dir), json);
+    }
+}
+
+/// Restarts each server whose process tree is over its memory ceiling. One
+/// restart runs at a time per server, and each hit counts as a crash, so a
+/// server that is over its limit straight after starting ends up flapping
+/// and is left alone until the flag is cleared.
+fn enforce_memory_limits(app: &tauri::AppHandle, rss: &HashMap<String, u64>) {
+    let state = app.state::<ServerState>();
+    let limits = state.resource_limits.lock().unwrap().clone();
+    for (name, &used_mb) in rss {
+        let Some(limit_mb) = limits.get(name).and_then(|l| l.memory_mb) else { continue };
+        if used_mb <= limit_mb { continue; }
+        if state.flapping.lock().unwrap().contains(name) { continue; }
+        if !state.memory_restarts.lock().unwrap().insert(name.clone()) { continue; }
+        record_event(app, "memory-limit", Some(name), serde_json::json!({ "rss_mb": used_mb, "limit_mb": limit_mb }));
+        let (_, flapping) = note_crash(app, name);
+        if flapping {
+            state.memory_restarts.lock().unwrap().remove(name);
+            let window = state.crash_policy.lock().unwrap().window_mins;
+            notify(app, "flapping", name, format!("'{}' went over its {} MB limit too often in {} min; memory restarts paused until cleared", name, limit_mb, window), notifications::DEFAULT_CRASH_SOUND);
+            continue;
+        }
+        notify(app, "restarted (memory limit)", name, format!("'{}' used {} MB, over its {} MB limit — restarted.", name, used_mb, limit_mb), "Funk");
+        let (app, name) = (app.clone(), name.clone());
+        std::thread::spawn(move || {
+            if let Err(e) = restart_server(&app, name.clone()) {
+                eprintln!("[DexHub] Restart of '{}' over its memory limit failed: {}", name, e);
+            }
+            app.state::<ServerState>().memory_restarts.lock().unwrap().remove(&name);
+        });
+    }
+}
+
+// ─── Audit Log ────────────────────────────────────────────────────────────────
+
+fn audit_log_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("audit.log")
+}
+
+fn unix_now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Appends one JSON line recording who did what, and when, to audit.log.
+fn append_audit(app: &tauri::AppHandle, action: &str, project: &str, detail: serde_json::Value) {
+    let Ok(app_data_dir) = app.path().app_data_dir() else { return };
+    let _ = std::fs::create_dir_all(&app_data_dir);
+    let entry = serde_json::json!({
+        "ts":      unix_now(),
+        "user":    std::env::var("USER").unwrap_or_default(),
+        "action":  action,
+        "project": project,
+        "detail":  detail,
+    });
+    if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(audit_log_path(&app_data_dir)) {
+        use std::io::Write;
+        let _ = writeln!(file, "{}", entry);
+    }
+}
+
+fn read_audit_log(app_data_dir: &Path, limit: usize) -> Vec<serde_json::Value> {
+    let content = std::fs::read_to_string(audit_log_path(app_data_dir)).unwrap_or_default();
+    let entries: Vec<serde_json::Value> = content.lines().filter_map(|l| serde_json::from_str(l).ok()).collect();
+    let skip = entries.len().saturating_sub(limit);
+    entries.into_iter().skip(skip).collect()
+}
+
+// ─── Event Log & Hooks ────────────────────────────────────────────────────────
+
+const EVENT_LOG_CAPACITY: usize = 1000;
+/// A hook that hasn't exited after this long is killed.
+const HOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One entry in the in-memory event log; also emitted as `dexhub-event` and
+/// appended to events.jsonl.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct EventRecord {
+    ts:      u64,
+    kind:    String,
+    project: Option<String>,
+    detail:  serde_json::Value,
+}
+
+fn record_event(app: &tauri::AppHandle, kind: &str, project: Option<&str>, detail: serde_json::Value) {
+    let record = EventRecord {
+        ts: unix_now(),
+        kind: kind.to_string(),
+        project: project.map(String::from),
+        detail,
+    };
+    let _ = app.emit("dexhub-event", &record);
+    remote::broadcast_event(app, &record);
+    let state = app.state::<ServerState>();
+    if let Some(endpoint) = state.general.lock().unwrap().otlp_endpoint.as_deref().filter(|e| !e.trim().is_empty()) {
+        events::export_otlp(endpoint, &record);
+    }
+    let mut log = state.event_log.lock().unwrap();
+    if let Ok(dir) = app.path().app_data_dir() { events::append(&dir, &record); }
+    if log.len() >= EVENT_LOG_CAPACITY { log.pop_front(); }
+    log.push_back(record);
+}
+
+fn hooks_dir(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("hooks")
+}
+
+/// A hook is any regular file with an execute bit; platforms without mode bits
+/// run every regular file in the hooks directory.
+#[cfg(unix)]
+fn is_runnable_hook(meta: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    meta.is_file() && meta.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_runnable_hook(meta: &std::fs::Metadata) -> bool {
+    meta.is_file()
+}
+
+fn list_hook_executables(app_data_dir: &Path) -> Vec<std::path::PathBuf> {
+    let mut hooks: Vec<std::path::PathBuf> = std::fs::read_dir(hooks_dir(app_data_dir))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| std::fs::metadata(p).map(|m| is_runnable_hook(&m)).unwrap_or(false))
+                .collect()
+        })
+        .unwrap_or_default();
+    hooks.sort();
+    hooks
+}
+
+/// Records `event` and runs every executable in `<app data>/hooks/` with
+/// `{"event": ..., "project": ..., ...payload}` on stdin. Hooks run in the
+/// background; their stdout/stderr land in the event log as `hook-output`.
+fn fire_hooks(app: &tauri::AppHandle, event: &str, project: Option<&str>, payload: serde_json::Value) {
+    record_event(app, event, project, payload.clone());
+    let Ok(app_data_dir) = app.path().app_data_dir() else { return };
+    let hooks = list_hook_executables(&app_data_dir);
+    if hooks.is_empty() { return; }
+
+    let mut input = serde_json::json!({ "event": event, "project": project, "ts": unix_now() });
+    if let (Some(obj), Some(extra)) = (input.as_object_mut(), payload.as_object()) {
+        for (k, v) in extra { obj.insert(k.clone(), v.clone()); }
+    }
+    let input = input.to_string();
+    let project = project.map(String::from);
+
+    for hook in hooks {
+        let app = app.clone();
+        let input = input.clone();
+        let project = project.clone();
+        std::thread::spawn(move || {
+            let hook_name = hook.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            let detail = match run_hook(&hook, &input) {
+                Ok((code, stdout, stderr)) => serde_json::json!({
+                    "hook": hook_name, "exit_code": code, "stdout": stdout, "stderr": stderr,
+                }),
+                Err(e) => serde_json::json!({ "hook": hook_name, "error": e }),
+            };
+            record_event(&app, "hook-output", project.as_deref(), detail);
+        });
+    }
+}
+
+fn run_hook(hook: &Path, input: &str) -> Result<(Option<i32>, String, String), String> {
+    use std::io::{Read, Write};
+    let mut child = std::process::Command::new(hook)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(input.as_bytes());
+    }
+    // Drain pipes on their own threads so a chatty hook can't block on a full pipe
+    let mut stdout = child.stdout.take();
+    let mut stderr = child.stderr.take();
+    let out_reader = std::thread::spawn(move || {
+        let mut s = String::new();
+        if let Some(o) = stdout.as_mut() { let _ = o.read_to_string(&mut s); }
+        s
+    });
+    let err_reader = std::thread::spawn(move || {
+        let mut s = String::new();
+        if let Some(e) = stderr.as_mut() { let _ = e.read_to_string(&mut s); }
+        s
+    });
+
+    let deadline = std::time::Instant::now() + HOOK_TIMEOUT;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if std::time::Instant::now() < deadline => std::thread::sleep(Duration::from_millis(50)),
+            _ => { let _ = child.kill(); let _ = child.wait(); break None; }
+        }
+    };
+    let stdout = out_reader.join().unwrap_or_default();
+    let stderr = err_reader.join().unwrap_or_default();
+    match status {
+        Some(s) => Ok((s.code(), stdout, stderr)),
+        None => Err(format!("timed out after {}s", HOOK_TIMEOUT.as_secs())),
+    }
+}
+
+// ─── Notification Digest ──────────────────────────────────────────────────────
+
+/// Queues a desktop notification. The first one in a burst opens a short
+/// window; when it closes a lone notification is sent as-is, while several
+/// collapse into one summary ("5 servers stopped, 1 failed") with the
+/// individual messages recorded as a `notification-digest` event.
+fn notify(app: &tauri::AppHandle, kind: &str, name: &str, message: String, sound: &str) {
+    let state = app.state::<ServerState>();
+    let config = state.notifications.lock().unwrap().clone();
+    if !config.shows(name) { return; }
+    if notifications::QUIET_KINDS.contains(&kind) && local_minute_of_day().is_some_and(|m| config.quiet_hours.contains(m)) {
+        record_event(app, "notification-suppressed", Some(name), serde_json::json!({ "kind": kind, "message": message }));
+        return;
+    }
+    // The active Focus can mute a project; it still shows, just without a sound
+    let sound = if focus::is_silenced(app, name) { String::new() } else { config.sound_for(name, kind, sound) };
+    let mut pending = state.notification_queue.pending.lock().unwrap();
+    pending.push(notifications::PendingNotification {
+        kind: kind.to_string(),
+        name: name.to_string(),
+        message,
+        sound,
+    });
+    if pending.len() > 1 { return; }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(notifications::DIGEST_WINDOW);
+        let items = std::mem::take(&mut *app.state::<ServerState>().notification_queue.pending.lock().unwrap());
+        match items.as_slice() {
+            [] => {}
+            [single] => send_notification(&app, &single.message, &single.sound),
+            many => {
+                let summary = notifications::summarize_digest(many);
+                send_notification(&app, &format!("{} — see the event log for details.", summary), notifications::digest_sound(many));
+                record_event(&app, "notification-digest", None, serde_json::json!({ "summary": summary, "items": many }));
+            }
+        }
+    });
+}
+
+/// Posts to the project's Slack/Discord webhook, when one is set.
+fn chat_alert(config: &NotificationConfig, name: &str, headline: &str, log_lines: &[String]) {
+    let Some(webhook) = config.webhook_for(name) else { return };
+    let lines: Vec<String> = log_lines.iter().map(|l| strip_ansi(l)).collect();
+    notifications::send_chat_alert(webhook, notifications::chat_message(webhook.service, name, headline, &lines));
+}
+
+// ─── Public Tunnels ───────────────────────────────────────────────────────────
+
+/// How often expired share links are looked for.
+const SHARE_EXPIRY_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Returns the first installed tunnel provider, preferring cloudflared.
+fn detect_tunnel_provider(shell: &str) -> Option<String> {
+    for provider in ["cloudflared", "ngrok"] {
+        let found = std::process::Command::new(shell)
+            .args(["-lc", &format!("command -v {}", provider)])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false);
+        if found { return Some(provider.to_string()); }
+    }
+    None
+}
+
+/// Pulls the public https URL out of a cloudflared, ngrok or Funnel log line.
+fn extract_tunnel_url(line: &str) -> Option<String> {
+    let start = line.find("https://")?;
+    let url: String = line[start..]
+        .chars()
+        .take_while(|c| !c.is_whitespace() && *c != '"' && *c != '|')
+        .collect();
+    if url.contains(".trycloudflare.com") || url.contains("ngrok") || url.contains(".ts.net") {
+        Some(url)
+    } else {
+        None
+    }
+}
+
+/// ngrok's limits on a basic-auth password, and what survives being written
+/// into its traffic policy file as a quoted string.
+fn validate_passcode(code: &str) -> Result<(), String> {
+    let len = code.chars().count();
+    if !(8..=128).contains(&len) {
+        return Err(format!("Passcodes must be 8–128 characters long, not {}", len));
+    }
+    if !code.chars().all(|c| c.is_ascii_graphic() && c != '"' && c != '\\') {
+        return Err("Passcodes may only use letters, digits and punctuation other than \" and \\".to_string());
+    }
+    Ok(())
+}
+
+/// Writes an ngrok traffic policy that puts the tunnel behind basic auth,
+/// readable only by the user, so the passcode never appears on a command
+/// line where `ps` shows it.
+fn write_ngrok_policy(app: &tauri::AppHandle, name: &str, code: &str) -> Result<std::path::PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?.join("tunnels");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("{}.policy.yml", proxy::host_slug(name)));
+    let policy = format!(
+        "on_http_request:\n  - actions:\n      - type: basic-auth\n        config:\n          credentials:\n            - \"dexhub:{}\"\n",
+        code,
+    );
+    use std::io::Write;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(&path).and_then(|mut f| f.write_all(policy.as_bytes()))
+        .map_err(|e| format!("Couldn't write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Starts a public tunnel for a project. `provider` is "cloudflared", "ngrok"
+/// or "funnel" (Tailscale Funnel); a passcode is only enforceable by ngrok,
+/// which puts the tunnel behind HTTP basic auth.
+fn start_tunnel(
+    app: &tauri::AppHandle,
+    name: String,
+    provider: Option<String>,
+    passcode: Option<String>,
+) -> Result<(), String> {
+    let state = app.state::<ServerState>();
+    if state.tunnels.lock().unwrap().contains_key(&name) { return Ok(()); }
+
+    // Where the server really listens, which may be a port it announced
+    let project = state.projects.lock().unwrap().iter().find(|p| p.name == name).cloned()
+        .ok_or_else(|| format!("Project '{}' not found", name))?;
+    let port = effective_port(&state, &project);
+
+    let shell = state.general.lock().unwrap().shell.clone();
+    let provider = match provider {
+        Some(p) => p,
+        None => detect_tunnel_provider(&shell).ok_or("Neither cloudflared nor ngrok is installed")?,
+    };
+    if passcode.is_some() && provider != "ngrok" {
+        return Err(format!("Passcode-protected links require ngrok, not {}", provider));
+    }
+    if let Some(code) = &passcode { validate_passcode(code)?; }
+    let policy_file = match &passcode {
+        Some(code) => Some(write_ngrok_policy(app, &name, code)?),
+        None => None,
+    };
+    let cmd_str = match provider.as_str() {
+        "cloudflared" => format!("cloudflared tunnel --no-autoupdate --url http://localhost:{}", port),
+        "ngrok" => match &policy_file {
+            Some(policy) => format!(
+                "ngrok http {} --log stdout --log-format logfmt --traffic-policy-file '{}'",
+                port, policy.to_string_lossy().replace('\'', "'\\''")
+            ),
+            None => format!("ngrok http {} --log stdout --log-format logfmt", port),
+        },
+        "funnel" => format!("tailscale funnel {}", port),
+        other => return Err(format!("Unknown tunnel provider '{}'", other)),
+    };
+
+    let spawned = std::process::Command::new(&shell)
+        .args(["-lc", &cmd_str])
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => {
+            if let Some(policy) = &policy_file { let _ = std::fs::remove_file(policy); }
+            return Err(e.to_string());
+        }
+    };
+
+    // cloudflared prints its URL on stderr, ngrok on stdout — watch both
+    let public_url = Arc::new(Mutex::new(None));
+    let streams: Vec<Box<dyn std::io::Read + Send>> = [
+        child.stdout.take().map(|s| Box::new(s) as Box<dyn std::io::Read + Send>),
+        child.stderr.take().map(|s| Box::new(s) as Box<dyn std::io::Read + Send>),
+    ].into_iter().flatten().collect();
+    for stream in streams {
+        let url_slot = Arc::clone(&public_url);
+        let app = app.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                if let Some(url) = extract_tunnel_url(&line) {
+                    let mut slot = url_slot.lock().unwrap();
+                    if slot.is_none() {
+                        *slot = Some(url);
+                        drop(slot);
+                        rebuild_tray(&app);
+                    }
+                }
+            }
+        });
+    }
+
+    append_audit(app, "share-start", &name, serde_json::json!({
+        "provider": provider,
+        "passcode": passcode.is_some(),
+    }));
+    let passcode = passcode.is_some();
+    state.tunnels.lock().unwrap().insert(name, Tunnel { child, provider, public_url, expires_at: None, passcode, policy_file });
+    rebuild_tray(app);
+    Ok(())
+}
+
+/// Closes every tunnel, including ones whose server DexHub didn't start.
+fn stop_all_tunnels(app: &tauri::AppHandle) {
+    let names: Vec<String> = app.state::<ServerState>().tunnels.lock().unwrap().keys().cloned().collect();
+    for name in names { stop_tunnel(app, &name); }
+}
+
+fn stop_tunnel(app: &tauri::AppHandle, name: &str) {
+    stop_tunnel_with_reason(app, name, "share-stop");
+}
+
+fn stop_tunnel_with_reason(app: &tauri::AppHandle, name: &str, reason: &str) {
+    let state = app.state::<ServerState>();
+    let removed = state.tunnels.lock().unwrap().remove(name);
+    if let Some(mut tunnel) = removed {
+        let _ = tunnel.child.kill();
+        if let Some(policy) = &tunnel.policy_file { let _ = std::fs::remove_file(policy); }
+        append_audit(app, reason, name, serde_json::json!({ "provider": tunnel.provider }));
+        rebuild_tray(app);
+    }
+}
+
+#[derive(serde::Serialize)]
+struct ShareLink {
+    name:       String,
+    provider:   String,
+    url:        Option<String>, // None until the provider reports it; see get_tunnel_url
+    expires_at: u64,            // unix seconds
+    passcode:   bool,
+}
+
+/// Opens a tunnel that is torn down automatically after `ttl_secs`.
+fn create_share_link(
+    app: &tauri::AppHandle,
+    name: String,
+    ttl_secs: u64,
+    passcode: Option<String>,
+    provider: Option<String>,
+) -> Result<ShareLink, String> {
+    if ttl_secs == 0 { return Err("TTL must be greater than zero".to_string()); }
+    let state = app.state::<ServerState>();
+    if state.tunnels.lock().unwrap().contains_key(&name) {
+        return Err(format!("'{}' is already shared; stop sharing first", name));
+    }
+    let passcode = passcode.filter(|p| !p.is_empty());
+    start_tunnel(app, name.clone(), provider, passcode.clone())?;
+
+    let expires_at = std::time::SystemTime::now() + Duration::from_secs(ttl_secs);
+    let mut tunnels = state.tunnels.lock().unwrap();
+    let tunnel = tunnels.get_mut(&name).ok_or("Tunnel exited immediately")?;
+    tunnel.expires_at = Some(expires_at);
+    let link = ShareLink {
+        name: name.clone(),
+        provider: tunnel.provider.clone(),
+        url: tunnel.public_url.lock().unwrap().clone(),
+        expires_at: unix_now() + ttl_secs,
+        passcode: tunnel.passcode,
+    };
+    drop(tunnels);
+    append_audit(app, "share-link-created", &name, serde_json::json!({ "ttl_secs": ttl_secs, "expires_at": link.expires_at }));
+    Ok(link)
+}
+
+fn spawn_share_expiry_sweeper(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(SHARE_EXPIRY_INTERVAL);
+        let now = std::time::SystemTime::now();
+        let expired: Vec<String> = {
+            let state = app.state::<ServerState>();
+            let tunnels = state.tunnels.lock().unwrap();
+            tunnels.iter()
+                .filter(|(_, t)| t.expires_at.map(|e| e <= now).unwrap_or(false))
+                .map(|(n, _)| n.clone())
+                .collect()
+        };
+        for name in expired {
+            stop_tunnel_with_reason(&app, &name, "share-expired");
+            notify(&app, "lost its public link", &name, format!("Public link for '{}' expired and was closed.", name), "Pop");
+        }
+    });
+}
+
+// ─── Project Scanner ──────────────────────────────────────────────────────────
+
+/// Scans every root in order, then settles port conflicts across all of them.
+fn scan_projects(
+    roots: &[String],
+    port_overrides: &HashMap<String, u16>,
+    app_data_dir: &Path,
+) -> Vec<ProjectConfig> {
+    let mut projects = Vec::new();
+    for root in roots.iter().filter(|r| !r.trim().is_empty()) {
+        projects.extend(scan_root(Path::new(root), port_overrides, app_data_dir));
+    }
+    dedupe_names(&mut projects);
+    // Overrides saved under a project name missed it; the re-keyed file has them
+    let migrated_overrides;
+    let port_overrides = if !ids::key_by_id(app_data_dir, &projects) { port_overrides } else {
+        migrated_overrides = load_port_overrides(app_data_dir);
+        for p in projects.iter_mut() {
+            p.port = migrated_overrides.get(&p.id).copied().unwrap_or(p.default_port);
+        }
+        &migrated_overrides
+    };
+    ids::migrate_renames(app_data_dir, &projects);
+    resolve_port_conflicts(&mut projects, port_overrides, app_data_dir);
+    fill_git_info(&mut projects);
+    projects.sort_by(|a, b| a.name.cmp(&b.name));
+    projects
+}
+
+fn scan_root(
+    base_dir: &Path,
+    port_overrides: &HashMap<String, u16>,
+    app_data_dir: &Path,
+) -> Vec<ProjectConfig> {
+    let mut projects = Vec::new();
+
+    let walker = WalkDir::new(base_dir)
+        .min_depth(1)
+        .max_depth(4)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| {
+            let s = e.path().to_string_lossy();
+            !s.contains("node_modules")
+                && !s.contains("/.git")
+                && !s.contains("/.cache")
+                && !s.contains("/.claude")
+                && !s.contains("/dist/")
+                && !s.contains("/build/")
+                && !s.contains("/.next")
+                && !s.contains("/target/")
+        });
+
+    for entry in walker.filter_map(|e| e.ok()) {
+        if entry.file_name() != "package.json" { continue; }
+
+        let pkg_path = entry.path();
+        let project_dir = match pkg_path.parent() { Some(d) => d, None => continue };
+
+        // Skip Tauri apps — launching them would conflict with the host
+        if project_dir.join("src-tauri").join("tauri.conf.json").exists() { continue; }
+
+        let content = match std::fs::read_to_string(pkg_path) { Ok(c) => c, Err(_) => continue };
+        let val: serde_json::Value = match serde_json::from_str(&content) { Ok(v) => v, Err(_) => continue };
+
+        let dev_script = match val["scripts"]["dev"].as_str() {
+            Some(s) if !s.trim().is_empty() => s.to_string(),
+            _ => continue,
+        };
+
+        let name = val["name"]
+            .as_str()
+            .unwrap_or_else(|| {
+                project_dir.file_name().and_then(|n| n.to_str()).unwrap_or("unknown")
+            })
+            .to_string();
+        if name.trim().is_empty() { continue; }
+
+        let package_manager = detect_package_manager(&val, project_dir, base_dir)
+            .unwrap_or_else(|| if dev_script.trim_start().starts_with("pnpm") { "pnpm" } else { "npm" }.to_string());
+        let (command, args) = if dev_script.trim_start().starts_with("pnpm") {
+            let rest = dev_script.trim_start_matches("pnpm").trim().to_string();
+            let pnpm_args: Vec<String> = if rest.is_empty() {
+                vec!["dev".to_string()]
+            } else {
+                rest.split_whitespace().map(|s| s.to_string()).collect()
+            };
+            ("pnpm".to_string(), pnpm_args)
+        } else {
+            let args: &[&str] = match package_manager.as_str() {
+                "yarn" | "pnpm" => &["dev"],
+                _ => &["run", "dev"], // npm, bun
+            };
+            (package_manager.clone(), args.iter().map(|a| a.to_string()).collect())
+        };
+
+        // default_port = what the project declares; port = after override
+        let default_port = extract_port(project_dir);
+        let mut port = default_port;
+        let id = ids::project_id(&project_dir.to_string_lossy());
+        if let Some(&override_port) = port_overrides.get(&id) { port = override_port; }
+
+        // Extra ports declared via  "dexhub": { "ports": [3000, { "port": 6006, "label": "Storybook" }] }
+        // in package.json
+        let extra_ports: Vec<ExtraPort> = val["dexhub"]["ports"]
+            .as_array()
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| match v.as_u64() {
+                        Some(p) => Some(ExtraPort { port: p as u16, label: None }),
+                        None => Some(ExtraPort {
+                            port: v["port"].as_u64()? as u16,
+                            label: v["label"].as_str().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()),
+                        }),
+                    })
+                    .filter(|p| p.port != port)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // "dexhub": { "openPath": "/docs" } — the page opened, not the one health-checked
+        let open_path = val["dexhub"]["openPath"].as_str()
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty() && *p != "/")
+            .map(|p| if p.starts_with('/') { p.to_string() } else { format!("/{}", p) });
+
+        let icon_path = find_icon(project_dir);
+        let icon_data = icon_path.as_ref().and_then(|p| icon_to_base64(p));
+        let accent_color = icon_path.as_ref().and_then(|p| icon_accent_color(p));
+        let workspace = extract_workspace(base_dir, &project_dir.to_string_lossy());
+
+        projects.push(ProjectConfig {
+            id,
+            name, cwd: project_dir.to_string_lossy().into_owned(),
+            command, args, port, default_port, extra_ports,
+            icon_path, icon_data, accent_color, workspace,
+            git: None,
+            compose_file: find_compose_file(project_dir),
+            deps: deps_status(project_dir),
+            package_manager: Some(package_manager),
+            open_path,
+        });
+    }
+
+    #[cfg(feature = "wasm-plugins")]
+    scan_plugin_projects(base_dir, app_data_dir, port_overrides, &mut projects);
+
+    projects
+}
+
+/// Re-scans the scan roots into state, rebuilds the tray and fires `scan-finished`.
+fn rescan_projects(app: &tauri::AppHandle) -> Vec<ProjectConfig> {
+    let app_data_dir = app.path().app_data_dir().unwrap_or_else(|_| std::path::PathBuf::from("/tmp"));
+    let overrides = load_port_overrides(&app_data_dir);
+    let roots = app.state::<ServerState>().general.lock().unwrap().scan_roots.clone();
+    let new_projects = scan_projects(&roots, &overrides, &app_data_dir);
+    // A project found under a new name had its settings migrated on disk;
+    // a full reload picks them up in state too
+    let renamed = {
+        let state = app.state::<ServerState>();
+        let old = state.projects.lock().unwrap();
+        new_projects.iter().any(|p| old.iter().any(|o| o.id == p.id && o.name != p.name))
+    };
+    if renamed {
+        if let Err(e) = reload_config(app) { eprintln!("[DexHub] Reload after a rename failed: {}", e); }
+        return app.state::<ServerState>().projects.lock().unwrap().clone();
+    }
+    { let state = app.state::<ServerState>(); *state.projects.lock().unwrap() = new_projects.clone(); }
+    rebuild_tray(app);
+    fire_hooks(app, "scan-finished", None, serde_json::json!({ "project_count": new_projects.len() }));
+    new_projects
+}
+
+// ─── Scheduled Scans ──────────────────────────────────────────────────────────
+
+/// How often the scheduler wakes to see whether a scan is due.
+const SCHEDULED_SCAN_POLL: Duration = Duration::from_secs(300);
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct ScheduledScanConfig {
+    enabled:  bool,
+    hour:     u8,  // local hour of day the nightly scan runs
+    last_run: u64, // unix seconds
+}
+
+impl Default for ScheduledScanConfig {
+    fn default() -> Self {
+        ScheduledScanConfig { enabled: false, hour: 3, last_run: 0 }
+    }
+}
+
+#[derive(Clone, Default, serde::Serialize)]
+struct ScanChanges {
+    added:   Vec<String>,
+    changed: Vec<String>, // dev command differs from the cached inventory
+}
+
+fn scheduled_scan_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("scheduled_scan.json")
+}
+
+fn load_scheduled_scan_config(app_data_dir: &Path) -> ScheduledScanConfig {
+    std::fs::read_to_string(scheduled_scan_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_scheduled_scan_config_to_disk(app_data_dir: &Path, config: &ScheduledScanConfig) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(config) {
+        let _ = std::fs::write(scheduled_scan_path(app_data_dir), json);
+    }
+}
+
+/// Project name → dev command line, as of the last scheduled scan.
+fn inventory_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("inventory.json")
+}
+
+fn load_inventory(app_data_dir: &Path) -> HashMap<String, String> {
+    std::fs::read_to_string(inventory_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_inventory_to_disk(app_data_dir: &Path, inventory: &HashMap<String, String>) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(inventory) {
+        let _ = std::fs::write(inventory_path(app_data_dir), json);
+    }
+}
+
+/// Current local hour via `date`, which already knows the user's time zone.
+fn local_hour() -> Option<u8> {
+    let output = std::process::Command::new("date").arg("+%H").output().ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Minutes since local midnight, for quiet hours and server schedules.
+fn local_minute_of_day() -> Option<u32> {
+    let output = std::process::Command::new("date").arg("+%H:%M").output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let (h, m) = text.trim().split_once(':')?;
+    Some(h.parse::<u32>().ok()? * 60 + m.parse::<u32>().ok()?)
+}
+
+/// ISO day of the week, 1 = Monday … 7 = Sunday.
+fn local_weekday() -> Option<u8> {
+    let output = std::process::Command::new("date").arg("+%u").output().ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Rescans, diffs against the cached inventory and notifies about new
+/// projects and changed dev commands. The first run only seeds the cache.
+fn run_scheduled_scan(app: &tauri::AppHandle) -> Result<ScanChanges, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let previous = load_inventory(&app_data_dir);
+    let projects = rescan_projects(app);
+    let inventory: HashMap<String, String> = projects.iter()
+        .map(|p| (p.name.clone(), format!("{} {}", p.command, p.args.join(" ")).trim().to_string()))
+        .collect();
+
+    let mut changes = ScanChanges::default();
+    if !previous.is_empty() {
+        for (name, command) in &inventory {
+            match previous.get(name) {
+                None => changes.added.push(name.clone()),
+                Some(old) if old != command => changes.changed.push(name.clone()),
+                _ => {}
+            }
+        }
+        changes.added.sort();
+        changes.changed.sort();
+    }
+    save_inventory_to_disk(&app_data_dir, &inventory);
+
+    let mut config = app.state::<ServerState>().scheduled_scan.lock().unwrap().clone();
+    config.last_run = unix_now();
+    save_scheduled_scan_config_to_disk(&app_data_dir, &config);
+    *app.state::<ServerState>().scheduled_scan.lock().unwrap() = config;
+
+    for name in &changes.added {
+        notify(app, "appeared", name, format!("New project found: {}", name), "Glass");
+    }
+    for name in &changes.changed {
+        notify(app, "changed", name, format!("{} now runs `{}`", name, inventory[name]), "Glass");
+    }
+    record_event(app, "scheduled-scan", None, serde_json::to_value(&changes).unwrap_or_default());
+    Ok(changes)
+}
+
+fn spawn_scan_scheduler(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(SCHEDULED_SCAN_POLL);
+        let config = app.state::<ServerState>().scheduled_scan.lock().unwrap().clone();
+        if !config.enabled || scans_paused(&app) { continue; }
+        let since_last = unix_now().saturating_sub(config.last_run);
+        // At the configured hour, at most once a day; if the hour can't be read, every 24h
+        let due = match local_hour() {
+            Some(hour) => hour == config.hour && since_last >= 20 * 3600,
+            None => since_last >= 24 * 3600,
+        };
+        if due {
+            if let Err(e) = run_scheduled_scan(&app) {
+                eprintln!("[DexHub] Scheduled scan failed: {}", e);
+            }
+        }
+    });
+}
+
+// ─── Server Schedules ─────────────────────────────────────────────────────────
+
+/// How often the scheduler checks the clock; a time is acted on once when
+/// the clock passes it, so polling more coarsely than a minute is fine.
+const SERVER_SCHEDULE_POLL: Duration = Duration::from_secs(20);
+
+/// "Start weekdays at 09:00, stop at 18:30". Either time may be left out.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct ServerSchedule {
+    enabled: bool,
+    days:    Vec<u8>,        // ISO weekdays, 1 = Monday … 7 = Sunday
+    start:   Option<String>, // "HH:MM", local time
+    stop:    Option<String>,
+}
+
+impl Default for ServerSchedule {
+    fn default() -> Self {
+        ServerSchedule { enabled: true, days: vec![1, 2, 3, 4, 5], start: None, stop: None }
+    }
+}
+
+fn server_schedules_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("server_schedules.json")
+}
+
+fn load_server_schedules(app_data_dir: &Path) -> HashMap<String, ServerSchedule> {
+    std::fs::read_to_string(server_schedules_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_server_schedules_to_disk(app_data_dir: &Path, schedules: &HashMap<String, ServerSchedule>) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(schedules) {
+        let _ = std::fs::write(server_schedules_path(app_data_dir), json);
+    }
+}
+
+/// Starts and stops scheduled servers whose time the clock passed since
+/// `last` (the previous check's weekday and minute). On the first check
+/// after launch, servers inside their start–stop window are started, so a
+/// mid-morning launch still brings the demo environment up.
+fn run_server_schedules(app: &tauri::AppHandle, last: Option<(u8, u32)>, day: u8, minute: u32) {
+    let state = app.state::<ServerState>();
+    let schedules = state.server_schedules.lock().unwrap().clone();
+    let known: HashSet<String> = state.projects.lock().unwrap().iter().map(|p| p.name.clone()).collect();
+    let passed = |time: u32| match last {
+        Some((d, m)) if d == day => m < time && time <= minute,
+        Some(_) => time <= minute, // first check of a new day
+        None => false,
+    };
+    for (name, schedule) in schedules {
+        if !schedule.enabled || !schedule.days.contains(&day) || !known.contains(&name) { continue; }
+        let start = schedule.start.as_deref().and_then(notifications::parse_hhmm);
+        let stop = schedule.stop.as_deref().and_then(notifications::parse_hhmm);
+        let running = is_running(&state, &name);
+        let in_window = matches!((start, stop), (Some(s), Some(e)) if s <= minute && minute < e);
+        if stop.is_some_and(passed) {
+            // Stopping is destructive, so it waits out presentation mode
+            if running && !presenting(app) {
+                if let Err(e) = stop_server(app, name.clone()) {
+                    eprintln!("[DexHub] {}", e);
+                }
+                record_event(app, "scheduled-stop", Some(&name), serde_json::json!({}));
+            }
+        } else if !running && (start.is_some_and(passed) || (last.is_none() && in_window)) {
+            match start_server(app, name.clone()) {
+                Ok(()) => record_event(app, "scheduled-start", Some(&name), serde_json::json!({})),
+                Err(e) => eprintln!("[DexHub] Scheduled start of '{}' failed: {}", name, e),
+            }
+        }
+    }
+}
+
+fn spawn_server_scheduler(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut last: Option<(u8, u32)> = None;
+        loop {
+            if let (Some(day), Some(minute)) = (local_weekday(), local_minute_of_day()) {
+                run_server_schedules(&app, last, day, minute);
+                last = Some((day, minute));
+            }
+            std::thread::sleep(SERVER_SCHEDULE_POLL);
+        }
+    });
+}
+
+// ─── Config Reload ────────────────────────────────────────────────────────────
+
+#[derive(Default, serde::Serialize)]
+struct ConfigReloadReport {
+    settings_changed: Vec<String>, // names of settings files whose contents changed
+    projects_added:   Vec<String>,
+    projects_removed: Vec<String>,
+    projects_changed: Vec<String>,
+    restart_needed:   Vec<String>, // running servers whose launch config changed
+}
+
+fn same_json<T: serde::Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// Re-reads every settings file and package.json, then swaps the results into
+/// state in one pass. Running servers are left alone; those whose launch
+/// config changed are listed in `restart_needed` instead.
+fn reload_config(app: &tauri::AppHandle) -> Result<ConfigReloadReport, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+
+    // Scan first: a renamed package's settings are migrated to its new name
+    // on disk, and everything below should read them from there
+    let scan_roots     = settings::load_settings(&app_data_dir).general.scan_roots;
+    let new_projects   = scan_projects(&scan_roots, &load_port_overrides(&app_data_dir), &app_data_dir);
+
+    // Read everything before touching state so a reload is all-or-nothing
+    let env_overrides  = load_env_overrides(&app_data_dir);
+    let env_profiles   = load_env_profiles(&app_data_dir);
+    let launch_commands = load_launch_commands(&app_data_dir);
+    let lifecycle_hooks = load_lifecycle_hooks(&app_data_dir);
+    let url_hosts      = load_url_hosts(&app_data_dir);
+    let warmup         = load_warmup_overrides(&app_data_dir);
+    let health_checks  = load_health_checks(&app_data_dir);
+    let settings       = settings::load_settings(&app_data_dir);
+    let server_schedules = load_server_schedules(&app_data_dir);
+    let resource_limits = load_resource_limits(&app_data_dir);
+    let focus_config   = focus::load_focus_config(&app_data_dir);
+    let pull_before_start = load_pull_before_start(&app_data_dir);
+    let remote_config  = remote::load_remote_api_config(&app_data_dir);
+    let devices        = remote::load_device_registry(&app_data_dir);
+    let proxy_lazy     = proxy::load_proxy_config(&app_data_dir).lazy;
+
+    let mut report = ConfigReloadReport::default();
+    let state = app.state::<ServerState>();
+    let running_keys: HashSet<String> = state.processes.lock().unwrap().keys().cloned().collect();
+    let running: HashSet<String> = running_names(&state).into_iter().collect();
+    let names_by_id: HashMap<String, String> = new_projects.iter().map(|p| (p.id.clone(), p.name.clone())).collect();
+
+    {
+        let mut projects = state.projects.lock().unwrap();
+        let old: HashMap<&str, &ProjectConfig> = projects.iter().map(|p| (p.name.as_str(), p)).collect();
+        let new_names: HashSet<&str> = new_projects.iter().map(|p| p.name.as_str()).collect();
+        for p in &new_projects {
+            match old.get(p.name.as_str()) {
+                None => report.projects_added.push(p.name.clone()),
+                Some(prev) if !same_json(*prev, p) => {
+                    report.projects_changed.push(p.name.clone());
+                    let relaunch = prev.command != p.command || prev.args != p.args
+                        || prev.port != p.port || prev.cwd != p.cwd;
+                    if relaunch && running.contains(&p.name) { report.restart_needed.push(p.name.clone()); }
+                }
+                _ => {}
+            }
+        }
+        report.projects_removed = old.keys().filter(|n| !new_names.contains(*n)).map(|n| n.to_string()).collect();
+        *projects = new_projects;
+    }
+    {
+        let mut current = state.env_overrides.lock().unwrap();
+        if *current != env_overrides {
+            report.settings_changed.push("env_overrides.json".to_string());
+            for key in env_overrides.keys().chain(current.keys()) {
+                let name = names_by_id.get(key).unwrap_or(key);
+                if running_keys.contains(key) && current.get(key) != env_overrides.get(key)
+                    && !report.restart_needed.contains(name)
+                {
+                    report.restart_needed.push(name.clone());
+                }
+            }
+            *current = env_overrides;
+        }
+    }
+    {
+        let mut current = state.env_profiles.lock().unwrap();
+        if *current != env_profiles {
+            report.settings_changed.push("env_profiles.json".to_string());
+            for name in env_profiles.keys().chain(current.keys()) {
+                let before = current.get(name).map(EnvProfiles::active_vars);
+                let after = env_profiles.get(name).map(EnvProfiles::active_vars);
+                if running.contains(name) && before != after && !report.restart_needed.contains(name) {
+                    report.restart_needed.push(name.clone());
+                }
+            }
+            *current = env_profiles;
+        }
+    }
+    {
+        let mut current = state.launch_commands.lock().unwrap();
+        if *current != launch_commands {
+            report.settings_changed.push("launch_commands.json".to_string());
+            for name in launch_commands.keys().chain(current.keys()) {
+                if running.contains(name) && current.get(name) != launch_commands.get(name)
+                    && !report.restart_needed.contains(name)
+                {
+                    report.restart_needed.push(name.clone());
+                }
+            }
+            *current = launch_commands;
+        }
+    }
+    {
+        let mut current = state.lifecycle_hooks.lock().unwrap();
+        if *current != lifecycle_hooks {
+            report.settings_changed.push("lifecycle_hooks.json".to_string());
+            *current = lifecycle_hooks;
+        }
+    }
+    {
+        let mut current = state.url_hosts.lock().unwrap();
+        if *current != url_hosts {
+            report.settings_changed.push("url_hosts.json".to_string());
+            *current = url_hosts;
+        }
+    }
+    {
+        let mut current = state.warmup_secs.lock().unwrap();
+        if *current != warmup {
+            report.settings_changed.push("health_warmup.json".to_string());
+            *current = warmup;
+        }
+    }
+    {
+        let mut current = state.health_checks.lock().unwrap();
+        if *current != health_checks {
+            report.settings_changed.push("health_checks.json".to_string());
+            *current = health_checks;
+        }
+    }
+    if !same_json(&settings::snapshot(&state), &settings) {
+        report.settings_changed.push("settings.json".to_string());
+        settings::apply(&state, settings);
+        let _ = app.emit("settings-changed", settings::snapshot(&state));
+    }
+    {
+        let mut current = state.server_schedules.lock().unwrap();
+        if !same_json(&*current, &server_schedules) {
+            report.settings_changed.push("server_schedules.json".to_string());
+            *current = server_schedules;
+        }
+    }
+    {
+        let mut current = state.resource_limits.lock().unwrap();
+        if !same_json(&*current, &resource_limits) {
+            report.settings_changed.push("resource_limits.json".to_string());
+            *current = resource_limits;
+        }
+    }
+    {
+        let mut current = state.focus.lock().unwrap();
+        if !same_json(&*current, &focus_config) {
+            report.settings_changed.push("focus.json".to_string());
+            *current = focus_config;
+        }
+    }
+    {
+        let mut current = state.pull_before_start.lock().unwrap();
+        if *current != pull_before_start {
+            report.settings_changed.push("pull_before_start.json".to_string());
+            *current = pull_before_start;
+        }
+    }
+    {
+        // Just the wake-on-demand list, which a rename can migrate; listener
+        // changes go through set_proxy_config
+        let proxy = app.state::<proxy::ProxyState>();
+        let mut current = proxy.config.lock().unwrap();
+        if current.lazy != proxy_lazy {
+            report.settings_changed.push("proxy.json".to_string());
+            current.lazy = proxy_lazy;
+        }
+    }
+
+    let remote = app.state::<remote::RemoteApiState>();
+    let remote_changed = {
+        let mut current = remote.config.lock().unwrap();
+        let changed = !same_json(&*current, &remote_config);
+        *current = remote_config;
+        changed
+    };
+    {
+        let mut current = remote.devices.lock().unwrap();
+        if !same_json(&*current, &devices) {
+            report.settings_changed.push("device_registry.json".to_string());
+            *current = devices;
+        }
+    }
+    if remote_changed {
+        report.settings_changed.push("remote_api.json".to_string());
+        remote::apply_remote_api_config(app)?;
+    }
+
+    // Favorites and tags are read from disk on demand; the tray picks them up here
+    rebuild_tray(app);
+    record_event(app, "config-reloaded", None, serde_json::to_value(&report).unwrap_or_default());
+    Ok(report)
+}
+
+/// Offers directories up to two levels deep that aren't already npm projects
+/// to the WASM detector plugins; the first plugin to claim one wins.
+#[cfg(feature = "wasm-plugins")]
+fn scan_plugin_projects(
+    base_dir: &Path,
+    app_data_dir: &Path,
+    port_overrides: &HashMap<String, u16>,
+    projects: &mut Vec<ProjectConfig>,
+) {
+    let detectors = plugins::load_detectors(app_data_dir);
+    if detectors.is_empty() { return; }
+    let known: HashSet<String> = projects.iter().map(|p| p.cwd.clone()).collect();
+
+    let dirs = WalkDir::new(base_dir)
+        .min_depth(1)
+        .max_depth(2)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| {
+            let n = e.file_name().to_string_lossy();
+            !n.starts_with('.') && n != "node_modules" && n != "target" && n != "dist" && n != "build"
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir());
+
+    for entry in dirs {
+        let dir = entry.path();
+        let cwd = dir.to_string_lossy().into_owned();
+        if known.contains(&cwd) || dir.join("package.json").exists() { continue; }
+        let Some(found) = detectors.iter().find_map(|d| d.detect(dir)) else { continue };
+        if found.name.trim().is_empty() || projects.iter().any(|p| p.name == found.name) { continue; }
+
+        let default_port = found.port.unwrap_or(8000);
+        let port = port_overrides.get(&ids::project_id(&cwd)).copied().unwrap_or(default_port);
+        let icon_path = find_icon(dir);
+        projects.push(ProjectConfig {
+            icon_data: icon_path.as_ref().and_then(|p| icon_to_base64(p)),
+            accent_color: icon_path.as_ref().and_then(|p| icon_accent_color(p)),
+            icon_path,
+            workspace: extract_workspace(base_dir, &cwd),
+            id: ids::project_id(&cwd),
+            name: found.name,
+            cwd,
+            command: found.command,
+            args: found.args,
+            port,
+            default_port,
+            extra_ports: Vec::new(),
+            git: None,
+            compose_file: find_compose_file(dir),
+            deps: deps_status(dir),
+            package_manager: None,
+            open_path: None,
+        });
+    }
+}
+
+// ─── Tray Menu Builder ────────────────────────────────────────────────────────
+
+fn running_tray_item<M: tauri::Manager<tauri::Wry>>(
+    manager: &M,
+    project: &ProjectConfig,
+    view: &TrayView,
+) -> Box<dyn IsMenuItem<tauri::Wry>> {
+    let url   = view.urls.get(&project.name).cloned().unwrap_or_default();
+    // 🟢 passing health checks, 🟡 still starting or failing them
+    let dot   = if view.healthy.contains(&project.name) { "🟢" } else { "🟡" };
+    let label = format!("{} {}", dot, project.name);
+    let sub   = Submenu::new(manager, &label, true).expect("submenu");
+    let info  = MenuItem::with_id(manager, format!("_info__{}", project.name), tray_info_text(manager, &project.name), false, None::<&str>).expect("info");
+    sub.append(&info).ok();
+    if let Some(rows) = manager.try_state::<TrayInfoRows>() {
+        rows.0.lock().unwrap().insert(project.name.clone(), info);
+    }
+    // Presentation mode: read-only entries, nothing that stops servers or reveals public URLs
+    if view.presenting {
+        sub.append(&MenuItem::with_id(manager, format!("open__{}", project.name), "Open in Browser", true, None::<&str>).expect("open")).ok();
+        sub.append(&MenuItem::with_id(manager, format!("url__{}", project.name), &url, true, None::<&str>).expect("url")).ok();
+        return Box::new(sub);
+    }
+    let restart_label = if view.runaway.contains(&project.name) { "⚠ Restart (runaway CPU)" } else { "Restart" };
+    sub.append(&MenuItem::with_id(manager, format!("restart__{}", project.name), restart_label, true, None::<&str>).expect("restart")).ok();
+    sub.append(&MenuItem::with_id(manager, format!("restartclean__{}", project.name), "Restart with Cleared Cache", true, None::<&str>).expect("restart clean")).ok();
+    sub.append(&MenuItem::with_id(manager, format!("open__{}", project.name), "Open in Browser", true, None::<&str>).expect("open")).ok();
+    for extra in &project.extra_ports {
+        let label = format!("Open {}", extra.display());
+        sub.append(&MenuItem::with_id(manager, format!("openport__{}__{}", extra.port, project.name), label, true, None::<&str>).expect("open port")).ok();
+    }
+    sub.append(&MenuItem::with_id(manager, format!("logs__{}", project.name), "View Logs", true, None::<&str>).expect("logs")).ok();
+    sub.append(&MenuItem::with_id(manager, format!("copylogs__{}", project.name), format!("Copy Last {} Log Lines", TRAY_COPY_LOG_LINES), true, None::<&str>).expect("copy logs")).ok();
+    sub.append(&MenuItem::with_id(manager, format!("copyerrors__{}", project.name), "Copy Error Lines", true, None::<&str>).expect("copy errors")).ok();
+    sub.append(&MenuItem::with_id(manager, format!("editor__{}", project.name), "Open in Editor", true, None::<&str>).expect("editor")).ok();
+    if project.compose_file.is_some() {
+        let (id, label) = if view.compose.contains(&project.name) { ("composedown__", "Stop Services") } else { ("composeup__", "Start Services") };
+        sub.append(&MenuItem::with_id(manager, format!("{}{}", id, project.name), label, true, None::<&str>).expect("compose")).ok();
+    }
+    let (id, label) = if view.previews.contains(&project.name) { ("previewstop__", "Stop Production Preview") } else { ("previewstart__", "Preview Production Build") };
+    sub.append(&MenuItem::with_id(manager, format!("{}{}", id, project.name), label, true, None::<&str>).expect("preview")).ok();
+    let tests = view.script_runs.get(&project.name).filter(|r| r.script == "test");
+    let idle = !tests.is_some_and(|r| r.running);
+    sub.append(&MenuItem::with_id(manager, format!("runtests__{}", project.name), script_status_label(tests, "Run Tests"), idle, None::<&str>).expect("tests")).ok();
+    sub.append(&MenuItem::with_id(manager, format!("reveal__{}", project.name), platform::REVEAL_LABEL, true, None::<&str>).expect("reveal")).ok();
+    sub.append(&copy_url_submenu(manager, project, &url)).ok();
+    match view.tunnel_urls.get(&project.name) {
+        Some(Some(public_url)) => {
+            sub.append(&MenuItem::with_id(manager, format!("tunnelurl__{}", project.name), public_url, true, None::<&str>).expect("tunnel url")).ok();
+            sub.append(&MenuItem::with_id(manager, format!("unshare__{}", project.name), "Stop Sharing", true, None::<&str>).expect("unshare")).ok();
+        }
+        Some(None) => {
+            sub.append(&MenuItem::with_id(manager, format!("unshare__{}", project.name), "Sharing… (Stop)", true, None::<&str>).expect("unshare")).ok();
+        }
+        None => {
+            sub.append(&MenuItem::with_id(manager, format!("share__{}", project.name), "Share Publicly", true, None::<&str>).expect("share")).ok();
+        }
+    }
+    sub.append(&MenuItem::with_id(manager, format!("stop__{}", project.name), "Stop", true, None::<&str>).expect("stop")).ok();
+    Box::new(sub)
+}
+
+/// The URL itself copies it as shown; the rest copy it in another form.
+fn copy_url_submenu<M: tauri::Manager<tauri::Wry>>(manager: &M, project: &ProjectConfig, url: &str) -> Submenu<tauri::Wry> {
+    let sub = Submenu::new(manager, "Copy URL", true).expect("submenu");
+    for (id, label) in [
+        ("url__", url),
+        ("urlmd__", "As Markdown Link"),
+        ("urlcurl__", "As curl Command"),
+        ("urllocal__", "localhost URL"),
+        ("urltailnet__", "Tailscale URL"),
+    ] {
+        sub.append(&MenuItem::with_id(manager, format!("{}{}", id, project.name), label, true, None::<&str>).expect("copy url")).ok();
+    }
+    sub
+}
+
+fn stopped_tray_item<M: tauri::Manager<tauri::Wry>>(
+    manager: &M,
+    project: &ProjectConfig,
+    view: &TrayView,
+) -> Box<dyn IsMenuItem<tauri::Wry>> {
+    let start_id = format!("start__{}", project.name);
+    // Crashed servers keep their logs; the red dot says there's something to read
+    if view.crashed.contains(&project.name) {
+        let label = if view.flapping.contains(&project.name) {
+            format!("🔴 {} (flapping)", project.name)
+        } else {
+            format!("🔴 {}", project.name)
+        };
+        return Box::new(MenuItem::with_id(manager, &start_id, &label, true, None::<&str>).expect("start"));
+    }
+    let label = match project.deps {
+        DepsStatus::Installed => project.name.clone(),
+        DepsStatus::Missing => format!("{} (no node_modules)", project.name),
+        DepsStatus::Stale => format!("{} (dependencies outdated)", project.name),
+    };
+    if let Some(icon) = project.icon_path.as_deref().and_then(load_icon_image) {
+        if let Ok(item) = IconMenuItem::with_id(manager, &start_id, &label, true, Some(icon), None::<&str>) {
+            return Box::new(item);
+        }
+    }
+    Box::new(MenuItem::with_id(manager, &start_id, &label, true, None::<&str>).expect("start"))
+}
+
+fn build_tray_menu<M: tauri::Manager<tauri::Wry>>(
+    manager: &M,
+    projects: &[ProjectConfig],
+    view: &TrayView,
+) -> Menu<tauri::Wry> {
+    let menu = Menu::new(manager).expect("menu");
+    if view.safe_mode {
+        menu.append(&MenuItem::with_id(manager, "_safe_", "⚠ Safe Mode — projects not scanned", false, None::<&str>).expect("safe mode")).ok();
+        menu.append(&MenuItem::with_id(manager, "restartnormal", "Restart Normally", true, None::<&str>).expect("restart normally")).ok();
+    }
+    menu.append(&PredefinedMenuItem::separator(manager).expect("sep")).ok();
+    menu.append(
+        &MenuItem::with_id(manager, "_header_", "─── Servers ───", false, None::<&str>).expect("header"),
+    ).ok();
+
+    let is_running = |p: &ProjectConfig| view.running.iter().any(|n| n == &p.name);
+    let pinned: Vec<&ProjectConfig> = view.favorites.iter()
+        .filter_map(|f| projects.iter().find(|p| &p.name == f))
+        .collect();
+    for project in &pinned {
+        let item = if is_running(project) { running_tray_item(manager, project, view) } else { stopped_tray_item(manager, project, view) };
+        menu.append(item.as_ref()).ok();
+    }
+    for (index, favorite) in &view.action_favorites {
+        if !projects.iter().any(|p| p.name == favorite.project) { continue; }
+        menu.append(&MenuItem::with_id(manager, format!("fav__{}", index), format!("★ {}", favorite.display_label()), true, None::<&str>).expect("favorite")).ok();
+    }
+    if !pinned.is_empty() || !view.action_favorites.is_empty() {
+        menu.append(&PredefinedMenuItem::separator(manager).expect("sep")).ok();
+    }
+
+    // Favorites already sit above, so Recent only lists what isn't pinned
+    let recent: Vec<&ProjectConfig> = view.recent.iter()
+        .filter(|r| !view.favorites.contains(r))
+        .filter_map(|r| projects.iter().find(|p| &p.name == r))
+        .collect();
+    if !recent.is_empty() {
+        menu.append(&MenuItem::with_id(manager, "_recent_", "Recent", false, None::<&str>).expect("header")).ok();
+        for project in &recent {
+            let item = if is_running(project) { running_tray_item(manager, project, view) } else { stopped_tray_item(manager, project, view) };
+            menu.append(item.as_ref()).ok();
+        }
+        menu.append(&PredefinedMenuItem::separator(manager).expect("sep")).ok();
+    }
+
+    let mut rest: Vec<&ProjectConfig> = projects.iter()
+        .filter(|p| !view.favorites.contains(&p.name) && !recent.iter().any(|r| r.name == p.name))
+        .collect();
+    rest.sort_by_key(|p| p.name.to_lowercase());
+
+    // Running and crashed servers stay at the top level; everything else is filed
+    // under its workspace so the menu stays scannable with dozens of projects.
+    let mut workspaces: BTreeMap<&str, Vec<&ProjectConfig>> = BTreeMap::new();
+    for project in rest {
+        if view.running.iter().any(|n| n == &project.name) {
+            menu.append(running_tray_item(manager, project, view).as_ref()).ok();
+        } else if view.crashed.contains(&project.name) {
+            menu.append(stopped_tray_item(manager, project, view).as_ref()).ok();
+        } else {
+            workspaces.entry(project.workspace.as_str()).or_default().push(project);
+        }
+    }
+    for (workspace, members) in &workspaces {
+        let label = if workspace.is_empty() { "Other" } else { workspace };
+        let sub = Submenu::new(manager, label, true).expect("submenu");
+        for project in members {
+            sub.append(stopped_tray_item(manager, project, view).as_ref()).ok();
+        }
+        menu.append(&sub).ok();
+    }
+
+    if !view.brew.is_empty() {
+        menu.append(&PredefinedMenuItem::separator(manager).expect("sep")).ok();
+        menu.append(&MenuItem::with_id(manager, "_brew_", "─── Homebrew ───", false, None::<&str>).expect("header")).ok();
+        for service in &view.brew {
+            let (dot, action) = if service.is_running() { ("🟢", "Stop") } else if service.status == "error" { ("🔴", "Start") } else { ("⚪", "Start") };
+            let label = format!("{} {} — {}", dot, service.name, action);
+            // Stopping is destructive, so running services are read-only while presenting
+            let enabled = !(view.presenting && service.is_running());
+            menu.append(&MenuItem::with_id(manager, format!("brew__{}", service.name), &label, enabled, None::<&str>).expect("brew")).ok();
+        }
+    }
+
+    if !view.containers.is_empty() {
+        menu.append(&PredefinedMenuItem::separator(manager).expect("sep")).ok();
+        menu.append(&MenuItem::with_id(manager, "_docker_", "─── Docker ───", false, None::<&str>).expect("header")).ok();
+        for container in &view.containers {
+            let (dot, action) = if container.is_running() { ("🟢", "Stop") } else { ("⚪", "Start") };
+            let ports = container.ports.iter().map(|p| format!(":{}", p)).collect::<Vec<_>>().join(" ");
+            let label = if ports.is_empty() {
+                format!("{} {} — {}", dot, container.name, action)
+            } else {
+                format!("{} {} {} — {}", dot, container.name, ports, action)
+            };
+            let enabled = !(view.presenting && container.is_running());
+            menu.append(&MenuItem::with_id(manager, format!("docker__{}", container.id), &label, enabled, None::<&str>).expect("docker")).ok();
+        }
+    }
+
+    menu.append(&PredefinedMenuItem::separator(manager).expect("sep")).ok();
+    let idle_favorites = view.favorites.iter()
+        .filter(|f| !view.running.contains(f) && projects.iter().any(|p| &p.name == *f))
+        .count();
+    if idle_favorites > 0 {
+        menu.append(&MenuItem::with_id(manager, "startfavorites", format!("Start All Favorites ({})", idle_favorites), true, None::<&str>).expect("start favorites")).ok();
+    }
+    if !view.running.is_empty() && !view.presenting {
+        menu.append(&MenuItem::with_id(manager, "stopall", format!("Stop All ({})", view.running.len()), true, None::<&str>).expect("stop all")).ok();
+    }
+    if view.low_power_stop > 0 && !view.presenting {
+        let label = format!("🔋 Stop Non-Favorites ({})", view.low_power_stop);
+        menu.append(&MenuItem::with_id(manager, "stopnonfavorites", label, true, None::<&str>).expect("stop non-favorites")).ok();
+    }
+    menu.append(&MenuItem::with_id(manager, "refresh", "Refresh", true, None::<&str>).expect("refresh")).ok();
+    let presentation_label = if view.presenting { "✓ Presentation Mode" } else { "Presentation Mode" };
+    menu.append(&MenuItem::with_id(manager, "presentation", presentation_label, true, None::<&str>).expect("presentation")).ok();
+    // Quitting stops every server, so it's hidden while presenting
+    if !view.presenting {
+        menu.append(&PredefinedMenuItem::separator(manager).expect("sep")).ok();
+        menu.append(&MenuItem::with_id(manager, "quit", "Quit DexHub", true, None::<&str>).expect("quit")).ok();
+    }
+    menu
+}
+
+/// "Up 2h 14m · 12 ms" — latency is omitted until the first health check.
+fn tray_info_text<M: tauri::Manager<tauri::Wry>>(manager: &M, name: &str) -> String {
+    let Some(state) = manager.try_state::<ServerState>() else { return String::new() };
+    let uptime = uptime_secs(&state, name);
+    let latency = state.latency_cache.lock().unwrap().get(name).copied();
+    let mut text = format!("Up {}", uptime.map(notifications::format_duration).unwrap_or_else(|| "—".to_string()));
+    if let Some(ms) = latency { text.push_str(&format!(" · {} ms", ms)); }
+    text
+}
+
+fn spawn_tray_info_refresher(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(TRAY_INFO_INTERVAL);
+        let rows: Vec<(String, MenuItem<tauri::Wry>)> = app.state::<TrayInfoRows>().0.lock().unwrap()
+            .iter()
+            .map(|(n, item)| (n.clone(), item.clone()))
+            .collect();
+        for (name, item) in rows {
+            let _ = item.set_text(tray_info_text(&app, &name));
+        }
+    });
+}
+
+/// Filled bolt plus a count next to it while anything runs; outlined and bare otherwise.
+fn update_tray_status(tray: &tauri::tray::TrayIcon<tauri::Wry>, running: usize) {
+    let icon = tauri::image::Image::new_owned(lightning_bolt_icon_rgba(running > 0), 22, 22);
+    let _ = tray.set_icon(Some(icon));
+    let _ = tray.set_icon_as_template(true);
+    let _ = tray.set_title(if running > 0 { Some(running.to_string()) } else { None });
+    let tooltip = match running {
+        0 => "DexHub — no servers running".to_string(),
+        1 => "DexHub — 1 server running".to_string(),
+        n => format!("DexHub — {} servers running", n),
+    };
+    let _ = tray.set_tooltip(Some(tooltip));
+}
+
+fn rebuild_tray(app: &tauri::AppHandle) {
+    let server_state = app.state::<ServerState>();
+    if server_state.tray_suspended.load(Ordering::SeqCst) { return; }
+    let tray_handle  = app.state::<TrayHandle>();
+    let projects: Vec<ProjectConfig> = server_state.projects.lock().unwrap().clone();
+    let running = running_names(&server_state);
+    let urls = projects.iter()
+        .filter(|p| running.contains(&p.name))
+        .map(|p| (p.name.clone(), project_url(&server_state, p)))
+        .collect();
+    let view = TrayView {
+        running,
+        urls,
+        tunnel_urls:    server_state.tunnels.lock().unwrap()
+            .iter()
+            .map(|(n, t)| (n.clone(), t.public_url.lock().unwrap().clone()))
+            .collect(),
+        runaway:        server_state.runaway.lock().unwrap().clone(),
+        favorites:      app.path().app_data_dir().map(|d| load_favorites_from_disk(&d)).unwrap_or_default(),
+        action_favorites: app.path().app_data_dir().map(|d| load_action_favorites(&d)).unwrap_or_default(),
+        recent:         app.path().app_data_dir().map(|d| load_recent_from_disk(&d)).unwrap_or_default(),
+        presenting:     server_state.presentation_mode.load(Ordering::SeqCst),
+        healthy:        server_state.last_health.lock().unwrap()
+            .iter()
+            .filter(|(_, ok)| **ok)
+            .map(|(n, _)| n.clone())
+            .collect(),
+        crashed:        server_state.crashed.lock().unwrap().clone(),
+        flapping:       server_state.flapping.lock().unwrap().clone(),
+        low_power_stop: if server_state.low_power.load(Ordering::SeqCst) && server_state.battery_policy.lock().unwrap().prompt_stop {
+            running_non_favorites(app).len()
+        } else { 0 },
+        compose:        compose_running(app).into_iter().collect(),
+        previews:       preview::previews_running(app).into_iter().collect(),
+        script_runs:    server_state.script_runs.lock().unwrap().clone(),
+        brew:           server_state.brew_services.lock().unwrap().clone(),
+        containers:     server_state.containers.lock().unwrap().clone(),
+        safe_mode:      server_state.safe_mode,
+    };
+    let running_count = view.running.len();
+    app.state::<TrayInfoRows>().0.lock().unwrap().clear();
+    let new_menu = build_tray_menu(app, &projects, &view);
+    let guard = tray_handle.0.lock().unwrap();
+    if let Some(tray) = guard.as_ref() {
+        let _ = tray.set_menu(Some(new_menu));
+        update_tray_status(tray, running_count);
+    }
+}
+
+// ─── Menu Event Handler ───────────────────────────────────────────────────────
+
+fn handle_menu_event(app: &tauri::AppHandle, id: &str) {
+    if id == "quit" && !presenting(app) {
+        // Graceful, with hooks and closed runs, like Stop All
+        stop_all_servers(app);
+        stop_all_tunnels(app);
+        app.exit(0);
+    } else if id == "restartnormal" {
+        restart_normally(app);
+    } else if id == "refresh" {
+        rescan_projects(app);
+        brew::spawn_brew_refresh(app);
+        docker::spawn_container_refresh(app);
+    } else if id == "stopall" && !presenting(app) {
+        stop_all_servers(app);
+    } else if id == "stopnonfavorites" && !presenting(app) {
+        let names = running_non_favorites(app);
+        let app = app.clone();
+        std::thread::spawn(move || { bulk_action(&app, names, BulkAction::Stop); });
+    } else if id == "startfavorites" {
+        let favorites = app.path().app_data_dir().map(|d| load_favorites_from_disk(&d)).unwrap_or_default();
+        let running = running_names(&app.state::<ServerState>());
+        let idle: Vec<String> = favorites.into_iter().filter(|f| !running.contains(f)).collect();
+        let app = app.clone();
+        // Menu events arrive on the main thread; don't block it on N spawns
+        std::thread::spawn(move || { bulk_action(&app, idle, BulkAction::Start); });
+    } else if id == "presentation" {
+        set_presentation_mode(app.clone(), !presenting(app));
+    } else if presenting(app) && ["stop__", "composedown__", "previewstop__", "restart__", "restartclean__", "share__", "unshare__", "tunnelurl__"].iter().any(|p| id.starts_with(p)) {
+        // Items are hidden in presentation mode; ignore stale menus
+    } else if let Some(name) = id.strip_prefix("start__") {
+        // Nothing on screen would say why otherwise
+        if let Err(e) = start_server(app, name.to_string()) {
+            notify(app, "failed to start", name, format!("'{}' didn't start: {}", name, e), "Basso");
+        }
+    } else if let Some(name) = id.strip_prefix("stop__") {
+        if let Err(e) = stop_server(app, name.to_string()) {
+            eprintln!("[DexHub] {}", e);
+        }
+    } else if let Some(name) = id.strip_prefix("restart__") {
+        if let Err(e) = restart_server(app, name.to_string()) {
+            eprintln!("[DexHub] Failed to restart '{}': {}", name, e);
+        }
+    } else if let Some(name) = id.strip_prefix("restartclean__") {
+        if let Err(e) = restart_server_clean(app, name.to_string()) {
+            eprintln!("[DexHub] Failed to restart '{}': {}", name, e);
+        }
+    } else if let Some(name) = id.strip_prefix("open__") {
+        open_in_browser(app, name.to_string());
+    } else if let Some((port, name)) = id.strip_prefix("openport__").and_then(|r| r.split_once("__")) {
+        if let Ok(port) = port.parse::<u16>() { open_extra_port(app, name, port); }
+    } else if let Some(name) = id.strip_prefix("logs__") {
+        if let Err(e) = open_log_window(app, name) {
+            eprintln!("[DexHub] Failed to open logs for '{}': {}", name, e);
+        }
+    } else if let Some(name) = id.strip_prefix("url__") {
+        copy_url(app, name.to_string());
+    } else if let Some(name) = id.strip_prefix("urlmd__") {
+        copy_url_as(app, name, UrlFormat::Markdown, UrlHost::Project);
+    } else if let Some(name) = id.strip_prefix("urlcurl__") {
+        copy_url_as(app, name, UrlFormat::Curl, UrlHost::Project);
+    } else if let Some(name) = id.strip_prefix("urllocal__") {
+        copy_url_as(app, name, UrlFormat::Plain, UrlHost::Localhost);
+    } else if let Some(name) = id.strip_prefix("urltailnet__") {
+        copy_url_as(app, name, UrlFormat::Plain, UrlHost::Tailscale);
+    } else if let Some(name) = id.strip_prefix("share__") {
+        if let Err(e) = start_tunnel(app, name.to_string(), None, None) {
+            eprintln!("[DexHub] Failed to share '{}': {}", name, e);
+        }
+    } else if let Some(name) = id.strip_prefix("unshare__") {
+        stop_tunnel(app, name);
+    } else if let Some(index) = id.strip_prefix("fav__").and_then(|i| i.parse::<usize>().ok()) {
+        if let Err(e) = run_favorite_cmd(app.clone(), index) {
+            eprintln!("[DexHub] Favorite failed: {}", e);
+        }
+    } else if let Some(name) = id.strip_prefix("editor__") {
+        if let Err(e) = open_in_editor(app.clone(), name.to_string()) {
+            eprintln!("[DexHub] Failed to open '{}' in an editor: {}", name, e);
+        }
+    } else if let Some(name) = id.strip_prefix("composeup__") {
+        if let Err(e) = start_compose(app, name) {
+            eprintln!("[DexHub] Failed to start services for '{}': {}", name, e);
+        }
+    } else if let Some(name) = id.strip_prefix("composedown__") {
+        let (app, name) = (app.clone(), name.to_string());
+        // `docker compose stop` waits for containers; keep it off the main thread
+        std::thread::spawn(move || { let _ = stop_compose(&app, &name); });
+    } else if let Some(name) = id.strip_prefix("previewstart__") {
+        if let Err(e) = preview::start_preview(app, name) {
+            eprintln!("[DexHub] Failed to preview '{}': {}", name, e);
+        }
+    } else if let Some(name) = id.strip_prefix("runtests__") {
+        if let Err(e) = spawn_script_run(app, name, "test") {
+            eprintln!("[DexHub] Failed to run tests for '{}': {}", name, e);
+        }
+    } else if let Some(name) = id.strip_prefix("previewstop__") {
+        preview::stop_preview(app, name);
+    } else if let Some(name) = id.strip_prefix("reveal__") {
+        if let Err(e) = reveal_project_folder(app.clone(), name.to_string()) {
+            eprintln!("[DexHub] Failed to reveal '{}': {}", name, e);
+        }
+    } else if let Some(name) = id.strip_prefix("brew__") {
+        brew::toggle_brew_service(app, name);
+    } else if let Some(container) = id.strip_prefix("docker__") {
+        docker::toggle_container(app, container);
+    } else if let Some(name) = id.strip_prefix("copylogs__").filter(|_| !presenting(app)) {
+        copy_logs(app, name, TRAY_COPY_LOG_LINES, false);
+    } else if let Some(name) = id.strip_prefix("copyerrors__").filter(|_| !presenting(app)) {
+        copy_logs(app, name, TRAY_COPY_LOG_LINES, true);
+    } else if let Some(name) = id.strip_prefix("tunnelurl__") {
+        let state = app.state::<ServerState>();
+        let url = state.tunnels.lock().unwrap().get(name).and_then(|t| t.public_url.lock().unwrap().clone());
+        if let Some(url) = url { copy_to_clipboard(app, &url); }
+    }
+}
+
+// ─── Server Lifecycle ────────────────────────────────────────────────────────
+
+fn start_server(app: &tauri::AppHandle, name: String) -> Result<(), String> {
+    start_server_on(app, name, None, &HashMap::new())
+}
+
+/// Why a server didn't come up, for the UI to explain rather than just
+/// report. Internal callers only need the message.
+#[derive(Debug, serde::Serialize)]
+struct StartError {
+    kind:    StartErrorKind,
+    message: String,
+}
+
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum StartErrorKind {
+    NotFound,         // no project by that name
+    MissingDirectory, // the project folder is gone
+    CommandNotFound,  // the launch command isn't on the login shell's PATH
+    PortInUse,
+    AlreadyStarting,  // another start of the project hasn't finished
+    HookFailed,
+    SpawnFailed,
+    Other,            // disk space, env secrets, …
+}
+
+impl StartError {
+    fn new(kind: StartErrorKind, message: impl Into<String>) -> Self {
+        StartError { kind, message: message.into() }
+    }
+}
+
+impl From<String> for StartError {
+    fn from(message: String) -> Self {
+        StartError::new(StartErrorKind::Other, message)
+    }
+}
+
+/// Marks a start of the project keyed `key` as in flight until dropped.
+struct StartClaim<'a> {
+    starting: &'a Mutex<HashSet<String>>,
+    key:      String,
+}
+
+impl Drop for StartClaim<'_> {
+    fn drop(&mut self) {
+        self.starting.lock().unwrap().remove(&self.key);
+    }
+}
+
+/// Claims the one start a project may have in flight. None if it's already
+/// running; an `AlreadyStarting` error while another start is under way.
+/// Checked and claimed under the `starting` lock, so two concurrent starts
+/// can't both spawn a child.
+fn claim_start<'a>(state: &'a ServerState, key: &str, name: &str) -> Result<Option<StartClaim<'a>>, StartError> {
+    let mut starting = state.starting.lock().unwrap();
+    if state.processes.lock().unwrap().contains_key(key) { return Ok(None); }
+    if !starting.insert(key.to_string()) {
+        return Err(StartError::new(StartErrorKind::AlreadyStarting, format!("'{}' is already starting", name)));
+    }
+    Ok(Some(StartClaim { starting: &state.starting, key: key.to_string() }))
+}
+
+/// Waits until no start of the project keyed `key` is in flight, or
+/// `timeout` passes.
+fn await_start(state: &ServerState, key: &str, timeout: Duration) {
+    let deadline = std::time::Instant::now() + timeout;
+    while state.starting.lock().unwrap().contains(key) && std::time::Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// Shell keywords and builtins a command line can open with; the program
+/// that runs comes later, if at all.
+const SHELL_WORDS: &[&str] = &[
+    "if", "then", "else", "elif", "fi", "case", "esac", "for", "while", "until", "do", "done",
+    "function", "select", "time", "coproc", "!", "{", "}", "[[", "[", ".", ":",
+    "cd", "exec", "export", "source", "eval", "set", "unset", "alias", "builtin", "command",
+    "noglob", "nocorrect", "local", "declare", "typeset", "readonly", "ulimit", "umask", "trap",
+    "pushd", "popd", "echo", "printf", "test", "true", "false", "wait",
+];
+
+/// The program a launch command starts, when its first word is a plain
+/// one whose absence is worth reporting up front. None for anything the
+/// shell interprets first: `FOO=1 pnpm dev`, `cd web && npm run dev`,
+/// `(cd app; make)`, `exec npm start`.
+fn checkable_program(command: &str) -> Option<&str> {
+    let first = command.split_whitespace().next()?;
+    let plain = first.chars().all(|c| c.is_ascii_alphanumeric() || "-_./+@,%".contains(c));
+    (plain && !SHELL_WORDS.contains(&first)).then_some(first)
+}
+
+/// Whether `command` resolves in the login shell servers are started from.
+fn shell_has_command(shell: &str, cwd: &str, command: &str) -> bool {
+    std::process::Command::new(shell)
+        .args(["-lc", "command -v \"$1\" >/dev/null", "sh", command])
+        .current_dir(cwd)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// Starts a server, optionally on a port other than its configured one.
+/// Canary-enabled projects are always told their port (`PORT` env plus the
+/// configured CLI flag) so they can be moved between ports.
+/// `extra_env` is layered over the saved env overrides and the active env
+/// profile, for this run only.
+fn start_server_on(
+    app: &tauri::AppHandle,
+    name: String,
+    port_override: Option<u16>,
+    extra_env: &HashMap<String, String>,
+) -> Result<(), String> {
+    launch_server(app, name, port_override, extra_env).map_err(|e| e.message)
+}
+
+/// `start_server_on` with the reason for a failure kept, see `StartError`.
+fn launch_server(
+    app: &tauri::AppHandle,
+    name: String,
+    port_override: Option<u16>,
+    extra_env: &HashMap<String, String>,
+) -> Result<(), StartError> {
+    let state = app.state::<ServerState>();
+    let key = project_key(&state, &name);
+    // Already running is nothing to do; a second child would orphan the first
+    let Some(_claim) = claim_start(&state, &key, &name)? else { return Ok(()) };
+
+    // Gather env overrides before locking projects
+    let mut env_vars: HashMap<String, String> = state
+        .env_overrides
+        .lock()
+        .unwrap()
+        .get(&key)
+        .cloned()
+        .unwrap_or_default();
+    let (profile, profile_vars) = state.env_profiles.lock().unwrap().get(&name)
+        .map(|p| (p.active.clone(), p.active_vars()))
+        .unwrap_or_default();
+    env_vars.extend(profile_vars);
+    env_vars.extend(extra_env.iter().map(|(k, v)| (k.clone(), v.clone())));
+    resolve_env_secrets(&name, &mut env_vars)?;
+
+    let project = {
+        let projects = state.projects.lock().unwrap();
+        match projects.iter().find(|p| p.name == name) {
+            Some(p) => p.clone(),
+            None => return Err(StartError::new(StartErrorKind::NotFound, format!("Project '{}' not found", name))),
+        }
+    };
+    if !Path::new(&project.cwd).is_dir() {
+        return Err(StartError::new(StartErrorKind::MissingDirectory, format!("{} no longer exists", project.cwd)));
+    }
+
+    check_disk_space(app, &project)?;
+
+    let custom_launch = state.launch_commands.lock().unwrap().get(&name).cloned();
+    let shell = state.general.lock().unwrap().shell.clone();
+    // The shell would start fine and only then exit 127; say so up front,
+    // before a pull, install or hook has run
+    let command = custom_launch.as_ref().map_or(project.command.as_str(), |l| l.command.as_str());
+    if let Some(program) = checkable_program(command) {
+        if !shell_has_command(&shell, &project.cwd, program) {
+            return Err(StartError::new(StartErrorKind::CommandNotFound, format!("'{}' isn't installed or isn't on {}'s PATH", program, shell)));
+        }
+    }
+
+    // Create a per-server log buffer (ring buffer, max 500 lines); a
+    // pre-start pull writes into it so its output shows with the run's logs
+    let log_buf: LogBuffer = Arc::new(Mutex::new(VecDeque::with_capacity(500)));
+    if state.pull_before_start.lock().unwrap().contains(&name) {
+        if let Err(e) = run_git_streamed(app, &name, &project.cwd, &["pull", "--ff-only"], &log_buf) {
+            push_log_line(app, &name, &log_buf, format!("[git] Pull failed, starting anyway: {}", e));
+        }
+        let _ = refresh_project_git(app, &name);
+    }
+    let auto_install = state.general.lock().unwrap().auto_install;
+    if auto_install && deps_status(Path::new(&project.cwd)) != DepsStatus::Installed {
+        if let Err(e) = install_deps_streamed(app, &project, &log_buf) {
+            push_log_line(app, &name, &log_buf, format!("[install] {}; starting anyway", e));
+        }
+    }
+    let pre_start = state.lifecycle_hooks.lock().unwrap().get(&name).map(|h| h.pre_start.clone()).unwrap_or_default();
+    for line in &pre_start {
+        if let Err(e) = run_lifecycle_hook(app, &name, &project.cwd, line, &env_vars, &log_buf) {
+            push_log_line(app, &name, &log_buf, format!("[hook] {}; not starting", e));
+            // Keep the hook's output viewable, as a crashed run's is
+            state.log_buffers.lock().unwrap().insert(key.clone(), log_buf);
+            record_event(app, "hook-failed", Some(&name), serde_json::json!({ "stage": "pre-start", "hook": line, "error": e }));
+            return Err(StartError::new(StartErrorKind::HookFailed, format!("Pre-start hook failed: {}", e)));
+        }
+    }
+
+    let canary = state.canary.lock().unwrap().get(&name).cloned().filter(|c| c.enabled);
+    let launch_port = port_override.unwrap_or(project.port);
+    state.served_ports.lock().unwrap().remove(&key);
+    // Off its own port (an override, a resolved conflict, a canary) the
+    // server must really bind where DexHub says, or URLs and health lie
+    let enforce_port = port_override.is_some() || launch_port != project.default_port;
+    if enforce_port && !is_port_free(launch_port) {
+        let e = format!("Port {} is already in use; '{}' is configured to run there", launch_port, name);
+        push_log_line(app, &name, &log_buf, format!("[dexhub] {}; not starting", e));
+        state.log_buffers.lock().unwrap().insert(key.clone(), log_buf);
+        record_event(app, "port-unavailable", Some(&name), serde_json::json!({ "port": launch_port }));
+        return Err(StartError::new(StartErrorKind::PortInUse, e));
+    }
+    let port_flags = match canary.as_ref().and_then(|c| c.port_flag.clone()) {
+        Some(flag) => Some(vec![flag, launch_port.to_string()]),
+        // A custom launch command is the user's to get right; it gets `PORT`
+        None if enforce_port && custom_launch.is_none() => std::fs::read_to_string(Path::new(&project.cwd).join("package.json")).ok()
+            .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+            .and_then(|pkg| pkg["scripts"]["dev"].as_str().and_then(|s| tool_port_flags(s, launch_port))),
+        None => None,
+    };
+    let launch = custom_launch
+        .unwrap_or_else(|| LaunchCommand { command: project.command.clone(), args: project.args.clone() });
+    let mut cmd_str = format!("{} {}", launch.command, launch.args.join(" "));
+    if let Some(flags) = port_flags {
+        // npm needs `--` to forward flags to the script; pnpm/yarn pass them through
+        let sep = if launch.command == "npm" { " --" } else { "" };
+        cmd_str.push_str(&format!("{} {}", sep, flags.join(" ")));
+    }
+    if let Some(nice) = state.resource_limits.lock().unwrap().get(&name).and_then(|l| l.nice) {
+        // Renice the shell itself so everything it launches inherits the level
+        cmd_str = format!("renice -n {} -p $$ >/dev/null; {}", nice.clamp(0, 19), cmd_str);
+    }
+    let mut cmd = std::process::Command::new(&shell);
+    cmd.args(["-lc", &cmd_str])
+        .current_dir(&project.cwd)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    for (k, v) in &env_vars { cmd.env(k, v); }
+    if canary.is_some() || enforce_port { cmd.env("PORT", launch_port.to_string()); }
+
+    match cmd.spawn() {
+        Ok(mut child) => {
+            let last_output: LastOutput = Arc::new(Mutex::new(std::time::Instant::now()));
+            // Set by whichever pipe prints the startup banner first
+            let banner_seen = Arc::new(AtomicBool::new(false));
+
+            // Stdout reader thread
+            if let Some(stdout) = child.stdout.take() {
+                let buf = Arc::clone(&log_buf);
+                let last = Arc::clone(&last_output);
+                let seen = Arc::clone(&banner_seen);
+                let (app, name, key) = (app.clone(), name.clone(), key.clone());
+                std::thread::spawn(move || {
+                    for line in BufReader::new(stdout).lines() {
+                        if let Ok(l) = line {
+                            *last.lock().unwrap() = std::time::Instant::now();
+                            if !seen.load(Ordering::SeqCst) && note_served_port(&app, &key, &l) { seen.store(true, Ordering::SeqCst); }
+                            push_log_line(&app, &name, &buf, l);
+                        }
+                    }
+                });
+            }
+            // Stderr reader thread
+            if let Some(stderr) = child.stderr.take() {
+                let buf = Arc::clone(&log_buf);
+                let last = Arc::clone(&last_output);
+                let seen = Arc::clone(&banner_seen);
+                let (app, name, key) = (app.clone(), name.clone(), key.clone());
+                std::thread::spawn(move || {
+                    for line in BufReader::new(stderr).lines() {
+                        if let Ok(l) = line {
+                            *last.lock().unwrap() = std::time::Instant::now();
+                            if !seen.load(Ordering::SeqCst) && note_served_port(&app, &key, &l) { seen.store(true, Ordering::SeqCst); }
+                            push_log_line(&app, &name, &buf, format!("[err] {}", l));
+                        }
+                    }
+                });
+            }
+
+            let started_at = unix_now();
+            let pid = child.id();
+            state.processes.lock().unwrap().insert(key.clone(), child);
+            state.start_times.lock().unwrap().insert(key.clone(), started_at);
+            state.last_output.lock().unwrap().insert(key.clone(), last_output);
+            state.last_success.lock().unwrap().remove(&key);
+            state.log_buffers.lock().unwrap().insert(key.clone(), log_buf);
+            state.last_health.lock().unwrap().remove(&name);
+            state.crashed.lock().unwrap().remove(&name);
+            if let Ok(dir) = app.path().app_data_dir() {
+                record_recent_start(&dir, &name);
+                record_observed_port(&dir, &name, launch_port);
+            }
+            db::record_run_start(app, &name, launch_port, started_at, pid);
+            rebuild_tray(app);
+            fire_hooks(app, "server-started", Some(&name), serde_json::json!({ "port": launch_port, "cwd": project.cwd, "profile": profile }));
+            Ok(())
+        }
+        Err(e) => Err(StartError::new(StartErrorKind::SpawnFailed, format!("Couldn't start {}: {}", shell, e))),
+    }
+}
+
+/// How long a stop waits for the server's port to be free again.
+const PORT_RELEASE_TIMEOUT: Duration = Duration::from_secs(3);
+/// How long a server gets to exit on SIGTERM before it's killed.
+const STOP_GRACE: Duration = Duration::from_secs(2);
+/// How long a restart waits for the new instance to accept connections.
+const RESTART_READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// SIGTERM first so the dev server can close its watchers and sockets,
+/// then a kill if it's still around after `STOP_GRACE`.
+fn terminate(child: &mut Child) {
+    let asked = std::process::Command::new("kill").arg(child.id().to_string()).status().is_ok_and(|s| s.success());
+    if asked {
+        let deadline = std::time::Instant::now() + STOP_GRACE;
+        while std::time::Instant::now() < deadline {
+            if matches!(child.try_wait(), Ok(Some(_))) { return; }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Free for a new server: nothing listening on the IPv4 or IPv6 loopback
+/// (Node often binds `localhost` as ::1 only).
+fn port_released(port: u16) -> bool {
+    let v6 = std::net::SocketAddr::from((std::net::Ipv6Addr::LOCALHOST, port));
+    is_port_free(port) && TcpStream::connect_timeout(&v6, Duration::from_millis(100)).is_err()
+}
+
+/// "node (pid 4242)" for whatever listens on `port`, via lsof.
+fn port_holder(port: u16) -> Option<String> {
+    let output = std::process::Command::new("lsof")
+        .args(["-nP", &format!("-iTCP:{}", port), "-sTCP:LISTEN", "-Fpc"])
+        .output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let pid = text.lines().find_map(|l| l.strip_prefix('p'))?;
+    let command = text.lines().find_map(|l| l.strip_prefix('c')).unwrap_or("unknown");
+    Some(format!("{} (pid {})", command, pid))
+}
+
+/// Polls until `port` is released. On timeout, names what still holds it
+/// in the server's log and the event log.
+fn await_port_release(app: &tauri::AppHandle, name: &str, port: u16) -> Result<(), String> {
+    let deadline = std::time::Instant::now() + PORT_RELEASE_TIMEOUT;
+    while !port_released(port) {
+        if std::time::Instant::now() >= deadline {
+            let holder = port_holder(port);
+            let e = format!("Port {} is still in use after stopping '{}'{}", port, name,
+                holder.as_ref().map(|h| format!(", held by {}", h)).unwrap_or_default());
+            let state = app.state::<ServerState>();
+            let key = project_key(&state, name);
+            let buf = state.log_buffers.lock().unwrap().get(&key).cloned();
+            if let Some(buf) = buf { push_log_line(app, name, &buf, format!("[dexhub] {}", e)); }
+            record_event(app, "port-held", Some(name), serde_json::json!({ "port": port, "holder": holder }));
+            return Err(e);
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    Ok(())
+}
+
+/// Stops the server and waits for its port to be released. An error means
+/// it stopped but something (often an orphaned child) still holds the port.
+fn stop_server(app: &tauri::AppHandle, name: String) -> Result<(), String> {
+    let state = app.state::<ServerState>();
+    let port = state.projects.lock().unwrap().iter().find(|p| p.name == name).map(|p| effective_port(&state, p));
+    let key = project_key(&state, &name);
+    let child = state.processes.lock().unwrap().remove(&key);
+    let stopped = child.is_some();
+    if let Some(mut child) = child {
+        terminate(&mut child);
+        db::record_run_end(app, &name, "stopped", None);
+    }
+    state.start_times.lock().unwrap().remove(&key);
+    state.served_ports.lock().unwrap().remove(&key);
+    state.last_health.lock().unwrap().remove(&name);
+    // A tunnel pointing at a stopped server is useless — tear it down too
+    stop_tunnel(app, &name);
+    // Keep log buffer around after stop for post-mortem viewing
+    rebuild_tray(app);
+    record_event(app, "server-stopped", Some(&name), serde_json::json!({}));
+    run_post_stop_hooks(app, &name);
+    match port {
+        Some(port) if stopped => await_port_release(app, &name, port),
+        _ => Ok(()),
+    }
+}
+
+/// How long each phase of a restart took, in milliseconds.
+#[derive(serde::Serialize)]
+struct RestartReport {
+    port:     u16,
+    stop_ms:  u64, // until the old instance exited and released its port
+    start_ms: u64, // spawning, including pull / install / pre-start hooks
+    ready_ms: u64, // until the new instance accepted a connection
+    total_ms: u64,
+}
+
+/// Stops gracefully, waits for the port, starts, then waits until the new
+/// instance accepts connections. Errors say which phase failed.
+fn restart_server(app: &tauri::AppHandle, name: String) -> Result<RestartReport, String> {
+    relaunch_server(app, name).map_err(|e| e.message)
+}
+
+/// `restart_server` with the reason for a failure kept, see `StartError`.
+/// A port the old instance left held counts as `PortInUse`.
+fn relaunch_server(app: &tauri::AppHandle, name: String) -> Result<RestartReport, StartError> {
+    let began = std::time::Instant::now();
+    stop_server(app, name.clone()).map_err(|e| StartError::new(StartErrorKind::PortInUse, e))?;
+    let stop_ms = began.elapsed().as_millis() as u64;
+    let started = std::time::Instant::now();
+    launch_server(app, name.clone(), None, &HashMap::new())?;
+    let start_ms = started.elapsed().as_millis() as u64;
+    let readying = std::time::Instant::now();
+    let port = await_ready(app, &name, RESTART_READY_TIMEOUT)?;
+    Ok(RestartReport {
+        port,
+        stop_ms,
+        start_ms,
+        ready_ms: readying.elapsed().as_millis() as u64,
+        total_ms: began.elapsed().as_millis() as u64,
+    })
+}
+
+/// Polls the server's effective port (which follows a startup banner)
+/// until it accepts a connection, the process exits, or `timeout` passes.
+fn await_ready(app: &tauri::AppHandle, name: &str, timeout: Duration) -> Result<u16, String> {
+    let state = app.state::<ServerState>();
+    let deadline = std::time::Instant::now() + timeout;
+    loop {
+        let port = state.projects.lock().unwrap().iter().find(|p| p.name == name).map(|p| effective_port(&state, p))
+            .ok_or_else(|| format!("Project '{}' not found", name))?;
+        if !is_running(&state, name) {
+            return Err(format!("'{}' exited while starting", name));
+        }
+        if !port_released(port) { return Ok(port); }
+        if std::time::Instant::now() >= deadline {
+            return Err(format!("'{}' wasn't ready on port {} within {}s", name, port, timeout.as_secs()));
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+}
+
+/// Bundler caches that go stale after dependency or config changes.
+const BUILD_CACHE_DIRS: &[&str] = &[".vite", "node_modules/.vite", "node_modules/.cache"];
+
+/// Restarts with the bundler caches deleted in between.
+fn restart_server_clean(app: &tauri::AppHandle, name: String) -> Result<(), String> {
+    let cwd = {
+        let state = app.state::<ServerState>();
+        let projects = state.projects.lock().unwrap();
+        projects.iter().find(|p| p.name == name).map(|p| p.cwd.clone())
+    }.ok_or_else(|| format!("Project '{}' not found", name))?;
+    stop_server(app, name.clone())?;
+    let mut cleared = Vec::new();
+    for dir in BUILD_CACHE_DIRS {
+        let path = Path::new(&cwd).join(dir);
+        if path.is_dir() && std::fs::remove_dir_all(&path).is_ok() { cleared.push(*dir); }
+    }
+    append_audit(app, "cache-cleared", &name, serde_json::json!({ "dirs": cleared }));
+    start_server(app, name)
+}
+
+/// Stops every running server through `stop_server`, side by side so the
+/// grace periods and port waits don't add up. The tray is rebuilt once.
+fn stop_all_servers(app: &tauri::AppHandle) {
+    let state = app.state::<ServerState>();
+    let keys: Vec<String> = state.processes.lock().unwrap().keys().cloned().collect();
+    let names: Vec<String> = keys.iter().map(|key| project_name(&state, key)).collect();
+    state.tray_suspended.store(true, Ordering::SeqCst);
+    std::thread::scope(|scope| {
+        for name in &names {
+            scope.spawn(move || {
+                if let Err(e) = stop_server(app, name.clone()) {
+                    eprintln!("[DexHub] Stopping '{}': {}", name, e);
+                }
+            });
+        }
+    });
+    state.tray_suspended.store(false, Ordering::SeqCst);
+    rebuild_tray(app);
+}
+
+// ─── Docker Compose ───────────────────────────────────────────────────────────
+
+/// Compose file names, in the order `docker compose` itself prefers them.
+const COMPOSE_FILES: &[&str] = &["compose.yaml", "compose.yml", "docker-compose.yaml", "docker-compose.yml"];
+
+fn find_compose_file(project_dir: &Path) -> Option<String> {
+    COMPOSE_FILES.iter().find(|f| project_dir.join(f).is_file()).map(|f| f.to_string())
+}
+
+/// Compose output lives in its own log buffer next to the dev server's.
+fn compose_log_key(name: &str) -> String {
+    format!("{}:services", name)
+}
+
+fn compose_project(app: &tauri::AppHandle, name: &str) -> Result<(String, String), String> {
+    let state = app.state::<ServerState>();
+    let projects = state.projects.lock().unwrap();
+    let project = projects.iter().find(|p| p.name == name).ok_or_else(|| format!("Project '{}' not found", name))?;
+    let file = project.compose_file.clone().ok_or_else(|| format!("'{}' has no compose file", name))?;
+    Ok((project.cwd.clone(), file))
+}
+
+/// Projects whose `docker compose up` is still attached; exited ones are dropped.
+fn compose_running(app: &tauri::AppHandle) -> Vec<String> {
+    let state = app.state::<ServerState>();
+    let mut compose = state.compose.lock().unwrap();
+    compose.retain(|_, child| matches!(child.try_wait(), Ok(None)));
+    compose.keys().cloned().collect()
+}
+
+/// Runs `docker compose up` attached, so its output is captured like a
+/// server's and the services stop if the process is interrupted.
+/// The file name comes from COMPOSE_FILES, so it's safe to put in the shell line.
+fn start_compose(app: &tauri::AppHandle, name: &str) -> Result<(), String> {
+    if compose_running(app).iter().any(|n| n == name) { return Ok(()); }
+    let (cwd, file) = compose_project(app, name)?;
+    let shell = app.state::<ServerState>().general.lock().unwrap().shell.clone();
+    let mut child = std::process::Command::new(shell)
+        .args(["-lc", &format!("docker compose -f {} up --remove-orphans", file)])
+        .current_dir(&cwd)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    let key = compose_log_key(name);
+    let log_buf: LogBuffer = Arc::new(Mutex::new(VecDeque::with_capacity(500)));
+    if let Some(stdout) = child.stdout.take() {
+        let (app, key, buf) = (app.clone(), key.clone(), Arc::clone(&log_buf));
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                push_log_line(&app, &key, &buf, line);
+            }
+        });
+    }
+    if let Some(stderr) = child.stderr.take() {
+        let (app, key, buf) = (app.clone(), key.clone(), Arc::clone(&log_buf));
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                push_log_line(&app, &key, &buf, format!("[err] {}", line));
+            }
+        });
+    }
+
+    let state = app.state::<ServerState>();
+    state.log_buffers.lock().unwrap().insert(key, log_buf);
+    state.compose.lock().unwrap().insert(name.to_string(), child);
+    record_event(app, "services-started", Some(name), serde_json::json!({ "file": file }));
+    rebuild_tray(app);
+    Ok(())
+}
+
+/// Stops the containers with `docker compose stop` (killing the attached
+/// `up` alone would leave them running), then reaps the `up` process.
+fn stop_compose(app: &tauri::AppHandle, name: &str) -> Result<(), String> {
+    let (cwd, file) = compose_project(app, name)?;
+    let shell = app.state::<ServerState>().general.lock().unwrap().shell.clone();
+    let output = std::process::Command::new(shell)
+        .args(["-lc", &format!("docker compose -f {} stop", file)])
+        .current_dir(&cwd)
+        .output()
+        .map_err(|e| e.to_string())?;
+    let child = app.state::<ServerState>().compose.lock().unwrap().remove(name);
+    if let Some(mut child) = child {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+    record_event(app, "services-stopped", Some(name), serde_json::json!({ "ok": output.status.success() }));
+    rebuild_tray(app);
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+// ─── Dependencies ─────────────────────────────────────────────────────────────
+
+/// Whether `node_modules` matches the lockfile, as far as timestamps can tell.
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum DepsStatus {
+    #[default]
+    Installed,
+    Missing, // no node_modules
+    Stale,   // the lockfile changed after the last install
+}
+
+/// Lockfile → package manager, in the order they're looked for.
+const LOCKFILES: &[(&str, &str)] = &[
+    ("pnpm-lock.yaml", "pnpm"),
+    ("yarn.lock", "yarn"),
+    ("bun.lockb", "bun"),
+    ("bun.lock", "bun"),
+    ("package-lock.json", "npm"),
+];
+
+/// The `packageManager` field (as Corepack reads it) if set, otherwise the
+/// first lockfile found in the project or a parent up to the scan root,
+/// since workspaces keep a single lockfile at the top.
+fn detect_package_manager(package: &serde_json::Value, project_dir: &Path, base_dir: &Path) -> Option<String> {
+    if let Some(field) = package["packageManager"].as_str() {
+        let name = field.split('@').next().unwrap_or("");
+        if LOCKFILES.iter().any(|(_, m)| *m == name) { return Some(name.to_string()); }
+    }
+    project_dir.ancestors()
+        .take_while(|dir| dir.starts_with(base_dir))
+        .find_map(|dir| LOCKFILES.iter().find(|(file, _)| dir.join(file).is_file()))
+        .map(|(_, manager)| manager.to_string())
+}
+
+/// Files each package manager rewrites inside node_modules on every install;
+/// a better "last installed" time than the folder, whose mtime only moves
+/// when its direct entries change.
+const INSTALL_MARKERS: &[&str] = &[".package-lock.json", ".modules.yaml", ".yarn-integrity", ".yarn-state.yml"];
+
+fn deps_status(project_dir: &Path) -> DepsStatus {
+    let modified = |p: std::path::PathBuf| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    // Plugin-detected projects may not be Node projects at all
+    if !project_dir.join("package.json").is_file() { return DepsStatus::Installed; }
+    let modules = project_dir.join("node_modules");
+    if !modules.is_dir() { return DepsStatus::Missing; }
+    let installed = INSTALL_MARKERS.iter().find_map(|m| modified(modules.join(m))).or_else(|| modified(modules.clone()));
+    let locked = LOCKFILES.iter().filter_map(|(l, _)| modified(project_dir.join(l))).max();
+    match (installed, locked) {
+        (Some(installed), Some(locked)) if locked > installed => DepsStatus::Stale,
+        _ => DepsStatus::Installed,
+    }
+}
+
+fn refresh_project_deps(app: &tauri::AppHandle, name: &str) -> DepsStatus {
+    let state = app.state::<ServerState>();
+    let mut projects = state.projects.lock().unwrap();
+    let Some(project) = projects.iter_mut().find(|p| p.name == name) else { return DepsStatus::Installed };
+    project.deps = deps_status(Path::new(&project.cwd));
+    project.deps
+}
+
+/// Runs the project's package manager install into `buf` as `[install] …`
+/// lines, then re-checks the project's dependency status.
+fn install_deps_streamed(app: &tauri::AppHandle, project: &ProjectConfig, buf: &LogBuffer) -> Result<DepsStatus, String> {
+    let line = format!("{} install", project.manager());
+    push_log_line(app, &project.name, buf, format!("[install] $ {}", line));
+    let shell = app.state::<ServerState>().general.lock().unwrap().shell.clone();
+    let mut child = std::process::Command::new(shell)
+        .args(["-lc", &line])
+        .current_dir(&project.cwd)
+        .env("CI", "1") // no interactive prompts
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Couldn't run {}: {}", line, e))?;
+    let status = stream_to_log(app, &project.name, &mut child, buf, "[install]")?;
+    let deps = refresh_project_deps(app, &project.name);
+    record_event(app, "dependencies-installed", Some(&project.name), serde_json::json!({ "ok": status.success(), "command": line }));
+    if status.success() {
+        Ok(deps)
+    } else {
+        Err(format!("{} exited with {}", line, status.code().map_or("a signal".to_string(), |c| c.to_string())))
+    }
+}
+
+/// Packages worth a chip in the UI, with their display names. The first
+/// framework found (top to bottom) is the project's framework, so
+/// meta-frameworks come before the libraries they're built on.
+const FRAMEWORKS: &[(&str, &str)] = &[
+    ("next", "Next.js"),
+    ("nuxt", "Nuxt"),
+    ("@sveltejs/kit", "SvelteKit"),
+    ("astro", "Astro"),
+    ("@remix-run/react", "Remix"),
+    ("gatsby", "Gatsby"),
+    ("@angular/core", "Angular"),
+    ("react", "React"),
+    ("vue", "Vue"),
+    ("svelte", "Svelte"),
+    ("solid-js", "Solid"),
+    ("preact", "Preact"),
+];
+const TOOLS: &[(&str, &str)] = &[
+    ("vite", "Vite"),
+    ("webpack", "webpack"),
+    ("parcel", "Parcel"),
+    ("@rspack/core", "Rspack"),
+    ("typescript", "TS"),
+    ("tailwindcss", "Tailwind"),
+    ("vitest", "Vitest"),
+    ("jest", "Jest"),
+    ("@playwright/test", "Playwright"),
+];
+
+#[derive(Clone, serde::Serialize)]
+struct KeyDependency {
+    package:   String,
+    label:     String,         // "React 19"; the bare name when no version is known
+    declared:  String,         // the range in package.json
+    installed: Option<String>, // from node_modules, when present
+    dev:       bool,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct DependencySummary {
+    framework:       Option<String>, // "Next.js", "React", …
+    typescript:      bool,
+    key:             Vec<KeyDependency>, // frameworks first, then tools
+    scripts:         BTreeMap<String, String>,
+    dependencies:    usize,
+    dev_dependencies: usize,
+}
+
+/// The major version of an installed version or a declared range
+/// ("^19.0.0", "~5.4", ">=4 <6", "19.x"); None for tags and URLs.
+fn major_version(version: &str) -> Option<u64> {
+    let start = version.find(|c: char| c.is_ascii_digit())?;
+    if version[..start].chars().any(|c| c.is_ascii_alphabetic()) { return None; }
+    version[start..].split(|c: char| !c.is_ascii_digit()).next()?.parse().ok()
+}
+
+fn dependency_summary(cwd: &Path) -> Result<DependencySummary, String> {
+    let content = std::fs::read_to_string(cwd.join("package.json")).map_err(|_| "No package.json".to_string())?;
+    let package: serde_json::Value = serde_json::from_str(&content).map_err(|e| format!("Invalid package.json: {}", e))?;
+    let deps = package["dependencies"].as_object().cloned().unwrap_or_default();
+    let dev_deps = package["devDependencies"].as_object().cloned().unwrap_or_default();
+    let installed = |name: &str| -> Option<String> {
+        let manifest = std::fs::read_to_string(cwd.join("node_modules").join(name).join("package.json")).ok()?;
+        serde_json::from_str::<serde_json::Value>(&manifest).ok()?["version"].as_str().map(String::from)
+    };
+
+    let key: Vec<KeyDependency> = FRAMEWORKS.iter().chain(TOOLS).filter_map(|(package, label)| {
+        let (declared, dev) = match deps.get(*package) {
+            Some(v) => (v, false),
+            None => (dev_deps.get(*package)?, true),
+        };
+        let declared = declared.as_str().unwrap_or("").to_string();
+        let installed = installed(package);
+        let label = match installed.as_deref().and_then(major_version).or_else(|| major_version(&declared)) {
+            Some(major) if *label != "TS" => format!("{} {}", label, major),
+            _ => label.to_string(),
+        };
+        Some(KeyDependency { package: package.to_string(), label, declared, installed, dev })
+    }).collect();
+
+    let framework = FRAMEWORKS.iter()
+        .find(|(package, _)| deps.contains_key(*package) || dev_deps.contains_key(*package))
+        .map(|(_, label)| label.to_string());
+    let scripts = package["scripts"].as_object()
+        .map(|s| s.iter().filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string()))).collect())
+        .unwrap_or_default();
+    Ok(DependencySummary {
+        framework,
+        typescript: dev_deps.contains_key("typescript") || deps.contains_key("typescript") || cwd.join("tsconfig.json").is_file(),
+        key,
+        scripts,
+        dependencies: deps.len(),
+        dev_dependencies: dev_deps.len(),
+    })
+}
+
+// ─── Project Stats ────────────────────────────────────────────────────────────
+
+/// Folder walks are slow on big node_modules; results are reused this long.
+const PROJECT_STATS_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Folders whose files don't count as the project being worked on.
+const NOT_SOURCE_DIRS: &[&str] = &["node_modules", ".git", "dist", "build", "out", ".next", ".nuxt", ".svelte-kit", ".turbo", ".cache", "coverage"];
+
+#[derive(Clone, serde::Serialize)]
+struct ProjectStats {
+    name:            String,
+    size_bytes:      u64,         // the whole folder, node_modules included
+    source_modified: Option<u64>, // newest file outside NOT_SOURCE_DIRS, unix secs
+    last_started:    Option<u64>, // from run history, unix secs
+}
+
+/// (size, newest source mtime) of a project folder.
+fn folder_stats(cwd: &Path) -> (u64, Option<u64>) {
+    let mut size = 0;
+    let mut newest: Option<u64> = None;
+    for entry in walkdir::WalkDir::new(cwd).follow_links(false).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() { continue; }
+        let Ok(meta) = entry.metadata() else { continue };
+        size += meta.len();
+        let in_output = entry.path().strip_prefix(cwd).ok().is_some_and(|rel| {
+            rel.components().any(|c| NOT_SOURCE_DIRS.contains(&c.as_os_str().to_string_lossy().as_ref()))
+        });
+        if in_output { continue; }
+        let modified = meta.modified().ok()
+            .and_then(|m| m.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+        newest = newest.max(modified);
+    }
+    (size, newest)
+}
+
+// ─── Package Scripts ──────────────────────────────────────────────────────────
+
+/// How long a finished run's result stays in the tray.
+const SCRIPT_STATUS_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// The latest run of a one-off package script (`test`, `lint`) per project.
+#[derive(Clone, serde::Serialize)]
+struct ScriptRun {
+    script:      String,
+    running:     bool,
+    passed:      Option<bool>,   // None while running; from the exit code
+    exit_code:   Option<i32>,
+    summary:     Option<String>, // the runner's own tally, e.g. "2 failed, 40 passed"
+    started_at:  u64,
+    #[serde(skip)]
+    finished:    Option<std::time::Instant>,
+}
+
+/// Script output lives in its own log buffer next to the dev server's.
+fn script_log_key(name: &str, script: &str) -> String {
+    format!("{}:{}", name, script)
+}
+
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // CSI sequences end at the first letter
+            for c in chars.by_ref() { if c.is_ascii_alphabetic() { break; } }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// The tally line of vitest (`Tests  2 failed | 40 passed (42)`) or jest
+/// (`Tests:       2 failed, 40 passed, 42 total`), from the end of the log.
+fn test_summary(lines: &[String]) -> Option<String> {
+    lines.iter().rev().map(|l| strip_ansi(l)).find_map(|line| {
+        // Past the `[test]` prefix stream_to_log adds
+        let line = line.split_once("] ").map_or(line.as_str(), |(_, rest)| rest).trim();
+        let rest = line.strip_prefix("Tests:").or_else(|| line.strip_prefix("Tests "))?.trim();
+        let counts: Vec<&str> = rest.split(['|', ','])
+            .map(|s| s.trim())
+            .filter(|s| s.contains("passed") || s.contains("failed") || s.contains("skipped") || s.contains("todo"))
+            .map(|s| s.split(" (").next().unwrap_or(s))
+            .collect();
+        (!counts.is_empty()).then(|| counts.join(", "))
+    })
+}
+
+fn script_status_label(run: Option<&ScriptRun>, idle: &str) -> String {
+    match run {
+        Some(r) if r.running => format!("{} (running…)", idle),
+        Some(r) if r.finished.is_some_and(|f| f.elapsed() < SCRIPT_STATUS_TTL) => {
+            let mark = if r.passed == Some(true) { "✓" } else { "✗" };
+            match &r.summary {
+                Some(summary) => format!("{} ({} {})", idle, mark, summary),
+                None => format!("{} ({})", idle, mark),
+            }
+        }
+        _ => idle.to_string(),
+    }
+}
+
+/// Marks `script` as running for the project once `prepare` (resolving its
+/// env) succeeds. A failure leaves no run behind to block the next one.
+fn claim_script_run<T>(
+    runs: &Mutex<HashMap<String, ScriptRun>>,
+    name: &str,
+    script: &str,
+    prepare: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    let mut runs = runs.lock().unwrap();
+    if let Some(run) = runs.get(name).filter(|r| r.running) {
+        return Err(format!("'{}' is already running {}", name, run.script));
+    }
+    let prepared = prepare()?;
+    runs.insert(name.to_string(), ScriptRun {
+        script: script.to_string(), running: true, passed: None, exit_code: None,
+        summary: None, started_at: unix_now(), finished: None,
+    });
+    Ok(prepared)
+}
+
+/// Runs `<package manager> run <script>` on a thread, streaming into the
+/// script's own log buffer. Progress is emitted as `script-run`; the result
+/// is also a notification, so a run can be started from the tray and left.
+fn spawn_script_run(app: &tauri::AppHandle, name: &str, script: &str) -> Result<(), String> {
+    let state = app.state::<ServerState>();
+    let project = state.projects.lock().unwrap().iter().find(|p| p.name == name).cloned()
+        .ok_or_else(|| format!("Project '{}' not found", name))?;
+    let declared = std::fs::read_to_string(Path::new(&project.cwd).join("package.json"))
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+        .is_some_and(|v| v["scripts"][script].is_string());
+    if !declared { return Err(format!("'{}' has no {} script", name, script)); }
+    let id = project_key(&state, name);
+    let env = claim_script_run(&state.script_runs, name, script, || {
+        let mut env = state.env_overrides.lock().unwrap().get(&id).cloned().unwrap_or_default();
+        resolve_env_secrets(name, &mut env)?;
+        Ok(env)
+    })?;
+    let key = script_log_key(name, script);
+    let log_buf: LogBuffer = Arc::new(Mutex::new(VecDeque::with_capacity(500)));
+    state.log_buffers.lock().unwrap().insert(key.clone(), Arc::clone(&log_buf));
+    let shell = state.general.lock().unwrap().shell.clone();
+    let _ = app.emit("script-run", serde_json::json!({ "name": name, "run": state.script_runs.lock().unwrap().get(name) }));
+    rebuild_tray(app);
+
+    let (app, name, script) = (app.clone(), name.to_string(), script.to_string());
+    std::thread::spawn(move || {
+        let line = format!("{} run {}", project.manager(), script);
+        push_log_line(&app, &key, &log_buf, format!("[{}] $ {}", script, line));
+        let result = std::process::Command::new(&shell)
+            .args(["-lc", &line])
+            .current_dir(&project.cwd)
+            .envs(&env)
+            .env("CI", "1") // no watch mode, no interactive prompts
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| e.to_string())
+            .and_then(|mut child| stream_to_log(&app, &key, &mut child, &log_buf, &format!("[{}]", script)));
+        if let Err(e) = &result { push_log_line(&app, &key, &log_buf, format!("[{}] Couldn't run it: {}", script, e)); }
+        let exit_code = result.as_ref().ok().and_then(|s| s.code());
+        let passed = result.is_ok_and(|s| s.success());
+        let lines = log_texts(&log_buf.lock().unwrap());
+        let summary = test_summary(&lines);
+
+        let state = app.state::<ServerState>();
+        let run = {
+            let mut runs = state.script_runs.lock().unwrap();
+            let Some(run) = runs.get_mut(&name) else { return };
+            run.running = false;
+            run.passed = Some(passed);
+            run.exit_code = exit_code;
+            run.summary = summary.clone();
+            run.finished = Some(std::time::Instant::now());
+            run.clone()
+        };
+        let _ = app.emit("script-run", serde_json::json!({ "name": name, "run": run }));
+        record_event(&app, "script-finished", Some(&name), serde_json::json!({
+            "script": script, "passed": passed, "exit_code": exit_code, "summary": summary,
+        }));
+        let outcome = if passed { "passed" } else { "failed" };
+        let message = match &summary {
+            Some(s) => format!("{} {} in '{}': {}", script, outcome, name, s),
+            None => format!("{} {} in '{}'", script, outcome, name),
+        };
+        notify(&app, &format!("{} {}", script, outcome), &name, message, if passed { "Glass" } else { "Basso" });
+        rebuild_tray(&app);
+    });
+    Ok(())
+}
+
+// ─── Canary Restart ───────────────────────────────────────────────────────────
+
+/// Per-project opt-in for blue-green restarts. The project must honour a
+/// `PORT` env var or `port_flag` (e.g. `--port`) so it can run on another port.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct CanaryConfig {
+    enabled:            bool,
+    port_flag:          Option<String>,
+    ready_timeout_secs: u64,
+}
+
+impl Default for CanaryConfig {
+    fn default() -> Self {
+        CanaryConfig { enabled: false, port_flag: None, ready_timeout_secs: 60 }
+    }
+}
+
+fn canary_config_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("canary.json")
+}
+
+fn load_canary_config(app_data_dir: &Path) -> HashMap<String, CanaryConfig> {
+    std::fs::read_to_string(canary_config_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_canary_config_to_disk(app_data_dir: &Path, config: &HashMap<String, CanaryConfig>) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(config) {
+        let _ = std::fs::write(canary_config_path(app_data_dir), json);
+    }
+}
+
+/// Starts a second instance on a free port, waits until it accepts
+/// connections, points URLs (and the proxy) at it, then stops the old one.
+/// If the new instance never comes up, the old one keeps serving.
+fn canary_restart(app: &tauri::AppHandle, name: String) -> Result<u16, String> {
+    let state = app.state::<ServerState>();
+    let config = state.canary.lock().unwrap().get(&name).cloned().filter(|c| c.enabled)
+        .ok_or_else(|| format!("Canary restart isn't enabled for '{}'", name))?;
+    let old_port = state.projects.lock().unwrap().iter().find(|p| p.name == name).map(|p| effective_port(&state, p))
+        .ok_or_else(|| format!("Project '{}' not found", name))?;
+    let key = project_key(&state, &name);
+    let old_child = state.processes.lock().unwrap().remove(&key)
+        .ok_or_else(|| format!("'{}' is not running", name))?;
+    let old_started = state.start_times.lock().unwrap().remove(&key);
+    let old_served = state.served_ports.lock().unwrap().get(&key).copied();
+
+    let taken: HashSet<u16> = state.projects.lock().unwrap().iter().map(|p| p.port).collect();
+    let mut new_port = old_port.wrapping_add(1).max(1024);
+    while taken.contains(&new_port) || !is_port_free(new_port) { new_port = new_port.wrapping_add(1).max(1024); }
+
+    // Puts the old instance back in charge after a failed canary
+    let restore = |child: Child| {
+        state.processes.lock().unwrap().insert(key.clone(), child);
+        if let Some(t) = old_started { state.start_times.lock().unwrap().insert(key.clone(), t); }
+        if let Some(port) = old_served { state.served_ports.lock().unwrap().insert(key.clone(), port); }
+        rebuild_tray(app);
+    };
+
+    if let Err(e) = start_server_on(app, name.clone(), Some(new_port), &HashMap::new()) {
+        restore(old_child);
+        return Err(e);
+    }
+    let deadline = std::time::Instant::now() + Duration::from_secs(config.ready_timeout_secs);
+    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], new_port));
+    while TcpStream::connect_timeout(&addr, Duration::from_millis(200)).is_err() {
+        if std::time::Instant::now() >= deadline {
+            if let Some(mut canary) = state.processes.lock().unwrap().remove(&key) { terminate(&mut canary); }
+            restore(old_child);
+            record_event(app, "canary-failed", Some(&name), serde_json::json!({ "port": new_port }));
+            return Err(format!("'{}' wasn't ready on port {} within {}s", name, new_port, config.ready_timeout_secs));
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
+
+    // With the announced ports, not in the project list a rescan rebuilds
+    state.served_ports.lock().unwrap().insert(key.clone(), new_port);
+    rebuild_tray(app);
+    record_event(app, "canary-switched", Some(&name), serde_json::json!({ "from": old_port, "to": new_port }));
+    let mut old_child = old_child;
+    terminate(&mut old_child);
+    // The canary already serves; a held old port is logged, not a failure
+    let _ = await_port_release(app, &name, old_port);
+    Ok(new_port)
+}
+
+// ─── Bulk Actions ─────────────────────────────────────────────────────────────
+
+/// Max lifecycle operations a bulk action runs at once.
+const BULK_CONCURRENCY: usize = 4;
+
+#[derive(Clone, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum BulkAction {
+    Start,
+    Stop,
+    Restart,
+    Tag { tag: String },
+    Untag { tag: String },
+    Favorite,
+    Unfavorite,
+}
+
+#[derive(serde::Serialize)]
+struct BulkItemResult {
+    name:  String,
+    ok:    bool,
+    error: Option<String>,
+}
+
+/// Applies one action to many projects.
+///
+/// Names are de-duplicated and processed in sorted order so results are
+/// deterministic. Lifecycle actions run at most `BULK_CONCURRENCY` at a time;
+/// metadata actions (tags/favorites) are a single read-modify-write. The tray
+/// is rebuilt once at the end instead of once per item.
+fn bulk_action(app: &tauri::AppHandle, names: Vec<String>, action: BulkAction) -> Vec<BulkItemResult> {
+    let mut names = names;
+    names.sort();
+    names.dedup();
+
+    let state = app.state::<ServerState>();
+    let known: HashSet<String> = state.projects.lock().unwrap().iter().map(|p| p.name.clone()).collect();
+    state.tray_suspended.store(true, Ordering::SeqCst);
+
+    let mut results: Vec<BulkItemResult> = Vec::with_capacity(names.len());
+    let (valid, unknown): (Vec<String>, Vec<String>) = names.into_iter().partition(|n| known.contains(n));
+    for name in unknown {
+        let error = Some(format!("Project '{}' not found", name));
+        results.push(BulkItemResult { name, ok: false, error });
+    }
+
+    let outcome: Result<(), String> = match &action {
+        BulkAction::Start | BulkAction::Stop | BulkAction::Restart => {
+            let total = valid.len();
+            for chunk in valid.chunks(BULK_CONCURRENCY) {
+                let chunk_results: Vec<BulkItemResult> = std::thread::scope(|scope| {
+                    let handles: Vec<_> = chunk.iter().map(|name| {
+                        let action = &action;
+                        scope.spawn(move || {
+                            let (r, verb) = match action {
+                                BulkAction::Start => (start_server(app, name.clone()), "started"),
+                                BulkAction::Stop => (stop_server(app, name.clone()), "stopped"),
+                                _ => (restart_server(app, name.clone()).map(|_| ()), "restarted"),
+                            };
+                            // Multi-server actions land in one digest notification
+                            if total > 1 {
+                                match &r {
+                                    Ok(()) => notify(app, verb, name, format!("'{}' {}.", name, verb), "Pop"),
+                                    Err(e) => notify(app, "failed", name, format!("'{}' failed: {}", name, e), "Basso"),
+                                }
+                            }
+                            BulkItemResult { name: name.clone(), ok: r.is_ok(), error: r.err() }
+                        })
+                    }).collect();
+                    handles.into_iter().filter_map(|h| h.join().ok()).collect()
+                });
+                results.extend(chunk_results);
+            }
+            Ok(())
+        }
+        BulkAction::Tag { tag } | BulkAction::Untag { tag } => {
+            app.path().app_data_dir().map_err(|e| e.to_string()).map(|dir| {
+                let mut tags = load_tags(&dir);
+                for name in &valid {
+                    let entry = tags.entry(name.clone()).or_default();
+                    entry.retain(|t| t != tag);
+                    if matches!(action, BulkAction::Tag { .. }) { entry.push(tag.clone()); }
+                }
+                tags.retain(|_, v| !v.is_empty());
+                save_tags_to_disk(&dir, &tags);
+            })
+        }
+        BulkAction::Favorite | BulkAction::Unfavorite => {
+            app.path().app_data_dir().map_err(|e| e.to_string()).map(|dir| {
+                let mut favorites = load_favorites_from_disk(&dir);
+                favorites.retain(|f| !valid.contains(f));
+                if matches!(action, BulkAction::Favorite) { favorites.extend(valid.iter().cloned()); }
+                save_favorites_to_disk(&dir, &favorites);
+            })
+        }
+    };
+    if !matches!(action, BulkAction::Start | BulkAction::Stop | BulkAction::Restart) {
+        for name in valid {
+            results.push(BulkItemResult { name, ok: outcome.is_ok(), error: outcome.clone().err() });
+        }
+    }
+
+    state.tray_suspended.store(false, Ordering::SeqCst);
+    rebuild_tray(app);
+    results.sort_by(|a, b| a.name.cmp(&b.name));
+    results
+}
+
+/// Opens (or focuses) a standalone log window for one server.
+fn open_log_window(app: &tauri::AppHandle, name: &str) -> Result<(), String> {
+    let label = format!("logs-{}", proxy::host_slug(name));
+    if let Some(win) = app.get_webview_window(&label) {
+        let _ = win.show();
+        return win.set_focus().map_err(|e| e.to_string());
+    }
+    let query: String = name.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' => (b as char).to_string(),
+        _ => format!("%{:02X}", b),
+    }).collect();
+    tauri::WebviewWindowBuilder::new(app, label, tauri::WebviewUrl::App(format!("index.html?logs={}", query).into()))
+        .title(format!("{} — Logs", name))
+        .inner_size(760.0, 480.0)
+        .build()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+fn open_in_browser(app: &tauri::AppHandle, name: String) {
+    let state = app.state::<ServerState>();
+    let projects = state.projects.lock().unwrap().clone();
+    if let Some(project) = projects.iter().find(|p| p.name == name) {
+        let url = browser_url(&state, project);
+        let choice = state.general.lock().unwrap().browser_for(&name);
+        if let Err(e) = platform::open_url_in(&url, choice.browser.as_deref(), choice.profile.as_deref()) {
+            eprintln!("[DexHub] Failed to open '{}': {}", url, e);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum UrlFormat {
+    #[default]
+    Plain,
+    Markdown, // [name](url)
+    Curl,     // curl -i url
+}
+
+/// Which host the copied URL uses: the project's own choice (see
+/// `resolve_url_host`), or always localhost / the tailnet name.
+#[derive(Clone, Copy, Default, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum UrlHost {
+    #[default]
+    Project,
+    Localhost,
+    Tailscale,
+}
+
+fn formatted_url(state: &ServerState, project: &ProjectConfig, format: UrlFormat, host: UrlHost) -> String {
+    let url = match host {
+        UrlHost::Project => project_url(state, project),
+        // The tailnet name is never shown in presentation mode
+        UrlHost::Localhost => format!("http://localhost:{}", effective_port(state, project)),
+        UrlHost::Tailscale if state.presentation_mode.load(Ordering::SeqCst) => format!("http://localhost:{}", effective_port(state, project)),
+        UrlHost::Tailscale => format!("http://{}:{}", state.tailscale_host.lock().unwrap(), effective_port(state, project)),
+    };
+    match format {
+        UrlFormat::Plain => url,
+        UrlFormat::Markdown => format!("[{}]({})", project.name.replace(']', "\\]"), url),
+        UrlFormat::Curl => format!("curl -i {}", url),
+    }
+}
+
+fn open_extra_port(app: &tauri::AppHandle, name: &str, port: u16) {
+    let state = app.state::<ServerState>();
+    let projects = state.projects.lock().unwrap().clone();
+    if let Some(project) = projects.iter().find(|p| p.name == name) {
+        let url = extra_port_url(&state, project, port);
+        let choice = state.general.lock().unwrap().browser_for(name);
+        if let Err(e) = platform::open_url_in(&url, choice.browser.as_deref(), choice.profile.as_deref()) {
+            eprintln!("[DexHub] Failed to open '{}': {}", url, e);
+        }
+    }
+}
+
+fn copy_url(app: &tauri::AppHandle, name: String) {
+    copy_url_as(app, &name, UrlFormat::Plain, UrlHost::Project);
+}
+
+fn copy_url_as(app: &tauri::AppHandle, name: &str, format: UrlFormat, host: UrlHost) -> Option<String> {
+    let state = app.state::<ServerState>();
+    let projects = state.projects.lock().unwrap().clone();
+    let project = projects.iter().find(|p| p.name == name)?;
+    let text = formatted_url(&state, project, format, host);
+    copy_to_clipboard(app, &text);
+    Some(text)
+}
+
+fn copy_to_clipboard(app: &tauri::AppHandle, text: &str) {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+    if let Err(e) = app.clipboard().write_text(text.to_string()) {
+        eprintln!("[DexHub] Couldn't copy to the clipboard: {}", e);
+    }
+}
+
+/// Lines copied by the tray's Copy Logs items.
+const TRAY_COPY_LOG_LINES: usize = 50;
+
+/// Copies the last `lines` lines of `name`'s log (of its error lines with
+/// `errors_only`), colour codes stripped. Returns how many were copied.
+fn copy_logs(app: &tauri::AppHandle, name: &str, lines: usize, errors_only: bool) -> usize {
+    let level = errors_only.then_some(LogLevel::Error);
+    let logs: Vec<String> = server_log_lines(app, name, level).into_iter()
+        .map(|l| strip_ansi(&l))
+        .collect();
+    let tail = &logs[logs.len().saturating_sub(lines)..];
+    if !tail.is_empty() { copy_to_clipboard(app, &tail.join("\n")); }
+    tail.len()
+}
+
+// ─── Deep Links ───────────────────────────────────────────────────────────────
+
+/// Routes `dexhub://<action>/<project>` URLs (from Raycast, Alfred, browser
+/// links…) to the same lifecycle functions the tray uses. `dexhub://refresh`
+/// and `dexhub://show` take no project.
+fn handle_deep_link(app: &tauri::AppHandle, url: &str) {
+    let Some(rest) = url.strip_prefix("dexhub://") else { return };
+    let rest = rest.split(['?', '#']).next().unwrap_or("").trim_matches('/');
+    let (action, name) = match rest.split_once('/') {
+        Some((a, n)) => (a, remote::percent_decode(n.trim_matches('/'))),
+        None => (rest, String::new()),
+    };
+    let result = match (action, name.is_empty()) {
+        ("refresh", _) => { rescan_projects(app); Ok(()) }
+        ("show", _) => {
+            if let Some(win) = app.get_webview_window("main") {
+                let _ = win.show();
+                let _ = win.set_focus();
+            }
+            Ok(())
+        }
+        (_, true) => Err("missing project name".to_string()),
+        ("start", _) => start_server(app, name.clone()),
+        ("stop" | "restart", _) if presenting(app) => Err(PRESENTATION_BLOCKED.to_string()),
+        ("stop", _) => stop_server(app, name.clone()),
+        ("restart", _) => restart_server(app, name.clone()).map(|_| ()),
+        ("open", _) => {
+            // Opening a stopped project starts it first
+            let running = is_running(&app.state::<ServerState>(), &name);
+            let started = if running { Ok(()) } else { start_server(app, name.clone()) };
+            started.map(|_| open_in_browser(app, name.clone()))
+        }
+        ("copy", _) => { copy_url(app, name.clone()); Ok(()) }
+        (other, _) => Err(format!("unknown action '{}'", other)),
+    };
+    if let Err(e) = result {
+        eprintln!("[DexHub] Deep link '{}' failed: {}", url, e);
+    }
+    record_event(app, "deep-link", (!name.is_empty()).then_some(name.as_str()), serde_json::json!({ "url": url }));
+}
+
+// ─── Tauri Commands ───────────────────────────────────────────────────────────
+
+#[tauri::command]
+fn list_projects(app: tauri::AppHandle) -> Vec<ProjectConfig> {
+    let projects = app.state::<ServerState>().projects.lock().unwrap().clone();
+    redact_projects(&app, projects)
+}
+
+#[tauri::command]
+fn get_running_servers(app: tauri::AppHandle) -> Vec<String> {
+    reap_exited_servers(&app)
+}
+
+/// Drops servers whose process has exited, notifying about each crash.
+/// Returns the names still running.
+fn reap_exited_servers(app: &tauri::AppHandle) -> Vec<String> {
+    let state = app.state::<ServerState>();
+    let (keys, exited) = {
+        let mut procs = state.processes.lock().unwrap();
+        let mut exited: Vec<(String, std::process::ExitStatus)> = Vec::new();
+        procs.retain(|key, child| match child.try_wait() {
+            Ok(Some(status)) => { exited.push((key.clone(), status)); false }
+            _ => true,
+        });
+        let keys = procs.keys().cloned().collect::<Vec<String>>();
+        (keys, exited)
+    };
+    let names: Vec<String> = keys.iter().map(|k| project_name(&state, k)).collect();
+    if !exited.is_empty() {
+        let config = state.notifications.lock().unwrap().clone();
+        for (key, status) in exited {
+            let n = project_name(&state, &key);
+            db::record_run_end(app, &n, if status.success() { "exited" } else { "crashed" }, status.code());
+            state.crashed.lock().unwrap().insert(n.clone());
+            state.last_health.lock().unwrap().remove(&n);
+            let uptime_secs = state.start_times.lock().unwrap().remove(&key).map(|t| unix_now().saturating_sub(t));
+            state.served_ports.lock().unwrap().remove(&key);
+            let buf = state.log_buffers.lock().unwrap().get(&key).cloned();
+            let lines: Vec<String> = buf.as_ref().map(|b| log_texts(&b.lock().unwrap())).unwrap_or_default();
+            let last_error_line = buf.as_ref().and_then(|b| last_error_line(&b.lock().unwrap()));
+            let snapshot_path = app.path().app_data_dir().ok()
+                .and_then(|dir| write_crash_snapshot(&dir, &n, status, uptime_secs, &lines))
+                .map(|p| p.to_string_lossy().into_owned());
+            let info = CrashInfo { name: n.clone(), exit: Some(status), uptime_secs, last_error_line, snapshot_path };
+            // A flapping server gets one notice, then its crashes go quiet
+            let (was_flapping, flapping) = note_crash(app, &n);
+            if flapping && !was_flapping {
+                let window = state.crash_policy.lock().unwrap().window_mins;
+                let crashes = state.crash_times.lock().unwrap().get(&n).map_or(0, |t| t.len());
+                notify(app, "flapping", &n, format!("{} keeps crashing ({} times in {} min); auto-restart paused until cleared", n, crashes, window), notifications::DEFAULT_CRASH_SOUND);
+                chat_alert(&config, &n, &format!("keeps crashing ({} times in {} min); auto-restart paused", crashes, window), &lines);
+            } else if !flapping {
+                notify(app, "crashed", &n, notifications::render_crash_message(&config.crash_template, &info), notifications::DEFAULT_CRASH_SOUND);
+                let exit = status.code().map_or_else(|| "by a signal".to_string(), |c| format!("with code {}", c));
+                let uptime = info.uptime_secs.map(notifications::format_duration).unwrap_or_else(|| "unknown time".to_string());
+                chat_alert(&config, &n, &format!("crashed {} after {}", exit, uptime), &lines);
+                schedule_auto_restart(app, n.clone());
+            }
+            fire_hooks(app, "server-crashed", Some(&n), serde_json::json!({
+                "exit_code": status.code(),
+                "uptime_secs": info.uptime_secs,
+                "last_error_line": info.last_error_line,
+                "snapshot": info.snapshot_path,
+            }));
+        }
+        rebuild_tray(app);
+    }
+    names
+}
+
+/// Reaps crashed servers and probes the rest, rebuilding the tray only on changes.
+fn run_health_pass(app: &tauri::AppHandle) {
+    let running = reap_exited_servers(app);
+    let state = app.state::<ServerState>();
+    let before = state.last_health.lock().unwrap().clone();
+    let now = unix_now();
+    for name in running {
+        // The monitor ticks at the shortest interval; the rest wait their turn.
+        // A second of slack absorbs checked_at rounding down.
+        let interval = state.general.lock().unwrap().health_interval_for(&name).as_secs();
+        let last = state.health_details.lock().unwrap().get(&name).map(|d| d.checked_at);
+        if !state.low_power.load(Ordering::SeqCst) && last.is_some_and(|t| now + 1 < t + interval) { continue; }
+        check_server_health(app.clone(), name);
+    }
+    let after = state.last_health.lock().unwrap().clone();
+    if before != after { rebuild_tray(app); }
+}
+
+/// Keeps the tray's status dots current without the main window open.
+fn spawn_health_monitor(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(health_monitor_interval(&app));
+        run_health_pass(&app);
+    });
+}
+
+// ─── Low-Power Mode ───────────────────────────────────────────────────────────
+
+/// On battery at or below `threshold_percent`, health probes slow down,
+/// scheduled scans pause and (optionally) the tray offers to stop every
+/// server that isn't a favorite. Plugging in restores normal behaviour.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct BatteryPolicy {
+    enabled:             bool,
+    threshold_percent:   u8,
+    probe_interval_secs: u64,  // health monitor interval while in low-power mode
+    pause_scans:         bool,
+    prompt_stop:         bool, // notify and offer "Stop Non-Favorites" in the tray
+}
+
+impl Default for BatteryPolicy {
+    fn default() -> Self {
+        BatteryPolicy { enabled: true, threshold_percent: 30, probe_interval_secs: 60, pause_scans: true, prompt_stop: false }
+    }
+}
+
+/// How often the battery is read.
+const BATTERY_POLL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, serde::Serialize)]
+struct PowerState {
+    battery:   Option<platform::BatteryStatus>,
+    low_power: bool,
+}
+
+fn battery_policy_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("battery_policy.json")
+}
+
+fn load_battery_policy(app_data_dir: &Path) -> BatteryPolicy {
+    std::fs::read_to_string(battery_policy_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn health_monitor_interval(app: &tauri::AppHandle) -> Duration {
+    let state = app.state::<ServerState>();
+    let normal = state.general.lock().unwrap().health_tick();
+    if !state.low_power.load(Ordering::SeqCst) { return normal; }
+    let secs = state.battery_policy.lock().unwrap().probe_interval_secs;
+    Duration::from_secs(secs).max(normal)
+}
+
+fn scans_paused(app: &tauri::AppHandle) -> bool {
+    let state = app.state::<ServerState>();
+    state.low_power.load(Ordering::SeqCst) && state.battery_policy.lock().unwrap().pause_scans
+}
+
+/// Running servers that aren't favorites, the ones offered up to save battery.
+fn running_non_favorites(app: &tauri::AppHandle) -> Vec<String> {
+    let favorites = app.path().app_data_dir().map(|d| load_favorites_from_disk(&d)).unwrap_or_default();
+    let mut names: Vec<String> = running_names(&app.state::<ServerState>()).into_iter()
+        .filter(|n| !favorites.contains(n))
+        .collect();
+    names.sort();
+    names
+}
+
+/// Re-reads the battery and enters or leaves low-power mode.
+fn update_power_state(app: &tauri::AppHandle) -> PowerState {
+    let state = app.state::<ServerState>();
+    let policy = state.battery_policy.lock().unwrap().clone();
+    let battery = platform::battery_status();
+    let low_power = policy.enabled && battery.is_some_and(|b| {
+        b.on_battery && b.percent.is_some_and(|p| p <= policy.threshold_percent)
+    });
+    let was_low = state.low_power.swap(low_power, Ordering::SeqCst);
+    if was_low != low_power {
+        let percent = battery.and_then(|b| b.percent);
+        record_event(app, "low-power", None, serde_json::json!({ "enabled": low_power, "percent": percent }));
+        let _ = app.emit("low-power-changed", low_power);
+        let idle = running_non_favorites(app);
+        if low_power && policy.prompt_stop && !idle.is_empty() {
+            notify(app, "low-power", "DexHub", format!(
+                "Battery at {}% — {} non-favorite server{} running. Stop them from the tray to save power.",
+                percent.unwrap_or(policy.threshold_percent), idle.len(), if idle.len() == 1 { "" } else { "s" },
+            ), "");
+        }
+        rebuild_tray(app);
+    }
+    PowerState { battery, low_power }
+}
+
+fn spawn_battery_monitor(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        update_power_state(&app);
+        std::thread::sleep(BATTERY_POLL);
+    });
+}
+
+// ─── Sleep / Wake ─────────────────────────────────────────────────────────────
+
+/// What happens to running servers around system sleep. Whatever the
+/// policy, waking re-reads Tailscale and re-probes health straight away
+/// rather than waiting for the next poll.
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct SleepPolicy {
+    stop_on_sleep:   bool,
+    restart_on_wake: bool, // restarts only what stop_on_sleep stopped
+}
+
+/// Time for Wi-Fi and Tailscale to reconnect before anything is probed.
+const WAKE_SETTLE: Duration = Duration::from_secs(5);
+
+fn sleep_policy_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("sleep_policy.json")
+}
+
+fn load_sleep_policy(app_data_dir: &Path) -> SleepPolicy {
+    std::fs::read_to_string(sleep_policy_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn handle_power_event(app: &tauri::AppHandle, event: platform::PowerEvent) {
+    let state = app.state::<ServerState>();
+    let policy = state.sleep_policy.lock().unwrap().clone();
+    match event {
+        platform::PowerEvent::Sleep => {
+            let mut names: Vec<String> = Vec::new();
+            if policy.stop_on_sleep {
+                names = running_names(&state);
+                names.sort();
+                for name in &names {
+                    let _ = stop_server(app, name.clone());
+                }
+            }
+            *state.stopped_for_sleep.lock().unwrap() = names.clone();
+            record_event(app, "sleep", None, serde_json::json!({ "stopped": names }));
+        }
+        platform::PowerEvent::Wake => {
+            std::thread::sleep(WAKE_SETTLE);
+            refresh_tailscale(app);
+            let stopped = std::mem::take(&mut *state.stopped_for_sleep.lock().unwrap());
+            let mut restarted = Vec::new();
+            if policy.restart_on_wake {
+                for name in stopped {
+                    if is_running(&state, &name) { continue; }
+                    match start_server(app, name.clone()) {
+                        Ok(()) => restarted.push(name),
+                        Err(e) => eprintln!("[DexHub] Restart of '{}' after wake failed: {}", name, e),
+                    }
+                }
+            }
+            run_health_pass(app);
+            record_event(app, "wake", None, serde_json::json!({ "restarted": restarted }));
+        }
+    }
+}
+
+// ─── Crash Recovery ───────────────────────────────────────────────────────────
+
+/// Restart-on-crash for opted-in projects, plus flapping detection: more
+/// than `max_crashes` crashes within `window_mins` marks a project as
+/// flapping, which silences its crash notifications and pauses
+/// auto-restarts until the flag is cleared by hand.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct CrashPolicy {
+    max_crashes:        usize,
+    window_mins:        u64,
+    restart_delay_secs: u64,
+    auto_restart:       Vec<String>, // projects restarted when they crash
+}
+
+impl Default for CrashPolicy {
+    fn default() -> Self {
+        CrashPolicy { max_crashes: 3, window_mins: 10, restart_delay_secs: 2, auto_restart: Vec::new() }
+    }
+}
+
+fn crash_policy_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("crash_policy.json")
+}
+
+fn load_crash_policy(app_data_dir: &Path) -> CrashPolicy {
+    std::fs::read_to_string(crash_policy_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+/// Records a crash and returns (flapping before it, flapping now).
+fn note_crash(app: &tauri::AppHandle, name: &str) -> (bool, bool) {
+    let state = app.state::<ServerState>();
+    let policy = state.crash_policy.lock().unwrap().clone();
+    let mut flapping = state.flapping.lock().unwrap();
+    let was_flapping = flapping.contains(name);
+    let now = unix_now();
+    let mut crash_times = state.crash_times.lock().unwrap();
+    let times = crash_times.entry(name.to_string()).or_default();
+    times.push_back(now);
+    while times.front().is_some_and(|t| now.saturating_sub(*t) > policy.window_mins * 60) {
+        times.pop_front();
+    }
+    if !was_flapping && times.len() > policy.max_crashes {
+        flapping.insert(name.to_string());
+        record_event(app, "flapping", Some(name), serde_json::json!({
+            "crashes": times.len(),
+            "window_mins": policy.window_mins,
+        }));
+    }
+    (was_flapping, flapping.contains(name))
+}
+
+/// Restarts a crashed opted-in project after the configured delay, unless
+/// it has started flapping or been started by hand in the meantime.
+fn schedule_auto_restart(app: &tauri::AppHandle, name: String) {
+    let state = app.state::<ServerState>();
+    let policy = state.crash_policy.lock().unwrap().clone();
+    if !policy.auto_restart.contains(&name) { return; }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_secs(policy.restart_delay_secs));
+        let state = app.state::<ServerState>();
+        if state.flapping.lock().unwrap().contains(&name) { return; }
+        if is_running(&state, &name) { return; }
+        match start_server(&app, name.clone()) {
+            Ok(()) => record_event(&app, "auto-restarted", Some(&name), serde_json::json!({})),
+            Err(e) => eprintln!("[DexHub] Auto-restart of '{}' failed: {}", name, e),
+        }
+    });
+}
+
+/// Lines of output kept in a crash snapshot.
+const CRASH_SNAPSHOT_LINES: usize = 200;
+
+/// `YYYYMMDD-HHMMSS` in UTC, for file names that sort by time.
+fn utc_file_stamp(secs: u64) -> String {
+    let (year, month, day, rem) = utc_date(secs);
+    format!("{:04}{:02}{:02}-{:02}{:02}{:02}", year, month, day, rem / 3600, (rem % 3600) / 60, rem % 60)
+}
+
+/// `YYYY-MM-DDTHH:MM:SS.mmmZ`, for timestamps people read.
+fn utc_iso(ms: u64) -> String {
+    let (year, month, day, rem) = utc_date(ms / 1000);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z", year, month, day, rem / 3600, (rem % 3600) / 60, rem % 60, ms % 1000)
+}
+
+/// (year, month, day, seconds into the day) in UTC.
+fn utc_date(secs: u64) -> (i64, i64, i64, u64) {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day, rem)
+}
+
+/// Writes the final log lines and exit status to
+/// `<app data>/crashes/<name>-<UTC stamp>.log`, so a post-mortem survives
+/// the next start (which replaces the in-memory buffer) and a relaunch.
+fn write_crash_snapshot(
+    app_data_dir: &Path,
+    name: &str,
+    status: std::process::ExitStatus,
+    uptime_secs: Option<u64>,
+    lines: &[String],
+) -> Option<std::path::PathBuf> {
+    let dir = app_data_dir.join("crashes");
+    std::fs::create_dir_all(&dir).ok()?;
+    let file_name: String = name.chars().map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect();
+    let path = dir.join(format!("{}-{}.log", file_name, utc_file_stamp(unix_now())));
+    let mut out = format!(
+        "project: {}\nexit: {}\nuptime: {}\n\n",
+        name,
+        status,
+        uptime_secs.map(notifications::format_duration).unwrap_or_else(|| "unknown".to_string()),
+    );
+    for line in &lines[lines.len().saturating_sub(CRASH_SNAPSHOT_LINES)..] {
+        out.push_str(line);
+        out.push('\n');
+    }
+    std::fs::write(&path, out).ok()?;
+    Some(path)
+}
+
+/// Most recent stderr line, or failing that the last line mentioning an error.
+fn last_error_line(buf: &VecDeque<LogLine>) -> Option<String> {
+    buf.iter().rev()
+        .find_map(|l| l.text.strip_prefix("[err] ").map(|s| s.trim().to_string()).filter(|s| !s.is_empty()))
+        .or_else(|| buf.iter().rev().find(|l| l.text.to_lowercase().contains("error")).map(|l| l.text.clone()))
+}
+
+/// With `profile`, that env profile becomes the active one before starting.
+#[tauri::command]
+fn start_server_cmd(app: tauri::AppHandle, name: String, profile: Option<String>) -> Result<(), StartError> {
+    if profile.is_some() { select_env_profile(app.clone(), name.clone(), profile)?; }
+    launch_server(&app, name, None, &HashMap::new())
+}
+
+#[tauri::command]
+fn stop_server_cmd(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    ensure_not_presenting(&app)?;
+    stop_server(&app, name)
+}
+
+#[tauri::command]
+fn restart_server_cmd(app: tauri::AppHandle, name: String) -> Result<RestartReport, StartError> {
+    ensure_not_presenting(&app)?;
+    relaunch_server(&app, name)
+}
+
+/// Re-reads git metadata for one project without a full rescan.
+#[tauri::command]
+fn refresh_git_info(app: tauri::AppHandle, name: String) -> Result<Option<GitInfo>, String> {
+    refresh_project_git(&app, &name)
+}
+
+#[tauri::command]
+fn start_compose_services(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    start_compose(&app, &name)
+}
+
+#[tauri::command]
+fn stop_compose_services(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    ensure_not_presenting(&app)?;
+    stop_compose(&app, &name)
+}
+
+/// Projects whose compose services are up.
+#[tauri::command]
+fn get_compose_running(app: tauri::AppHandle) -> Vec<String> {
+    compose_running(&app)
+}
+
+/// Size, last source change and last start per project, for "recently
+/// active" sorting. Folder figures are cached for PROJECT_STATS_TTL unless
+/// `refresh` is set.
+#[tauri::command]
+fn get_project_stats(app: tauri::AppHandle, refresh: Option<bool>) -> Vec<ProjectStats> {
+    let state = app.state::<ServerState>();
+    let projects: Vec<(String, String)> = state.projects.lock().unwrap().iter().map(|p| (p.name.clone(), p.cwd.clone())).collect();
+    let last_started = db::last_started(&app);
+    let refresh = refresh.unwrap_or(false);
+    projects.into_iter().map(|(name, cwd)| {
+        let cached = state.folder_stats.lock().unwrap().get(&name).copied()
+            .filter(|(at, _, _)| !refresh && at.elapsed() < PROJECT_STATS_TTL);
+        let (size_bytes, source_modified) = match cached {
+            Some((_, size, modified)) => (size, modified),
+            None => {
+                let (size, modified) = folder_stats(Path::new(&cwd));
+                state.folder_stats.lock().unwrap().insert(name.clone(), (std::time::Instant::now(), size, modified));
+                (size, modified)
+            }
+        };
+        let last_started = last_started.get(&name).copied();
+        ProjectStats { name, size_bytes, source_modified, last_started }
+    }).collect()
+}
+
+/// Framework, key package versions and scripts from package.json, for the
+/// "Vite 5 · React 19 · TS" chips.
+#[tauri::command]
+fn get_project_dependencies(app: tauri::AppHandle, name: String) -> Result<DependencySummary, String> {
+    let cwd = app.state::<ServerState>().projects.lock().unwrap()
+        .iter().find(|p| p.name == name).map(|p| p.cwd.clone())
+        .ok_or_else(|| format!("Project '{}' not found", name))?;
+    dependency_summary(Path::new(&cwd))
+}
+
+/// Installs dependencies with the project's package manager, streaming into
+/// its logs, and returns the status afterwards.
+#[tauri::command]
+fn install_dependencies(app: tauri::AppHandle, name: String) -> Result<DepsStatus, String> {
+    let state = app.state::<ServerState>();
+    let project = state.projects.lock().unwrap().iter().find(|p| p.name == name).cloned()
+        .ok_or_else(|| format!("Project '{}' not found", name))?;
+    let buf = Arc::clone(
+        state.log_buffers.lock().unwrap()
+            .entry(project.id.clone())
+            .or_insert_with(|| Arc::new(Mutex::new(VecDeque::with_capacity(500)))),
+    );
+    let result = install_deps_streamed(&app, &project, &buf);
+    rebuild_tray(&app);
+    result
+}
+
+/// Runs a package.json script (e.g. "test") to completion in the background;
+/// see `script-run` events and `get_script_run`.
+#[tauri::command]
+fn run_script(app: tauri::AppHandle, name: String, script: String) -> Result<(), String> {
+    spawn_script_run(&app, &name, &script)
+}
+
+/// The latest script run for a project, running or finished.
+#[tauri::command]
+fn get_script_run(app: tauri::AppHandle, name: String) -> Option<ScriptRun> {
+    app.state::<ServerState>().script_runs.lock().unwrap().get(&name).cloned()
+}
+
+#[derive(Clone, serde::Serialize)]
+struct CombinedLogLine {
+    source: String, // the server (or `name:services`-style log) the line came from
+    ts_ms:  u64,
+    level:  LogLevel,
+    text:   String,
+}
+
+/// The newest `limit` lines (200 by default) across `names`, oldest first,
+/// interleaved by arrival time. Names are log keys, so a project's compose
+/// or preview log (`web:services`, `web:preview`) can be mixed in too.
+#[tauri::command]
+fn get_combined_logs(app: tauri::AppHandle, names: Vec<String>, limit: Option<usize>) -> Vec<CombinedLogLine> {
+    let state = app.state::<ServerState>();
+    let keys: Vec<(String, String)> = names.iter().map(|n| (n.clone(), project_key(&state, n))).collect();
+    let buffers: Vec<(String, LogBuffer)> = {
+        let all = state.log_buffers.lock().unwrap();
+        keys.iter().filter_map(|(n, key)| all.get(key).map(|b| (n.clone(), Arc::clone(b)))).collect()
+    };
+    let mut lines: Vec<CombinedLogLine> = buffers.iter().flat_map(|(source, buf)| {
+        buf.lock().unwrap().iter()
+            .map(|l| CombinedLogLine { source: source.clone(), ts_ms: l.ts_ms, level: l.level, text: l.text.clone() })
+            .collect::<Vec<_>>()
+    }).collect();
+    // Stable, so lines from one source that share a millisecond keep their order
+    lines.sort_by_key(|l| l.ts_ms);
+    let skip = lines.len().saturating_sub(limit.unwrap_or(200));
+    let mut lines = lines.split_off(skip);
+    if presenting(&app) {
+        let texts = redact_log_lines(&app, lines.iter().map(|l| l.text.clone()).collect());
+        for (line, text) in lines.iter_mut().zip(texts) { line.text = text; }
+    }
+    lines
+}
+
+/// Copies the last `lines` (100 by default) log lines, or only the error
+/// lines, to the clipboard; returns how many were copied.
+#[tauri::command]
+fn copy_server_logs(app: tauri::AppHandle, name: String, lines: Option<usize>, errors_only: Option<bool>) -> Result<usize, String> {
+    // The clipboard outlives presentation mode; masked lines would be no use there
+    ensure_not_presenting(&app)?;
+    Ok(copy_logs(&app, &name, lines.unwrap_or(100), errors_only.unwrap_or(false)))
+}
+
+/// Writes `name`'s log to `path` for a bug report: its saved crash
+/// snapshots first, oldest to newest, then the current buffer with a UTC
+/// timestamp on every line. Returns how many buffered lines were written.
+#[tauri::command]
+fn export_server_logs(app: tauri::AppHandle, name: String, path: String) -> Result<usize, String> {
+    ensure_not_presenting(&app)?;
+    let state = app.state::<ServerState>();
+    if !state.projects.lock().unwrap().iter().any(|p| p.name == name) {
+        return Err(format!("Project '{}' not found", name));
+    }
+    let key = project_key(&state, &name);
+    let lines: Vec<LogLine> = state.log_buffers.lock().unwrap().get(&key)
+        .map(|b| b.lock().unwrap().iter().cloned().collect())
+        .unwrap_or_default();
+
+    let mut out = format!("# {} — exported {}\n", name, utc_iso(unix_now_ms()));
+    // Snapshot files are named `<name>-<stamp>.log`, sanitized as write_crash_snapshot does
+    let file_name: String = name.chars().map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect();
+    let crashes_dir = app.path().app_data_dir().map_err(|e| e.to_string())?.join("crashes");
+    let mut snapshots: Vec<std::path::PathBuf> = std::fs::read_dir(&crashes_dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    snapshots.retain(|p| p.file_name().and_then(|f| f.to_str()).and_then(|f| f.strip_prefix(&format!("{}-", file_name)))
+        .is_some_and(|rest| rest.len() == "YYYYMMDD-HHMMSS.log".len()));
+    snapshots.sort();
+    for snapshot in &snapshots {
+        let Ok(content) = std::fs::read_to_string(snapshot) else { continue };
+        out.push_str(&format!("\n## Crash snapshot {}\n\n{}", snapshot.file_name().unwrap_or_default().to_string_lossy(), content));
+    }
+    out.push_str("\n## Current log\n\n");
+    for line in &lines {
+        out.push_str(&format!("{}  {}\n", utc_iso(line.ts_ms), strip_ansi(&line.text)));
+    }
+    std::fs::write(&path, out).map_err(|e| format!("Couldn't write {}: {}", path, e))?;
+    Ok(lines.len())
+}
+
+#[tauri::command]
+fn get_script_logs(app: tauri::AppHandle, name: String, script: String) -> Vec<String> {
+    get_server_logs(app, script_log_key(&name, &script), None)
+}
+
+/// Output of `docker compose up`, kept separately from the dev server's logs.
+#[tauri::command]
+fn get_compose_logs(app: tauri::AppHandle, name: String) -> Vec<String> {
+    get_server_logs(app, compose_log_key(&name), None)
+}
+
+/// Fetches all remotes, streaming git's output into the project's logs.
+#[tauri::command]
+fn git_fetch(app: tauri::AppHandle, name: String) -> Result<Option<GitInfo>, String> {
+    run_git_action(&app, &name, &["fetch", "--all", "--prune"])
+}
+
+/// Fast-forwards the current branch; never creates a merge commit.
+#[tauri::command]
+fn git_pull(app: tauri::AppHandle, name: String) -> Result<Option<GitInfo>, String> {
+    run_git_action(&app, &name, &["pull", "--ff-only"])
+}
+
+#[tauri::command]
+fn get_pull_before_start(state: tauri::State<'_, ServerState>, name: String) -> bool {
+    state.pull_before_start.lock().unwrap().contains(&name)
+}
+
+#[tauri::command]
+fn set_pull_before_start(app: tauri::AppHandle, name: String, enabled: bool) -> Result<(), String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let state = app.state::<ServerState>();
+    let mut names = state.pull_before_start.lock().unwrap();
+    if enabled { names.insert(name); } else { names.remove(&name); }
+    save_pull_before_start_to_disk(&app_data_dir, &names);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_scheduled_scan_config(state: tauri::State<'_, ServerState>) -> ScheduledScanConfig {
+    state.scheduled_scan.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn get_server_schedules(state: tauri::State<'_, ServerState>) -> HashMap<String, ServerSchedule> {
+    state.server_schedules.lock().unwrap().clone()
+}
+
+/// Sets or (with None) removes a project's start/stop schedule.
+#[tauri::command]
+fn set_server_schedule(app: tauri::AppHandle, name: String, schedule: Option<ServerSchedule>) -> Result<(), String> {
+    if let Some(schedule) = &schedule {
+        if schedule.days.iter().any(|d| !(1..=7).contains(d)) {
+            return Err("days must be 1 (Monday) – 7 (Sunday)".to_string());
+        }
+        for time in schedule.start.iter().chain(schedule.stop.iter()) {
+            if notifications::parse_hhmm(time).is_none() {
+                return Err(format!("'{}' isn't a valid HH:MM time", time));
+            }
+        }
+    }
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let state = app.state::<ServerState>();
+    let mut schedules = state.server_schedules.lock().unwrap();
+    match schedule {
+        Some(schedule) => { schedules.insert(name, schedule); }
+        None => { schedules.remove(&name); }
+    }
+    save_server_schedules_to_disk(&app_data_dir, &schedules);
+    Ok(())
+}
+
+/// `last_run` is kept from the current config; only `enabled` and `hour` are taken.
+#[tauri::command]
+fn set_scheduled_scan_config(app: tauri::AppHandle, config: ScheduledScanConfig) -> Result<(), String> {
+    if config.hour > 23 { return Err("hour must be 0–23".to_string()); }
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let state = app.state::<ServerState>();
+    let mut current = state.scheduled_scan.lock().unwrap();
+    current.enabled = config.enabled;
+    current.hour = config.hour;
+    save_scheduled_scan_config_to_disk(&app_data_dir, &current);
+    Ok(())
+}
+
+/// Runs the nightly scan now and returns what it found.
+#[tauri::command]
+fn run_scheduled_scan_now(app: tauri::AppHandle) -> Result<ScanChanges, String> {
+    run_scheduled_scan(&app)
+}
+
+#[tauri::command]
+fn get_disk_guard_config(state: tauri::State<'_, ServerState>) -> DiskGuardConfig {
+    state.disk_guard.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_disk_guard_config(app: tauri::AppHandle, config: DiskGuardConfig) -> Result<(), String> {
+    *app.state::<ServerState>().disk_guard.lock().unwrap() = config;
+    settings::persist(&app)
+}
+
+/// Free MB on the project's volume, or `None` if `df` couldn't tell.
+#[tauri::command]
+fn get_free_disk_space(app: tauri::AppHandle, name: String) -> Option<u64> {
+    let state = app.state::<ServerState>();
+    let cwd = state.projects.lock().unwrap().iter().find(|p| p.name == name).map(|p| p.cwd.clone())?;
+    free_disk_mb(&cwd)
+}
+
+/// Blue-green restart; returns the port the new instance serves on.
+#[tauri::command]
+fn canary_restart_cmd(app: tauri::AppHandle, name: String) -> Result<u16, String> {
+    ensure_not_presenting(&app)?;
+    canary_restart(&app, name)
+}
+
+#[tauri::command]
+fn get_canary_config(app: tauri::AppHandle, name: String) -> CanaryConfig {
+    let state = app.state::<ServerState>();
+    let result = state.canary.lock().unwrap().get(&name).cloned().unwrap_or_default();
+    result
+}
+
+#[tauri::command]
+fn set_canary_config(app: tauri::AppHandle, name: String, config: CanaryConfig) -> Result<(), String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let state = app.state::<ServerState>();
+    let mut all = state.canary.lock().unwrap();
+    all.insert(name, config);
+    save_canary_config_to_disk(&app_data_dir, &all);
+    Ok(())
+}
+
+/// Copies the project's URL in `format` (plain by default) with `host`
+/// (the project's own by default), and returns what was copied.
+#[tauri::command]
+fn copy_project_url(app: tauri::AppHandle, name: String, format: Option<UrlFormat>, host: Option<UrlHost>) -> Result<String, String> {
+    copy_url_as(&app, &name, format.unwrap_or_default(), host.unwrap_or_default())
+        .ok_or_else(|| format!("Project '{}' not found", name))
+}
+
+#[tauri::command]
+fn open_log_window_cmd(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    open_log_window(&app, &name)
+}
+
+#[tauri::command]
+fn restart_server_clean_cmd(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    ensure_not_presenting(&app)?;
+    restart_server_clean(&app, name)
+}
+
+#[tauri::command]
+fn bulk_action_cmd(app: tauri::AppHandle, names: Vec<String>, action: BulkAction) -> Vec<BulkItemResult> {
+    if presenting(&app) && matches!(action, BulkAction::Stop | BulkAction::Restart) {
+        let error = Some(PRESENTATION_BLOCKED.to_string());
+        return names.into_iter().map(|name| BulkItemResult { name, ok: false, error: error.clone() }).collect();
+    }
+    bulk_action(&app, names, action)
+}
+
+#[tauri::command]
+fn stop_all_servers_cmd(app: tauri::AppHandle) -> Result<(), String> {
+    ensure_not_presenting(&app)?;
+    stop_all_servers(&app);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_port_registry(app: tauri::AppHandle) -> BTreeMap<String, PortHistory> {
+    match app.path().app_data_dir() {
+        Ok(d) => load_port_registry(&d),
+        Err(_) => BTreeMap::new(),
+    }
+}
+
+/// Current default-port collisions and the ports DexHub assigned to resolve them.
+#[tauri::command]
+fn get_port_conflicts(app: tauri::AppHandle) -> Vec<PortConflict> {
+    let Ok(app_data_dir) = app.path().app_data_dir() else { return Vec::new() };
+    let overrides = load_port_overrides(&app_data_dir);
+    let state = app.state::<ServerState>();
+    let projects = state.projects.lock().unwrap();
+    let mut by_port: BTreeMap<u16, Vec<&ProjectConfig>> = BTreeMap::new();
+    for p in projects.iter().filter(|p| !overrides.contains_key(&p.id)) {
+        by_port.entry(p.default_port).or_default().push(p);
+    }
+    by_port.into_iter().filter(|(_, m)| m.len() > 1).map(|(port, members)| PortConflict {
+        port,
+        projects: members.iter().map(|p| p.name.clone()).collect(),
+        kept_by: members.iter().find(|p| p.port == port).map(|p| p.name.clone()).unwrap_or_default(),
+        assigned: members.iter().filter(|p| p.port != port).map(|p| (p.name.clone(), p.port)).collect(),
+    }).collect()
+}
+
+#[tauri::command]
+fn update_server_port(app: tauri::AppHandle, name: String, port: u16) -> Result<(), String> {
+    ensure_not_presenting(&app)?;
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let state = app.state::<ServerState>();
+    let mut projects = state.projects.lock().unwrap();
+    let project = projects.iter_mut().find(|p| p.name == name)
+        .ok_or_else(|| format!("Project '{}' not found", name))?;
+    project.port = port;
+    let mut overrides = load_port_overrides(&app_data_dir);
+    overrides.insert(project.id.clone(), port);
+    save_port_overrides(&app_data_dir, &overrides);
+    Ok(())
+}
+
+#[tauri::command]
+fn open_terminal_here(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    let state = app.state::<ServerState>();
+    let projects = state.projects.lock().unwrap().clone();
+    if let Some(project) = projects.iter().find(|p| p.name == name) {
+        platform::open_terminal(&project.cwd)?;
+    }
+    Ok(())
+}
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct EditorConfig {
+    editor: Option<String>, // platform::EDITORS id; first installed one when unset
+}
+
+fn editor_config_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("editor.json")
+}
+
+/// Only read to seed settings.json, which holds the editor choice now.
+fn load_editor_config(app_data_dir: &Path) -> EditorConfig {
+    std::fs::read_to_string(editor_config_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+#[derive(serde::Serialize)]
+struct EditorInfo {
+    id:        String,
+    label:     String,
+    installed: bool,
+}
+
+#[tauri::command]
+fn list_editors() -> Vec<EditorInfo> {
+    platform::EDITORS.iter()
+        .map(|e| EditorInfo { id: e.id.to_string(), label: e.label.to_string(), installed: platform::editor_installed(e) })
+        .collect()
+}
+
+#[tauri::command]
+fn get_editor_config(state: tauri::State<'_, ServerState>) -> EditorConfig {
+    state.editor.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_editor_config(app: tauri::AppHandle, config: EditorConfig) -> Result<(), String> {
+    *app.state::<ServerState>().editor.lock().unwrap() = config;
+    settings::persist(&app)
+}
+
+/// Opens the project in the configured editor, or the first one installed.
+#[tauri::command]
+fn open_in_editor(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    let cwd = app.state::<ServerState>().projects.lock().unwrap()
+        .iter()
+        .find(|p| p.name == name)
+        .map(|p| p.cwd.clone())
+        .ok_or_else(|| format!("Project '{}' not found", name))?;
+    let configured = app.state::<ServerState>().editor.lock().unwrap().editor.clone();
+    let editor = match configured {
+        Some(id) => id,
+        None => platform::EDITORS.iter()
+            .find(|e| platform::editor_installed(e))
+            .map(|e| e.id.to_string())
+            .ok_or("No supported editor found (VS Code, Cursor, Zed or JetBrains)")?,
+    };
+    platform::open_in_editor(&editor, &cwd)
+}
+
+#[tauri::command]
+fn reveal_project_folder(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    let state = app.state::<ServerState>();
+    let cwd = state.projects.lock().unwrap().iter().find(|p| p.name == name).map(|p| p.cwd.clone());
+    match cwd {
+        Some(cwd) => platform::reveal_in_file_manager(&cwd),
+        None => Err(format!("Project '{}' not found", name)),
+    }
+}
+
+/// The project's URL, or with `label` the URL of its extra port by that
+/// label (case-insensitive).
+#[tauri::command]
+fn get_server_url(app: tauri::AppHandle, name: String, label: Option<String>) -> Result<String, String> {
+    let state = app.state::<ServerState>();
+    let projects = state.projects.lock().unwrap().clone();
+    let project = projects.iter().find(|p| p.name == name).ok_or_else(|| format!("Project '{}' not found", name))?;
+    let Some(label) = label else { return Ok(browser_url(&state, project)) };
+    project.extra_ports.iter()
+        .find(|e| e.label.as_deref().is_some_and(|l| l.eq_ignore_ascii_case(&label)))
+        .map(|e| extra_port_url(&state, project, e.port))
+        .ok_or_else(|| format!("'{}' has no port labeled '{}'", name, label))
+}
+
+#[tauri::command]
+fn check_server_health(app: tauri::AppHandle, name: String) -> bool {
+    let state = app.state::<ServerState>();
+    let (port, extra_ports) = {
+        let projects = state.projects.lock().unwrap();
+        match projects.iter().find(|p| p.name == name) {
+            Some(p) => (Some(effective_port(&state, p)), p.extra_ports.clone()),
+            None => (None, Vec::new()),
+        }
+    };
+    let check = state.health_checks.lock().unwrap().get(&name).cloned();
+    let timeout = state.general.lock().unwrap().health_timeout_for(&name);
+    let mut primary = port.map(|p| probe_port(p, None, true, timeout));
+    let reachable = primary.as_ref().is_some_and(|p| p.reachable);
+    // Connecting to localhost takes ~0 ms whatever the server is doing;
+    // time a real request instead, falling back to the connect for non-HTTP servers
+    if let (true, Some(p), Some(probe)) = (reachable, port, primary.as_mut()) {
+        let path = match &check {
+            Some(c) if c.path.starts_with('/') => c.path.clone(),
+            Some(c) => format!("/{}", c.path),
+            None => "/".to_string(),
+        };
+        if let Some(ttfb) = http_ttfb(p, &path, timeout.max(Duration::from_secs(2))) { probe.latency_ms = Some(ttfb); }
+    }
+    if let Some(latency) = primary.as_ref().and_then(|p| p.latency_ms) {
+        state.latency_cache.lock().unwrap().insert(name.clone(), latency);
+        db::record_latency(&app, &name, latency);
+    }
+    // A 200 with an error page is still unhealthy when the project has assertions
+    let failure = match (reachable, port, &check) {
+        (true, Some(p), Some(check)) => run_health_check(p, check).err(),
+        _ => None,
+    };
+    // The other ports are parts of the same app (its API, Storybook); one
+    // going down makes the whole project unhealthy
+    let extras: Vec<PortHealth> = extra_ports.iter().map(|e| probe_port(e.port, e.label.clone(), false, timeout)).collect();
+    let failure = failure.or_else(|| {
+        if !reachable { return None; }
+        extras.iter().find(|p| !p.reachable).map(|p| {
+            let port = ExtraPort { port: p.port, label: p.label.clone() };
+            format!("{} not reachable", port.display())
+        })
+    });
+    let healthy = reachable && failure.is_none();
+    if healthy { note_success(&state, &name); }
+    state.health_details.lock().unwrap().insert(name.clone(), HealthDetails {
+        name: name.clone(),
+        healthy,
+        reason: failure.clone(),
+        checked_at: unix_now(),
+        ports: primary.into_iter().chain(extras).collect(),
+    });
+    // Slow-booting servers are expected to fail checks right after launch;
+    // don't let that register as a transition worth alerting on.
+    if !healthy && in_health_warmup(&state, &name) {
+        state.last_health.lock().unwrap().remove(&name);
+        return healthy;
+    }
+    let previous = state.last_health.lock().unwrap().insert(name.clone(), healthy);
+    if previous.is_some_and(|p| p != healthy) {
+        record_event(&app, "health-changed", Some(&name), serde_json::json!({ "healthy": healthy, "reason": failure }));
+    }
+    healthy
+}
+
+#[derive(Clone, serde::Serialize)]
+struct PortHealth {
+    port:       u16,
+    label:      Option<String>,
+    primary:    bool,
+    reachable:  bool,
+    latency_ms: Option<u64>, // HTTP time to first byte on the main port, TCP connect time on the others; None when unreachable
+}
+
+/// The last health check of a project, port by port.
+#[derive(Clone, serde::Serialize)]
+struct HealthDetails {
+    name:       String,
+    healthy:    bool,
+    reason:     Option<String>, // first failed assertion or unreachable extra port
+    checked_at: u64,            // unix secs
+    ports:      Vec<PortHealth>, // the main port first
+}
+
+fn probe_port(port: u16, label: Option<String>, primary: bool, timeout: Duration) -> PortHealth {
+    let start = std::time::Instant::now();
+    let reachable = TcpStream::connect_timeout(
+        &std::net::SocketAddr::from(([127, 0, 0, 1], port)),
+        timeout,
+    ).is_ok();
+    let latency_ms = reachable.then(|| start.elapsed().as_millis() as u64);
+    PortHealth { port, label, primary, reachable, latency_ms }
+}
+
+/// Per-port results of the latest health pass, for running servers only;
+/// all of them when `name` is None.
+#[tauri::command]
+fn get_health_details(app: tauri::AppHandle, name: Option<String>) -> Vec<HealthDetails> {
+    let state = app.state::<ServerState>();
+    let running: HashSet<String> = running_names(&state).into_iter().collect();
+    let details = state.health_details.lock().unwrap();
+    let mut result: Vec<HealthDetails> = details.values()
+        .filter(|d| running.contains(&d.name))
+        .filter(|d| match &name { Some(n) => *n == d.name, None => true })
+        .cloned()
+        .collect();
+    result.sort_by(|a, b| a.name.cmp(&b.name));
+    result
+}
+
+#[tauri::command]
+fn get_health_check(app: tauri::AppHandle, name: String) -> Option<HealthCheck> {
+    let state = app.state::<ServerState>();
+    let result = state.health_checks.lock().unwrap().get(&name).cloned();
+    result
+}
+
+/// Sets the project's HTTP health check; `None` reverts to a TCP connect check.
+#[tauri::command]
+fn set_health_check(app: tauri::AppHandle, name: String, check: Option<HealthCheck>) -> Result<(), String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let state = app.state::<ServerState>();
+    let mut checks = state.health_checks.lock().unwrap();
+    match check {
+        Some(c) => { checks.insert(name, c); }
+        None => { checks.remove(&name); }
+    }
+    save_health_checks_to_disk(&app_data_dir, &checks);
+    Ok(())
+}
+
+/// Runs the configured assertions once and reports the first failure, if any.
+#[tauri::command]
+fn test_health_check(app: tauri::AppHandle, name: String, check: HealthCheck) -> Result<(), String> {
+    let state = app.state::<ServerState>();
+    let port = state.projects.lock().unwrap().iter().find(|p| p.name == name).map(|p| effective_port(&state, p));
+    let port = port.ok_or_else(|| format!("Project '{}' not found", name))?;
+    run_health_check(port, &check)
+}
+
+/// True while the server is inside its post-start warm-up window.
+#[tauri::command]
+fn is_health_warming_up(app: tauri::AppHandle, name: String) -> bool {
+    in_health_warmup(&app.state::<ServerState>(), &name)
+}
+
+#[tauri::command]
+fn get_health_warmup(app: tauri::AppHandle, name: String) -> u64 {
+    let state = app.state::<ServerState>();
+    let result = state.warmup_secs.lock().unwrap().get(&name).copied().unwrap_or(DEFAULT_HEALTH_WARMUP_SECS);
+    result
+}
+
+/// Sets the project's warm-up window; `None` restores the default.
+#[tauri::command]
+fn set_health_warmup(app: tauri::AppHandle, name: String, secs: Option<u64>) -> Result<(), String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let state = app.state::<ServerState>();
+    let mut overrides = state.warmup_secs.lock().unwrap();
+    match secs {
+        Some(s) => { overrides.insert(name, s); }
+        None => { overrides.remove(&name); }
+    }
+    save_warmup_overrides_to_disk(&app_data_dir, &overrides);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_server_latency(app: tauri::AppHandle, name: String) -> Option<u64> {
+    let state = app.state::<ServerState>();
+    let result = state.latency_cache.lock().unwrap().get(&name).copied();
+    result
+}
+
+#[tauri::command]
+fn get_server_uptime(app: tauri::AppHandle, name: String) -> Option<u64> {
+    let state = app.state::<ServerState>();
+    // A server a previous session started outlives it only in run_history
+    if is_running(&state, &name) { return uptime_secs(&state, &name); }
+    db::open_run_started(&app, &name).map(|t| unix_now().saturating_sub(t))
+}
+
+/// Latest %CPU of the server's whole process tree, sampled by the watchdog.
+#[tauri::command]
+fn get_server_cpu(app: tauri::AppHandle, name: String) -> Option<f32> {
+    let state = app.state::<ServerState>();
+    let result = state.cpu_samples.lock().unwrap().get(&name).copied();
+    result
+}
+
+/// Resident memory of the server's process tree in MB, sampled with the CPU.
+#[tauri::command]
+fn get_server_memory(app: tauri::AppHandle, name: String) -> Option<u64> {
+    let state = app.state::<ServerState>();
+    let result = state.rss_samples.lock().unwrap().get(&name).copied();
+    result
+}
+
+#[tauri::command]
+fn get_resource_limits(state: tauri::State<'_, ServerState>) -> HashMap<String, ResourceLimits> {
+    state.resource_limits.lock().unwrap().clone()
+}
+
+/// Niceness takes effect on the next start; the memory ceiling immediately.
+#[tauri::command]
+fn set_resource_limits(app: tauri::AppHandle, name: String, limits: ResourceLimits) -> Result<(), String> {
+    if limits.nice.is_some_and(|n| !(0..=19).contains(&n)) {
+        return Err("nice must be 0–19".to_string());
+    }
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let state = app.state::<ServerState>();
+    let mut all = state.resource_limits.lock().unwrap();
+    if limits == ResourceLimits::default() {
+        all.remove(&name);
+    } else {
+        all.insert(name, limits);
+    }
+    save_resource_limits_to_disk(&app_data_dir, &all);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_runaway_servers(app: tauri::AppHandle) -> Vec<String> {
+    let state = app.state::<ServerState>();
+    let mut names: Vec<String> = state.runaway.lock().unwrap().iter().cloned().collect();
+    names.sort();
+    names
+}
+
+#[tauri::command]
+fn get_watchdog_config(state: tauri::State<'_, ServerState>) -> WatchdogConfig {
+    state.watchdog.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn get_crash_policy(state: tauri::State<'_, ServerState>) -> CrashPolicy {
+    state.crash_policy.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_crash_policy(app: tauri::AppHandle, policy: CrashPolicy) -> Result<(), String> {
+    *app.state::<ServerState>().crash_policy.lock().unwrap() = policy;
+    settings::persist(&app)
+}
+
+#[tauri::command]
+fn get_battery_policy(state: tauri::State<'_, ServerState>) -> BatteryPolicy {
+    state.battery_policy.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_battery_policy(app: tauri::AppHandle, policy: BatteryPolicy) -> Result<(), String> {
+    *app.state::<ServerState>().battery_policy.lock().unwrap() = policy;
+    update_power_state(&app);
+    settings::persist(&app)
+}
+
+/// Battery reading and whether low-power mode is on; also emitted as `low-power-changed`.
+#[tauri::command]
+fn get_power_state(app: tauri::AppHandle) -> PowerState {
+    update_power_state(&app)
+}
+
+/// Stops every running server that isn't a favorite.
+#[tauri::command]
+fn stop_non_favorite_servers(app: tauri::AppHandle) -> Result<Vec<BulkItemResult>, String> {
+    ensure_not_presenting(&app)?;
+    let names = running_non_favorites(&app);
+    Ok(bulk_action(&app, names, BulkAction::Stop))
+}
+
+#[tauri::command]
+fn get_sleep_policy(state: tauri::State<'_, ServerState>) -> SleepPolicy {
+    state.sleep_policy.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_sleep_policy(app: tauri::AppHandle, policy: SleepPolicy) -> Result<(), String> {
+    *app.state::<ServerState>().sleep_policy.lock().unwrap() = policy;
+    settings::persist(&app)
+}
+
+#[tauri::command]
+fn get_flapping(state: tauri::State<'_, ServerState>) -> Vec<String> {
+    let mut names: Vec<String> = state.flapping.lock().unwrap().iter().cloned().collect();
+    names.sort();
+    names
+}
+
+/// Clears the flag and the crash count, re-enabling notifications and auto-restart.
+#[tauri::command]
+fn clear_flapping(app: tauri::AppHandle, name: String) {
+    let state = app.state::<ServerState>();
+    let was_flapping = state.flapping.lock().unwrap().remove(&name);
+    state.crash_times.lock().unwrap().remove(&name);
+    if was_flapping {
+        record_event(&app, "flapping-cleared", Some(&name), serde_json::json!({}));
+        rebuild_tray(&app);
+    }
+}
+
+#[tauri::command]
+fn set_watchdog_config(app: tauri::AppHandle, config: WatchdogConfig) -> Result<(), String> {
+    *app.state::<ServerState>().watchdog.lock().unwrap() = config;
+    settings::persist(&app)
+}
+
+/// A log buffer's lines as stored, unmasked; callers that show them go
+/// through `redact_log_lines`. With `level`, only lines at least that severe.
+fn server_log_lines(app: &tauri::AppHandle, name: &str, level: Option<LogLevel>) -> Vec<String> {
+    let state = app.state::<ServerState>();
+    let key = project_key(&state, name);
+    let buf = state.log_buffers.lock().unwrap().get(&key).cloned();
+    buf.map(|buf| {
+        let buf = buf.lock().unwrap();
+        match level {
+            None => log_texts(&buf),
+            Some(min) => buf.iter().filter(|l| l.level >= min).map(|l| l.text.clone()).collect(),
+        }
+    }).unwrap_or_default()
+}
+
+/// With `level`, only lines at least that severe ("error" for errors only).
+/// Masked in presentation mode.
+#[tauri::command]
+fn get_server_logs(app: tauri::AppHandle, name: String, level: Option<LogLevel>) -> Vec<String> {
+    redact_log_lines(&app, server_log_lines(&app, &name, level))
+}
+
+#[tauri::command]
+fn get_tailscale_address(state: tauri::State<'_, ServerState>) -> String {
+    if state.presentation_mode.load(Ordering::SeqCst) { return REDACTED.to_string(); }
+    state.tailscale_host.lock().unwrap().clone()
 }
 
-fn stop_server(app: &tauri::AppHandle, name: String) {
-    let state = app.state::<ServerState>();
-    if let Some(mut child) = state.processes.lock().unwrap().remove(&name) {
-        let _ = child.kill();
+#[tauri::command]
+fn get_tailscale_status_cmd(state: tauri::State<'_, ServerState>) -> TailscaleStatus {
+    if state.presentation_mode.load(Ordering::SeqCst) {
+        return TailscaleStatus {
+            host:   REDACTED.to_string(),
+            online: state.tailscale_online.load(Ordering::SeqCst),
+            lan_ip: None,
+        };
+    }
+    TailscaleStatus {
+        host:   state.tailscale_host.lock().unwrap().clone(),
+        online: state.tailscale_online.load(Ordering::SeqCst),
+        lan_ip: state.lan_ip.lock().unwrap().clone(),
     }
-    state.start_times.lock().unwrap().remove(&name);
-    // Keep log buffer around after stop for post-mortem viewing
-    rebuild_tray(app);
 }
 
-fn open_in_browser(app: &tauri::AppHandle, name: String) {
+#[tauri::command]
+fn list_network_addresses(app: tauri::AppHandle) -> Vec<NetworkAddress> {
+    if presenting(&app) { return Vec::new(); }
+    list_local_addresses()
+}
+
+/// The project's host choice: "auto", "lan", "localhost" or a custom host.
+#[tauri::command]
+fn get_url_host(app: tauri::AppHandle, name: String) -> String {
     let state = app.state::<ServerState>();
-    let projects = state.projects.lock().unwrap().clone();
-    if let Some(project) = projects.iter().find(|p| p.name == name) {
-        let url = format!("http://{}:{}", state.tailscale_host, project.port);
-        let _ = std::process::Command::new("open").arg(&url).spawn();
+    let result = state.url_hosts.lock().unwrap().get(&name).cloned().unwrap_or_else(|| "auto".to_string());
+    result
+}
+
+/// Sets the host `get_server_url` uses for a project; `None` or "auto" resets it.
+#[tauri::command]
+fn set_url_host(app: tauri::AppHandle, name: String, host: Option<String>) -> Result<(), String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    {
+        let state = app.state::<ServerState>();
+        let mut hosts = state.url_hosts.lock().unwrap();
+        match host.map(|h| h.trim().to_string()) {
+            Some(h) if !h.is_empty() && h != "auto" => { hosts.insert(name, h); }
+            _ => { hosts.remove(&name); }
+        }
+        save_url_hosts_to_disk(&app_data_dir, &hosts);
     }
+    rebuild_tray(&app);
+    Ok(())
 }
 
-fn copy_url(app: &tauri::AppHandle, name: String) {
-    let state = app.state::<ServerState>();
-    let projects = state.projects.lock().unwrap().clone();
-    if let Some(project) = projects.iter().find(|p| p.name == name) {
-        let url = format!("http://{}:{}", state.tailscale_host, project.port);
-        let _ = std::process::Command::new("bash")
-            .args(["-c", &format!("echo -n '{}' | pbcopy", url)])
-            .spawn();
+#[tauri::command]
+fn list_tailnet_devices(app: tauri::AppHandle) -> Vec<TailnetDevice> {
+    if presenting(&app) { return Vec::new(); }
+    get_tailnet_devices()
+}
+
+/// Pushes a project's URL to another tailnet device via Taildrop, as a
+/// `.webloc` file the receiving device can open with one tap.
+#[tauri::command]
+fn send_url_to_device(app: tauri::AppHandle, name: String, device: String) -> Result<(), String> {
+    ensure_not_presenting(&app)?;
+    let url = get_server_url(app, name.clone(), None)?;
+    let target = get_tailnet_devices()
+        .into_iter()
+        .find(|d| !d.is_self && (d.host_name == device || d.dns_name == device))
+        .ok_or_else(|| format!("Device '{}' not found on tailnet", device))?;
+    if !target.online {
+        return Err(format!("Device '{}' is offline", target.host_name));
+    }
+    let safe_name: String = name.chars().map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect();
+    let file = std::env::temp_dir().join(format!("{}.webloc", safe_name));
+    let webloc = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>URL</key>
+    <string>{}</string>
+</dict>
+</plist>"#,
+        url
+    );
+    std::fs::write(&file, webloc).map_err(|e| e.to_string())?;
+    let output = std::process::Command::new("tailscale")
+        .args(["file", "cp", &file.to_string_lossy(), &format!("{}:", target.host_name)])
+        .output()
+        .map_err(|e| e.to_string())?;
+    let _ = std::fs::remove_file(&file);
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
     }
 }
 
-// ─── Tauri Commands ───────────────────────────────────────────────────────────
+#[tauri::command]
+fn start_tunnel_cmd(app: tauri::AppHandle, name: String, provider: Option<String>) -> Result<(), String> {
+    ensure_not_presenting(&app)?;
+    start_tunnel(&app, name, provider, None)
+}
 
 #[tauri::command]
-fn list_projects(state: tauri::State<'_, ServerState>) -> Vec<ProjectConfig> {
-    state.projects.lock().unwrap().clone()
+fn create_share_link_cmd(
+    app: tauri::AppHandle,
+    name: String,
+    ttl_secs: u64,
+    passcode: Option<String>,
+    provider: Option<String>,
+) -> Result<ShareLink, String> {
+    ensure_not_presenting(&app)?;
+    create_share_link(&app, name, ttl_secs, passcode, provider)
 }
 
+/// Most recent `limit` events (server lifecycle, scans, hook output), oldest first.
 #[tauri::command]
-fn get_running_servers(app: tauri::AppHandle) -> Vec<String> {
+fn get_event_log(app: tauri::AppHandle, limit: Option<usize>) -> Vec<EventRecord> {
     let state = app.state::<ServerState>();
-    let (names, crashed_names) = {
-        let mut procs = state.processes.lock().unwrap();
-        let before: Vec<String> = procs.keys().cloned().collect();
-        procs.retain(|_, child| child.try_wait().map(|s| s.is_none()).unwrap_or(true));
-        let after: HashSet<&String> = procs.keys().collect();
-        let crashed: Vec<String> = before.into_iter().filter(|n| !after.contains(n)).collect();
-        let names = procs.keys().cloned().collect::<Vec<String>>();
-        (names, crashed)
-    };
-    if !crashed_names.is_empty() {
-        let mut start_times = state.start_times.lock().unwrap();
-        for n in &crashed_names { start_times.remove(n); }
-        drop(start_times);
-        for n in &crashed_names { notify_crash(n); }
-        rebuild_tray(&app);
-    }
-    names
+    let log = state.event_log.lock().unwrap();
+    let skip = log.len().saturating_sub(limit.unwrap_or(EVENT_LOG_CAPACITY));
+    let result = log.iter().skip(skip).cloned().collect();
+    result
 }
 
+/// Executables currently registered in the hooks directory.
 #[tauri::command]
-fn start_server_cmd(app: tauri::AppHandle, name: String) -> Result<(), String> {
-    start_server(&app, name);
-    Ok(())
+fn list_hooks(app: tauri::AppHandle) -> Vec<String> {
+    match app.path().app_data_dir() {
+        Ok(d) => list_hook_executables(&d)
+            .iter()
+            .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .collect(),
+        Err(_) => Vec::new(),
+    }
 }
 
 #[tauri::command]
-fn stop_server_cmd(app: tauri::AppHandle, name: String) -> Result<(), String> {
-    stop_server(&app, name);
-    Ok(())
+fn get_notification_config(state: tauri::State<'_, ServerState>) -> NotificationConfig {
+    state.notifications.lock().unwrap().clone()
 }
 
 #[tauri::command]
-fn restart_server_cmd(app: tauri::AppHandle, name: String) -> Result<(), String> {
-    stop_server(&app, name.clone());
-    // Brief yield so the OS can reclaim the port before re-binding
-    std::thread::sleep(Duration::from_millis(300));
-    start_server(&app, name);
-    Ok(())
+fn set_notification_config(app: tauri::AppHandle, config: NotificationConfig) -> Result<(), String> {
+    *app.state::<ServerState>().notifications.lock().unwrap() = config;
+    settings::persist(&app)
 }
 
+/// Per-project notification overrides; None goes back to the defaults.
 #[tauri::command]
-fn stop_all_servers_cmd(app: tauri::AppHandle) -> Result<(), String> {
+fn set_project_alerts(app: tauri::AppHandle, name: String, alerts: Option<notifications::ProjectAlerts>) -> Result<(), String> {
     {
         let state = app.state::<ServerState>();
-        let mut procs = state.processes.lock().unwrap();
-        for (_, child) in procs.iter_mut() { let _ = child.kill(); }
-        procs.clear();
-        state.start_times.lock().unwrap().clear();
+        let mut config = state.notifications.lock().unwrap();
+        match alerts {
+            Some(alerts) => { config.projects.insert(name, alerts); }
+            None => { config.projects.remove(&name); }
+        }
     }
-    rebuild_tray(&app);
-    Ok(())
+    settings::persist(&app)
 }
 
 #[tauri::command]
-fn update_server_port(app: tauri::AppHandle, name: String, port: u16) -> Result<(), String> {
-    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
-    let mut overrides = load_port_overrides(&app_data_dir);
-    overrides.insert(name.clone(), port);
-    save_port_overrides(&app_data_dir, &overrides);
-    let state = app.state::<ServerState>();
-    let mut projects = state.projects.lock().unwrap();
-    if let Some(p) = projects.iter_mut().find(|p| p.name == name) { p.port = port; }
-    Ok(())
+fn set_notifications_muted(app: tauri::AppHandle, muted: bool) -> Result<(), String> {
+    app.state::<ServerState>().notifications.lock().unwrap().muted = muted;
+    settings::persist(&app)
 }
 
+/// Renders a crash template against sample values so the UI can preview it.
 #[tauri::command]
-fn open_terminal_here(app: tauri::AppHandle, name: String) -> Result<(), String> {
+fn preview_crash_notification(template: String) -> String {
+    notifications::render_crash_message(&template, &CrashInfo {
+        name: "my-app".to_string(),
+        exit: None,
+        uptime_secs: Some(754),
+        last_error_line: Some("Error: listen EADDRINUSE: address already in use :::5173".to_string()),
+        snapshot_path: Some("~/Library/Application Support/com.dexhub.client/crashes/my-app-20250101-120000.log".to_string()),
+    })
+}
+
+/// Last license inventory for the project, if one has been collected.
+#[tauri::command]
+fn get_license_report(app: tauri::AppHandle, name: String) -> Option<licenses::LicenseReport> {
     let state = app.state::<ServerState>();
-    let projects = state.projects.lock().unwrap().clone();
-    if let Some(project) = projects.iter().find(|p| p.name == name) {
-        std::process::Command::new("open")
-            .args(["-a", "Terminal", &project.cwd])
-            .spawn()
-            .map_err(|e| e.to_string())?;
-    }
+    let result = state.license_reports.lock().unwrap().get(&name).cloned();
+    result
+}
+
+/// Collects the inventory in the background; emits `license-report-ready` when done.
+#[tauri::command]
+fn refresh_license_report(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    let cwd = {
+        let state = app.state::<ServerState>();
+        let projects = state.projects.lock().unwrap();
+        projects.iter().find(|p| p.name == name).map(|p| p.cwd.clone())
+    }.ok_or_else(|| format!("Project '{}' not found", name))?;
+    let policy = app.state::<ServerState>().license_policy.lock().unwrap().clone();
+    std::thread::spawn(move || {
+        let report = licenses::collect_license_report(&name, Path::new(&cwd), &policy, unix_now());
+        app.state::<ServerState>().license_reports.lock().unwrap().insert(name.clone(), report.clone());
+        let _ = app.emit("license-report-ready", report);
+    });
     Ok(())
 }
 
 #[tauri::command]
-fn get_server_url(app: tauri::AppHandle, name: String) -> Result<String, String> {
-    let state = app.state::<ServerState>();
-    let projects = state.projects.lock().unwrap().clone();
-    match projects.iter().find(|p| p.name == name) {
-        Some(project) => Ok(format!("http://{}:{}", state.tailscale_host, project.port)),
-        None => Err(format!("Project '{}' not found", name)),
-    }
+fn get_license_policy(state: tauri::State<'_, ServerState>) -> licenses::LicensePolicy {
+    state.license_policy.lock().unwrap().clone()
 }
 
+/// Replaces the disallowed list; cached reports are cleared so flags stay accurate.
 #[tauri::command]
-fn check_server_health(app: tauri::AppHandle, name: String) -> bool {
+fn set_license_policy(app: tauri::AppHandle, policy: licenses::LicensePolicy) -> Result<(), String> {
     let state = app.state::<ServerState>();
-    let port = {
-        let projects = state.projects.lock().unwrap();
-        projects.iter().find(|p| p.name == name).map(|p| p.port)
-    };
-    let start = std::time::Instant::now();
-    let healthy = port.map(|p| {
-        TcpStream::connect_timeout(
-            &std::net::SocketAddr::from(([127, 0, 0, 1], p)),
-            Duration::from_millis(200),
-        ).is_ok()
-    }).unwrap_or(false);
-    if healthy {
-        let latency = start.elapsed().as_millis() as u64;
-        state.latency_cache.lock().unwrap().insert(name, latency);
+    *state.license_policy.lock().unwrap() = policy;
+    state.license_reports.lock().unwrap().clear();
+    settings::persist(&app)
+}
+
+#[tauri::command]
+fn get_presentation_mode(state: tauri::State<'_, ServerState>) -> bool {
+    state.presentation_mode.load(Ordering::SeqCst)
+}
+
+/// Toggles presentation mode; emits `presentation-mode-changed` so every window re-renders.
+#[tauri::command]
+fn set_presentation_mode(app: tauri::AppHandle, enabled: bool) {
+    app.state::<ServerState>().presentation_mode.store(enabled, Ordering::SeqCst);
+    rebuild_tray(&app);
+    let _ = app.emit("presentation-mode-changed", enabled);
+    append_audit(&app, if enabled { "presentation-on" } else { "presentation-off" }, "", serde_json::json!({}));
+}
+
+/// Most recent `limit` audit entries, oldest first.
+#[tauri::command]
+fn get_audit_log(app: tauri::AppHandle, limit: Option<usize>) -> Vec<serde_json::Value> {
+    match app.path().app_data_dir() {
+        Ok(d) => read_audit_log(&d, limit.unwrap_or(200)),
+        Err(_) => Vec::new(),
     }
-    healthy
 }
 
 #[tauri::command]
-fn get_server_latency(app: tauri::AppHandle, name: String) -> Option<u64> {
-    let state = app.state::<ServerState>();
-    let result = state.latency_cache.lock().unwrap().get(&name).copied();
-    result
+fn stop_tunnel_cmd(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    stop_tunnel(&app, &name);
+    Ok(())
 }
 
+/// Public URL of the project's tunnel, once the provider has reported it.
 #[tauri::command]
-fn get_server_uptime(app: tauri::AppHandle, name: String) -> Option<u64> {
+fn get_tunnel_url(app: tauri::AppHandle, name: String) -> Option<String> {
     let state = app.state::<ServerState>();
-    let result = state.start_times.lock().unwrap().get(&name).map(|t| t.elapsed().as_secs());
+    let tunnels = state.tunnels.lock().unwrap();
+    let result = tunnels.get(&name).and_then(|t| t.public_url.lock().unwrap().clone());
     result
 }
 
 #[tauri::command]
-fn get_server_logs(app: tauri::AppHandle, name: String) -> Vec<String> {
+fn list_tunnels(app: tauri::AppHandle) -> HashMap<String, serde_json::Value> {
     let state = app.state::<ServerState>();
-    let buffers = state.log_buffers.lock().unwrap();
-    if let Some(buf) = buffers.get(&name) {
-        buf.lock().unwrap().iter().cloned().collect()
-    } else {
-        Vec::new()
-    }
+    let tunnels = state.tunnels.lock().unwrap();
+    tunnels.iter().map(|(name, t)| {
+        let expires_at = t.expires_at
+            .and_then(|e| e.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+        (name.clone(), serde_json::json!({
+            "provider": t.provider,
+            "url": *t.public_url.lock().unwrap(),
+            "expires_at": expires_at,
+            "passcode": t.passcode,
+        }))
+    }).collect()
 }
 
+/// Recently started projects, most recent first.
 #[tauri::command]
-fn get_tailscale_address(state: tauri::State<'_, ServerState>) -> String {
-    state.tailscale_host.clone()
+fn get_recent_servers(app: tauri::AppHandle) -> Vec<String> {
+    match app.path().app_data_dir() {
+        Ok(d) => load_recent_from_disk(&d),
+        Err(_) => Vec::new(),
+    }
 }
 
 #[tauri::command]
@@ -697,9 +6286,41 @@ fn get_favorites(app: tauri::AppHandle) -> Vec<String> {
 fn set_favorites(app: tauri::AppHandle, names: Vec<String>) -> Result<(), String> {
     let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
     save_favorites_to_disk(&app_data_dir, &names);
+    rebuild_tray(&app);
+    Ok(())
+}
+
+/// Every favorite, whole-project and action alike, in saved order.
+#[tauri::command]
+fn get_favorite_entries(app: tauri::AppHandle) -> Vec<Favorite> {
+    app.path().app_data_dir().map(|d| load_favorite_entries(&d)).unwrap_or_default()
+}
+
+#[tauri::command]
+fn set_favorite_entries(app: tauri::AppHandle, entries: Vec<Favorite>) -> Result<(), String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    save_favorite_entries_to_disk(&app_data_dir, &entries);
+    rebuild_tray(&app);
     Ok(())
 }
 
+/// Runs the favorite at `index` in favorites.json (tray items and hotkeys use this).
+#[tauri::command]
+fn run_favorite_cmd(app: tauri::AppHandle, index: usize) -> Result<(), String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let favorite = load_favorite_entries(&app_data_dir).into_iter().nth(index)
+        .ok_or_else(|| format!("No favorite at position {}", index))?;
+    run_favorite(&app, &favorite)
+}
+
+#[tauri::command]
+fn get_tags(app: tauri::AppHandle) -> HashMap<String, Vec<String>> {
+    match app.path().app_data_dir() {
+        Ok(d) => load_tags(&d),
+        Err(_) => HashMap::new(),
+    }
+}
+
 #[tauri::command]
 fn set_pin(app: tauri::AppHandle, pinned: bool) -> Result<(), String> {
     if let Some(win) = app.get_webview_window("main") {
@@ -710,12 +6331,13 @@ fn set_pin(app: tauri::AppHandle, pinned: bool) -> Result<(), String> {
 
 #[tauri::command]
 fn refresh_projects_cmd(app: tauri::AppHandle) -> Vec<ProjectConfig> {
-    let app_data_dir = app.path().app_data_dir().unwrap_or_else(|_| std::path::PathBuf::from("/tmp"));
-    let overrides = load_port_overrides(&app_data_dir);
-    let new_projects = scan_projects(Path::new(PROJECTS_DIR), &overrides);
-    { let state = app.state::<ServerState>(); *state.projects.lock().unwrap() = new_projects.clone(); }
-    rebuild_tray(&app);
-    new_projects
+    let projects = rescan_projects(&app);
+    redact_projects(&app, projects)
+}
+
+#[tauri::command]
+fn reload_config_cmd(app: tauri::AppHandle) -> Result<ConfigReloadReport, String> {
+    reload_config(&app)
 }
 
 #[tauri::command]
@@ -745,7 +6367,7 @@ fn scan_external_servers(app: tauri::AppHandle) -> Vec<u16> {
         let projects = state.projects.lock().unwrap();
         projects.iter().flat_map(|p| {
             let mut v = vec![p.port];
-            v.extend_from_slice(&p.extra_ports);
+            v.extend(p.extra_ports.iter().map(|e| e.port));
             v
         }).collect()
     };
@@ -766,10 +6388,15 @@ fn scan_external_servers(app: tauri::AppHandle) -> Vec<u16> {
     external
 }
 
+/// Secret values come back as the keyring placeholder, never resolved.
 #[tauri::command]
 fn get_env_overrides(app: tauri::AppHandle, name: String) -> HashMap<String, String> {
     let state = app.state::<ServerState>();
-    let result = state.env_overrides.lock().unwrap().get(&name).cloned().unwrap_or_default();
+    let id = project_key(&state, &name);
+    let mut result = state.env_overrides.lock().unwrap().get(&id).cloned().unwrap_or_default();
+    if presenting(&app) {
+        for value in result.values_mut() { *value = REDACTED.to_string(); }
+    }
     result
 }
 
@@ -779,72 +6406,203 @@ fn set_env_overrides(
     name: String,
     vars: HashMap<String, String>,
 ) -> Result<(), String> {
+    // The editor only ever saw masked values; saving them back would wipe the real ones
+    ensure_not_presenting(&app)?;
     let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
     let state = app.state::<ServerState>();
+    let id = project_key(&state, &name);
     let mut overrides = state.env_overrides.lock().unwrap();
-    overrides.insert(name, vars);
+    // Secrets that were dropped or replaced by a plain value leave the keyring
+    for (key, value) in overrides.get(&id).into_iter().flatten() {
+        if value == SECRET_PLACEHOLDER && vars.get(key).map(String::as_str) != Some(SECRET_PLACEHOLDER) {
+            let _ = platform::keyring_delete(&env_secret_account(&name, key));
+        }
+    }
+    overrides.insert(id, vars);
     save_env_overrides_to_disk(&app_data_dir, &*overrides);
     Ok(())
 }
 
+/// Sets an env override whose value goes to the keyring instead of
+/// env_overrides.json, which only records the placeholder.
 #[tauri::command]
-fn get_autostart_enabled() -> bool {
-    let home = std::env::var("HOME").unwrap_or_default();
-    let plist_path = format!("{}/Library/LaunchAgents/com.dexhub.client.plist", home);
-    std::path::Path::new(&plist_path).exists()
+fn set_env_secret(app: tauri::AppHandle, name: String, key: String, value: String) -> Result<(), String> {
+    ensure_not_presenting(&app)?;
+    if value == SECRET_PLACEHOLDER { return Err("That value is reserved".to_string()); }
+    platform::keyring_set(&env_secret_account(&name, &key), &value)?;
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let state = app.state::<ServerState>();
+    let id = project_key(&state, &name);
+    let mut overrides = state.env_overrides.lock().unwrap();
+    overrides.entry(id).or_default().insert(key, SECRET_PLACEHOLDER.to_string());
+    save_env_overrides_to_disk(&app_data_dir, &*overrides);
+    Ok(())
 }
 
+/// Moves an existing override's value into the keyring (`secret`) or back
+/// into env_overrides.json.
 #[tauri::command]
-fn set_autostart_enabled(enabled: bool) -> Result<(), String> {
-    let home = std::env::var("HOME").map_err(|e| e.to_string())?;
-    let agents_dir = format!("{}/Library/LaunchAgents", home);
-    let plist_path  = format!("{}/com.dexhub.client.plist", agents_dir);
-
-    if enabled {
-        let exe = std::env::current_exe().map_err(|e| e.to_string())?;
-        let exe_str = exe.to_string_lossy();
-        let plist = format!(
-            r#"<?xml version="1.0" encoding="UTF-8"?>
-<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
-<plist version="1.0">
-<dict>
-    <key>Label</key>
-    <string>com.dexhub.client</string>
-    <key>ProgramArguments</key>
-    <array>
-        <string>{}</string>
-    </array>
-    <key>RunAtLoad</key>
-    <true/>
-    <key>KeepAlive</key>
-    <false/>
-</dict>
-</plist>"#,
-            exe_str
-        );
-        std::fs::create_dir_all(&agents_dir).map_err(|e| e.to_string())?;
-        std::fs::write(&plist_path, plist).map_err(|e| e.to_string())?;
-        let _ = std::process::Command::new("launchctl")
-            .args(["load", &plist_path])
-            .output();
+fn mark_env_secret(app: tauri::AppHandle, name: String, key: String, secret: bool) -> Result<(), String> {
+    ensure_not_presenting(&app)?;
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let state = app.state::<ServerState>();
+    let id = project_key(&state, &name);
+    let mut overrides = state.env_overrides.lock().unwrap();
+    let value = overrides.get_mut(&id).and_then(|vars| vars.get_mut(&key))
+        .ok_or_else(|| format!("'{}' has no env override {}", name, key))?;
+    let account = env_secret_account(&name, &key);
+    match (secret, value.as_str() == SECRET_PLACEHOLDER) {
+        (true, false) => {
+            platform::keyring_set(&account, value)?;
+            *value = SECRET_PLACEHOLDER.to_string();
+        }
+        (false, true) => {
+            *value = platform::keyring_get(&account).ok_or("The secret is missing from the keyring")?;
+            platform::keyring_delete(&account)?;
+        }
+        _ => return Ok(()),
+    }
+    save_env_overrides_to_disk(&app_data_dir, &*overrides);
+    Ok(())
+}
+
+/// The project's env profiles; values are masked in presentation mode.
+#[tauri::command]
+fn list_env_profiles(app: tauri::AppHandle, name: String) -> EnvProfiles {
+    let state = app.state::<ServerState>();
+    let mut result = state.env_profiles.lock().unwrap().get(&name).cloned().unwrap_or_default();
+    if presenting(&app) {
+        for vars in result.profiles.values_mut() {
+            for value in vars.values_mut() { *value = REDACTED.to_string(); }
+        }
+    }
+    result
+}
+
+/// Creates or replaces a named profile.
+#[tauri::command]
+fn save_env_profile(app: tauri::AppHandle, name: String, profile: String, vars: HashMap<String, String>) -> Result<(), String> {
+    ensure_not_presenting(&app)?;
+    let profile = profile.trim().to_string();
+    if profile.is_empty() { return Err("Profile name can't be empty".to_string()); }
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let state = app.state::<ServerState>();
+    let mut all = state.env_profiles.lock().unwrap();
+    all.entry(name).or_default().profiles.insert(profile, vars);
+    save_env_profiles_to_disk(&app_data_dir, &all);
+    Ok(())
+}
+
+/// Deleting the active profile leaves none selected.
+#[tauri::command]
+fn delete_env_profile(app: tauri::AppHandle, name: String, profile: String) -> Result<(), String> {
+    ensure_not_presenting(&app)?;
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let state = app.state::<ServerState>();
+    let mut all = state.env_profiles.lock().unwrap();
+    let Some(entry) = all.get_mut(&name) else { return Ok(()) };
+    entry.profiles.remove(&profile);
+    if entry.active.as_deref() == Some(profile.as_str()) { entry.active = None; }
+    if *entry == EnvProfiles::default() { all.remove(&name); }
+    save_env_profiles_to_disk(&app_data_dir, &all);
+    Ok(())
+}
+
+/// Selects the profile used from the next start; None for the plain overrides only.
+#[tauri::command]
+fn select_env_profile(app: tauri::AppHandle, name: String, profile: Option<String>) -> Result<(), String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let state = app.state::<ServerState>();
+    let mut all = state.env_profiles.lock().unwrap();
+    let entry = all.entry(name.clone()).or_default();
+    if let Some(p) = &profile {
+        if !entry.profiles.contains_key(p) {
+            return Err(format!("'{}' has no env profile named '{}'", name, p));
+        }
+    }
+    if entry.active == profile { return Ok(()); }
+    entry.active = profile.clone();
+    save_env_profiles_to_disk(&app_data_dir, &all);
+    drop(all);
+    record_event(&app, "env-profile-selected", Some(&name), serde_json::json!({ "profile": profile }));
+    Ok(())
+}
+
+#[tauri::command]
+fn get_launch_command(app: tauri::AppHandle, name: String) -> Option<LaunchCommand> {
+    app.state::<ServerState>().launch_commands.lock().unwrap().get(&name).cloned()
+}
+
+/// None (or an empty command) goes back to the scanned script. Takes effect
+/// on the next start.
+#[tauri::command]
+fn set_launch_command(app: tauri::AppHandle, name: String, launch: Option<LaunchCommand>) -> Result<(), String> {
+    ensure_not_presenting(&app)?;
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let launch = launch.filter(|l| !l.command.trim().is_empty());
+    let state = app.state::<ServerState>();
+    if !state.projects.lock().unwrap().iter().any(|p| p.name == name) {
+        return Err(format!("Project '{}' not found", name));
+    }
+    let mut all = state.launch_commands.lock().unwrap();
+    match &launch {
+        Some(l) => { all.insert(name.clone(), l.clone()); }
+        None => { all.remove(&name); }
+    }
+    save_launch_commands_to_disk(&app_data_dir, &all);
+    drop(all);
+    record_event(&app, "launch-command-set", Some(&name), serde_json::json!({ "launch": launch }));
+    Ok(())
+}
+
+#[tauri::command]
+fn get_lifecycle_hooks(app: tauri::AppHandle, name: String) -> LifecycleHooks {
+    app.state::<ServerState>().lifecycle_hooks.lock().unwrap().get(&name).cloned().unwrap_or_default()
+}
+
+/// Blank lines are dropped; empty lists remove the entry. Applies from the
+/// next start or stop.
+#[tauri::command]
+fn set_lifecycle_hooks(app: tauri::AppHandle, name: String, hooks: LifecycleHooks) -> Result<(), String> {
+    ensure_not_presenting(&app)?;
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let clean = |lines: Vec<String>| -> Vec<String> {
+        lines.into_iter().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect()
+    };
+    let hooks = LifecycleHooks { pre_start: clean(hooks.pre_start), post_stop: clean(hooks.post_stop) };
+    let state = app.state::<ServerState>();
+    let mut all = state.lifecycle_hooks.lock().unwrap();
+    if hooks == LifecycleHooks::default() {
+        all.remove(&name);
     } else {
-        let _ = std::process::Command::new("launchctl")
-            .args(["unload", &plist_path])
-            .output();
-        let _ = std::fs::remove_file(&plist_path);
+        all.insert(name.clone(), hooks);
     }
+    save_lifecycle_hooks_to_disk(&app_data_dir, &all);
     Ok(())
 }
 
+#[tauri::command]
+fn get_autostart_enabled() -> bool {
+    platform::autostart_enabled()
+}
+
+/// LaunchAgent on macOS, XDG autostart entry on Linux, `Run` registry key on Windows.
+#[tauri::command]
+fn set_autostart_enabled(enabled: bool) -> Result<(), String> {
+    platform::set_autostart(enabled)
+}
+
 // ─── Tray Icon ────────────────────────────────────────────────────────────────
 
 /// Generates a 22×22 RGBA lightning-bolt icon (black on transparent).
 /// The bolt is drawn as two parallelogram bands that together form a ⚡ shape.
+/// `filled` is used while any server is running; otherwise only the band
+/// edges are drawn, giving an outlined bolt.
 ///
 /// The icon is registered as a macOS *template image* via `.icon_as_template(true)`.
 /// Template images must be black silhouettes; macOS automatically renders them
 /// white on a dark menu bar and black on a light menu bar, matching system appearance.
-fn lightning_bolt_icon_rgba() -> Vec<u8> {
+fn lightning_bolt_icon_rgba(filled: bool) -> Vec<u8> {
     const W: u32 = 22;
     const H: u32 = 22;
     let mut rgba = vec![0u8; (W * H * 4) as usize];
@@ -867,7 +6625,9 @@ fn lightning_bolt_icon_rgba() -> Vec<u8> {
         // Centre of stroke: column shifts from 16 down to 6 as row increases
         let cx = 16u32.saturating_sub(row);
         for dx in 0u32..4 {
-            set(cx + dx, row);
+            if filled || dx == 0 || dx == 3 || row == 0 {
+                set(cx + dx, row);
+            }
         }
     }
 
@@ -878,7 +6638,9 @@ fn lightning_bolt_icon_rgba() -> Vec<u8> {
         // Centre of stroke: column shifts from 6 up to 16 as row increases
         let cx = 6u32 + offset;
         for dx in 0u32..4 {
-            set(cx.saturating_sub(2) + dx, row);
+            if filled || dx == 0 || dx == 3 || row == 21 {
+                set(cx.saturating_sub(2) + dx, row);
+            }
         }
     }
 
@@ -888,32 +6650,114 @@ fn lightning_bolt_icon_rgba() -> Vec<u8> {
 // ─── Main ─────────────────────────────────────────────────────────────────────
 
 fn main() {
+    // Launched by an AI assistant as its MCP server: relay to the running app
+    #[cfg(unix)]
+    if std::env::args().any(|a| a == "--mcp-stdio") {
+        if let Err(e) = mcp::run_stdio_bridge() {
+            eprintln!("[DexHub] {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_positioner::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .manage(telemetry::CommandTelemetry::default())
+        .manage(telemetry::LaunchTime(std::time::Instant::now()))
+        .manage(github::GithubCache::default())
         .setup(|app| {
             #[cfg(target_os = "macos")]
             app.set_activation_policy(tauri::ActivationPolicy::Regular);
 
+            let safe_mode       = safe_mode_requested();
             let app_data_dir    = app.path().app_data_dir().expect("path failed");
             let port_overrides  = load_port_overrides(&app_data_dir);
+            // Scanned before the rest is read, so renamed packages' settings are already migrated
+            let scan_roots      = settings::load_settings(&app_data_dir).general.scan_roots;
+            let projects        = if safe_mode { Vec::new() } else { scan_projects(&scan_roots, &port_overrides, &app_data_dir) };
             let env_overrides   = load_env_overrides(&app_data_dir);
-            let tailscale_host  = get_tailscale_host();
-            let projects        = scan_projects(Path::new(PROJECTS_DIR), &port_overrides);
-            let initial_menu    = build_tray_menu(app, &projects, &[], &tailscale_host);
+            let tailscale       = if safe_mode {
+                TailscaleStatus { host: "localhost".to_string(), online: false, lan_ip: None }
+            } else {
+                get_tailscale_status()
+            };
+            let url_hosts       = load_url_hosts(&app_data_dir);
+            let settings        = settings::load_settings(&app_data_dir);
+            let initial_menu    = build_tray_menu(app, &projects, &TrayView {
+                favorites: load_favorites_from_disk(&app_data_dir),
+                action_favorites: load_action_favorites(&app_data_dir),
+                recent:    load_recent_from_disk(&app_data_dir),
+                safe_mode,
+                ..Default::default()
+            });
 
             app.manage(ServerState {
                 processes:      Mutex::new(HashMap::new()),
+                starting:       Mutex::new(HashSet::new()),
                 start_times:    Mutex::new(HashMap::new()),
+                served_ports:   Mutex::new(HashMap::new()),
                 log_buffers:    Mutex::new(HashMap::new()),
                 latency_cache:  Mutex::new(HashMap::new()),
                 projects:       Mutex::new(projects),
-                tailscale_host,
+                tailscale_host:   Mutex::new(tailscale.host),
+                tailscale_online: AtomicBool::new(tailscale.online),
+                lan_ip:         Mutex::new(tailscale.lan_ip),
+                url_hosts:      Mutex::new(url_hosts),
                 env_overrides:  Mutex::new(env_overrides),
+                env_profiles:   Mutex::new(load_env_profiles(&app_data_dir)),
+                tunnels:        Mutex::new(HashMap::new()),
+                tray_suspended: AtomicBool::new(false),
+                last_output:    Mutex::new(HashMap::new()),
+                last_success:   Mutex::new(HashMap::new()),
+                cpu_samples:    Mutex::new(HashMap::new()),
+                rss_samples:    Mutex::new(HashMap::new()),
+                resource_limits: Mutex::new(load_resource_limits(&app_data_dir)),
+                launch_commands: Mutex::new(load_launch_commands(&app_data_dir)),
+                lifecycle_hooks: Mutex::new(load_lifecycle_hooks(&app_data_dir)),
+                script_runs:   Mutex::new(HashMap::new()),
+                folder_stats:  Mutex::new(HashMap::new()),
+                general:        Mutex::new(settings.general),
+                watchdog:       Mutex::new(settings.watchdog),
+                crash_policy:   Mutex::new(settings.crash_policy),
+                sleep_policy:   Mutex::new(settings.sleep),
+                stopped_for_sleep: Mutex::new(Vec::new()),
+                battery_policy: Mutex::new(settings.battery),
+                low_power:      AtomicBool::new(false),
+                server_schedules: Mutex::new(load_server_schedules(&app_data_dir)),
+                crash_times:    Mutex::new(HashMap::new()),
+                flapping:       Mutex::new(HashSet::new()),
+                memory_restarts: Mutex::new(HashSet::new()),
+                runaway:        Mutex::new(HashSet::new()),
+                event_log:      Mutex::new(VecDeque::new()),
+                last_health:    Mutex::new(HashMap::new()),
+                health_details: Mutex::new(HashMap::new()),
+                crashed:        Mutex::new(HashSet::new()),
+                notifications:  Mutex::new(settings.notifications),
+                warmup_secs:    Mutex::new(load_warmup_overrides(&app_data_dir)),
+                health_checks:  Mutex::new(load_health_checks(&app_data_dir)),
+                license_reports: Mutex::new(HashMap::new()),
+                license_policy: Mutex::new(settings.licenses),
+                editor:         Mutex::new(settings.editor),
+                notification_queue: notifications::NotificationQueue::default(),
+                presentation_mode: AtomicBool::new(false),
+                canary:         Mutex::new(load_canary_config(&app_data_dir)),
+                disk_guard:     Mutex::new(settings.disk_guard),
+                pull_before_start: Mutex::new(load_pull_before_start(&app_data_dir)),
+                compose:        Mutex::new(HashMap::new()),
+                brew_services:  Mutex::new(Vec::new()),
+                containers:     Mutex::new(Vec::new()),
+                scheduled_scan: Mutex::new(load_scheduled_scan_config(&app_data_dir)),
+                focus:          Mutex::new(focus::load_focus_config(&app_data_dir)),
+                active_focus:   Mutex::new(None),
+                safe_mode,
             });
 
             let tray = TrayIconBuilder::new()
                 .menu(&initial_menu)
-                .icon(tauri::image::Image::new_owned(lightning_bolt_icon_rgba(), 22, 22))
+                .icon(tauri::image::Image::new_owned(lightning_bolt_icon_rgba(false), 22, 22))
                 .icon_as_template(true)   // macOS: renders white on dark bar, black on light bar
                 .on_menu_event(|app: &tauri::AppHandle, event: tauri::menu::MenuEvent| {
                     handle_menu_event(app, event.id().as_ref());
@@ -939,42 +6783,302 @@ fn main() {
                 .build(app)?;
 
             app.manage(TrayHandle(Mutex::new(Some(tray))));
+            app.manage(TrayInfoRows::default());
+            spawn_tray_info_refresher(app.handle().clone());
+            // Managed state still has to exist for commands, but nothing below starts
+            app.manage(remote::RemoteApiState::new(
+                remote::load_remote_api_config(&app_data_dir),
+                remote::load_device_registry(&app_data_dir),
+                remote::load_ui_tokens(&app_data_dir),
+            ));
+            app.manage(proxy::ProxyState::new(proxy::load_proxy_config(&app_data_dir)));
+            app.manage(preview::PreviewState::default());
+            // Safe mode leaves the database closed; nothing runs to record anyway
+            let history = if safe_mode { None } else {
+                db::open(&app_data_dir).map_err(|e| eprintln!("[DexHub] Run history disabled: {}", e)).ok()
+            };
+            app.manage(db::HistoryDb(Mutex::new(history)));
+            if safe_mode {
+                eprintln!("[DexHub] Safe mode: skipped project scan, integrations and local servers");
+                return Ok(());
+            }
+
+            spawn_health_monitor(app.handle().clone());
+            brew::spawn_brew_refresh(app.handle());
+            docker::spawn_container_refresh(app.handle());
+            spawn_scan_scheduler(app.handle().clone());
+            spawn_server_scheduler(app.handle().clone());
+            focus::spawn_focus_watcher(app.handle().clone());
+            spawn_cpu_watchdog(app.handle().clone());
+            spawn_tailscale_monitor(app.handle().clone());
+            let power_app = app.handle().clone();
+            platform::watch_power_events(move |event| handle_power_event(&power_app, event));
+            spawn_battery_monitor(app.handle().clone());
+            spawn_share_expiry_sweeper(app.handle().clone());
+
+            if let Err(e) = remote::apply_remote_api_config(app.handle()) {
+                eprintln!("[DexHub] Remote API failed to start: {}", e);
+            }
+            if let Err(e) = proxy::apply_proxy_config(app.handle()) {
+                eprintln!("[DexHub] Proxy failed to start: {}", e);
+            }
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+                let handle = app.handle().clone();
+                app.deep_link().on_open_url(move |event| {
+                    for url in event.urls() { handle_deep_link(&handle, url.as_str()); }
+                });
+            }
+
+            #[cfg(unix)]
+            if let Err(e) = ipc::spawn_ipc_server(app.handle().clone(), &app_data_dir) {
+                eprintln!("[DexHub] CLI socket failed to start: {}", e);
+            }
+            #[cfg(unix)]
+            if let Err(e) = mcp::spawn_mcp_server(app.handle().clone(), &app_data_dir) {
+                eprintln!("[DexHub] MCP socket failed to start: {}", e);
+            }
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(telemetry::timed_handler(tauri::generate_handler![
             list_projects,
             get_running_servers,
             start_server_cmd,
             stop_server_cmd,
             stop_all_servers_cmd,
             restart_server_cmd,
+            restart_server_clean_cmd,
+            open_log_window_cmd,
+            copy_project_url,
+            canary_restart_cmd,
+            brew::list_brew_services,
+            brew::brew_service_action,
+            docker::list_docker_containers,
+            db::get_run_history,
+            db::count_crashes,
+            db::get_dashboard_stats,
+            docker::docker_container_action,
+            refresh_git_info,
+            git_fetch,
+            start_compose_services,
+            stop_compose_services,
+            get_compose_running,
+            get_compose_logs,
+            get_project_dependencies,
+            get_project_stats,
+            install_dependencies,
+            run_script,
+            get_script_run,
+            get_script_logs,
+            export_server_logs,
+            copy_server_logs,
+            get_combined_logs,
+            preview::start_build_preview,
+            preview::stop_build_preview,
+            preview::get_build_previews,
+            preview::get_build_preview_logs,
+            git_pull,
+            get_pull_before_start,
+            set_pull_before_start,
+            get_scheduled_scan_config,
+            set_scheduled_scan_config,
+            run_scheduled_scan_now,
+            get_server_schedules,
+            set_server_schedule,
+            get_disk_guard_config,
+            set_disk_guard_config,
+            get_free_disk_space,
+            get_canary_config,
+            set_canary_config,
+            bulk_action_cmd,
             update_server_port,
             open_terminal_here,
+            reveal_project_folder,
+            open_in_editor,
+            list_editors,
+            get_editor_config,
+            set_editor_config,
             get_server_url,
             check_server_health,
+            is_health_warming_up,
+            get_health_warmup,
+            set_health_warmup,
+            get_health_check,
+            get_health_details,
+            set_health_check,
+            test_health_check,
+            get_license_report,
+            refresh_license_report,
+            get_license_policy,
+            set_license_policy,
             get_server_latency,
             get_server_uptime,
             get_server_logs,
+            get_server_cpu,
+            get_server_memory,
+            get_resource_limits,
+            set_resource_limits,
+            get_runaway_servers,
+            get_watchdog_config,
+            set_watchdog_config,
+            get_crash_policy,
+            set_crash_policy,
+            get_flapping,
+            get_sleep_policy,
+            set_sleep_policy,
+            settings::get_settings,
+            settings::update_settings,
+            settings::set_project_browser,
+            settings::set_project_health_probe,
+            settings::export_config,
+            settings::import_config,
+            get_battery_policy,
+            set_battery_policy,
+            get_power_state,
+            stop_non_favorite_servers,
+            clear_flapping,
             get_tailscale_address,
+            get_tailscale_status_cmd,
+            list_network_addresses,
+            get_url_host,
+            set_url_host,
+            list_tailnet_devices,
+            send_url_to_device,
+            start_tunnel_cmd,
+            stop_tunnel_cmd,
+            get_tunnel_url,
+            list_tunnels,
+            create_share_link_cmd,
+            get_audit_log,
+            get_presentation_mode,
+            set_presentation_mode,
+            focus::get_focus_config,
+            focus::set_focus_config,
+            focus::get_active_focus,
+            get_notification_config,
+            set_notification_config,
+            preview_crash_notification,
+            set_project_alerts,
+            set_notifications_muted,
+            get_event_log,
+            events::get_events,
+            list_hooks,
+            remote::get_remote_api_config,
+            remote::set_remote_api_config,
+            remote::start_remote_pairing,
+            remote::list_paired_devices,
+            remote::revoke_paired_device,
+            remote::create_remote_ui_link,
+            proxy::get_proxy_config,
+            proxy::set_proxy_config,
+            proxy::set_wake_on_demand,
+            proxy::get_proxy_url,
             get_favorites,
+            get_recent_servers,
+            get_port_registry,
+            get_port_conflicts,
             set_favorites,
+            get_favorite_entries,
+            set_favorite_entries,
+            run_favorite_cmd,
+            get_tags,
             set_pin,
             refresh_projects_cmd,
+            reload_config_cmd,
             get_project_readme,
+            readme::get_project_readme_full,
             scan_external_servers,
             get_env_overrides,
             set_env_overrides,
+            set_env_secret,
+            mark_env_secret,
+            list_env_profiles,
+            save_env_profile,
+            delete_env_profile,
+            select_env_profile,
+            get_launch_command,
+            set_launch_command,
+            get_lifecycle_hooks,
+            set_lifecycle_hooks,
+            envfiles::read_env_files,
+            envfiles::diff_env_file,
+            envfiles::write_env_file,
+            envfiles::get_effective_env,
             get_autostart_enabled,
             set_autostart_enabled,
-        ])
+            telemetry::get_diagnostics,
+            telemetry::reset_command_stats,
+            github::get_github_status,
+            github::has_github_token,
+            github::set_github_token,
+        ]))
         .build(tauri::generate_context!())
         .expect("error building tauri")
         .run(|app, event| {
             if let tauri::RunEvent::Exit = event {
                 if let Some(state) = app.try_state::<ServerState>() {
-                    let mut procs = state.processes.lock().unwrap();
-                    for (_, child) in procs.iter_mut() { let _ = child.kill(); }
+                    // Nothing left to do after Quit; any other exit stops servers the same way
+                    stop_all_servers(app);
+                    stop_all_tunnels(app);
+                    // SIGTERM lets the attached `docker compose up` stop its containers
+                    for (_, child) in state.compose.lock().unwrap().iter() {
+                        let _ = std::process::Command::new("kill").arg(child.id().to_string()).status();
+                    }
                 }
+                preview::kill_all(app);
+                db::close_open_runs(app);
             }
         });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_plain_launch_commands_are_checked_up_front() {
+        assert_eq!(checkable_program("pnpm"), Some("pnpm"));
+        assert_eq!(checkable_program("./bin/dev --watch"), Some("./bin/dev"));
+        assert_eq!(checkable_program("make dev"), Some("make"));
+        assert_eq!(checkable_program(""), None);
+    }
+
+    #[test]
+    fn env_prefixed_and_compound_launch_commands_are_left_to_the_shell() {
+        assert_eq!(checkable_program("FOO=1 pnpm dev"), None);
+        assert_eq!(checkable_program("cd web && npm run dev"), None);
+        assert_eq!(checkable_program("(cd app; make)"), None);
+        assert_eq!(checkable_program("exec npm start"), None);
+        assert_eq!(checkable_program("$HOME/bin/serve"), None);
+        assert_eq!(checkable_program("if [ -f .env ]; then npm run dev; fi"), None);
+    }
+
+    #[test]
+    fn passcodes_must_fit_ngroks_limits() {
+        assert!(validate_passcode("correct-horse").is_ok());
+        assert!(validate_passcode(&"x".repeat(128)).is_ok());
+        assert!(validate_passcode("").is_err());
+        assert!(validate_passcode("short").is_err());
+        assert!(validate_passcode(&"x".repeat(129)).is_err());
+        assert!(validate_passcode("has a space").is_err());
+        assert!(validate_passcode("quote\"marks").is_err());
+        assert!(validate_passcode("back\\slash").is_err());
+        assert!(validate_passcode("pässwörter").is_err());
+        assert!(validate_passcode("it's-fine!").is_ok());
+    }
+
+    #[test]
+    fn a_script_run_that_fails_to_start_is_not_left_running() {
+        let runs = Mutex::new(HashMap::new());
+        let failed = claim_script_run(&runs, "web", "test", || -> Result<(), String> {
+            Err("Secret API_KEY for 'web' is missing from the keyring; set it again".to_string())
+        });
+        assert!(failed.unwrap_err().contains("missing from the keyring"));
+        assert!(runs.lock().unwrap().get("web").is_none());
+
+        assert_eq!(claim_script_run(&runs, "web", "test", || Ok(42)), Ok(42));
+        assert!(runs.lock().unwrap()["web"].running);
+        let busy = claim_script_run(&runs, "web", "lint", || Ok(()));
+        assert_eq!(busy, Err("'web' is already running test".to_string()));
+    }
+}
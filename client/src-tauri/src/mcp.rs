@@ -0,0 +1,181 @@
+//! A Model Context Protocol server, so AI assistants can manage dev servers
+//! as tools: list projects, start / stop / restart, read logs, check health.
+//!
+//! It listens on `<app data>/dexhub-mcp.sock` and speaks MCP's JSON-RPC 2.0,
+//! one message per line. Assistants that launch MCP servers as a
+//! subprocess run `DexHub --mcp-stdio`, which bridges stdin/stdout to that
+//! socket of the running app instead of starting a second one.
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+use crate::{check_server_health, ensure_not_presenting, is_running, restart_server, server_log_lines, start_server, stop_server, ServerState};
+
+const PROTOCOL_VERSION: &str = "2024-11-05";
+const IDENTIFIER: &str = "com.dexhub.client"; // tauri.conf.json; names the app data folder
+
+pub fn socket_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("dexhub-mcp.sock")
+}
+
+/// Where Tauri puts app data, worked out without a running app for the bridge.
+fn app_data_dir() -> Option<PathBuf> {
+    let home = PathBuf::from(std::env::var_os("HOME")?);
+    let base = if cfg!(target_os = "macos") {
+        home.join("Library/Application Support")
+    } else {
+        std::env::var_os("XDG_DATA_HOME").map(PathBuf::from).unwrap_or_else(|| home.join(".local/share"))
+    };
+    Some(base.join(IDENTIFIER))
+}
+
+fn name_schema(extra: serde_json::Value) -> serde_json::Value {
+    let mut properties = serde_json::json!({ "name": { "type": "string", "description": "Project name, as listed by list_projects" } });
+    if let (Some(props), Some(extra)) = (properties.as_object_mut(), extra.as_object()) {
+        for (k, v) in extra { props.insert(k.clone(), v.clone()); }
+    }
+    serde_json::json!({ "type": "object", "properties": properties, "required": ["name"] })
+}
+
+fn tools() -> serde_json::Value {
+    serde_json::json!([
+        {
+            "name": "list_projects",
+            "description": "Every dev-server project DexHub knows: name, port, URL, whether it's running and for how long.",
+            "inputSchema": { "type": "object", "properties": {} },
+        },
+        { "name": "start_server", "description": "Start a project's dev server.", "inputSchema": name_schema(serde_json::json!({})) },
+        { "name": "stop_server", "description": "Stop a project's dev server.", "inputSchema": name_schema(serde_json::json!({})) },
+        { "name": "restart_server", "description": "Restart a project's dev server and wait until it accepts connections; reports how long each phase took.", "inputSchema": name_schema(serde_json::json!({})) },
+        {
+            "name": "get_logs",
+            "description": "The most recent output lines of a project's dev server, kept after a crash.",
+            "inputSchema": name_schema(serde_json::json!({ "lines": { "type": "integer", "description": "How many lines (default 100)" } })),
+        },
+        {
+            "name": "get_health",
+            "description": "Probe a running dev server now: whether it's healthy, why not, and each port's reachability and latency.",
+            "inputSchema": name_schema(serde_json::json!({})),
+        },
+    ])
+}
+
+fn call_tool(app: &tauri::AppHandle, tool: &str, args: &serde_json::Value) -> Result<serde_json::Value, String> {
+    if tool == "list_projects" { return Ok(crate::ipc::status(app)); }
+    let name = args["name"].as_str().ok_or("'name' is required")?.to_string();
+    let state = app.state::<ServerState>();
+    if !state.projects.lock().unwrap().iter().any(|p| p.name == name) {
+        return Err(format!("Project '{}' not found", name));
+    }
+    match tool {
+        "start_server" => start_server(app, name).map(|_| serde_json::json!("started")),
+        "stop_server" => {
+            ensure_not_presenting(app)?;
+            stop_server(app, name).map(|_| serde_json::json!("stopped"))
+        }
+        "restart_server" => {
+            ensure_not_presenting(app)?;
+            restart_server(app, name).map(|report| serde_json::json!(report))
+        }
+        "get_logs" => {
+            // Masked while presenting, as over the CLI socket and remote API
+            let logs = crate::redact_log_lines(app, server_log_lines(app, &name, None));
+            let skip = logs.len().saturating_sub(args["lines"].as_u64().unwrap_or(100) as usize);
+            Ok(serde_json::json!(logs[skip..].join("\n")))
+        }
+        "get_health" => {
+            if !is_running(&state, &name) {
+                return Err(format!("'{}' is not running", name));
+            }
+            check_server_health(app.clone(), name.clone());
+            let details = state.health_details.lock().unwrap().get(&name).cloned();
+            serde_json::to_value(details).map_err(|e| e.to_string())
+        }
+        other => Err(format!("Unknown tool '{}'", other)),
+    }
+}
+
+/// The reply to one JSON-RPC message; None for notifications.
+fn handle_message(app: &tauri::AppHandle, message: &serde_json::Value) -> Option<serde_json::Value> {
+    let id = message.get("id")?.clone();
+    let method = message["method"].as_str().unwrap_or("");
+    let result = match method {
+        "initialize" => serde_json::json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "dexhub", "version": env!("CARGO_PKG_VERSION") },
+        }),
+        "ping" => serde_json::json!({}),
+        "tools/list" => serde_json::json!({ "tools": tools() }),
+        "tools/call" => {
+            let params = &message["params"];
+            // Tool failures are results the assistant can read, not protocol errors
+            let (text, is_error) = match call_tool(app, params["name"].as_str().unwrap_or(""), &params["arguments"]) {
+                Ok(serde_json::Value::String(s)) => (s, false),
+                Ok(value) => (serde_json::to_string_pretty(&value).unwrap_or_default(), false),
+                Err(e) => (e, true),
+            };
+            serde_json::json!({ "content": [{ "type": "text", "text": text }], "isError": is_error })
+        }
+        other => {
+            return Some(serde_json::json!({
+                "jsonrpc": "2.0", "id": id,
+                "error": { "code": -32601, "message": format!("Method '{}' not found", other) },
+            }));
+        }
+    };
+    Some(serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+}
+
+fn handle_client(app: &tauri::AppHandle, stream: UnixStream) {
+    let Ok(mut writer) = stream.try_clone() else { return };
+    for line in BufReader::new(stream).lines().map_while(Result::ok) {
+        if line.trim().is_empty() { continue; }
+        let reply = match serde_json::from_str::<serde_json::Value>(&line) {
+            Ok(message) => handle_message(app, &message),
+            Err(e) => Some(serde_json::json!({
+                "jsonrpc": "2.0", "id": null,
+                "error": { "code": -32700, "message": format!("Parse error: {}", e) },
+            })),
+        };
+        if let Some(reply) = reply {
+            if writeln!(writer, "{}", reply).is_err() { return; }
+        }
+    }
+}
+
+/// Binds the MCP socket, replacing a stale one left by a previous run.
+pub fn spawn_mcp_server(app: tauri::AppHandle, app_data_dir: &Path) -> Result<(), String> {
+    let path = socket_path(app_data_dir);
+    let _ = std::fs::create_dir_all(app_data_dir);
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).map_err(|e| e.to_string())?;
+    std::thread::spawn(move || {
+        for stream in listener.incoming().filter_map(|s| s.ok()) {
+            let app = app.clone();
+            std::thread::spawn(move || handle_client(&app, stream));
+        }
+    });
+    Ok(())
+}
+
+/// `--mcp-stdio`: copies stdin to the running app's MCP socket and its
+/// replies to stdout, until either side closes.
+pub fn run_stdio_bridge() -> Result<(), String> {
+    let path = app_data_dir().map(|d| socket_path(&d)).ok_or("Can't find the DexHub data folder")?;
+    let stream = UnixStream::connect(&path).map_err(|e| format!("DexHub isn't running ({}): {}", path.display(), e))?;
+    let mut to_app = stream.try_clone().map_err(|e| e.to_string())?;
+    std::thread::spawn(move || {
+        let mut stdout = std::io::stdout();
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            if writeln!(stdout, "{}", line).and_then(|_| stdout.flush()).is_err() { break; }
+        }
+        std::process::exit(0);
+    });
+    for line in std::io::stdin().lock().lines().map_while(Result::ok) {
+        if writeln!(to_app, "{}", line).is_err() { break; }
+    }
+    Ok(())
+}
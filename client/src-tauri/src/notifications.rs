@@ -0,0 +1,327 @@
+//! Desktop notifications and their user-editable message templates.
+//!
+//! Delivery goes through tauri-plugin-notification (Notification Center on
+//! macOS, libnotify on Linux, toast notifications on Windows). Crash and
+//! flapping alerts can also be posted to a Slack or Discord webhook.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tauri_plugin_notification::NotificationExt;
+
+/// Placeholders: `{name}`, `{code}`, `{uptime}`, `{last_error_line}`,
+/// `{snapshot}` (the crash file's path; appended if the template omits it).
+pub const DEFAULT_CRASH_TEMPLATE: &str = "{name} exited with {code} after {uptime}; last error: {last_error_line}";
+
+pub const DEFAULT_CRASH_SOUND: &str = "Basso";
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    pub crash_template: String,
+    pub muted:          bool,   // global: no notifications at all
+    pub crash_sound:    String, // default crash sound; "" for silent
+    pub projects:       HashMap<String, ProjectAlerts>,
+    pub quiet_hours:    QuietHours,
+    pub webhook:        Option<ChatWebhook>, // crash and flapping alerts for every project
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        NotificationConfig {
+            crash_template: DEFAULT_CRASH_TEMPLATE.to_string(),
+            muted: false,
+            crash_sound: DEFAULT_CRASH_SOUND.to_string(),
+            projects: HashMap::new(),
+            quiet_hours: QuietHours::default(),
+            webhook: None,
+        }
+    }
+}
+
+/// Per-project overrides. Projects without an entry use the defaults.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ProjectAlerts {
+    pub banner:      bool,           // false: no notification for this project
+    pub sound:       bool,           // false: banners are silent
+    pub crash_sound: Option<String>, // None: the global crash sound
+    pub chat:        bool,           // false: no webhook alerts for this project
+    pub webhook:     Option<ChatWebhook>, // None: the global webhook
+}
+
+impl Default for ProjectAlerts {
+    fn default() -> Self {
+        ProjectAlerts { banner: true, sound: true, crash_sound: None, chat: true, webhook: None }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatService {
+    Slack,
+    Discord,
+}
+
+/// An incoming webhook URL from Slack or Discord.
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ChatWebhook {
+    pub service: ChatService,
+    pub url:     String,
+}
+
+/// A daily do-not-disturb window in local time, "HH:MM" to "HH:MM";
+/// a start after the end wraps past midnight.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct QuietHours {
+    pub enabled: bool,
+    pub start:   String,
+    pub end:     String,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        QuietHours { enabled: false, start: "19:00".to_string(), end: "09:00".to_string() }
+    }
+}
+
+/// Kinds held back during quiet hours; they're still in the event log and run history.
+pub const QUIET_KINDS: &[&str] = &["crashed", "flapping", "unhealthy"];
+
+/// "HH:MM" as minutes since midnight.
+pub fn parse_hhmm(s: &str) -> Option<u32> {
+    let (h, m) = s.trim().split_once(':')?;
+    let (h, m): (u32, u32) = (h.parse().ok()?, m.parse().ok()?);
+    (h < 24 && m < 60).then_some(h * 60 + m)
+}
+
+impl QuietHours {
+    /// Whether `minute` (minutes since local midnight) falls in the window.
+    pub fn contains(&self, minute: u32) -> bool {
+        if !self.enabled { return false; }
+        let (Some(start), Some(end)) = (parse_hhmm(&self.start), parse_hhmm(&self.end)) else { return false };
+        if start <= end {
+            minute >= start && minute < end
+        } else {
+            minute >= start || minute < end
+        }
+    }
+}
+
+impl NotificationConfig {
+    /// Whether a notification about `name` should be shown at all.
+    pub fn shows(&self, name: &str) -> bool {
+        !self.muted && self.projects.get(name).is_none_or(|p| p.banner)
+    }
+
+    /// The sound to use for `name`, given the sound the caller would pick;
+    /// crashes use the configured crash sound instead.
+    pub fn sound_for(&self, name: &str, kind: &str, sound: &str) -> String {
+        let project = self.projects.get(name);
+        if project.is_some_and(|p| !p.sound) { return String::new(); }
+        if kind == "crashed" || kind == "flapping" {
+            return project.and_then(|p| p.crash_sound.clone()).unwrap_or_else(|| self.crash_sound.clone());
+        }
+        sound.to_string()
+    }
+
+    /// Where chat alerts about `name` go, if anywhere. Muting everything
+    /// mutes chat too.
+    pub fn webhook_for(&self, name: &str) -> Option<&ChatWebhook> {
+        if self.muted { return None; }
+        match self.projects.get(name) {
+            Some(p) if !p.chat => None,
+            Some(p) => p.webhook.as_ref().or(self.webhook.as_ref()),
+            None => self.webhook.as_ref(),
+        }
+    }
+}
+
+fn notification_config_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("notifications.json")
+}
+
+/// The pre-settings.json file, read once when settings are migrated.
+pub fn load_notification_config(app_data_dir: &Path) -> NotificationConfig {
+    std::fs::read_to_string(notification_config_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+/// What is known about a server at the moment it exited on its own.
+pub struct CrashInfo {
+    pub name:            String,
+    pub exit:            Option<std::process::ExitStatus>,
+    pub uptime_secs:     Option<u64>,
+    pub last_error_line: Option<String>,
+    pub snapshot_path:   Option<String>, // crash file with the final log lines
+}
+
+pub fn format_duration(secs: u64) -> String {
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m {}s", s / 60, s % 60),
+        s => format!("{}h {}m", s / 3600, (s % 3600) / 60),
+    }
+}
+
+fn describe_exit(exit: Option<std::process::ExitStatus>) -> String {
+    let Some(status) = exit else { return "unknown status".to_string() };
+    if let Some(code) = status.code() {
+        return format!("code {}", code);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return format!("signal {}", signal);
+        }
+    }
+    "unknown status".to_string()
+}
+
+/// Replaces `{key}` placeholders; unknown placeholders are left as written.
+pub fn render_template(template: &str, values: &HashMap<&str, String>) -> String {
+    let mut out = template.to_string();
+    for (key, value) in values {
+        out = out.replace(&format!("{{{}}}", key), value);
+    }
+    out
+}
+
+pub fn render_crash_message(template: &str, info: &CrashInfo) -> String {
+    let values = HashMap::from([
+        ("name", info.name.clone()),
+        ("code", describe_exit(info.exit)),
+        ("uptime", info.uptime_secs.map(format_duration).unwrap_or_else(|| "unknown time".to_string())),
+        ("last_error_line", info.last_error_line.clone().unwrap_or_else(|| "none".to_string())),
+        ("snapshot", info.snapshot_path.clone().unwrap_or_else(|| "not saved".to_string())),
+    ]);
+    let message = render_template(template, &values);
+    match &info.snapshot_path {
+        Some(path) if !template.contains("{snapshot}") => format!("{} (logs: {})", message, path),
+        _ => message,
+    }
+}
+
+/// Sounds are named after macOS system sounds ("Basso", "Glass", …); other
+/// platforms don't ship those, so they get their own default alert sound.
+fn platform_sound(sound: &str) -> Option<String> {
+    if sound.is_empty() { return None; }
+    if cfg!(target_os = "macos") {
+        Some(sound.to_string())
+    } else if cfg!(target_os = "windows") {
+        Some("Default".to_string())
+    } else {
+        Some("message-new-instant".to_string()) // freedesktop sound theme name
+    }
+}
+
+/// An empty `sound` posts the notification silently.
+pub fn send_notification(app: &tauri::AppHandle, message: &str, sound: &str) {
+    let mut builder = app.notification().builder().title("DexHub").body(message);
+    if let Some(sound) = platform_sound(sound) {
+        builder = builder.sound(sound);
+    }
+    if let Err(e) = builder.show() {
+        eprintln!("[DexHub] Notification failed: {}", e);
+    }
+}
+
+// ─── Chat Webhooks ────────────────────────────────────────────────────────────
+
+/// Log lines quoted in a chat alert, and the most characters kept of each;
+/// Discord rejects messages over 2000 characters.
+const CHAT_LOG_LINES: usize = 8;
+const CHAT_LINE_CHARS: usize = 200;
+const CHAT_TIMEOUT_SECS: &str = "10";
+
+/// "**my-app** crashed with code 1" and the last few log lines in a code block.
+pub fn chat_message(service: ChatService, name: &str, headline: &str, log_tail: &[String]) -> String {
+    let bold = match service { ChatService::Slack => format!("*{}*", name), ChatService::Discord => format!("**{}**", name) };
+    let mut text = format!("{} {}", bold, headline);
+    let lines: Vec<String> = log_tail.iter()
+        .rev().take(CHAT_LOG_LINES).rev()
+        // A fence inside the logs would end the code block early
+        .map(|l| l.chars().take(CHAT_LINE_CHARS).collect::<String>().replace("```", "'''"))
+        .collect();
+    if !lines.is_empty() {
+        text.push_str(&format!("\n```\n{}\n```", lines.join("\n")));
+    }
+    text
+}
+
+/// Posts in the background; failures are only logged.
+pub fn send_chat_alert(webhook: &ChatWebhook, text: String) {
+    let body = match webhook.service {
+        ChatService::Slack => serde_json::json!({ "text": text }),
+        ChatService::Discord => serde_json::json!({ "content": text }),
+    }.to_string();
+    let url = webhook.url.clone();
+    std::thread::spawn(move || {
+        let child = Command::new("curl")
+            .args(["-sS", "--fail", "--max-time", CHAT_TIMEOUT_SECS, "-X", "POST", "-H", "Content-Type: application/json", "--data-binary", "@-"])
+            .arg(&url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn();
+        let Ok(mut child) = child else { return };
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(body.as_bytes());
+        }
+        if let Ok(output) = child.wait_with_output() {
+            if !output.status.success() {
+                eprintln!("[DexHub] Chat webhook failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+            }
+        }
+    });
+}
+
+// ─── Digest ───────────────────────────────────────────────────────────────────
+
+/// Notifications arriving within this window of the first are coalesced.
+pub const DIGEST_WINDOW: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// A notification waiting out the digest window. `kind` is a past-tense verb
+/// ("crashed", "stopped", "failed", …) used to summarise bursts.
+#[derive(Clone, serde::Serialize)]
+pub struct PendingNotification {
+    pub kind:    String,
+    pub name:    String,
+    pub message: String,
+    pub sound:   String,
+}
+
+#[derive(Default)]
+pub struct NotificationQueue {
+    pub pending: std::sync::Mutex<Vec<PendingNotification>>,
+}
+
+/// "5 servers stopped, 1 failed" — kinds in order of first appearance.
+pub fn summarize_digest(items: &[PendingNotification]) -> String {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for item in items {
+        match counts.iter_mut().find(|(k, _)| *k == item.kind) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((&item.kind, 1)),
+        }
+    }
+    counts.iter().enumerate().map(|(i, (kind, n))| match (i, n) {
+        (0, 1) => format!("1 server {}", kind),
+        (0, n) => format!("{} servers {}", n, kind),
+        (_, n) => format!("{} {}", n, kind),
+    }).collect::<Vec<_>>().join(", ")
+}
+
+/// Sound of the most severe item: a crash or failure outranks anything else.
+pub fn digest_sound(items: &[PendingNotification]) -> &str {
+    items.iter()
+        .find(|i| i.kind == "crashed" || i.kind == "failed")
+        .or(items.first())
+        .map(|i| i.sound.as_str())
+        .unwrap_or("Pop")
+}
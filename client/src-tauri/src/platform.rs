@@ -0,0 +1,555 @@
+//! Platform-specific integration: handing URLs and folders to the OS (the
+//! browser, the file manager, a terminal), launching at login, keeping
+//! secrets in the system keyring and hearing about sleep and wake.
+//!
+//! macOS uses `open`, a LaunchAgent and the keychain (read with `security`,
+//! written through the Security framework), Linux the freedesktop tools
+//! (`xdg-open`, whichever terminal emulator is installed, an XDG autostart
+//! entry, `secret-tool`), Windows `cmd /C start`, Explorer and the `Run`
+//! registry key.
+//!
+//! Sleep and wake come from IOKit's system power notifications on macOS.
+//! Elsewhere only wake is seen, as a jump in the wall clock that the
+//! monotonic clock (which stops while suspended) didn't make.
+
+use std::process::{Command, Stdio};
+
+/// Tray label for revealing a project folder, in each platform's own words.
+pub const REVEAL_LABEL: &str = if cfg!(target_os = "macos") {
+    "Show in Finder"
+} else if cfg!(target_os = "windows") {
+    "Show in Explorer"
+} else {
+    "Open Folder"
+};
+
+/// Opens a URL in the default browser, or a path with its default app.
+pub fn open_target(target: &str) -> Result<(), String> {
+    let mut cmd = if cfg!(target_os = "macos") {
+        let mut c = Command::new("open");
+        c.arg(target);
+        c
+    } else if cfg!(target_os = "windows") {
+        // The empty string is `start`'s window title; without it a quoted
+        // target would be taken as the title
+        let mut c = Command::new("cmd");
+        c.args(["/C", "start", "", target]);
+        c
+    } else {
+        let mut c = Command::new("xdg-open");
+        c.arg(target);
+        c
+    };
+    cmd.spawn().map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// The flags that pick a browser profile: Chromium-based browsers take the
+/// profile's directory name ("Profile 1"), Firefox its profile name.
+fn profile_args(browser: &str, profile: &str) -> Vec<String> {
+    let lower = browser.to_ascii_lowercase();
+    if lower.contains("firefox") {
+        vec!["-P".to_string(), profile.to_string()]
+    } else if ["chrome", "chromium", "brave", "edge", "vivaldi", "opera"].iter().any(|b| lower.contains(b)) {
+        vec![format!("--profile-directory={}", profile)]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Opens a URL in the given browser (an app name on macOS, a program
+/// elsewhere), or the default one when None. `profile` is ignored by
+/// browsers without profile flags (Safari, the system default).
+pub fn open_url_in(url: &str, browser: Option<&str>, profile: Option<&str>) -> Result<(), String> {
+    let Some(browser) = browser.filter(|b| !b.trim().is_empty()) else { return open_target(url) };
+    let extra = profile.filter(|p| !p.trim().is_empty()).map(|p| profile_args(browser, p)).unwrap_or_default();
+    let result = if cfg!(target_os = "macos") {
+        if extra.is_empty() {
+            Command::new("open").args(["-a", browser, url]).spawn()
+        } else {
+            // -n so the flags reach the browser even when it's already open
+            Command::new("open").args(["-na", browser, "--args"]).args(&extra).arg(url).spawn()
+        }
+    } else if cfg!(target_os = "windows") {
+        Command::new("cmd").args(["/C", "start", "", browser]).args(&extra).arg(url).spawn()
+    } else {
+        Command::new(browser).args(&extra).arg(url).spawn()
+    };
+    result.map(|_| ()).map_err(|e| format!("Couldn't open {}: {}", browser, e))
+}
+
+/// Shows a folder in Finder / Explorer / the desktop's file manager.
+pub fn reveal_in_file_manager(path: &str) -> Result<(), String> {
+    let result = if cfg!(target_os = "macos") {
+        Command::new("open").args(["-R", path]).spawn()
+    } else if cfg!(target_os = "windows") {
+        Command::new("explorer").arg(format!("/select,{}", path)).spawn()
+    } else {
+        // xdg-open can't select an entry, so open the folder itself
+        Command::new("xdg-open").arg(path).spawn()
+    };
+    result.map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Linux terminals in preference order, with the flag that sets their working directory.
+const LINUX_TERMINALS: &[(&str, &str)] = &[
+    ("x-terminal-emulator", "--working-directory"),
+    ("gnome-terminal", "--working-directory"),
+    ("konsole", "--workdir"),
+    ("xfce4-terminal", "--working-directory"),
+    ("alacritty", "--working-directory"),
+    ("kitty", "--directory"),
+];
+
+/// Opens a terminal window in `dir`.
+pub fn open_terminal(dir: &str) -> Result<(), String> {
+    if cfg!(target_os = "macos") {
+        return Command::new("open").args(["-a", "Terminal", dir]).spawn().map(|_| ()).map_err(|e| e.to_string());
+    }
+    if cfg!(target_os = "windows") {
+        // Windows Terminal when present, plain cmd otherwise
+        if Command::new("wt").args(["-d", dir]).spawn().is_ok() { return Ok(()); }
+        return Command::new("cmd")
+            .args(["/C", "start", "", "cmd", "/K", "cd", "/d", dir])
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| e.to_string());
+    }
+    for (terminal, flag) in LINUX_TERMINALS {
+        if Command::new(terminal).arg(flag).arg(dir).current_dir(dir).spawn().is_ok() {
+            return Ok(());
+        }
+    }
+    Err("No supported terminal emulator found".to_string())
+}
+
+// ─── Editors ──────────────────────────────────────────────────────────────────
+
+pub struct Editor {
+    pub id:      &'static str,
+    pub label:   &'static str,
+    cli:         &'static str,
+    mac_app:     &'static str, // used via `open -a` when the CLI isn't installed
+}
+
+/// In detection order when no editor is configured.
+pub const EDITORS: &[Editor] = &[
+    Editor { id: "vscode",   label: "VS Code",       cli: "code",     mac_app: "Visual Studio Code" },
+    Editor { id: "cursor",   label: "Cursor",        cli: "cursor",   mac_app: "Cursor" },
+    Editor { id: "zed",      label: "Zed",           cli: "zed",      mac_app: "Zed" },
+    Editor { id: "webstorm", label: "WebStorm",      cli: "webstorm", mac_app: "WebStorm" },
+    Editor { id: "idea",     label: "IntelliJ IDEA", cli: "idea",     mac_app: "IntelliJ IDEA" },
+    Editor { id: "pycharm",  label: "PyCharm",       cli: "pycharm",  mac_app: "PyCharm" },
+];
+
+/// GUI apps get a minimal PATH, so the usual install locations are checked too.
+const EXTRA_BIN_DIRS: &[&str] = &["/usr/local/bin", "/opt/homebrew/bin", "/snap/bin"];
+
+fn find_on_path(bin: &str) -> Option<std::path::PathBuf> {
+    let path = std::env::var("PATH").unwrap_or_default();
+    let exts: &[&str] = if cfg!(target_os = "windows") { &[".cmd", ".exe", ""] } else { &[""] };
+    std::env::split_paths(&path)
+        .chain(EXTRA_BIN_DIRS.iter().map(std::path::PathBuf::from))
+        .flat_map(|dir| exts.iter().map(move |ext| dir.join(format!("{}{}", bin, ext))))
+        .find(|candidate| candidate.is_file())
+}
+
+fn mac_app_installed(app: &str) -> bool {
+    cfg!(target_os = "macos")
+        && [format!("/Applications/{}.app", app), format!("{}/Applications/{}.app", home_dir(), app)]
+            .iter()
+            .any(|p| std::path::Path::new(p).exists())
+}
+
+pub fn editor_installed(editor: &Editor) -> bool {
+    find_on_path(editor.cli).is_some() || mac_app_installed(editor.mac_app)
+}
+
+/// Opens `dir` in the given editor, preferring its CLI over the macOS app bundle.
+pub fn open_in_editor(editor_id: &str, dir: &str) -> Result<(), String> {
+    let editor = EDITORS.iter().find(|e| e.id == editor_id).ok_or_else(|| format!("Unknown editor '{}'", editor_id))?;
+    let result = if let Some(cli) = find_on_path(editor.cli) {
+        Command::new(cli).arg(dir).spawn()
+    } else if mac_app_installed(editor.mac_app) {
+        Command::new("open").args(["-a", editor.mac_app, dir]).spawn()
+    } else {
+        return Err(format!("{} isn't installed", editor.label));
+    };
+    result.map(|_| ()).map_err(|e| e.to_string())
+}
+
+// ─── Launch at Login ──────────────────────────────────────────────────────────
+
+const AUTOSTART_LABEL: &str = "com.dexhub.client";
+const WINDOWS_RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+const WINDOWS_RUN_VALUE: &str = "DexHub";
+
+fn home_dir() -> String {
+    std::env::var("HOME").unwrap_or_default()
+}
+
+fn launch_agent_path() -> String {
+    format!("{}/Library/LaunchAgents/{}.plist", home_dir(), AUTOSTART_LABEL)
+}
+
+/// `$XDG_CONFIG_HOME/autostart/dexhub.desktop`, defaulting to `~/.config`.
+fn xdg_autostart_path() -> std::path::PathBuf {
+    let config_home = std::env::var("XDG_CONFIG_HOME")
+        .ok()
+        .filter(|d| !d.is_empty())
+        .unwrap_or_else(|| format!("{}/.config", home_dir()));
+    std::path::Path::new(&config_home).join("autostart").join("dexhub.desktop")
+}
+
+pub fn autostart_enabled() -> bool {
+    if cfg!(target_os = "macos") {
+        std::path::Path::new(&launch_agent_path()).exists()
+    } else if cfg!(target_os = "windows") {
+        Command::new("reg")
+            .args(["query", WINDOWS_RUN_KEY, "/v", WINDOWS_RUN_VALUE])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    } else {
+        xdg_autostart_path().exists()
+    }
+}
+
+pub fn set_autostart(enabled: bool) -> Result<(), String> {
+    if cfg!(target_os = "macos") {
+        set_launch_agent(enabled)
+    } else if cfg!(target_os = "windows") {
+        set_windows_run_key(enabled)
+    } else {
+        set_xdg_autostart(enabled)
+    }
+}
+
+fn set_launch_agent(enabled: bool) -> Result<(), String> {
+    let plist_path = launch_agent_path();
+
+    if enabled {
+        let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+        let exe_str = exe.to_string_lossy();
+        let plist = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <false/>
+</dict>
+</plist>"#,
+            AUTOSTART_LABEL, exe_str
+        );
+        if let Some(dir) = std::path::Path::new(&plist_path).parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&plist_path, plist).map_err(|e| e.to_string())?;
+        let _ = std::process::Command::new("launchctl")
+            .args(["load", &plist_path])
+            .output();
+    } else {
+        let _ = std::process::Command::new("launchctl")
+            .args(["unload", &plist_path])
+            .output();
+        let _ = std::fs::remove_file(&plist_path);
+    }
+    Ok(())
+}
+
+fn set_xdg_autostart(enabled: bool) -> Result<(), String> {
+    let path = xdg_autostart_path();
+    if !enabled {
+        let _ = std::fs::remove_file(&path);
+        return Ok(());
+    }
+    // An AppImage's current_exe is inside a temporary mount; $APPIMAGE is the real file
+    let exe = match std::env::var_os("APPIMAGE") {
+        Some(appimage) => std::path::PathBuf::from(appimage),
+        None => std::env::current_exe().map_err(|e| e.to_string())?,
+    };
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName=DexHub\nExec=\"{}\"\nX-GNOME-Autostart-enabled=true\nNoDisplay=true\n",
+        exe.to_string_lossy().replace('"', "\\\"")
+    );
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&path, entry).map_err(|e| e.to_string())
+}
+
+fn set_windows_run_key(enabled: bool) -> Result<(), String> {
+    let output = if enabled {
+        let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+        Command::new("reg")
+            .args(["add", WINDOWS_RUN_KEY, "/v", WINDOWS_RUN_VALUE, "/t", "REG_SZ", "/d", &format!("\"{}\"", exe.to_string_lossy()), "/f"])
+            .output()
+    } else {
+        if !autostart_enabled() { return Ok(()); }
+        Command::new("reg").args(["delete", WINDOWS_RUN_KEY, "/v", WINDOWS_RUN_VALUE, "/f"]).output()
+    };
+    let output = output.map_err(|e| e.to_string())?;
+    if output.status.success() { Ok(()) } else { Err(String::from_utf8_lossy(&output.stderr).trim().to_string()) }
+}
+
+// ─── Keyring ──────────────────────────────────────────────────────────────────
+
+/// Service name every DexHub secret is filed under.
+const KEYRING_SERVICE: &str = "DexHub";
+
+/// Reads a secret; None if it isn't stored or there's no keyring.
+pub fn keyring_get(account: &str) -> Option<String> {
+    keyring_lookup(account).ok().flatten()
+}
+
+/// Reads a secret, telling "not stored" (`Ok(None)`) apart from a keyring
+/// that couldn't be asked: locked, missing its tool, or timing out.
+pub fn keyring_lookup(account: &str) -> Result<Option<String>, String> {
+    let output = if cfg!(target_os = "macos") {
+        Command::new("security")
+            .args(["find-generic-password", "-s", KEYRING_SERVICE, "-a", account, "-w"])
+            .output()
+    } else if cfg!(target_os = "windows") {
+        return Ok(None);
+    } else {
+        Command::new("secret-tool")
+            .args(["lookup", "service", KEYRING_SERVICE, "account", account])
+            .output()
+    }.map_err(|e| format!("Keyring unavailable: {}", e))?;
+    let secret = String::from_utf8_lossy(&output.stdout).trim_end_matches('\n').to_string();
+    if output.status.success() {
+        return Ok(Some(secret).filter(|s| !s.is_empty()));
+    }
+    // `security` exits 44 for errSecItemNotFound; secret-tool exits 1 quietly
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    let not_found = if cfg!(target_os = "macos") { output.status.code() == Some(44) } else { stderr.is_empty() };
+    if not_found { Ok(None) } else { Err(stderr) }
+}
+
+/// Stores or replaces a secret. The Security framework takes it in memory,
+/// so it never shows up in `ps` and nothing prompts on a terminal.
+#[cfg(target_os = "macos")]
+pub fn keyring_set(account: &str, secret: &str) -> Result<(), String> {
+    security_framework::passwords::set_generic_password(KEYRING_SERVICE, account, secret.as_bytes())
+        .map_err(|e| format!("Couldn't store '{}' in the keychain: {}", account, e))
+}
+
+/// Stores or replaces a secret.
+#[cfg(not(target_os = "macos"))]
+pub fn keyring_set(account: &str, secret: &str) -> Result<(), String> {
+    use std::io::Write;
+    if cfg!(target_os = "windows") {
+        return Err("No system keyring on this platform".to_string());
+    }
+    // secret-tool reads the secret from stdin, keeping it out of `ps`
+    let label = format!("{} {}", KEYRING_SERVICE, account);
+    let mut child = Command::new("secret-tool")
+        .args(["store", "--label", &label, "service", KEYRING_SERVICE, "account", account])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("secret-tool unavailable: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(secret.as_bytes()).map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if output.status.success() { return Ok(()); }
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    Err(format!("Couldn't store '{}' in the keyring{}", account, if stderr.is_empty() { String::new() } else { format!(": {}", stderr) }))
+}
+
+/// Re-files a secret under another account. Nothing to move succeeds, and
+/// a secret already stored under `to` is left alone, as is `from`.
+pub fn keyring_move(from: &str, to: &str) -> Result<(), String> {
+    let Some(secret) = keyring_get(from) else { return Ok(()) };
+    if keyring_get(to).is_some() { return Ok(()); }
+    keyring_set(to, &secret)?;
+    keyring_delete(from)
+}
+
+/// Removes a secret; succeeds if it wasn't there.
+pub fn keyring_delete(account: &str) -> Result<(), String> {
+    if keyring_get(account).is_none() { return Ok(()); }
+    let status = if cfg!(target_os = "macos") {
+        Command::new("security")
+            .args(["delete-generic-password", "-s", KEYRING_SERVICE, "-a", account])
+            .stdout(Stdio::null())
+            .status()
+    } else {
+        Command::new("secret-tool")
+            .args(["clear", "service", KEYRING_SERVICE, "account", account])
+            .status()
+    }.map_err(|e| e.to_string())?;
+    if status.success() { Ok(()) } else { Err(format!("Couldn't remove '{}' from the keyring", account)) }
+}
+
+// ─── Launch Modifiers ─────────────────────────────────────────────────────────
+
+#[cfg(target_os = "macos")]
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGEventSourceFlagsState(state_id: i32) -> u64;
+}
+
+/// True while Shift is held down. Only macOS can ask without a window
+/// having focus; elsewhere this is always false.
+pub fn shift_held() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        const COMBINED_SESSION_STATE: i32 = 0;
+        const FLAG_MASK_SHIFT: u64 = 0x0002_0000;
+        // SAFETY: a pure query with no pointers involved
+        return unsafe { CGEventSourceFlagsState(COMBINED_SESSION_STATE) } & FLAG_MASK_SHIFT != 0;
+    }
+    #[allow(unreachable_code)]
+    false
+}
+
+// ─── Battery ──────────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy, PartialEq, serde::Serialize)]
+pub struct BatteryStatus {
+    pub on_battery: bool,
+    pub percent:    Option<u8>,
+}
+
+/// None on machines without a battery (and on Windows, which isn't asked).
+pub fn battery_status() -> Option<BatteryStatus> {
+    #[cfg(target_os = "macos")]
+    {
+        // Now drawing from 'Battery Power'
+        //  -InternalBattery-0 (id=4653155)	85%; discharging; 4:20 remaining present: true
+        let output = Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        let line = text.lines().find(|l| l.contains("InternalBattery"))?;
+        let percent = line.split_whitespace()
+            .find_map(|w| w.strip_suffix("%;"))
+            .and_then(|p| p.parse().ok());
+        return Some(BatteryStatus { on_battery: text.contains("'Battery Power'"), percent });
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let read = |path: std::path::PathBuf| std::fs::read_to_string(path).ok().map(|s| s.trim().to_string());
+        let entries = std::fs::read_dir("/sys/class/power_supply").ok()?;
+        let battery = entries.flatten()
+            .map(|e| e.path())
+            .find(|p| read(p.join("type")).as_deref() == Some("Battery"))?;
+        return Some(BatteryStatus {
+            on_battery: read(battery.join("status")).as_deref() == Some("Discharging"),
+            percent:    read(battery.join("capacity")).and_then(|c| c.parse().ok()),
+        });
+    }
+    #[allow(unreachable_code)]
+    None
+}
+
+// ─── Power Events ─────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PowerEvent {
+    Sleep, // the system is about to sleep; the handler runs before it's allowed to
+    Wake,
+}
+
+type PowerHandler = Box<dyn Fn(PowerEvent) + Send + Sync>;
+
+#[cfg(target_os = "macos")]
+mod power {
+    use super::{PowerEvent, PowerHandler};
+    use std::ffi::c_void;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    type IoCallback = extern "C" fn(*mut c_void, u32, u32, *mut c_void);
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IORegisterForSystemPower(refcon: *mut c_void, port: *mut *mut c_void, callback: IoCallback, notifier: *mut u32) -> u32;
+        fn IONotificationPortGetRunLoopSource(port: *mut c_void) -> *mut c_void;
+        fn IOAllowPowerChange(kernel_port: u32, notification_id: isize) -> i32;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        static kCFRunLoopCommonModes: *const c_void;
+        fn CFRunLoopGetCurrent() -> *mut c_void;
+        fn CFRunLoopAddSource(run_loop: *mut c_void, source: *mut c_void, mode: *const c_void);
+        fn CFRunLoopRun();
+    }
+
+    const CAN_SYSTEM_SLEEP: u32 = 0xE000_0270;
+    const SYSTEM_WILL_SLEEP: u32 = 0xE000_0280;
+    const SYSTEM_HAS_POWERED_ON: u32 = 0xE000_0300;
+
+    static ROOT_PORT: AtomicU32 = AtomicU32::new(0);
+
+    extern "C" fn on_power_message(refcon: *mut c_void, _service: u32, message: u32, argument: *mut c_void) {
+        // SAFETY: refcon is the handler leaked in `watch`, alive for the process
+        let handler = unsafe { &*(refcon as *const PowerHandler) };
+        // SAFETY: replies to the notification IOKit just delivered
+        let allow = || unsafe { IOAllowPowerChange(ROOT_PORT.load(Ordering::SeqCst), argument as isize) };
+        match message {
+            CAN_SYSTEM_SLEEP => { allow(); }
+            // macOS waits up to 30s for the reply, which is the handler's window
+            SYSTEM_WILL_SLEEP => { handler(PowerEvent::Sleep); allow(); }
+            SYSTEM_HAS_POWERED_ON => handler(PowerEvent::Wake),
+            _ => {}
+        }
+    }
+
+    pub fn watch(handler: PowerHandler) {
+        std::thread::spawn(move || {
+            let refcon = Box::into_raw(Box::new(handler)) as *mut c_void;
+            let mut port: *mut c_void = std::ptr::null_mut();
+            let mut notifier = 0u32;
+            // SAFETY: out-pointers are valid locals; the run loop belongs to this thread
+            unsafe {
+                let root = IORegisterForSystemPower(refcon, &mut port, on_power_message, &mut notifier);
+                if root == 0 {
+                    eprintln!("[DexHub] Couldn't register for sleep/wake notifications");
+                    return;
+                }
+                ROOT_PORT.store(root, Ordering::SeqCst);
+                CFRunLoopAddSource(CFRunLoopGetCurrent(), IONotificationPortGetRunLoopSource(port), kCFRunLoopCommonModes);
+                CFRunLoopRun();
+            }
+        });
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod power {
+    use super::{PowerEvent, PowerHandler};
+    use std::time::{Duration, Instant, SystemTime};
+
+    const TICK: Duration = Duration::from_secs(5);
+    /// Wall-clock time beyond the monotonic clock that counts as having slept.
+    const SLEEP_GAP: Duration = Duration::from_secs(30);
+
+    pub fn watch(handler: PowerHandler) {
+        std::thread::spawn(move || {
+            let (mut mono, mut wall) = (Instant::now(), SystemTime::now());
+            loop {
+                std::thread::sleep(TICK);
+                let (now_mono, now_wall) = (Instant::now(), SystemTime::now());
+                let wall_elapsed = now_wall.duration_since(wall).unwrap_or_default();
+                if wall_elapsed > now_mono.duration_since(mono) + SLEEP_GAP {
+                    handler(PowerEvent::Wake);
+                }
+                (mono, wall) = (now_mono, now_wall);
+            }
+        });
+    }
+}
+
+/// Calls `handler` on a background thread for each sleep and wake.
+pub fn watch_power_events(handler: impl Fn(PowerEvent) + Send + Sync + 'static) {
+    power::watch(Box::new(handler));
+}
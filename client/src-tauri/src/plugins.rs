@@ -0,0 +1,130 @@
+//! Sandboxed WebAssembly detector plugins (cargo feature `wasm-plugins`).
+//!
+//! Each `<app data>/plugins/*.wasm` module can teach the scanner a new kind of
+//! project. Plugins get no WASI and a fuel budget; the only host capability is
+//! `dexhub.read_file`, confined to the directory being inspected.
+//!
+//! ABI — the module must export:
+//! - `memory`
+//! - `alloc(len: i32) -> i32` — returns a buffer the host writes input into
+//! - `detect(ptr: i32, len: i32) -> i64` — input is JSON
+//!   `{"dir_name": "...", "files": ["..."]}`; returns `0` for "not mine", or
+//!   `(out_ptr << 32) | out_len` pointing at JSON
+//!   `{"name": "...", "command": "...", "args": [...], "port": 8000}`
+//!
+//! and may import:
+//! - `dexhub.read_file(path_ptr, path_len, out_ptr, out_cap) -> i32` — reads a
+//!   file relative to the project dir; returns bytes written or `-1`.
+
+use std::path::{Path, PathBuf};
+use wasmtime::{Caller, Config, Engine, Extern, Linker, Module, Store};
+
+/// Instruction budget per `detect` call; runaway plugins trap instead of hanging a scan.
+const FUEL_PER_DETECT: u64 = 50_000_000;
+/// Largest file a plugin may read through `dexhub.read_file`.
+const MAX_READ_BYTES: u64 = 1 << 20;
+
+pub struct Detector {
+    pub name: String,
+    engine:   Engine,
+    module:   Module,
+}
+
+#[derive(serde::Deserialize)]
+pub struct Detection {
+    pub name:    String,
+    pub command: String,
+    #[serde(default)]
+    pub args:    Vec<String>,
+    pub port:    Option<u16>,
+}
+
+struct HostCtx {
+    root: PathBuf,
+}
+
+pub fn plugins_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("plugins")
+}
+
+/// Compiles every `.wasm` in the plugins directory; broken modules are skipped.
+pub fn load_detectors(app_data_dir: &Path) -> Vec<Detector> {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    let Ok(engine) = Engine::new(&config) else { return Vec::new() };
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(plugins_dir(app_data_dir))
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    paths.retain(|p| p.extension().map(|e| e == "wasm").unwrap_or(false));
+    paths.sort();
+
+    paths.into_iter().filter_map(|path| {
+        let name = path.file_stem()?.to_string_lossy().into_owned();
+        match Module::from_file(&engine, &path) {
+            Ok(module) => Some(Detector { name, engine: engine.clone(), module }),
+            Err(e) => {
+                eprintln!("[DexHub] Plugin '{}' failed to load: {}", name, e);
+                None
+            }
+        }
+    }).collect()
+}
+
+/// Reads `rel` under `root`, refusing anything that resolves outside it.
+fn read_confined(root: &Path, rel: &str) -> Option<Vec<u8>> {
+    if Path::new(rel).is_absolute() { return None; }
+    let root = root.canonicalize().ok()?;
+    let target = root.join(rel).canonicalize().ok()?;
+    if !target.starts_with(&root) { return None; }
+    if std::fs::metadata(&target).ok()?.len() > MAX_READ_BYTES { return None; }
+    std::fs::read(target).ok()
+}
+
+impl Detector {
+    pub fn detect(&self, project_dir: &Path) -> Option<Detection> {
+        let mut store = Store::new(&self.engine, HostCtx { root: project_dir.to_path_buf() });
+        store.set_fuel(FUEL_PER_DETECT).ok()?;
+
+        let mut linker: Linker<HostCtx> = Linker::new(&self.engine);
+        linker.func_wrap(
+            "dexhub",
+            "read_file",
+            |mut caller: Caller<'_, HostCtx>, path_ptr: i32, path_len: i32, out_ptr: i32, out_cap: i32| -> i32 {
+                let Some(Extern::Memory(mem)) = caller.get_export("memory") else { return -1 };
+                let mut buf = vec![0u8; path_len.max(0) as usize];
+                if mem.read(&caller, path_ptr as usize, &mut buf).is_err() { return -1; }
+                let Ok(rel) = String::from_utf8(buf) else { return -1 };
+                let root = caller.data().root.clone();
+                let Some(data) = read_confined(&root, &rel) else { return -1 };
+                let n = data.len().min(out_cap.max(0) as usize);
+                if mem.write(&mut caller, out_ptr as usize, &data[..n]).is_err() { return -1; }
+                n as i32
+            },
+        ).ok()?;
+
+        let instance = linker.instantiate(&mut store, &self.module).ok()?;
+        let memory = instance.get_memory(&mut store, "memory")?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc").ok()?;
+        let detect = instance.get_typed_func::<(i32, i32), i64>(&mut store, "detect").ok()?;
+
+        let files: Vec<String> = std::fs::read_dir(project_dir)
+            .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.file_name().to_string_lossy().into_owned()).collect())
+            .unwrap_or_default();
+        let input = serde_json::json!({
+            "dir_name": project_dir.file_name().map(|n| n.to_string_lossy().into_owned()),
+            "files": files,
+        }).to_string();
+
+        let in_ptr = alloc.call(&mut store, input.len() as i32).ok()?;
+        memory.write(&mut store, in_ptr as usize, input.as_bytes()).ok()?;
+        let packed = detect.call(&mut store, (in_ptr, input.len() as i32)).ok()?;
+        if packed == 0 { return None; }
+
+        let out_ptr = (packed >> 32) as u32 as usize;
+        let out_len = (packed & 0xffff_ffff) as usize;
+        let mut out = vec![0u8; out_len];
+        memory.read(&store, out_ptr, &mut out).ok()?;
+        serde_json::from_slice(&out).ok()
+    }
+}
@@ -0,0 +1,364 @@
+//! Production previews: runs a project's `build` script, then serves the
+//! output on a port of its own while the dev server keeps running, so dev
+//! and prod behaviour can be compared side by side.
+//!
+//! The project's own `preview` script (`vite preview`, `next start`) serves
+//! the build when there is one; otherwise a small static file server here
+//! serves the output folder, falling back to `index.html` for client-side
+//! routes.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::{ensure_not_presenting, push_log_line, record_event, stream_to_log, LogBuffer, ServerState};
+
+/// Where build tools put their output, in the order they're looked for.
+const OUTPUT_DIRS: &[&str] = &["dist", "build", "out"];
+
+enum Server {
+    Script(Child),               // the project's `preview` script
+    Static(Arc<AtomicBool>),     // shutdown flag for the built-in server
+}
+
+struct Preview {
+    status: PreviewStatus,
+    port:   u16,
+    server: Option<Server>,      // None while building
+}
+
+#[derive(Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewStatus {
+    Building,
+    Serving,
+    Failed,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct PreviewInfo {
+    pub name:      String,
+    pub status:    PreviewStatus,
+    pub port:      u16,
+    pub url:       String,
+    pub served_by: Option<String>, // "script" or "static"; None until serving
+}
+
+#[derive(Default)]
+pub struct PreviewState {
+    previews: Mutex<HashMap<String, Preview>>,
+}
+
+/// Preview output lives in its own log buffer next to the dev server's.
+pub fn preview_log_key(name: &str) -> String {
+    format!("{}:preview", name)
+}
+
+/// A port nothing is listening on right now, picked by the OS.
+fn pick_free_port() -> Result<u16, String> {
+    TcpListener::bind(("127.0.0.1", 0))
+        .and_then(|l| l.local_addr())
+        .map(|a| a.port())
+        .map_err(|e| format!("Couldn't find a free port: {}", e))
+}
+
+fn has_script(cwd: &str, script: &str) -> bool {
+    std::fs::read_to_string(Path::new(cwd).join("package.json"))
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+        .is_some_and(|v| v["scripts"][script].as_str().is_some_and(|s| !s.trim().is_empty()))
+}
+
+fn output_dir(cwd: &str) -> Option<PathBuf> {
+    OUTPUT_DIRS.iter().map(|d| Path::new(cwd).join(d)).find(|p| p.join("index.html").is_file())
+}
+
+fn info(app: &tauri::AppHandle, name: &str, preview: &Preview) -> PreviewInfo {
+    let host = crate::resolve_url_host(&app.state::<ServerState>(), name);
+    PreviewInfo {
+        name:      name.to_string(),
+        status:    preview.status.clone(),
+        port:      preview.port,
+        url:       format!("http://{}:{}", host, preview.port),
+        served_by: preview.server.as_ref().map(|s| match s {
+            Server::Script(_) => "script".to_string(),
+            Server::Static(_) => "static".to_string(),
+        }),
+    }
+}
+
+fn set_status(app: &tauri::AppHandle, name: &str, status: PreviewStatus, server: Option<Server>) {
+    let previews = app.state::<PreviewState>();
+    let mut previews = previews.previews.lock().unwrap();
+    let Some(preview) = previews.get_mut(name) else { return };
+    preview.status = status;
+    preview.server = server;
+    let _ = app.emit("preview-changed", info(app, name, preview));
+}
+
+/// Projects with a preview being built or served.
+pub fn previews_running(app: &tauri::AppHandle) -> Vec<String> {
+    let previews = app.state::<PreviewState>();
+    let previews = previews.previews.lock().unwrap();
+    previews.iter().filter(|(_, p)| p.status != PreviewStatus::Failed).map(|(n, _)| n.clone()).collect()
+}
+
+/// Registers the preview and builds and serves it on a thread of its own;
+/// progress is emitted as `preview-changed`.
+pub fn start_preview(app: &tauri::AppHandle, name: &str) -> Result<PreviewInfo, String> {
+    let project = app.state::<ServerState>().projects.lock().unwrap()
+        .iter().find(|p| p.name == name).cloned()
+        .ok_or_else(|| format!("Project '{}' not found", name))?;
+    if !has_script(&project.cwd, "build") {
+        return Err(format!("'{}' has no build script", name));
+    }
+    let port = pick_free_port()?;
+    let preview = Preview { status: PreviewStatus::Building, port, server: None };
+    let started = info(app, name, &preview);
+    {
+        let previews = app.state::<PreviewState>();
+        let mut previews = previews.previews.lock().unwrap();
+        if previews.get(name).is_some_and(|p| p.status != PreviewStatus::Failed) {
+            return Err(format!("'{}' already has a preview; stop it first", name));
+        }
+        previews.insert(name.to_string(), preview);
+    }
+
+    let key = preview_log_key(name);
+    let log_buf: LogBuffer = Arc::new(Mutex::new(VecDeque::with_capacity(500)));
+    app.state::<ServerState>().log_buffers.lock().unwrap().insert(key.clone(), Arc::clone(&log_buf));
+    record_event(app, "preview-started", Some(name), serde_json::json!({ "port": port }));
+    crate::rebuild_tray(app);
+
+    let (app, name) = (app.clone(), name.to_string());
+    std::thread::spawn(move || {
+        let shell = app.state::<ServerState>().general.lock().unwrap().shell.clone();
+        let build = format!("{} run build", project.manager());
+        push_log_line(&app, &key, &log_buf, format!("[build] $ {}", build));
+        let built = std::process::Command::new(&shell)
+            .args(["-lc", &build])
+            .current_dir(&project.cwd)
+            .env("NODE_ENV", "production")
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| e.to_string())
+            .and_then(|mut child| stream_to_log(&app, &key, &mut child, &log_buf, "[build]"));
+        match built {
+            Ok(status) if status.success() => {}
+            Ok(status) => return fail(&app, &name, &key, &log_buf, format!("Build exited with {}", status.code().map_or("a signal".to_string(), |c| c.to_string()))),
+            Err(e) => return fail(&app, &name, &key, &log_buf, format!("Couldn't run the build: {}", e)),
+        }
+        // Stopped while building
+        if !app.state::<PreviewState>().previews.lock().unwrap().contains_key(&name) { return; }
+
+        let server = if has_script(&project.cwd, "preview") {
+            // npm needs `--` to forward flags to the script; the others pass them through
+            let sep = if project.manager() == "npm" { " --" } else { "" };
+            let line = format!("{} run preview{} --port {}", project.manager(), sep, port);
+            push_log_line(&app, &key, &log_buf, format!("[preview] $ {}", line));
+            spawn_script(&app, &key, &log_buf, &shell, &project.cwd, &line, port).map(Server::Script)
+        } else {
+            match output_dir(&project.cwd) {
+                Some(root) => {
+                    push_log_line(&app, &key, &log_buf, format!("[preview] Serving {} on port {}", root.display(), port));
+                    serve_static(root, port).map(Server::Static)
+                }
+                None => Err(format!("No preview script, and no {} with an index.html after the build", OUTPUT_DIRS.join("/"))),
+            }
+        };
+        match server {
+            Ok(server) => {
+                set_status(&app, &name, PreviewStatus::Serving, Some(server));
+                crate::rebuild_tray(&app);
+            }
+            Err(e) => fail(&app, &name, &key, &log_buf, e),
+        }
+    });
+    Ok(started)
+}
+
+fn fail(app: &tauri::AppHandle, name: &str, key: &str, buf: &LogBuffer, message: String) {
+    push_log_line(app, key, buf, format!("[preview] {}", message));
+    set_status(app, name, PreviewStatus::Failed, None);
+    record_event(app, "preview-failed", Some(name), serde_json::json!({ "error": message }));
+    crate::rebuild_tray(app);
+}
+
+fn spawn_script(app: &tauri::AppHandle, key: &str, buf: &LogBuffer, shell: &str, cwd: &str, line: &str, port: u16) -> Result<Child, String> {
+    let mut child = std::process::Command::new(shell)
+        .args(["-lc", line])
+        .current_dir(cwd)
+        .env("PORT", port.to_string())
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    if let Some(stdout) = child.stdout.take() {
+        let (app, key, buf) = (app.clone(), key.to_string(), Arc::clone(buf));
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                push_log_line(&app, &key, &buf, line);
+            }
+        });
+    }
+    if let Some(stderr) = child.stderr.take() {
+        let (app, key, buf) = (app.clone(), key.to_string(), Arc::clone(buf));
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                push_log_line(&app, &key, &buf, format!("[err] {}", line));
+            }
+        });
+    }
+    Ok(child)
+}
+
+/// Kills the server (or stops a build from being served). Returns whether
+/// there was a preview.
+pub fn stop_preview(app: &tauri::AppHandle, name: &str) -> bool {
+    let removed = app.state::<PreviewState>().previews.lock().unwrap().remove(name);
+    let Some(preview) = removed else { return false };
+    match preview.server {
+        Some(Server::Script(mut child)) => { let _ = child.kill(); let _ = child.wait(); }
+        Some(Server::Static(shutdown)) => shutdown.store(true, Ordering::SeqCst),
+        None => {}
+    }
+    record_event(app, "preview-stopped", Some(name), serde_json::json!({}));
+    crate::rebuild_tray(app);
+    true
+}
+
+/// Kills every preview server; for app exit.
+pub fn kill_all(app: &tauri::AppHandle) {
+    let Some(state) = app.try_state::<PreviewState>() else { return };
+    for (_, preview) in state.previews.lock().unwrap().iter_mut() {
+        match &mut preview.server {
+            Some(Server::Script(child)) => { let _ = child.kill(); }
+            Some(Server::Static(shutdown)) => shutdown.store(true, Ordering::SeqCst),
+            None => {}
+        }
+    }
+}
+
+// ─── Static Server ────────────────────────────────────────────────────────────
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).unwrap_or("") {
+        "html" => "text/html; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "json" | "map" => "application/json",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "wasm" => "application/wasm",
+        "txt" => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+/// The file for a request path. Paths that climb out of the root are
+/// refused; unknown paths without an extension get `index.html`, so client
+/// routes load the app.
+fn resolve_file(root: &Path, request_path: &str) -> Option<PathBuf> {
+    let path = request_path.split(['?', '#']).next().unwrap_or("/");
+    let mut file = root.to_path_buf();
+    for part in path.split('/').filter(|p| !p.is_empty() && *p != ".") {
+        if part == ".." || part.contains('\\') { return None; }
+        file.push(part);
+    }
+    if file.is_dir() { file.push("index.html"); }
+    if file.is_file() { return Some(file); }
+    if Path::new(path).extension().is_none() { return Some(root.join("index.html")); }
+    None
+}
+
+fn handle_static(root: &Path, mut stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(10)));
+    let mut head = [0u8; 8192];
+    let Ok(n) = stream.read(&mut head) else { return };
+    let head = String::from_utf8_lossy(&head[..n]);
+    let mut parts = head.lines().next().unwrap_or("").split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or("/"));
+    if method != "GET" && method != "HEAD" {
+        let _ = stream.write_all(b"HTTP/1.1 405 Method Not Allowed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        return;
+    }
+    let Some(body) = resolve_file(root, path).and_then(|f| std::fs::read(&f).ok().map(|b| (f, b))) else {
+        let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        return;
+    };
+    let (file, body) = body;
+    let head = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        content_type(&file), body.len()
+    );
+    let _ = stream.write_all(head.as_bytes());
+    if method == "GET" { let _ = stream.write_all(&body); }
+}
+
+/// Serves `root` on all interfaces, so the tailnet URL works as it does for
+/// dev servers, until the returned flag is set.
+fn serve_static(root: PathBuf, port: u16) -> Result<Arc<AtomicBool>, String> {
+    let listener = TcpListener::bind(("0.0.0.0", port)).map_err(|e| e.to_string())?;
+    listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&shutdown);
+    std::thread::spawn(move || {
+        while !flag.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    let _ = stream.set_nonblocking(false);
+                    let root = root.clone();
+                    std::thread::spawn(move || handle_static(&root, stream));
+                }
+                Err(_) => std::thread::sleep(Duration::from_millis(100)),
+            }
+        }
+    });
+    Ok(shutdown)
+}
+
+// ─── Tauri Commands ───────────────────────────────────────────────────────────
+
+/// Starts building; the result arrives as `preview-changed` events.
+#[tauri::command]
+pub fn start_build_preview(app: tauri::AppHandle, name: String) -> Result<PreviewInfo, String> {
+    start_preview(&app, &name)
+}
+
+#[tauri::command]
+pub fn stop_build_preview(app: tauri::AppHandle, name: String) -> Result<bool, String> {
+    ensure_not_presenting(&app)?;
+    Ok(stop_preview(&app, &name))
+}
+
+#[tauri::command]
+pub fn get_build_previews(app: tauri::AppHandle) -> Vec<PreviewInfo> {
+    let previews = app.state::<PreviewState>();
+    let previews = previews.previews.lock().unwrap();
+    let mut list: Vec<PreviewInfo> = previews.iter().map(|(name, p)| info(&app, name, p)).collect();
+    list.sort_by(|a, b| a.name.cmp(&b.name));
+    list
+}
+
+/// Build and preview server output, kept apart from the dev server's logs.
+#[tauri::command]
+pub fn get_build_preview_logs(app: tauri::AppHandle, name: String) -> Vec<String> {
+    let state = app.state::<ServerState>();
+    let buf = state.log_buffers.lock().unwrap().get(&preview_log_key(&name)).cloned();
+    crate::redact_log_lines(&app, buf.map(|b| crate::log_texts(&b.lock().unwrap())).unwrap_or_default())
+}
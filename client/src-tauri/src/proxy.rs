@@ -0,0 +1,238 @@
+//! Host-routed local reverse proxy: `http://<slug>.<domain>:<port>` forwards
+//! to the project's dev server (`web.dex.test` → project "web").
+//!
+//! Projects listed in `lazy` are started on their first request. While they
+//! boot, the browser gets a self-refreshing "starting…" page, and once the
+//! port accepts connections requests are proxied as usual.
+//!
+//! Name resolution is up to the user (`/etc/hosts`, dnsmasq, or a `*.dex.test`
+//! resolver); the proxy only looks at the `Host` header.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::{launch_server, ServerState, StartErrorKind};
+
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+/// How long a request waits on a wake-up another request already began.
+const WAKE_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ProxyConfig {
+    pub enabled: bool,
+    pub port:    u16,
+    pub domain:  String,
+    pub lazy:    Vec<String>, // projects started on first request
+}
+
+impl Default for ProxyConfig {
+    fn default() -> Self {
+        ProxyConfig { enabled: false, port: 8088, domain: "dex.test".to_string(), lazy: Vec::new() }
+    }
+}
+
+fn proxy_config_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("proxy.json")
+}
+
+pub fn load_proxy_config(app_data_dir: &Path) -> ProxyConfig {
+    std::fs::read_to_string(proxy_config_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_proxy_config_to_disk(app_data_dir: &Path, config: &ProxyConfig) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(config) {
+        let _ = std::fs::write(proxy_config_path(app_data_dir), json);
+    }
+}
+
+pub struct ProxyState {
+    pub config: Mutex<ProxyConfig>,
+    shutdown:   Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl ProxyState {
+    pub fn new(config: ProxyConfig) -> Self {
+        ProxyState { config: Mutex::new(config), shutdown: Mutex::new(None) }
+    }
+}
+
+/// Lower-cases and replaces anything outside `[a-z0-9]` with `-`.
+pub fn host_slug(name: &str) -> String {
+    let slug: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    slug.trim_matches('-').to_string()
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn write_page(stream: &mut TcpStream, status: &str, title: &str, message: &str, refresh: bool) {
+    let body = format!(
+        "<!doctype html><html><head><meta charset=\"utf-8\"><title>{title}</title>{meta}\
+         <style>body{{font:16px -apple-system,system-ui,sans-serif;display:grid;place-items:center;height:90vh;color:#444}}</style>\
+         </head><body><div><h2>{title}</h2><p>{message}</p></div></body></html>",
+        title = html_escape(title),
+        message = html_escape(message),
+        meta = if refresh { "<meta http-equiv=\"refresh\" content=\"1\">" } else { "" },
+    );
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nCache-Control: no-store\r\n{}Connection: close\r\n\r\n",
+        status, body.len(), if refresh { "Retry-After: 1\r\n" } else { "" }
+    );
+    let _ = stream.write_all(head.as_bytes());
+    let _ = stream.write_all(body.as_bytes());
+}
+
+/// Reads until the end of the request head; returns everything read so far.
+fn read_head(stream: &mut TcpStream) -> Option<Vec<u8>> {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(10)));
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        if buf.len() > MAX_HEAD_BYTES { return None; }
+        let n = stream.read(&mut chunk).ok()?;
+        if n == 0 { return None; }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    let _ = stream.set_read_timeout(None);
+    Some(buf)
+}
+
+fn host_header(head: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(head);
+    text.lines()
+        .find_map(|l| l.split_once(':').filter(|(k, _)| k.eq_ignore_ascii_case("host")).map(|(_, v)| v.trim().to_string()))
+}
+
+/// Splices client and upstream together until either side closes.
+fn pipe(client: TcpStream, upstream: TcpStream) {
+    let (Ok(mut client_r), Ok(mut upstream_w)) = (client.try_clone(), upstream.try_clone()) else { return };
+    let (mut client_w, mut upstream_r) = (client, upstream);
+    let up = std::thread::spawn(move || {
+        let _ = std::io::copy(&mut client_r, &mut upstream_w);
+        let _ = upstream_w.shutdown(std::net::Shutdown::Write);
+    });
+    let _ = std::io::copy(&mut upstream_r, &mut client_w);
+    let _ = client_w.shutdown(std::net::Shutdown::Both);
+    let _ = up.join();
+}
+
+fn handle_connection(app: &tauri::AppHandle, mut stream: TcpStream) {
+    let Some(head) = read_head(&mut stream) else { return };
+    let config = app.state::<ProxyState>().config.lock().unwrap().clone();
+
+    let host = host_header(&head).unwrap_or_default();
+    let hostname = host.split(':').next().unwrap_or("").to_ascii_lowercase();
+    let suffix = format!(".{}", config.domain.to_ascii_lowercase());
+    let Some(slug) = hostname.strip_suffix(&suffix) else {
+        return write_page(&mut stream, "404 Not Found", "Unknown host", &format!("Use <project>{} to reach a project.", suffix), false);
+    };
+
+    let state = app.state::<ServerState>();
+    let project = state.projects.lock().unwrap().iter().find(|p| host_slug(&p.name) == slug).cloned();
+    let Some(project) = project else {
+        return write_page(&mut stream, "404 Not Found", "No such project", &format!("No project matches '{}'.", slug), false);
+    };
+    let running = state.processes.lock().unwrap().contains_key(&project.id);
+
+    if !running {
+        if !config.lazy.contains(&project.name) {
+            return write_page(&mut stream, "502 Bad Gateway", &format!("{} is stopped", project.name), "Start it from DexHub, or enable wake-on-demand for it.", false);
+        }
+        // Concurrent requests to a sleeping project share the one start
+        match launch_server(app, project.name.clone(), None, &HashMap::new()) {
+            Ok(()) => crate::record_event(app, "server-woken", Some(&project.name), serde_json::json!({ "host": hostname })),
+            Err(e) if matches!(e.kind, StartErrorKind::AlreadyStarting) => crate::await_start(&state, &project.id, WAKE_TIMEOUT),
+            Err(e) => return write_page(&mut stream, "502 Bad Gateway", &format!("{} failed to start", project.name), &e.message, false),
+        }
+    }
+
+    match TcpStream::connect(("127.0.0.1", crate::effective_port(&state, &project))) {
+        Ok(mut upstream) => {
+            if upstream.write_all(&head).is_err() { return; }
+            crate::note_success(&state, &project.name);
+            pipe(stream, upstream);
+        }
+        Err(_) => write_page(&mut stream, "503 Service Unavailable", &format!("Starting {}…", project.name), "This page reloads once the server is ready.", true),
+    }
+}
+
+fn spawn_listener(app: tauri::AppHandle, port: u16, shutdown: Arc<AtomicBool>) -> Result<(), String> {
+    let listener = TcpListener::bind(("127.0.0.1", port)).map_err(|e| e.to_string())?;
+    listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+    std::thread::spawn(move || {
+        while !shutdown.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    let _ = stream.set_nonblocking(false);
+                    let app = app.clone();
+                    std::thread::spawn(move || handle_connection(&app, stream));
+                }
+                Err(_) => std::thread::sleep(Duration::from_millis(100)),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Stops any running listener and starts a new one if the config enables it.
+pub fn apply_proxy_config(app: &tauri::AppHandle) -> Result<(), String> {
+    let proxy = app.state::<ProxyState>();
+    if let Some(flag) = proxy.shutdown.lock().unwrap().take() {
+        flag.store(true, Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(150));
+    }
+    let config = proxy.config.lock().unwrap().clone();
+    if !config.enabled { return Ok(()); }
+    let flag = Arc::new(AtomicBool::new(false));
+    spawn_listener(app.clone(), config.port, Arc::clone(&flag))?;
+    *proxy.shutdown.lock().unwrap() = Some(flag);
+    Ok(())
+}
+
+// ─── Tauri Commands ───────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_proxy_config(proxy: tauri::State<'_, ProxyState>) -> ProxyConfig {
+    proxy.config.lock().unwrap().clone()
+}
+
+#[tauri::command]
+pub fn set_proxy_config(app: tauri::AppHandle, config: ProxyConfig) -> Result<(), String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    save_proxy_config_to_disk(&app_data_dir, &config);
+    *app.state::<ProxyState>().config.lock().unwrap() = config;
+    apply_proxy_config(&app)
+}
+
+/// Toggles wake-on-demand for one project without resending the whole config.
+#[tauri::command]
+pub fn set_wake_on_demand(app: tauri::AppHandle, name: String, enabled: bool) -> Result<(), String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let proxy = app.state::<ProxyState>();
+    let mut config = proxy.config.lock().unwrap();
+    config.lazy.retain(|n| n != &name);
+    if enabled { config.lazy.push(name); }
+    save_proxy_config_to_disk(&app_data_dir, &config);
+    Ok(())
+}
+
+/// `http://<slug>.<domain>:<port>` for a project, or `None` while the proxy is off.
+#[tauri::command]
+pub fn get_proxy_url(proxy: tauri::State<'_, ProxyState>, name: String) -> Option<String> {
+    let config = proxy.config.lock().unwrap();
+    config.enabled.then(|| format!("http://{}.{}:{}", host_slug(&name), config.domain, config.port))
+}
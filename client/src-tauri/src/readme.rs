@@ -0,0 +1,209 @@
+//! A project's README, whole, prepared for the frontend to render as a
+//! project page: the title pulled out, CI/coverage badges dropped, and
+//! relative image paths turned into file URLs so they load outside the repo.
+//!
+//! `get_project_readme` in main.rs is the short tray/card snippet; this is
+//! the full document.
+
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+use crate::{presenting, ServerState};
+
+/// Hosts and path fragments that mark an image as a status badge.
+const BADGE_MARKERS: &[&str] = &[
+    "shields.io", "badgen.net", "badge.fury.io", "badge.svg", "/badge/", "/badges/",
+    "travis-ci.", "circleci.com", "codecov.io", "coveralls.io", "app.netlify.com/sites", "snyk.io/test",
+];
+
+#[derive(Clone, serde::Serialize)]
+pub struct ProjectReadme {
+    pub file:           String,         // e.g. "README.md"
+    pub title:          Option<String>, // the first heading, if the document has one
+    pub markdown:       String,         // the title heading itself is kept; the UI may hide it
+    pub badges_removed: usize,
+}
+
+fn find_readme(dir: &Path) -> Option<PathBuf> {
+    let mut candidates: Vec<PathBuf> = std::fs::read_dir(dir).ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .filter(|p| {
+            let name = p.file_name().map(|n| n.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+            matches!(name.as_str(), "readme.md" | "readme.markdown" | "readme.mdx" | "readme")
+        })
+        .collect();
+    // README.md before readme.markdown before a bare README
+    candidates.sort_by_key(|p| {
+        let name = p.file_name().map(|n| n.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+        (!name.ends_with(".md"), name)
+    });
+    candidates.into_iter().next()
+}
+
+fn is_badge(url: &str) -> bool {
+    let url = url.to_ascii_lowercase();
+    BADGE_MARKERS.iter().any(|m| url.contains(m))
+}
+
+/// A Markdown image `![alt](url "title")` at `start`, as (end, url).
+fn image_at(line: &str, start: usize) -> Option<(usize, &str)> {
+    let rest = line.get(start..)?.strip_prefix("![")?;
+    let alt_end = rest.find("](")?;
+    let target = &rest[alt_end + 2..];
+    let close = target.find(')')?;
+    let url = target[..close].split_whitespace().next().unwrap_or("");
+    Some((start + 2 + alt_end + 2 + close + 1, url))
+}
+
+/// Drops badge images, including a link wrapped around one
+/// (`[![build](…/badge.svg)](…/actions)`). Returns the line and the count.
+fn strip_badges(line: &str) -> (String, usize) {
+    let mut out = String::with_capacity(line.len());
+    let mut removed = 0;
+    let mut i = 0;
+    while i < line.len() {
+        let linked = line[i..].starts_with("[![");
+        let image_start = if linked { i + 1 } else { i };
+        if let Some((mut end, _)) = image_at(line, image_start).filter(|(_, url)| is_badge(url)) {
+            if linked {
+                // `](link)` after the image closes the wrapping link
+                if let Some(rest) = line[end..].strip_prefix("](") {
+                    end += 2 + rest.find(')').map_or(rest.len(), |c| c + 1);
+                }
+            }
+            removed += 1;
+            i = end;
+            continue;
+        }
+        let ch = line[i..].chars().next().unwrap_or(' ');
+        out.push(ch);
+        i += ch.len_utf8();
+    }
+    (out, removed)
+}
+
+/// Paths relative to the README, resolved to `file://` URLs. Absolute URLs,
+/// data URIs and anchors are left alone.
+fn file_url(dir: &Path, path: &str) -> Option<String> {
+    let lower = path.to_ascii_lowercase();
+    if path.is_empty() || path.starts_with('#') || lower.starts_with("data:") || lower.contains("://") || lower.starts_with("mailto:") {
+        return None;
+    }
+    let file = dir.join(path.trim_start_matches("./").trim_start_matches('/'));
+    Some(format!("file://{}", file.to_string_lossy().replace(' ', "%20")))
+}
+
+/// Rewrites `![alt](relative)` and `<img src="relative">` to file URLs.
+fn rewrite_images(line: &str, dir: &Path) -> String {
+    let mut out = line.to_string();
+    let mut search = 0;
+    while let Some(offset) = out[search..].find("![") {
+        let start = search + offset;
+        let Some((end, url)) = image_at(&out, start) else { search = start + 2; continue };
+        match file_url(dir, url) {
+            Some(replacement) => {
+                let url_start = start + out[start..end].rfind(url).unwrap_or(0);
+                let url = url.to_string();
+                out.replace_range(url_start..url_start + url.len(), &replacement);
+                search = end + replacement.len() - url.len();
+            }
+            None => search = end,
+        }
+    }
+    for quote in ['"', '\''] {
+        let pattern = format!("src={}", quote);
+        let mut search = 0;
+        while let Some(offset) = out[search..].find(&pattern) {
+            let url_start = search + offset + pattern.len();
+            let Some(len) = out[url_start..].find(quote) else { break };
+            let url = out[url_start..url_start + len].to_string();
+            match file_url(dir, &url) {
+                Some(replacement) => {
+                    out.replace_range(url_start..url_start + len, &replacement);
+                    search = url_start + replacement.len();
+                }
+                None => search = url_start + len,
+            }
+        }
+    }
+    out
+}
+
+/// `# Title`, a setext `Title\n===`, or `<h1>Title</h1>`, whichever comes first.
+fn detect_title(lines: &[String]) -> Option<String> {
+    let mut in_fence = false;
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") { in_fence = !in_fence; continue; }
+        if in_fence || trimmed.is_empty() { continue; }
+        if let Some(title) = trimmed.strip_prefix("# ") {
+            return Some(title.trim().trim_end_matches('#').trim().to_string()).filter(|t| !t.is_empty());
+        }
+        if lines.get(i + 1).is_some_and(|next| next.trim().len() >= 2 && next.trim().chars().all(|c| c == '=')) {
+            return Some(trimmed.to_string());
+        }
+        let lower = trimmed.to_ascii_lowercase();
+        if let Some(start) = lower.find("<h1") {
+            let open_end = lower[start..].find('>')? + start + 1;
+            let close = lower[open_end..].find("</h1>")? + open_end;
+            let inner = strip_tags(&trimmed[open_end..close]);
+            if !inner.is_empty() { return Some(inner); }
+        }
+    }
+    None
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut out = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out.trim().to_string()
+}
+
+/// The full README for a project page. Image paths are left relative in
+/// presentation mode, since file URLs show where the project lives.
+#[tauri::command]
+pub fn get_project_readme_full(app: tauri::AppHandle, name: String) -> Option<ProjectReadme> {
+    let cwd = app.state::<ServerState>().projects.lock().unwrap()
+        .iter().find(|p| p.name == name).map(|p| PathBuf::from(&p.cwd))?;
+    let path = find_readme(&cwd)?;
+    let content = std::fs::read_to_string(&path).ok()?;
+    let dir = path.parent().unwrap_or(&cwd).to_path_buf();
+    let rewrite = !presenting(&app);
+
+    let mut badges_removed = 0;
+    let mut in_fence = false;
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            lines.push(line.to_string());
+            continue;
+        }
+        if in_fence { lines.push(line.to_string()); continue; }
+        let (stripped, removed) = strip_badges(line);
+        badges_removed += removed;
+        // A line that held only badges goes entirely
+        if removed > 0 && stripped.trim().is_empty() { continue; }
+        lines.push(if rewrite { rewrite_images(&stripped, &dir) } else { stripped });
+    }
+    // Badge rows often leave runs of blank lines behind
+    lines.dedup_by(|a, b| a.trim().is_empty() && b.trim().is_empty());
+
+    Some(ProjectReadme {
+        file: path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(),
+        title: detect_title(&lines),
+        markdown: lines.join("\n").trim().to_string(),
+        badges_removed,
+    })
+}
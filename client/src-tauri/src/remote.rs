@@ -0,0 +1,543 @@
+//! Remote-control HTTP API for paired devices.
+//!
+//! Requests are authenticated with the same Ed25519 header scheme the sensory
+//! server uses (see `dexhub-proto` for the headers and canonical form), so a
+//! phone that signs requests for the sensory server can drive DexHub unchanged.
+//!
+//! `GET /ui` serves a small mobile web UI. Browsers can't sign requests, so it
+//! authenticates its `/ui/api/*` calls with a bearer token minted for a paired
+//! device from the desktop app; revoking the device revokes its token.
+
+use base64::{engine::general_purpose, Engine as _};
+pub use dexhub_proto::http::{percent_decode, HttpRequest};
+use dexhub_proto::{pairing_message, random_token, verify_ed25519, PairConfirm, PairConfirmed, PairingCodes, SignedHeaders, SIGNED_HEADERS, TIMESTAMP_WINDOW_MS};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::{ServerState, effective_port, get_running_servers, project_url, restart_server, server_log_lines, start_server, stop_server, uptime_secs};
+
+const MAX_BODY_BYTES: usize = 1 << 20;
+/// WebSocket clients get a ping this often so dead connections are noticed.
+const WS_PING_INTERVAL: Duration = Duration::from_secs(30);
+const WS_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+const MOBILE_UI_HTML: &str = include_str!("remote_ui.html");
+
+// ─── HTTP Plumbing ────────────────────────────────────────────────────────────
+
+/// Reads one request, refusing bodies over `MAX_BODY_BYTES`.
+pub fn read_request(stream: &mut TcpStream) -> Option<HttpRequest> {
+    dexhub_proto::http::read_request(stream, MAX_BODY_BYTES)
+}
+
+pub fn write_response(stream: &mut TcpStream, status: u16, content_type: &str, body: &[u8]) {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        429 => "Too Many Requests",
+        _ => "Error",
+    };
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status, reason, content_type, body.len()
+    );
+    let _ = stream.write_all(head.as_bytes());
+    let _ = stream.write_all(body);
+}
+
+pub fn write_json(stream: &mut TcpStream, status: u16, value: &serde_json::Value) {
+    write_response(stream, status, "application/json", value.to_string().as_bytes());
+}
+
+fn write_error(stream: &mut TcpStream, status: u16, detail: &str) {
+    write_json(stream, status, &serde_json::json!({ "detail": detail }));
+}
+
+// ─── Config & Device Registry ─────────────────────────────────────────────────
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct RemoteApiConfig {
+    pub enabled: bool,
+    pub port:    u16,
+}
+
+impl Default for RemoteApiConfig {
+    fn default() -> Self {
+        RemoteApiConfig { enabled: false, port: 5055 }
+    }
+}
+
+/// Same shape as the sensory server's device_registry.json entries.
+pub use dexhub_proto::Device as PairedDevice;
+
+fn remote_api_config_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("remote_api.json")
+}
+
+pub fn load_remote_api_config(app_data_dir: &Path) -> RemoteApiConfig {
+    std::fs::read_to_string(remote_api_config_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_remote_api_config_to_disk(app_data_dir: &Path, config: &RemoteApiConfig) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(config) {
+        let _ = std::fs::write(remote_api_config_path(app_data_dir), json);
+    }
+}
+
+fn device_registry_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("device_registry.json")
+}
+
+pub fn load_device_registry(app_data_dir: &Path) -> HashMap<String, PairedDevice> {
+    std::fs::read_to_string(device_registry_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_device_registry_to_disk(app_data_dir: &Path, devices: &HashMap<String, PairedDevice>) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(devices) {
+        let _ = std::fs::write(device_registry_path(app_data_dir), json);
+    }
+}
+
+fn ui_tokens_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("remote_ui_tokens.json")
+}
+
+/// Mobile UI bearer tokens: token → device id.
+pub fn load_ui_tokens(app_data_dir: &Path) -> HashMap<String, String> {
+    std::fs::read_to_string(ui_tokens_path(app_data_dir))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_ui_tokens_to_disk(app_data_dir: &Path, tokens: &HashMap<String, String>) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(tokens) {
+        let _ = std::fs::write(ui_tokens_path(app_data_dir), json);
+    }
+}
+
+/// A connected WebSocket client; messages are pre-serialised JSON strings.
+struct Subscriber {
+    tx:   Sender<String>,
+    logs: bool, // opted into log lines with `?logs=1`
+}
+
+pub struct RemoteApiState {
+    pub config:  Mutex<RemoteApiConfig>,
+    pub devices: Mutex<HashMap<String, PairedDevice>>,
+    nonces:      Mutex<HashMap<String, HashMap<String, u128>>>, // device → nonce → expiry (ms)
+    pairing:     Mutex<PairingCodes>,
+    shutdown:    Mutex<Option<Arc<AtomicBool>>>,                // stop flag of the live listener
+    subscribers: Mutex<Vec<Subscriber>>,
+    ui_tokens:   Mutex<HashMap<String, String>>,
+}
+
+impl RemoteApiState {
+    pub fn new(config: RemoteApiConfig, devices: HashMap<String, PairedDevice>, ui_tokens: HashMap<String, String>) -> Self {
+        RemoteApiState {
+            config:   Mutex::new(config),
+            devices:  Mutex::new(devices),
+            ui_tokens: Mutex::new(ui_tokens),
+            nonces:   Mutex::new(HashMap::new()),
+            pairing:  Mutex::new(PairingCodes::default()),
+            shutdown: Mutex::new(None),
+            subscribers: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+// ─── Signature Verification ───────────────────────────────────────────────────
+
+fn now_ms() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+/// Checks the signed headers of a request and returns the device id.
+pub fn verify_request(remote: &RemoteApiState, req: &HttpRequest) -> Result<String, (u16, &'static str)> {
+    let Some(signed) = SignedHeaders::from_lookup(|k| req.headers.get(k).cloned()) else {
+        return Err((401, "Missing Auth Headers"));
+    };
+
+    let now = now_ms();
+    let req_ts = signed.timestamp_ms().ok_or((401u16, "Invalid Timestamp"))?;
+    if now.abs_diff(req_ts) > TIMESTAMP_WINDOW_MS {
+        return Err((401, "Request Expired"));
+    }
+
+    let public_key = match remote.devices.lock().unwrap().get(&signed.device_id) {
+        Some(d) if d.enabled => d.public_key.clone(),
+        _ => return Err((403, "Device Unauthorized or Unknown")),
+    };
+    signed.verify(&public_key, &req.method, &req.path, &req.body)?;
+    let SignedHeaders { device_id: dev_id, nonce, .. } = signed;
+
+    // Record the nonce only after the signature checks out, so forged
+    // requests can't burn a legitimate device's nonces.
+    let mut nonces = remote.nonces.lock().unwrap();
+    let seen = nonces.entry(dev_id.clone()).or_default();
+    seen.retain(|_, exp| *exp > now);
+    if seen.contains_key(&nonce) {
+        return Err((409, "Nonce Replay Detected"));
+    }
+    seen.insert(nonce, now + TIMESTAMP_WINDOW_MS + 5_000);
+    Ok(dev_id)
+}
+
+/// Checks a mobile UI `Authorization: Bearer` token and returns the device id.
+fn verify_bearer(remote: &RemoteApiState, req: &HttpRequest) -> Result<String, (u16, &'static str)> {
+    let token = req.headers.get("authorization")
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or((401u16, "Missing Bearer Token"))?;
+    let dev_id = remote.ui_tokens.lock().unwrap().get(token.trim()).cloned().ok_or((401u16, "Invalid Token"))?;
+    match remote.devices.lock().unwrap().get(&dev_id) {
+        Some(d) if d.enabled => Ok(dev_id),
+        _ => Err((403, "Device Unauthorized or Unknown")),
+    }
+}
+
+// ─── Pairing ──────────────────────────────────────────────────────────────────
+
+fn confirm_pairing(app: &tauri::AppHandle, body: &[u8]) -> Result<serde_json::Value, (u16, &'static str)> {
+    let remote = app.state::<RemoteApiState>();
+    let payload: PairConfirm = serde_json::from_slice(body).map_err(|_| (400u16, "Invalid JSON"))?;
+    let PairConfirm { code, public_key, signature, role } = payload;
+
+    {
+        let mut pairing = remote.pairing.lock().unwrap();
+        pairing.check(&code, Instant::now())?;
+        if !verify_ed25519(&public_key, pairing_message(&code).as_bytes(), &signature) {
+            pairing.fail();
+            return Err((401, "Proof of Possession Failed"));
+        }
+        pairing.consume(&code);
+    }
+
+    let dev_id = dexhub_proto::device_id(&public_key);
+    let created_at = now_ms() as f64 / 1000.0;
+    let mut devices = remote.devices.lock().unwrap();
+    devices.insert(dev_id.clone(), PairedDevice {
+        public_key,
+        role: role.clone(),
+        enabled: true,
+        created_at,
+    });
+    if let Ok(dir) = app.path().app_data_dir() {
+        save_device_registry_to_disk(&dir, &devices);
+    }
+    drop(devices);
+    crate::append_audit(app, "device-paired", "", serde_json::json!({ "device_id": dev_id, "role": role }));
+    Ok(serde_json::to_value(PairConfirmed { device_id: dev_id, status: "Paired".to_string() }).unwrap_or_default())
+}
+
+// ─── WebSocket Push ───────────────────────────────────────────────────────────
+
+fn ws_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        n if n < 126 => frame.push(n as u8),
+        n if n <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        n => {
+            frame.push(127);
+            frame.extend_from_slice(&(n as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+fn broadcast(app: &tauri::AppHandle, message: String, is_log: bool) {
+    let Some(remote) = app.try_state::<RemoteApiState>() else { return };
+    let mut subscribers = remote.subscribers.lock().unwrap();
+    subscribers.retain(|s| (is_log && !s.logs) || s.tx.send(message.clone()).is_ok());
+}
+
+/// Registers an in-process listener for the same stream WebSocket clients get.
+/// Used by the local CLI socket to follow logs.
+pub fn subscribe(app: &tauri::AppHandle, logs: bool) -> Option<Receiver<String>> {
+    let remote = app.try_state::<RemoteApiState>()?;
+    let (tx, rx) = std::sync::mpsc::channel();
+    remote.subscribers.lock().unwrap().push(Subscriber { tx, logs });
+    Some(rx)
+}
+
+/// Pushes an event-log record to every WebSocket client.
+pub fn broadcast_event<T: serde::Serialize>(app: &tauri::AppHandle, record: &T) {
+    let mut value = serde_json::to_value(record).unwrap_or_default();
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("type".to_string(), serde_json::json!("event"));
+    }
+    broadcast(app, value.to_string(), false);
+}
+
+/// Pushes one server log line to clients that subscribed with `?logs=1`.
+pub fn broadcast_log(app: &tauri::AppHandle, project: &str, line: &str) {
+    let Some(remote) = app.try_state::<RemoteApiState>() else { return };
+    if !remote.subscribers.lock().unwrap().iter().any(|s| s.logs) { return; }
+    let line = crate::redact_log_lines(app, vec![line.to_string()]).remove(0);
+    let message = serde_json::json!({ "type": "log", "project": project, "line": line }).to_string();
+    broadcast(app, message, true);
+}
+
+/// Completes the upgrade handshake, then forwards broadcast messages as text
+/// frames until the client goes away. Client frames are not read.
+fn serve_websocket(app: &tauri::AppHandle, mut stream: TcpStream, req: &HttpRequest) {
+    let Some(key) = req.headers.get("sec-websocket-key") else {
+        return write_error(&mut stream, 400, "Expected WebSocket upgrade");
+    };
+    let mut hasher = Sha1::new();
+    hasher.update(format!("{}{}", key, WS_GUID).as_bytes());
+    let accept = general_purpose::STANDARD.encode(hasher.finalize());
+    let head = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept
+    );
+    if stream.write_all(head.as_bytes()).is_err() { return; }
+
+    let logs = req.query.get("logs").map(|v| v == "1" || v == "true").unwrap_or(false);
+    let Some(rx) = subscribe(app, logs) else { return };
+
+    let mut last_ping = Instant::now();
+    loop {
+        match rx.recv_timeout(Duration::from_secs(1)) {
+            Ok(msg) => {
+                if stream.write_all(&ws_frame(0x1, msg.as_bytes())).is_err() { break; }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        if last_ping.elapsed() >= WS_PING_INTERVAL {
+            if stream.write_all(&ws_frame(0x9, b"")).is_err() { break; }
+            last_ping = Instant::now();
+        }
+    }
+    // Dropping `rx` makes the next broadcast prune this subscriber
+}
+
+// ─── Routing ──────────────────────────────────────────────────────────────────
+
+fn server_status(app: &tauri::AppHandle) -> serde_json::Value {
+    let running = get_running_servers(app.clone());
+    let state = app.state::<ServerState>();
+    let projects = state.projects.lock().unwrap().clone();
+    let health = state.last_health.lock().unwrap().clone();
+    let servers: Vec<serde_json::Value> = projects.iter().map(|p| {
+        serde_json::json!({
+            "name": p.name,
+            "port": effective_port(&state, p),
+            "running": running.contains(&p.name),
+            "healthy": health.get(&p.name),
+            "url": project_url(&state, p),
+            "uptime_secs": uptime_secs(&state, &p.name),
+        })
+    }).collect();
+    serde_json::json!({ "servers": servers })
+}
+
+fn handle_connection(app: &tauri::AppHandle, mut stream: TcpStream) {
+    let Some(mut req) = read_request(&mut stream) else { return };
+
+    // Browsers can't set headers on WebSocket handshakes, so the signed
+    // X-DEX-* values may also arrive as query parameters.
+    for key in SIGNED_HEADERS {
+        if !req.headers.contains_key(key) {
+            if let Some(v) = req.query.get(key).cloned() { req.headers.insert(key.to_string(), v); }
+        }
+    }
+
+    if req.method == "POST" && req.path == "/pair/confirm" {
+        match confirm_pairing(app, &req.body) {
+            Ok(v) => write_json(&mut stream, 200, &v),
+            Err((status, detail)) => write_error(&mut stream, status, detail),
+        }
+        return;
+    }
+
+    if req.method == "GET" && (req.path == "/ui" || req.path == "/ui/") {
+        return write_response(&mut stream, 200, "text/html; charset=utf-8", MOBILE_UI_HTML.as_bytes());
+    }
+
+    // The mobile UI reaches the same routes under /ui/api with a bearer token
+    let remote = app.state::<RemoteApiState>();
+    let (auth, path) = match req.path.strip_prefix("/ui/api/") {
+        Some(rest) => (verify_bearer(&remote, &req), rest.to_string()),
+        None => (verify_request(&remote, &req), req.path.clone()),
+    };
+    let dev_id = match auth {
+        Ok(id) => id,
+        Err((status, detail)) => return write_error(&mut stream, status, detail),
+    };
+
+    let segments: Vec<String> = path.trim_matches('/').split('/').map(percent_decode).collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    match (req.method.as_str(), segments.as_slice()) {
+        ("GET", ["status"]) => write_json(&mut stream, 200, &server_status(app)),
+        ("GET", ["events"]) => serve_websocket(app, stream, &req),
+        ("GET", ["servers", name, "logs"]) => {
+            let lines: usize = req.query.get("lines").and_then(|v| v.parse().ok()).unwrap_or(200);
+            let logs = crate::redact_log_lines(app, server_log_lines(app, name, None));
+            let skip = logs.len().saturating_sub(lines);
+            write_json(&mut stream, 200, &serde_json::json!({ "name": name, "lines": &logs[skip..] }));
+        }
+        ("POST", ["servers", name, action @ ("start" | "stop" | "restart")]) => {
+            let exists = app.state::<ServerState>().projects.lock().unwrap().iter().any(|p| p.name == *name);
+            if !exists { return write_error(&mut stream, 404, "Project not found"); }
+            if *action != "start" {
+                if let Err(e) = crate::ensure_not_presenting(app) { return write_error(&mut stream, 403, &e); }
+            }
+            let result = match *action {
+                "start" => start_server(app, name.to_string()),
+                "stop" => stop_server(app, name.to_string()),
+                _ => restart_server(app, name.to_string()).map(|_| ()),
+            };
+            crate::append_audit(app, &format!("remote-{}", action), name, serde_json::json!({ "device_id": dev_id }));
+            match result {
+                Ok(()) => write_json(&mut stream, 200, &serde_json::json!({ "status": "ok" })),
+                Err(e) => write_json(&mut stream, 500, &serde_json::json!({ "detail": e })),
+            }
+        }
+        _ => write_error(&mut stream, 404, "Not Found"),
+    }
+}
+
+/// Binds the API on all interfaces (tailnet included) and serves each
+/// connection on its own thread until `shutdown` is set.
+fn spawn_listener(app: tauri::AppHandle, port: u16, shutdown: Arc<AtomicBool>) -> Result<(), String> {
+    let listener = TcpListener::bind(("0.0.0.0", port)).map_err(|e| e.to_string())?;
+    listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+    std::thread::spawn(move || {
+        while !shutdown.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    let _ = stream.set_nonblocking(false);
+                    let app = app.clone();
+                    std::thread::spawn(move || handle_connection(&app, stream));
+                }
+                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                Err(_) => std::thread::sleep(Duration::from_millis(100)),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Stops any running listener and starts a new one if the config enables it.
+pub fn apply_remote_api_config(app: &tauri::AppHandle) -> Result<(), String> {
+    let remote = app.state::<RemoteApiState>();
+    if let Some(flag) = remote.shutdown.lock().unwrap().take() {
+        flag.store(true, Ordering::SeqCst);
+        // Let the old accept loop notice the flag and release the port
+        std::thread::sleep(Duration::from_millis(150));
+    }
+    let config = remote.config.lock().unwrap().clone();
+    if !config.enabled { return Ok(()); }
+    let flag = Arc::new(AtomicBool::new(false));
+    spawn_listener(app.clone(), config.port, Arc::clone(&flag))?;
+    *remote.shutdown.lock().unwrap() = Some(flag);
+    Ok(())
+}
+
+// ─── Tauri Commands ───────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_remote_api_config(remote: tauri::State<'_, RemoteApiState>) -> RemoteApiConfig {
+    remote.config.lock().unwrap().clone()
+}
+
+#[tauri::command]
+pub fn set_remote_api_config(app: tauri::AppHandle, config: RemoteApiConfig) -> Result<(), String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    save_remote_api_config_to_disk(&app_data_dir, &config);
+    *app.state::<RemoteApiState>().config.lock().unwrap() = config;
+    apply_remote_api_config(&app)
+}
+
+/// Issues a 6-digit code the device signs as `PAIR:<code>` and posts to
+/// `/pair/confirm` within five minutes. Only the newest code is live.
+#[tauri::command]
+pub fn start_remote_pairing(remote: tauri::State<'_, RemoteApiState>) -> Result<String, String> {
+    let mut pairing = remote.pairing.lock().unwrap();
+    pairing.clear();
+    pairing.issue(Instant::now())
+}
+
+#[tauri::command]
+pub fn list_paired_devices(remote: tauri::State<'_, RemoteApiState>) -> HashMap<String, PairedDevice> {
+    remote.devices.lock().unwrap().clone()
+}
+
+#[tauri::command]
+pub fn revoke_paired_device(app: tauri::AppHandle, device_id: String) -> Result<(), String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let remote = app.state::<RemoteApiState>();
+    let mut devices = remote.devices.lock().unwrap();
+    let device = devices.get_mut(&device_id).ok_or_else(|| format!("Device '{}' not found", device_id))?;
+    device.enabled = false;
+    save_device_registry_to_disk(&app_data_dir, &devices);
+    drop(devices);
+    let mut tokens = remote.ui_tokens.lock().unwrap();
+    tokens.retain(|_, d| *d != device_id);
+    save_ui_tokens_to_disk(&app_data_dir, &tokens);
+    drop(tokens);
+    crate::append_audit(&app, "device-revoked", "", serde_json::json!({ "device_id": device_id }));
+    Ok(())
+}
+
+/// Mints a mobile UI token for a paired device (replacing any earlier one) and
+/// returns the link to open on that device — tailnet host when available.
+#[tauri::command]
+pub fn create_remote_ui_link(app: tauri::AppHandle, device_id: String) -> Result<String, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let remote = app.state::<RemoteApiState>();
+    let config = remote.config.lock().unwrap().clone();
+    if !config.enabled { return Err("Remote API is disabled".to_string()); }
+    match remote.devices.lock().unwrap().get(&device_id) {
+        Some(d) if d.enabled => {}
+        _ => return Err(format!("Device '{}' is not paired", device_id)),
+    }
+
+    let token = random_token()?;
+    {
+        let mut tokens = remote.ui_tokens.lock().unwrap();
+        tokens.retain(|_, d| *d != device_id);
+        tokens.insert(token.clone(), device_id.clone());
+        save_ui_tokens_to_disk(&app_data_dir, &tokens);
+    }
+    crate::append_audit(&app, "remote-ui-link", "", serde_json::json!({ "device_id": device_id }));
+
+    let state = app.state::<ServerState>();
+    let tailnet = state.tailscale_host.lock().unwrap().clone();
+    let host = if state.tailscale_online.load(Ordering::SeqCst) && !tailnet.is_empty() {
+        tailnet
+    } else {
+        state.lan_ip.lock().unwrap().clone().unwrap_or_else(|| "localhost".to_string())
+    };
+    Ok(format!("http://{}:{}/ui?token={}", host, config.port, token))
+}
@@ -0,0 +1,414 @@
+//! Global settings in one typed `settings.json`: where projects are scanned
+//! for, the shell servers launch in, the browser URLs open in, how often
+//! things are probed, and the feature configs that used to sit in files of
+//! their own (notifications, CPU watchdog, crash policy, sleep, battery,
+//! disk guard, editor, license policy).
+//!
+//! Each section still lives in its own `ServerState` field, which is what
+//! the rest of the app reads. This module assembles them into one document
+//! to save and to hand to the UI, and applies a saved or updated document
+//! back. When `settings.json` doesn't exist yet it's built once from the
+//! old per-feature files, which are then left alone; a section added later
+//! is seeded from its old file the first time it's missing.
+//!
+//! Global files that stay separate: `proxy.json` and `remote_api.json`
+//! (changing them restarts a listener, which their own commands do),
+//! `scheduled_scan.json` (rewritten by every scan), `focus.json` (rules name
+//! projects and follow renames), credentials (`device_registry.json`,
+//! `remote_ui_tokens.json`) and app-kept records (`port_registry.json`,
+//! `inventory.json`, `project_ids.json`, `recent.json`).
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::licenses::{self, LicensePolicy};
+use crate::notifications::{self, NotificationConfig};
+use crate::{
+    ensure_not_presenting, presenting, BatteryPolicy, ConfigReloadReport, CrashPolicy, DiskGuardConfig, EditorConfig,
+    EnvProfiles, Favorite, LaunchCommand, LifecycleHooks, ServerState, SleepPolicy, WatchdogConfig, REDACTED,
+};
+
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct GeneralSettings {
+    pub scan_roots:              Vec<String>,    // folders searched for projects, in order
+    pub shell:                   String,         // servers run as `<shell> -lc <command>`
+    pub browser:                 Option<String>, // app name or binary; None for the system default
+    pub browser_profile:         Option<String>, // profile for `browser`, e.g. "Profile 1" for Chrome
+    pub project_browsers:        HashMap<String, BrowserChoice>, // per-project overrides of the two above
+    pub health_interval_secs:    u64,
+    pub health_timeout_ms:       u64,            // how long a health probe waits for a connection
+    pub project_health:          HashMap<String, HealthProbeSettings>, // per-project overrides of the two above
+    pub tailscale_interval_secs: u64,
+    pub watchdog_interval_secs:  u64,
+    pub auto_install:            bool,           // install missing or stale dependencies before a start
+    pub otlp_endpoint:           Option<String>, // OTLP/HTTP collector events are also sent to, e.g. http://localhost:4318
+}
+
+impl Default for GeneralSettings {
+    fn default() -> Self {
+        GeneralSettings {
+            scan_roots: vec![crate::PROJECTS_DIR.to_string()],
+            shell: default_shell(),
+            browser: None,
+            browser_profile: None,
+            project_browsers: HashMap::new(),
+            health_interval_secs: 10,
+            health_timeout_ms: 200,
+            project_health: HashMap::new(),
+            tailscale_interval_secs: 30,
+            watchdog_interval_secs: 5,
+            auto_install: false,
+            otlp_endpoint: None,
+        }
+    }
+}
+
+/// The user's login shell from `$SHELL`, else `/bin/sh`.
+fn default_shell() -> String {
+    std::env::var("SHELL").ok().filter(|s| !s.trim().is_empty()).unwrap_or_else(|| "/bin/sh".to_string())
+}
+
+/// Which browser, and which of its profiles, a project's URL opens in.
+#[derive(Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct BrowserChoice {
+    pub browser: Option<String>, // None for the system default
+    pub profile: Option<String>,
+}
+
+/// A project's own health probe cadence, e.g. a slow SSR server that needs
+/// longer than the global timeout to accept connections under load.
+#[derive(Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct HealthProbeSettings {
+    pub interval_secs: Option<u64>, // None for the global interval
+    pub timeout_ms:    Option<u64>, // None for the global timeout
+}
+
+impl GeneralSettings {
+    /// The project's own choice, or the global one.
+    pub fn browser_for(&self, project: &str) -> BrowserChoice {
+        self.project_browsers.get(project).cloned().unwrap_or_else(|| BrowserChoice {
+            browser: self.browser.clone(),
+            profile: self.browser_profile.clone(),
+        })
+    }
+
+    pub fn health_interval(&self) -> Duration { Duration::from_secs(self.health_interval_secs.max(1)) }
+    pub fn tailscale_interval(&self) -> Duration { Duration::from_secs(self.tailscale_interval_secs.max(1)) }
+    pub fn watchdog_interval(&self) -> Duration { Duration::from_secs(self.watchdog_interval_secs.max(1)) }
+
+    pub fn health_interval_for(&self, project: &str) -> Duration {
+        match self.project_health.get(project).and_then(|p| p.interval_secs) {
+            Some(secs) => Duration::from_secs(secs.max(1)),
+            None => self.health_interval(),
+        }
+    }
+
+    pub fn health_timeout_for(&self, project: &str) -> Duration {
+        let ms = self.project_health.get(project).and_then(|p| p.timeout_ms).unwrap_or(self.health_timeout_ms);
+        Duration::from_millis(ms.max(1))
+    }
+
+    /// How often the health monitor wakes: the shortest interval any project asks for.
+    pub fn health_tick(&self) -> Duration {
+        self.project_health.values()
+            .filter_map(|p| p.interval_secs)
+            .map(|secs| Duration::from_secs(secs.max(1)))
+            .fold(self.health_interval(), Duration::min)
+    }
+}
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub general:       GeneralSettings,
+    pub notifications: NotificationConfig,
+    pub watchdog:      WatchdogConfig,
+    pub crash_policy:  CrashPolicy,
+    pub sleep:         SleepPolicy,
+    pub battery:       BatteryPolicy,
+    pub disk_guard:    DiskGuardConfig,
+    pub editor:        EditorConfig,
+    pub licenses:      LicensePolicy,
+}
+
+fn settings_path(app_data_dir: &Path) -> std::path::PathBuf {
+    app_data_dir.join("settings.json")
+}
+
+/// Builds the document from the per-feature files it replaces.
+fn migrate(app_data_dir: &Path) -> Settings {
+    Settings {
+        general:       GeneralSettings::default(),
+        notifications: notifications::load_notification_config(app_data_dir),
+        watchdog:      crate::load_watchdog_config(app_data_dir),
+        crash_policy:  crate::load_crash_policy(app_data_dir),
+        sleep:         crate::load_sleep_policy(app_data_dir),
+        battery:       crate::load_battery_policy(app_data_dir),
+        disk_guard:    crate::load_disk_guard_config(app_data_dir),
+        editor:        crate::load_editor_config(app_data_dir),
+        licenses:      licenses::load_license_policy(app_data_dir),
+    }
+}
+
+/// Fills the sections folded in after `document` was written from their old
+/// files. True if any was missing.
+fn adopt_late_sections(app_data_dir: &Path, document: &serde_json::Value, settings: &mut Settings) -> bool {
+    let mut adopted = false;
+    if document.get("editor").is_none() {
+        settings.editor = crate::load_editor_config(app_data_dir);
+        adopted = true;
+    }
+    if document.get("licenses").is_none() {
+        settings.licenses = licenses::load_license_policy(app_data_dir);
+        adopted = true;
+    }
+    adopted
+}
+
+pub fn load_settings(app_data_dir: &Path) -> Settings {
+    match std::fs::read_to_string(settings_path(app_data_dir)) {
+        Ok(content) => {
+            let Ok(document) = serde_json::from_str::<serde_json::Value>(&content) else { return Settings::default() };
+            let mut settings: Settings = serde_json::from_value(document.clone()).unwrap_or_default();
+            if adopt_late_sections(app_data_dir, &document, &mut settings) {
+                save_settings_to_disk(app_data_dir, &settings);
+            }
+            settings
+        }
+        Err(_) => {
+            let settings = migrate(app_data_dir);
+            save_settings_to_disk(app_data_dir, &settings);
+            settings
+        }
+    }
+}
+
+pub fn save_settings_to_disk(app_data_dir: &Path, settings: &Settings) {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    if let Ok(json) = serde_json::to_string_pretty(settings) {
+        let _ = std::fs::write(settings_path(app_data_dir), json);
+    }
+}
+
+/// The current settings, read from each section's state.
+pub fn snapshot(state: &ServerState) -> Settings {
+    Settings {
+        general:       state.general.lock().unwrap().clone(),
+        notifications: state.notifications.lock().unwrap().clone(),
+        watchdog:      state.watchdog.lock().unwrap().clone(),
+        crash_policy:  state.crash_policy.lock().unwrap().clone(),
+        sleep:         state.sleep_policy.lock().unwrap().clone(),
+        battery:       state.battery_policy.lock().unwrap().clone(),
+        disk_guard:    state.disk_guard.lock().unwrap().clone(),
+        editor:        state.editor.lock().unwrap().clone(),
+        licenses:      state.license_policy.lock().unwrap().clone(),
+    }
+}
+
+pub fn apply(state: &ServerState, settings: Settings) {
+    *state.general.lock().unwrap() = settings.general;
+    *state.notifications.lock().unwrap() = settings.notifications;
+    *state.watchdog.lock().unwrap() = settings.watchdog;
+    *state.crash_policy.lock().unwrap() = settings.crash_policy;
+    *state.sleep_policy.lock().unwrap() = settings.sleep;
+    *state.battery_policy.lock().unwrap() = settings.battery;
+    *state.disk_guard.lock().unwrap() = settings.disk_guard;
+    *state.editor.lock().unwrap() = settings.editor;
+    let mut policy = state.license_policy.lock().unwrap();
+    // Cached reports flag packages by the old list
+    if policy.disallowed != settings.licenses.disallowed { state.license_reports.lock().unwrap().clear(); }
+    *policy = settings.licenses;
+}
+
+/// Writes the current settings to disk and emits `settings-changed`.
+/// Called by every command that changes a section.
+pub fn persist(app: &tauri::AppHandle) -> Result<(), String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let settings = snapshot(&app.state::<ServerState>());
+    save_settings_to_disk(&app_data_dir, &settings);
+    let _ = app.emit("settings-changed", &settings);
+    Ok(())
+}
+
+fn validate(settings: &Settings) -> Result<(), String> {
+    let general = &settings.general;
+    if general.scan_roots.iter().all(|r| r.trim().is_empty()) {
+        return Err("At least one scan root is needed".to_string());
+    }
+    if !Path::new(&general.shell).is_file() {
+        return Err(format!("Shell '{}' doesn't exist", general.shell));
+    }
+    if general.health_interval_secs == 0 || general.tailscale_interval_secs == 0 || general.watchdog_interval_secs == 0 {
+        return Err("Probe intervals must be at least 1 second".to_string());
+    }
+    if general.health_timeout_ms == 0 {
+        return Err("The health probe timeout must be at least 1 ms".to_string());
+    }
+    for (name, probe) in &general.project_health {
+        if probe.interval_secs == Some(0) || probe.timeout_ms == Some(0) {
+            return Err(format!("'{}': probe interval and timeout must be above zero", name));
+        }
+    }
+    Ok(())
+}
+
+/// Objects merge key by key; anything else in `patch` replaces `base`.
+fn merge(base: &mut serde_json::Value, patch: serde_json::Value) {
+    match (base, patch) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge(base.entry(key).or_insert(serde_json::Value::Null), value);
+            }
+        }
+        (base, patch) => *base = patch,
+    }
+}
+
+// ─── Export / Import ──────────────────────────────────────────────────────────
+
+/// Bumped when a section changes shape in a way old bundles can't load into.
+const BUNDLE_VERSION: u32 = 1;
+
+/// Everything a user sets up by hand, in one file to carry to another
+/// machine. Sections left out of a bundle are left alone on import. Secret
+/// env values stay as the keyring placeholder; they have to be set again.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ConfigBundle {
+    version:        u32,
+    exported_at:    u64,
+    #[serde(default)]
+    settings:       Option<Settings>,
+    #[serde(default)]
+    favorites:      Option<Vec<Favorite>>,
+    #[serde(default)]
+    port_overrides: Option<HashMap<String, u16>>,
+    #[serde(default)]
+    env_overrides:  Option<HashMap<String, HashMap<String, String>>>,
+    #[serde(default)]
+    env_profiles:   Option<HashMap<String, EnvProfiles>>,
+    #[serde(default)]
+    tags:           Option<HashMap<String, Vec<String>>>,
+    #[serde(default)]
+    launch_commands: Option<HashMap<String, LaunchCommand>>,
+    #[serde(default)]
+    lifecycle_hooks: Option<HashMap<String, LifecycleHooks>>,
+}
+
+// ─── Tauri Commands ───────────────────────────────────────────────────────────
+
+/// Scan roots are masked in presentation mode; they're paths.
+#[tauri::command]
+pub fn get_settings(app: tauri::AppHandle) -> Settings {
+    let mut settings = snapshot(&app.state::<ServerState>());
+    if presenting(&app) {
+        for root in &mut settings.general.scan_roots { *root = REDACTED.to_string(); }
+    }
+    settings
+}
+
+/// Applies a partial document, e.g. `{ "general": { "shell": "/bin/bash" } }`,
+/// and returns the result. Changed scan roots take effect on the next scan,
+/// a changed shell on each server's next start.
+#[tauri::command]
+pub fn update_settings(app: tauri::AppHandle, patch: serde_json::Value) -> Result<Settings, String> {
+    ensure_not_presenting(&app)?;
+    let state = app.state::<ServerState>();
+    let mut document = serde_json::to_value(snapshot(&state)).map_err(|e| e.to_string())?;
+    merge(&mut document, patch);
+    let settings: Settings = serde_json::from_value(document).map_err(|e| format!("Invalid settings: {}", e))?;
+    validate(&settings)?;
+    apply(&state, settings.clone());
+    persist(&app)?;
+    Ok(settings)
+}
+
+/// Sets or, with None, clears the browser `name` opens in.
+#[tauri::command]
+pub fn set_project_browser(app: tauri::AppHandle, name: String, choice: Option<BrowserChoice>) -> Result<(), String> {
+    ensure_not_presenting(&app)?;
+    {
+        let state = app.state::<ServerState>();
+        let mut general = state.general.lock().unwrap();
+        match choice {
+            Some(choice) => { general.project_browsers.insert(name, choice); }
+            None => { general.project_browsers.remove(&name); }
+        }
+    }
+    persist(&app)
+}
+
+/// Sets or, with None, clears `name`'s own health probe interval and timeout.
+#[tauri::command]
+pub fn set_project_health_probe(app: tauri::AppHandle, name: String, probe: Option<HealthProbeSettings>) -> Result<(), String> {
+    ensure_not_presenting(&app)?;
+    if probe.as_ref().is_some_and(|p| p.interval_secs == Some(0) || p.timeout_ms == Some(0)) {
+        return Err("Probe interval and timeout must be above zero".to_string());
+    }
+    {
+        let state = app.state::<ServerState>();
+        let mut general = state.general.lock().unwrap();
+        match probe {
+            Some(probe) => { general.project_health.insert(name, probe); }
+            None => { general.project_health.remove(&name); }
+        }
+    }
+    persist(&app)
+}
+
+/// Project IDs hash the local folder path, so ID-keyed sections travel
+/// under project names; the scan after an import keys them by ID again.
+fn keyed_by_name<V>(map: HashMap<String, V>, names: &HashMap<String, String>) -> HashMap<String, V> {
+    map.into_iter().map(|(key, v)| (names.get(&key).cloned().unwrap_or(key), v)).collect()
+}
+
+/// The bundle to save; the UI picks where.
+#[tauri::command]
+pub fn export_config(app: tauri::AppHandle) -> Result<ConfigBundle, String> {
+    ensure_not_presenting(&app)?;
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let state = app.state::<ServerState>();
+    let names: HashMap<String, String> = state.projects.lock().unwrap().iter()
+        .map(|p| (p.id.clone(), p.name.clone()))
+        .collect();
+    Ok(ConfigBundle {
+        version:        BUNDLE_VERSION,
+        exported_at:    crate::unix_now(),
+        settings:       Some(snapshot(&state)),
+        favorites:      Some(crate::load_favorite_entries(&app_data_dir)),
+        port_overrides: Some(keyed_by_name(crate::load_port_overrides(&app_data_dir), &names)),
+        env_overrides:  Some(keyed_by_name(state.env_overrides.lock().unwrap().clone(), &names)),
+        env_profiles:   Some(state.env_profiles.lock().unwrap().clone()),
+        tags:           Some(crate::load_tags(&app_data_dir)),
+        launch_commands: Some(state.launch_commands.lock().unwrap().clone()),
+        lifecycle_hooks: Some(state.lifecycle_hooks.lock().unwrap().clone()),
+    })
+}
+
+/// Writes each section in the bundle over the local file, then reloads as
+/// `reload_config` does, so running servers keep going and the report says
+/// which need a restart.
+#[tauri::command]
+pub fn import_config(app: tauri::AppHandle, bundle: ConfigBundle) -> Result<ConfigReloadReport, String> {
+    ensure_not_presenting(&app)?;
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!("This bundle is from a newer DexHub (format {}); update first", bundle.version));
+    }
+    if let Some(settings) = &bundle.settings { validate(settings)?; }
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    if let Some(settings) = &bundle.settings { save_settings_to_disk(&app_data_dir, settings); }
+    if let Some(favorites) = &bundle.favorites { crate::save_favorite_entries_to_disk(&app_data_dir, favorites); }
+    if let Some(overrides) = &bundle.port_overrides { crate::save_port_overrides(&app_data_dir, overrides); }
+    if let Some(overrides) = &bundle.env_overrides { crate::save_env_overrides_to_disk(&app_data_dir, overrides); }
+    if let Some(profiles) = &bundle.env_profiles { crate::save_env_profiles_to_disk(&app_data_dir, profiles); }
+    if let Some(tags) = &bundle.tags { crate::save_tags_to_disk(&app_data_dir, tags); }
+    if let Some(commands) = &bundle.launch_commands { crate::save_launch_commands_to_disk(&app_data_dir, commands); }
+    if let Some(hooks) = &bundle.lifecycle_hooks { crate::save_lifecycle_hooks_to_disk(&app_data_dir, hooks); }
+    let report = crate::reload_config(&app)?;
+    crate::record_event(&app, "config-imported", None, serde_json::json!({ "exported_at": bundle.exported_at }));
+    Ok(report)
+}
@@ -0,0 +1,102 @@
+//! In-process command counters: how often each Tauri command is invoked and
+//! how long it takes. Nothing leaves the machine; the numbers are only
+//! surfaced through `get_diagnostics` so regressions can be measured.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+use crate::ServerState;
+
+#[derive(Clone, Default, serde::Serialize)]
+pub struct CommandStats {
+    pub calls:    u64,
+    pub total_ms: f64,
+    pub avg_ms:   f64,
+    pub max_ms:   f64,
+    pub last_ms:  f64,
+}
+
+#[derive(Default)]
+pub struct CommandTelemetry {
+    stats: Mutex<HashMap<String, CommandStats>>,
+}
+
+impl CommandTelemetry {
+    pub fn record(&self, command: &str, elapsed: Duration) {
+        let ms = elapsed.as_secs_f64() * 1000.0;
+        let mut stats = self.stats.lock().unwrap();
+        let entry = stats.entry(command.to_string()).or_default();
+        entry.calls += 1;
+        entry.total_ms += ms;
+        entry.avg_ms = entry.total_ms / entry.calls as f64;
+        entry.max_ms = entry.max_ms.max(ms);
+        entry.last_ms = ms;
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<String, CommandStats> {
+        self.stats.lock().unwrap().iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+
+    pub fn reset(&self) {
+        self.stats.lock().unwrap().clear();
+    }
+}
+
+/// Wraps the generated invoke handler so every call is timed. Commands here
+/// are synchronous, so the handler returns only after the command has run.
+pub fn timed_handler<F>(handler: F) -> impl Fn(tauri::ipc::Invoke<tauri::Wry>) -> bool + Send + Sync + 'static
+where
+    F: Fn(tauri::ipc::Invoke<tauri::Wry>) -> bool + Send + Sync + 'static,
+{
+    move |invoke| {
+        let command = invoke.message.command().to_string();
+        let app = invoke.message.webview().app_handle().clone();
+        let start = Instant::now();
+        let handled = handler(invoke);
+        if let Some(telemetry) = app.try_state::<CommandTelemetry>() {
+            telemetry.record(&command, start.elapsed());
+        }
+        handled
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct Diagnostics {
+    pub version:       String,
+    pub os:            String,
+    pub arch:          String,
+    pub uptime_secs:   u64,
+    pub project_count: usize,
+    pub running_count: usize,
+    pub safe_mode:     bool,
+    pub commands:      BTreeMap<String, CommandStats>, // command name → timings
+}
+
+/// When the process started, for `uptime_secs`.
+pub struct LaunchTime(pub Instant);
+
+// ─── Tauri Commands ───────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn get_diagnostics(app: tauri::AppHandle) -> Diagnostics {
+    let state = app.state::<ServerState>();
+    let project_count = state.projects.lock().unwrap().len();
+    let running_count = state.processes.lock().unwrap().len();
+    Diagnostics {
+        version: app.package_info().version.to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        uptime_secs: app.try_state::<LaunchTime>().map(|t| t.0.elapsed().as_secs()).unwrap_or(0),
+        project_count,
+        running_count,
+        safe_mode: state.safe_mode,
+        commands: app.state::<CommandTelemetry>().snapshot(),
+    }
+}
+
+#[tauri::command]
+pub fn reset_command_stats(telemetry: tauri::State<'_, CommandTelemetry>) {
+    telemetry.reset();
+}
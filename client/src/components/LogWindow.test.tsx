@@ -0,0 +1,33 @@
+import { describe, it, expect, vi, beforeEach } from 'vitest';
+import { invoke } from '@tauri-apps/api/core';
+import { render, screen } from '@testing-library/react';
+import { LogWindow } from './LogWindow';
+
+const mockInvoke = vi.mocked(invoke);
+
+beforeEach(() => {
+  vi.clearAllMocks();
+});
+
+describe('LogWindow', () => {
+  it('shows the server name and its log lines', async () => {
+    mockInvoke.mockResolvedValue(['ready on :5173', '[err] something broke']);
+    render(<LogWindow name="web" />);
+    expect(screen.getByText('web — logs')).toBeInTheDocument();
+    expect(await screen.findByText('ready on :5173')).toBeInTheDocument();
+    expect(mockInvoke).toHaveBeenCalledWith('get_server_logs', { name: 'web' });
+  });
+
+  it('highlights stderr lines', async () => {
+    mockInvoke.mockResolvedValue(['[err] something broke']);
+    render(<LogWindow name="web" />);
+    const line = await screen.findByText('[err] something broke');
+    expect(line.className).toContain('text-red-400');
+  });
+
+  it('shows a placeholder when there is no output', async () => {
+    mockInvoke.mockResolvedValue([]);
+    render(<LogWindow name="web" />);
+    expect(await screen.findByText('No logs yet.')).toBeInTheDocument();
+  });
+});
@@ -0,0 +1,54 @@
+import { useEffect, useRef, useState } from 'react';
+import clsx from 'clsx';
+import { getServerLogs } from '../lib/servers';
+
+interface Props {
+  name: string;
+}
+
+/** Standalone log viewer rendered in the per-server window opened from the tray. */
+export function LogWindow({ name }: Props) {
+  const [logs,   setLogs]   = useState<string[]>([]);
+  const [follow, setFollow] = useState(true);
+  const logsRef = useRef<HTMLDivElement>(null);
+
+  useEffect(() => {
+    const load = () => getServerLogs(name).then(setLogs).catch(() => {});
+    load();
+    const interval = setInterval(load, 1000);
+    return () => clearInterval(interval);
+  }, [name]);
+
+  // Stick to the bottom unless the user scrolled up to read something
+  useEffect(() => {
+    if (follow && logsRef.current) logsRef.current.scrollTop = logsRef.current.scrollHeight;
+  }, [logs, follow]);
+
+  const handleScroll = () => {
+    const el = logsRef.current;
+    if (!el) return;
+    setFollow(el.scrollHeight - el.scrollTop - el.clientHeight < 24);
+  };
+
+  return (
+    <div className="flex flex-col h-screen text-white font-sans bg-black/80">
+      <div className="flex items-center justify-between px-4 py-2 border-b border-white/5">
+        <h1 className="text-sm font-semibold">{name} — logs</h1>
+        <span className="text-[10px] text-gray-500">{logs.length} lines{follow ? ' · following' : ''}</span>
+      </div>
+      <div
+        ref={logsRef}
+        onScroll={handleScroll}
+        className="log-drawer flex-1 m-3 font-mono text-[11px] leading-relaxed overflow-y-auto custom-scrollbar"
+      >
+        {logs.length === 0
+          ? <span className="text-gray-600">No logs yet.</span>
+          : logs.map((l, i) => (
+              <div key={i} className={clsx('log-line', l.startsWith('[err]') && 'text-red-400/80')}>
+                {l}
+              </div>
+            ))}
+      </div>
+    </div>
+  );
+}
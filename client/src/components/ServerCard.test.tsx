@@ -8,6 +8,7 @@ import type { ProjectConfig } from '../lib/servers';
 const mockInvoke = vi.mocked(invoke);
 
 const baseProject: ProjectConfig = {
+  id: '3f2a9c1b7e4d',
   name: 'my-app',
   cwd: '/Users/andrew/Projects/my-app',
   command: 'npm',
@@ -17,7 +18,13 @@ const baseProject: ProjectConfig = {
   extra_ports: [],
   icon_path: null,
   icon_data: null,
+  accent_color: null,
   workspace: 'Root',
+  git: null,
+  compose_file: null,
+  deps: 'installed',
+  package_manager: 'npm',
+  open_path: null,
 };
 
 const defaultProps = {
@@ -190,10 +197,16 @@ describe('ServerCard', () => {
 
   describe('extra ports', () => {
     it('renders extra port badge for each extra port', () => {
-      const project = { ...baseProject, extra_ports: [3000] };
+      const project = { ...baseProject, extra_ports: [{ port: 3000, label: null }] };
       render(<ServerCard {...defaultProps} project={project} />);
       expect(screen.getByText(':3000')).toBeInTheDocument();
     });
+
+    it('shows the label of a labeled extra port', () => {
+      const project = { ...baseProject, extra_ports: [{ port: 6006, label: 'Storybook' }] };
+      render(<ServerCard {...defaultProps} project={project} />);
+      expect(screen.getByText('Storybook :6006')).toBeInTheDocument();
+    });
   });
 
   describe('interactions', () => {
@@ -447,7 +447,16 @@ export function ServerCard({
         </div>
         <div className="flex-1 min-w-0">
           <p className="text-sm font-semibold truncate leading-tight tracking-tight">{project.name}</p>
-          <p className="text-[10px] text-gray-600 truncate">{project.workspace}</p>
+          <p className="text-[10px] text-gray-600 truncate">
+            {project.workspace}
+            {project.git?.branch && (
+              <span className="font-mono" title={project.git.last_commit ?? undefined}>
+                {' · '}{project.git.branch}{project.git.dirty && '*'}
+                {project.git.ahead > 0 && ` ↑${project.git.ahead}`}
+                {project.git.behind > 0 && ` ↓${project.git.behind}`}
+              </span>
+            )}
+          </p>
         </div>
         <div className="flex items-center gap-1.5 flex-shrink-0">
           {/* Uptime badge */}
@@ -510,8 +519,8 @@ export function ServerCard({
         )}
         {/* Extra ports */}
         {project.extra_ports?.map(ep => (
-          <span key={ep} className="text-[10px] font-mono text-gray-600 border border-white/8 rounded px-1.5 py-0.5">
-            :{ep}
+          <span key={ep.port} className="text-[10px] font-mono text-gray-600 border border-white/8 rounded px-1.5 py-0.5">
+            {ep.label ? `${ep.label} :${ep.port}` : `:${ep.port}`}
           </span>
         ))}
         {running && health === 'starting' && (
@@ -7,6 +7,7 @@ import type { ProjectConfig } from '../lib/servers';
 const mockInvoke = vi.mocked(invoke);
 
 const makeProject = (overrides: Partial<ProjectConfig> = {}): ProjectConfig => ({
+  id: '8c1e5a0f2b9d',
   name: 'test-app',
   cwd: '/Users/andrew/Projects/test-app',
   command: 'npm',
@@ -16,7 +17,13 @@ const makeProject = (overrides: Partial<ProjectConfig> = {}): ProjectConfig => (
   extra_ports: [],
   icon_path: null,
   icon_data: null,
+  accent_color: null,
   workspace: 'Root',
+  git: null,
+  compose_file: null,
+  deps: 'installed',
+  package_manager: 'npm',
+  open_path: null,
   ...overrides,
 });
 
@@ -218,6 +225,40 @@ describe('ServersView', () => {
     });
   });
 
+  describe('start failures', () => {
+    it('shows why a server did not start', async () => {
+      setupDefaultMocks([makeProject({ name: 'web' })]);
+      const defaults = mockInvoke.getMockImplementation()!;
+      mockInvoke.mockImplementation((cmd: string, args?: unknown) => cmd === 'start_server_cmd'
+        ? Promise.reject({ kind: 'command_not_found', message: "'pnpm' isn't installed or isn't on /bin/zsh's PATH" })
+        : defaults(cmd, args as never));
+      render(<ServersView />);
+      await flushAll();
+      await waitFor(() => { expect(screen.getByText('Start')).toBeInTheDocument(); });
+      fireEvent.click(screen.getByText('Start'));
+      await waitFor(() => {
+        expect(screen.getByText("web didn't start: 'pnpm' isn't installed or isn't on /bin/zsh's PATH")).toBeInTheDocument();
+      });
+    });
+
+    it('shows why a restart failed', async () => {
+      setupDefaultMocks([makeProject({ name: 'web' })]);
+      const defaults = mockInvoke.getMockImplementation()!;
+      mockInvoke.mockImplementation((cmd: string, args?: unknown) => {
+        if (cmd === 'get_running_servers') return Promise.resolve(['web']);
+        if (cmd === 'restart_server_cmd') return Promise.reject({ kind: 'port_in_use', message: 'Port 3000 is still in use' });
+        return defaults(cmd, args as never);
+      });
+      render(<ServersView />);
+      await flushAll();
+      await waitFor(() => { expect(screen.getByTitle('Restart server')).toBeInTheDocument(); });
+      fireEvent.click(screen.getByTitle('Restart server'));
+      await waitFor(() => {
+        expect(screen.getByText("web didn't start: Port 3000 is still in use")).toBeInTheDocument();
+      });
+    });
+  });
+
   describe('running count', () => {
     it('shows running count in tailscale bar', async () => {
       mockInvoke.mockImplementation((cmd: string) => {
@@ -7,6 +7,7 @@ import { QRCodeSVG } from 'qrcode.react';
 import { ServerCard, type HealthStatus } from './ServerCard';
 import {
   type ProjectConfig,
+  type StartError,
   listProjects,
   getRunningServers,
   stopAllServers,
@@ -76,6 +77,7 @@ export function ServersView({ onRunningCountChange }: Props) {
 
   // ── Undo stop-all (UX #5) ─────────────────────────────────────────────────
   const [undoPayload, setUndoPayload] = useState<string[] | null>(null);
+  const [startError, setStartError] = useState<{ name: string; message: string } | null>(null);
   const undoTimer = useRef<ReturnType<typeof setTimeout> | null>(null);
 
   // ── Keyboard navigation (UX #10 / Feature #7) ─────────────────────────────
@@ -225,7 +227,11 @@ export function ServersView({ onRunningCountChange }: Props) {
     userStartedRef.current.add(name);
     setRunning(prev => new Set([...prev, name]));
     setHealth(prev => ({ ...prev, [name]: 'starting' }));
-    startServer(name).catch(() => {});
+    startServer(name).catch((e: StartError) => {
+      setRunning(prev => { const n = new Set(prev); n.delete(name); return n; });
+      setHealth(prev => ({ ...prev, [name]: 'down' }));
+      setStartError({ name, message: e?.message ?? String(e) });
+    });
   }
 
   function handleStop(name: string) {
@@ -236,7 +242,12 @@ export function ServersView({ onRunningCountChange }: Props) {
 
   function handleRestart(name: string) {
     setHealth(prev => ({ ...prev, [name]: 'starting' }));
-    restartServer(name).catch(() => {});
+    restartServer(name).catch((e: StartError) => {
+      // The old instance may be gone even though the new one never came up
+      getRunningServers().then(list => setRunning(new Set(list))).catch(() => {});
+      setHealth(prev => ({ ...prev, [name]: 'down' }));
+      setStartError({ name, message: e?.message ?? String(e) });
+    });
   }
 
   function handleToggleFavorite(name: string) {
@@ -436,6 +447,20 @@ export function ServersView({ onRunningCountChange }: Props) {
       </div>
 
       {/* ── Undo toast (UX #5) ── */}
+      {startError && (
+        <div className="mx-3 mt-2 flex items-center justify-between gap-2 px-3 py-2 rounded-lg bg-gray-900 border border-red-500/30 text-xs animate-slide-up flex-shrink-0 relative z-10">
+          <span className="text-red-300 truncate" title={startError.message}>
+            {startError.name} didn't start: {startError.message}
+          </span>
+          <button
+            onClick={() => setStartError(null)}
+            className="text-gray-400 hover:text-white font-medium transition-colors"
+          >
+            Dismiss
+          </button>
+        </div>
+      )}
+
       {undoPayload && (
         <div className="mx-3 mt-2 flex items-center justify-between gap-2 px-3 py-2 rounded-lg bg-gray-900 border border-white/10 text-xs animate-slide-up flex-shrink-0 relative z-10">
           <span className="text-gray-400">Stopped {undoPayload.length} server{undoPayload.length !== 1 ? 's' : ''}</span>
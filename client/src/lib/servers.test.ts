@@ -60,6 +60,12 @@ describe('servers.ts — invoke wrappers', () => {
       await startServer('my-app');
       expect(mockInvoke).toHaveBeenCalledWith('start_server_cmd', { name: 'my-app' });
     });
+
+    it('passes the env profile when given', async () => {
+      mockInvoke.mockResolvedValue(undefined);
+      await startServer('my-app', 'staging-api');
+      expect(mockInvoke).toHaveBeenCalledWith('start_server_cmd', { name: 'my-app', profile: 'staging-api' });
+    });
   });
 
   describe('stopServer', () => {
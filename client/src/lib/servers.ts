@@ -1,16 +1,37 @@
 import { invoke } from '@tauri-apps/api/core';
 
 export interface ProjectConfig {
+  id: string;                  // hashed from cwd; settings follow it across package renames
   name: string;
   cwd: string;
   command: string;
   args: string[];
   port: number;
   default_port: number;        // original port before user override
-  extra_ports: number[];       // additional ports from dexhub.ports in package.json
+  extra_ports: ExtraPort[];    // additional ports from dexhub.ports in package.json
   icon_path: string | null;
   icon_data: string | null;    // data:image/png;base64,… for webview display
+  accent_color: string | null; // dominant icon colour as #rrggbb
   workspace: string;           // parent dir name for grouping
+  git: GitInfo | null;         // null outside a git work tree
+  compose_file: string | null; // compose.yaml / docker-compose.yml in the project root
+  deps: 'installed' | 'missing' | 'stale'; // stale: lockfile newer than the last install
+  package_manager: 'npm' | 'pnpm' | 'yarn' | 'bun' | null; // from packageManager or the lockfile; null for plugin projects
+  open_path: string | null;    // dexhub.openPath, e.g. "/admin"; appended by getServerUrl and Open in Browser
+}
+
+/** dexhub.ports entries: a bare number, or { port, label } e.g. Storybook on 6006 */
+export interface ExtraPort {
+  port: number;
+  label: string | null;
+}
+
+export interface GitInfo {
+  branch: string | null;       // null when HEAD is detached
+  dirty: boolean;
+  ahead: number;
+  behind: number;              // both 0 without an upstream
+  last_commit: string | null;  // "<short sha> <subject>"
 }
 
 // ─── Core project/server commands ────────────────────────────────────────────
@@ -18,56 +39,936 @@ export interface ProjectConfig {
 export const listProjects = (): Promise<ProjectConfig[]> =>
   invoke('list_projects');
 
+/** Re-reads branch/dirty/ahead-behind for one project without a rescan */
+export const refreshGitInfo = (name: string): Promise<GitInfo | null> =>
+  invoke('refresh_git_info', { name });
+
+/** Fetches all remotes; output streams into the project's logs */
+export const gitFetch = (name: string): Promise<GitInfo | null> =>
+  invoke('git_fetch', { name });
+
+/** Fast-forward-only pull; output streams into the project's logs */
+export const gitPull = (name: string): Promise<GitInfo | null> =>
+  invoke('git_pull', { name });
+
+export const getPullBeforeStart = (name: string): Promise<boolean> =>
+  invoke('get_pull_before_start', { name });
+
+export const setPullBeforeStart = (name: string, enabled: boolean): Promise<void> =>
+  invoke('set_pull_before_start', { name, enabled });
+
 export const getRunningServers = (): Promise<string[]> =>
   invoke('get_running_servers');
 
-export const startServer = (name: string): Promise<void> =>
-  invoke('start_server_cmd', { name });
+export type StartErrorKind =
+  | 'not_found' | 'missing_directory' | 'command_not_found'
+  | 'port_in_use' | 'already_starting' | 'hook_failed' | 'spawn_failed' | 'other';
+
+/** What startServer rejects with */
+export interface StartError {
+  kind: StartErrorKind;
+  message: string;
+}
+
+/** With `profile`, that env profile becomes the active one before starting.
+ *  Rejects with a StartError. */
+export const startServer = (name: string, profile?: string): Promise<void> =>
+  invoke('start_server_cmd', profile ? { name, profile } : { name });
 
 export const stopServer = (name: string): Promise<void> =>
   invoke('stop_server_cmd', { name });
 
-export const restartServer = (name: string): Promise<void> =>
+/** Milliseconds per phase; rejects with the phase that failed */
+export interface RestartReport {
+  port: number;
+  stop_ms: number;             // until the old instance released its port
+  start_ms: number;
+  ready_ms: number;            // until the new instance accepted a connection
+  total_ms: number;
+}
+
+/** Resolves once the new instance accepts connections; rejects with a StartError */
+export const restartServer = (name: string): Promise<RestartReport> =>
   invoke('restart_server_cmd', { name });
 
+export interface ScheduledScanConfig {
+  enabled: boolean;
+  hour: number;              // local hour, 0–23 (default 3)
+  last_run: number;          // unix seconds; ignored when saving
+}
+
+export interface ScanChanges {
+  added: string[];
+  changed: string[];         // dev command differs from the last scheduled scan
+}
+
+/** Nightly rescan that notifies about new projects and changed dev commands */
+export const getScheduledScanConfig = (): Promise<ScheduledScanConfig> =>
+  invoke('get_scheduled_scan_config');
+
+export const setScheduledScanConfig = (config: ScheduledScanConfig): Promise<void> =>
+  invoke('set_scheduled_scan_config', { config });
+
+export const runScheduledScanNow = (): Promise<ScanChanges> =>
+  invoke('run_scheduled_scan_now');
+
+export interface ServerSchedule {
+  enabled: boolean;
+  days: number[];            // ISO weekdays, 1 = Monday … 7 = Sunday
+  start: string | null;      // "HH:MM" local time
+  stop: string | null;
+}
+
+/** Per-project start/stop times, keyed by project name */
+export const getServerSchedules = (): Promise<Record<string, ServerSchedule>> =>
+  invoke('get_server_schedules');
+
+/** Pass null to remove the project's schedule */
+export const setServerSchedule = (name: string, schedule: ServerSchedule | null): Promise<void> =>
+  invoke('set_server_schedule', { name, schedule });
+
+export interface DiskGuardConfig {
+  enabled: boolean;
+  min_free_mb: number;       // default 2048
+  refuse_below: boolean;     // refuse to start instead of warning
+}
+
+/** Free-space check before starting Next/Turbo/Vite servers */
+export const getDiskGuardConfig = (): Promise<DiskGuardConfig> =>
+  invoke('get_disk_guard_config');
+
+export const setDiskGuardConfig = (config: DiskGuardConfig): Promise<void> =>
+  invoke('set_disk_guard_config', { config });
+
+/** Free MB on the project's volume; null when unknown */
+export const getFreeDiskSpace = (name: string): Promise<number | null> =>
+  invoke('get_free_disk_space', { name });
+
+export interface CanaryConfig {
+  enabled: boolean;
+  port_flag: string | null;      // e.g. "--port"; PORT env is always set
+  ready_timeout_secs: number;    // default 60
+}
+
+/** Blue-green restart: new instance on a free port, switch URLs, stop the old. Resolves to the new port */
+export const canaryRestart = (name: string): Promise<number> =>
+  invoke('canary_restart_cmd', { name });
+
+export const getCanaryConfig = (name: string): Promise<CanaryConfig> =>
+  invoke('get_canary_config', { name });
+
+export const setCanaryConfig = (name: string, config: CanaryConfig): Promise<void> =>
+  invoke('set_canary_config', { name, config });
+
+/** Restart after deleting .vite, node_modules/.vite and node_modules/.cache */
+export const restartServerClean = (name: string): Promise<void> =>
+  invoke('restart_server_clean_cmd', { name });
+
 export const stopAllServers = (): Promise<void> =>
   invoke('stop_all_servers_cmd');
 
+// ─── Homebrew services ───────────────────────────────────────────────────────
+
+export interface BrewService {
+  name: string;
+  status: string;            // "started" | "stopped" | "none" | "error" | "scheduled" | …
+  user: string | null;
+  exit_code: number | null;
+}
+
+/** `brew services list`; empty when Homebrew isn't installed */
+export const listBrewServices = (): Promise<BrewService[]> =>
+  invoke('list_brew_services');
+
+export const brewServiceAction = (name: string, action: 'start' | 'stop' | 'restart'): Promise<void> =>
+  invoke('brew_service_action', { name, action });
+
+// ─── Docker containers ───────────────────────────────────────────────────────
+
+export interface DockerContainer {
+  id: string;                // short id
+  name: string;
+  image: string;
+  state: string;             // "running" | "exited" | "paused" | "created" | …
+  status: string;            // docker's "Up 3 hours" / "Exited (0) 2 days ago"
+  ports: number[];           // published host ports
+  compose_project: string | null;
+}
+
+/** `docker ps --all`; empty when Docker isn't installed or not running */
+export const listDockerContainers = (): Promise<DockerContainer[]> =>
+  invoke('list_docker_containers');
+
+export const dockerContainerAction = (id: string, action: 'start' | 'stop' | 'restart'): Promise<void> =>
+  invoke('docker_container_action', { id, action });
+
+// ─── Docker compose ──────────────────────────────────────────────────────────
+
+/** `docker compose up`, attached so its output is captured like a server's */
+export const startComposeServices = (name: string): Promise<void> =>
+  invoke('start_compose_services', { name });
+
+export const stopComposeServices = (name: string): Promise<void> =>
+  invoke('stop_compose_services', { name });
+
+/** Projects whose compose services are up */
+export const getComposeRunning = (): Promise<string[]> =>
+  invoke('get_compose_running');
+
+export const getComposeLogs = (name: string): Promise<string[]> =>
+  invoke('get_compose_logs', { name });
+
+// ─── Production preview ──────────────────────────────────────────────────────
+
+export interface BuildPreview {
+  name: string;
+  status: 'building' | 'serving' | 'failed';
+  port: number;                // its own port, next to the dev server's
+  url: string;
+  served_by: 'script' | 'static' | null; // the project's `preview` script, or DexHub serving dist/
+}
+
+/** Runs the build script, then serves the output; progress arrives as `preview-changed` */
+export const startBuildPreview = (name: string): Promise<BuildPreview> =>
+  invoke('start_build_preview', { name });
+
+/** Resolves false if there was no preview */
+export const stopBuildPreview = (name: string): Promise<boolean> =>
+  invoke('stop_build_preview', { name });
+
+export const getBuildPreviews = (): Promise<BuildPreview[]> =>
+  invoke('get_build_previews');
+
+export const getBuildPreviewLogs = (name: string): Promise<string[]> =>
+  invoke('get_build_preview_logs', { name });
+
+// ─── Package scripts ─────────────────────────────────────────────────────────
+
+export interface ScriptRun {
+  script: string;
+  running: boolean;
+  passed: boolean | null;      // null while running; from the exit code
+  exit_code: number | null;
+  summary: string | null;      // vitest/jest tally, e.g. "2 failed, 40 passed"
+  started_at: number;          // unix secs
+}
+
+export interface ProjectStats {
+  name: string;
+  size_bytes: number;          // whole folder, node_modules included
+  source_modified: number | null; // newest file outside node_modules/.git/build output, unix secs
+  last_started: number | null; // from run history, unix secs
+}
+
+/** For "recently active" sorting; folder figures are cached for 10 minutes unless refresh is set */
+export const getProjectStats = (refresh = false): Promise<ProjectStats[]> =>
+  invoke('get_project_stats', { refresh });
+
+export interface KeyDependency {
+  package: string;
+  label: string;               // "React 19"; the bare name when no version is known
+  declared: string;            // range from package.json
+  installed: string | null;    // from node_modules
+  dev: boolean;
+}
+
+export interface DependencySummary {
+  framework: string | null;    // "Next.js", "React", …
+  typescript: boolean;
+  key: KeyDependency[];        // frameworks first, then tools
+  scripts: Record<string, string>;
+  dependencies: number;
+  dev_dependencies: number;
+}
+
+export const getProjectDependencies = (name: string): Promise<DependencySummary> =>
+  invoke('get_project_dependencies', { name });
+
+/** `<package manager> install`, streamed into the project's logs; resolves to the status afterwards */
+export const installDependencies = (name: string): Promise<ProjectConfig['deps']> =>
+  invoke('install_dependencies', { name });
+
+/** Runs a package.json script (e.g. "test") in the background; progress arrives as `script-run` */
+export const runScript = (name: string, script: string): Promise<void> =>
+  invoke('run_script', { name, script });
+
+export const getScriptRun = (name: string): Promise<ScriptRun | null> =>
+  invoke('get_script_run', { name });
+
+export const getScriptLogs = (name: string, script: string): Promise<string[]> =>
+  invoke('get_script_logs', { name, script });
+
+// ─── Bulk actions ────────────────────────────────────────────────────────────
+
+export type BulkAction =
+  | { type: 'start' }
+  | { type: 'stop' }
+  | { type: 'restart' }
+  | { type: 'tag'; tag: string }
+  | { type: 'untag'; tag: string }
+  | { type: 'favorite' }
+  | { type: 'unfavorite' };
+
+export interface BulkItemResult {
+  name: string;
+  ok: boolean;
+  error: string | null;
+}
+
+/** Apply one action to many projects with a single tray rebuild; results sorted by name */
+export const bulkAction = (names: string[], action: BulkAction): Promise<BulkItemResult[]> =>
+  invoke('bulk_action_cmd', { names, action });
+
 export const updateServerPort = (name: string, port: number): Promise<void> =>
   invoke('update_server_port', { name, port });
 
+export interface PortHistory {
+  declared: number[];
+  overridden: number[];
+  observed: number[];          // ports the server was actually launched on
+  assigned: number | null;     // set by DexHub to resolve a collision
+}
+
+export interface PortConflict {
+  port: number;
+  projects: string[];
+  kept_by: string;
+  assigned: Record<string, number>;
+}
+
+/** Every port each project has ever used */
+export const getPortRegistry = (): Promise<Record<string, PortHistory>> =>
+  invoke('get_port_registry');
+
+/** Projects declaring the same default port, and the ports assigned at scan time */
+export const getPortConflicts = (): Promise<PortConflict[]> =>
+  invoke('get_port_conflicts');
+
 export const openTerminalHere = (name: string): Promise<void> =>
   invoke('open_terminal_here', { name });
 
-export const getServerUrl = (name: string): Promise<string> =>
-  invoke('get_server_url', { name });
+export interface EditorInfo {
+  id: string;                // "vscode" | "cursor" | "zed" | "webstorm" | "idea" | "pycharm"
+  label: string;
+  installed: boolean;
+}
+
+/** Configured editor, or the first installed one */
+export const openInEditor = (name: string): Promise<void> =>
+  invoke('open_in_editor', { name });
+
+export const listEditors = (): Promise<EditorInfo[]> =>
+  invoke('list_editors');
+
+export const getEditorConfig = (): Promise<{ editor: string | null }> =>
+  invoke('get_editor_config');
+
+export const setEditorConfig = (config: { editor: string | null }): Promise<void> =>
+  invoke('set_editor_config', { config });
+
+/** Finder on macOS, Explorer on Windows, the default file manager on Linux */
+export const revealProjectFolder = (name: string): Promise<void> =>
+  invoke('reveal_project_folder', { name });
+
+/** With a label, the URL of the extra port carrying it */
+export const getServerUrl = (name: string, label?: string): Promise<string> =>
+  invoke('get_server_url', { name, ...(label && { label }) });
 
 export const checkServerHealth = (name: string): Promise<boolean> =>
   invoke('check_server_health', { name });
 
+// ─── GitHub status ───────────────────────────────────────────────────────────
+
+export interface GithubPullRequest {
+  number: number;
+  title: string;
+  url: string;
+  draft: boolean;
+}
+
+export interface GithubStatus {
+  repo: string;              // owner/name, from the origin remote
+  branch: string;
+  pr: GithubPullRequest | null; // open PR for the branch, if any
+  checks: 'success' | 'failure' | 'pending' | 'none';
+  failing: string[];         // names of failed check runs
+}
+
+/** PR and CI status for the current branch; null if not a GitHub checkout. Cached for a minute unless `refresh` */
+export const getGithubStatus = (name: string, refresh = false): Promise<GithubStatus | null> =>
+  invoke('get_github_status', { name, refresh });
+
+export const hasGithubToken = (): Promise<boolean> =>
+  invoke('has_github_token');
+
+/** Stores the token in the system keyring; null removes it */
+export const setGithubToken = (token: string | null): Promise<void> =>
+  invoke('set_github_token', { token });
+
 // ─── Health & diagnostics ────────────────────────────────────────────────────
 
-/** Last measured TCP connect latency in ms (populated by checkServerHealth) */
+export interface CommandStats {
+  calls: number;
+  total_ms: number;
+  avg_ms: number;
+  max_ms: number;
+  last_ms: number;
+}
+
+export interface Diagnostics {
+  version: string;
+  os: string;
+  arch: string;
+  uptime_secs: number;
+  project_count: number;
+  running_count: number;
+  safe_mode: boolean;        // launched with --safe-mode / Shift held
+  commands: Record<string, CommandStats>; // per Tauri command, since launch or last reset
+}
+
+export const getDiagnostics = (): Promise<Diagnostics> =>
+  invoke('get_diagnostics');
+
+export const resetCommandStats = (): Promise<void> =>
+  invoke('reset_command_stats');
+
+/** True during the post-start warm-up window, when failed checks don't alert */
+export const isHealthWarmingUp = (name: string): Promise<boolean> =>
+  invoke('is_health_warming_up', { name });
+
+/** Warm-up window in seconds (default 30) */
+export const getHealthWarmup = (name: string): Promise<number> =>
+  invoke('get_health_warmup', { name });
+
+/** Pass null to restore the default window */
+export const setHealthWarmup = (name: string, secs: number | null): Promise<void> =>
+  invoke('set_health_warmup', { name, secs });
+
+/** HTTP assertions layered on top of the TCP check */
+export interface HealthCheck {
+  path: string;
+  expect_status: number | null;   // any 2xx/3xx when null
+  body_contains: string | null;
+  json_field: string | null;      // dot path, e.g. "db.ok"
+  json_equals: unknown;
+}
+
+export interface PortHealth {
+  port: number;
+  label: string | null;
+  primary: boolean;
+  reachable: boolean;
+  latency_ms: number | null;   // HTTP time to first byte on the main port, TCP connect time on the others
+}
+
+export interface HealthDetails {
+  name: string;
+  healthy: boolean;
+  reason: string | null;       // first failed assertion or unreachable extra port
+  checked_at: number;          // unix secs
+  ports: PortHealth[];         // the main port first
+}
+
+/** Latest per-port health of running servers; all of them when name is omitted */
+export const getHealthDetails = (name?: string): Promise<HealthDetails[]> =>
+  invoke('get_health_details', { name: name ?? null });
+
+export const getHealthCheck = (name: string): Promise<HealthCheck | null> =>
+  invoke('get_health_check', { name });
+
+/** Pass null to go back to a plain TCP connect check */
+export const setHealthCheck = (name: string, check: HealthCheck | null): Promise<void> =>
+  invoke('set_health_check', { name, check });
+
+/** Runs the check once; rejects with the first failed assertion */
+export const testHealthCheck = (name: string, check: HealthCheck): Promise<void> =>
+  invoke('test_health_check', { name, check });
+
+/** Last measured time to first byte of a GET in ms (the health check path, else /), populated by checkServerHealth */
 export const getServerLatency = (name: string): Promise<number | null> =>
   invoke('get_server_latency', { name });
 
-/** Seconds since the server was started by DexHub (null if not running) */
+/** Seconds since the server was started by DexHub, by the wall clock (null if not running) */
 export const getServerUptime = (name: string): Promise<number | null> =>
   invoke('get_server_uptime', { name });
 
+/** Opens (or focuses) a separate log window for the server */
+export const openLogWindow = (name: string): Promise<void> =>
+  invoke('open_log_window_cmd', { name });
+
+export type UrlFormat = 'plain' | 'markdown' | 'curl';
+/** `project` follows the project's host choice; the others force a host (localhost in presentation mode) */
+export type UrlHost = 'project' | 'localhost' | 'tailscale';
+
+/** Copies the URL to the clipboard and returns the copied text */
+export const copyProjectUrl = (name: string, format: UrlFormat = 'plain', host: UrlHost = 'project'): Promise<string> =>
+  invoke('copy_project_url', { name, format, host });
+
 /** Captured stdout/stderr lines (last 500) — available even after crash */
-export const getServerLogs = (name: string): Promise<string[]> =>
-  invoke('get_server_logs', { name });
+export type LogLevel = 'info' | 'warn' | 'error';
+
+/** With a level, only lines at least that severe (classified from stderr origin and error/warning wording) */
+export const getServerLogs = (name: string, level?: LogLevel): Promise<string[]> =>
+  invoke('get_server_logs', { name, ...(level && { level }) });
+
+export interface CombinedLogLine {
+  source: string;              // the log the line came from, e.g. "web" or "web:services"
+  ts_ms: number;               // unix millis
+  level: LogLevel;
+  text: string;
+}
+
+/** Newest `limit` lines across several logs, interleaved by time, oldest first */
+export const getCombinedLogs = (names: string[], limit = 200): Promise<CombinedLogLine[]> =>
+  invoke('get_combined_logs', { names, limit });
+
+/** Copies the last `lines` log lines (only stderr/error lines with errorsOnly); resolves to the count copied */
+export const copyServerLogs = (name: string, lines = 100, errorsOnly = false): Promise<number> =>
+  invoke('copy_server_logs', { name, lines, errorsOnly });
+
+/** Writes saved crash snapshots plus the timestamped current log to `path`; resolves to the buffered line count */
+export const exportServerLogs = (name: string, path: string): Promise<number> =>
+  invoke('export_server_logs', { name, path });
+
+/** %CPU of the server's process tree (incl. children), sampled every 5s */
+export const getServerCpu = (name: string): Promise<number | null> =>
+  invoke('get_server_cpu', { name });
+
+/** Resident memory of the server's process tree in MB, sampled with the CPU */
+export const getServerMemory = (name: string): Promise<number | null> =>
+  invoke('get_server_memory', { name });
+
+export interface ResourceLimits {
+  nice: number | null;         // 0–19, applied on the next start
+  memory_mb: number | null;    // the server is restarted when its tree goes over
+}
+
+export const getResourceLimits = (): Promise<Record<string, ResourceLimits>> =>
+  invoke('get_resource_limits');
+
+export const setResourceLimits = (name: string, limits: ResourceLimits): Promise<void> =>
+  invoke('set_resource_limits', { name, limits });
+
+// ─── CPU watchdog ────────────────────────────────────────────────────────────
+
+export interface WatchdogConfig {
+  enabled: boolean;
+  cpu_threshold: number;       // percent, summed across the process tree
+  sustain_secs: number;        // how long CPU must stay high with no successful request
+  quiet_logs: boolean;         // secondary heuristic: before any request succeeds, log silence counts as idle
+}
+
+/** Servers currently flagged as runaway (also emitted as `watchdog-alert` events) */
+export const getRunawayServers = (): Promise<string[]> =>
+  invoke('get_runaway_servers');
+
+export const getWatchdogConfig = (): Promise<WatchdogConfig> =>
+  invoke('get_watchdog_config');
+
+export const setWatchdogConfig = (config: WatchdogConfig): Promise<void> =>
+  invoke('set_watchdog_config', { config });
+
+// ─── Crash recovery ──────────────────────────────────────────────────────────
+
+export interface CrashPolicy {
+  max_crashes: number;         // more crashes than this within the window = flapping
+  window_mins: number;
+  restart_delay_secs: number;
+  auto_restart: string[];      // projects restarted when they crash (unless flapping)
+}
+
+export const getCrashPolicy = (): Promise<CrashPolicy> =>
+  invoke('get_crash_policy');
+
+export const setCrashPolicy = (policy: CrashPolicy): Promise<void> =>
+  invoke('set_crash_policy', { policy });
+
+/** Projects flagged as flapping: crash notifications muted, auto-restart paused */
+export const getFlapping = (): Promise<string[]> =>
+  invoke('get_flapping');
+
+export const clearFlapping = (name: string): Promise<void> =>
+  invoke('clear_flapping', { name });
+
+// ─── Sleep / wake ────────────────────────────────────────────────────────────
+
+/** Waking always re-checks Tailscale and health; these control the servers themselves */
+export interface SleepPolicy {
+  stop_on_sleep: boolean;
+  restart_on_wake: boolean;    // restarts only what stop_on_sleep stopped
+}
+
+export const getSleepPolicy = (): Promise<SleepPolicy> =>
+  invoke('get_sleep_policy');
+
+export const setSleepPolicy = (policy: SleepPolicy): Promise<void> =>
+  invoke('set_sleep_policy', { policy });
+
+// ─── Low-power mode ──────────────────────────────────────────────────────────
+
+export interface BatteryPolicy {
+  enabled: boolean;
+  threshold_percent: number;   // low-power mode at or below this, on battery only
+  probe_interval_secs: number; // health monitor interval while in low-power mode
+  pause_scans: boolean;
+  prompt_stop: boolean;        // notify and offer "Stop Non-Favorites" in the tray
+}
+
+export interface PowerState {
+  battery: { on_battery: boolean; percent: number | null } | null; // null without a battery
+  low_power: boolean;
+}
+
+export const getBatteryPolicy = (): Promise<BatteryPolicy> =>
+  invoke('get_battery_policy');
+
+export const setBatteryPolicy = (policy: BatteryPolicy): Promise<void> =>
+  invoke('set_battery_policy', { policy });
+
+/** Current reading; changes are also emitted as `low-power-changed` */
+export const getPowerState = (): Promise<PowerState> =>
+  invoke('get_power_state');
+
+export const stopNonFavoriteServers = (): Promise<BulkItemResult[]> =>
+  invoke('stop_non_favorite_servers');
 
 // ─── Network / Tailscale ─────────────────────────────────────────────────────
 
 export const getTailscaleAddress = (): Promise<string> =>
   invoke('get_tailscale_address');
 
+export interface TailscaleStatus {
+  host: string;                // MagicDNS name / tailnet IP; LAN IP or "localhost" when offline
+  online: boolean;
+  lan_ip: string | null;
+}
+
+/** Current Tailscale state; refreshed every 30s and emitted as `tailscale-changed` */
+export const getTailscaleStatus = (): Promise<TailscaleStatus> =>
+  invoke('get_tailscale_status_cmd');
+
+export interface TailnetDevice {
+  host_name: string;
+  dns_name: string;            // MagicDNS name without trailing dot
+  os: string;
+  ips: string[];
+  online: boolean;
+  is_self: boolean;
+  last_seen: string | null;
+}
+
+/** All tailnet nodes — self first, then online peers */
+export const listTailnetDevices = (): Promise<TailnetDevice[]> =>
+  invoke('list_tailnet_devices');
+
+/** Push a project's URL to a tailnet device via Taildrop */
+export const sendUrlToDevice = (name: string, device: string): Promise<void> =>
+  invoke('send_url_to_device', { name, device });
+
+export interface NetworkAddress {
+  interface: string;           // e.g. "en0", "utun4"
+  ip: string;
+}
+
+/** Non-loopback IPv4 addresses, for picking a per-project URL host */
+export const listNetworkAddresses = (): Promise<NetworkAddress[]> =>
+  invoke('list_network_addresses');
+
+/** "auto" | "lan" | "localhost" | custom hostname */
+export const getUrlHost = (name: string): Promise<string> =>
+  invoke('get_url_host', { name });
+
+export const setUrlHost = (name: string, host: string | null): Promise<void> =>
+  invoke('set_url_host', { name, host });
+
 /** Scan well-known dev ports for servers not managed by DexHub */
 export const scanExternalServers = (): Promise<number[]> =>
   invoke('scan_external_servers');
 
+// ─── Public tunnels ──────────────────────────────────────────────────────────
+
+export interface TunnelInfo {
+  provider: string;            // "cloudflared" | "ngrok" | "funnel"
+  url: string | null;          // null until the provider reports its public URL
+  expires_at: number | null;   // unix seconds, for time-boxed share links
+  passcode: boolean;
+}
+
+/** Expose a project publicly via cloudflared, ngrok or Funnel (auto-detected when provider omitted) */
+export const startTunnel = (name: string, provider?: string): Promise<void> =>
+  invoke('start_tunnel_cmd', { name, provider: provider ?? null });
+
+export const stopTunnel = (name: string): Promise<void> =>
+  invoke('stop_tunnel_cmd', { name });
+
+export const getTunnelUrl = (name: string): Promise<string | null> =>
+  invoke('get_tunnel_url', { name });
+
+export const listTunnels = (): Promise<Record<string, TunnelInfo>> =>
+  invoke('list_tunnels');
+
+export interface ShareLink {
+  name: string;
+  provider: string;
+  url: string | null;
+  expires_at: number;          // unix seconds
+  passcode: boolean;
+}
+
+/** Share a project for `ttlSecs`, then tear the tunnel down; passcodes require ngrok */
+export const createShareLink = (
+  name: string,
+  ttlSecs: number,
+  passcode?: string,
+  provider?: string,
+): Promise<ShareLink> =>
+  invoke('create_share_link_cmd', { name, ttlSecs, passcode: passcode ?? null, provider: provider ?? null });
+
+export interface AuditEntry {
+  ts: number;                  // unix seconds
+  user: string;
+  action: string;
+  project: string;
+  detail: unknown;
+}
+
+export const getAuditLog = (limit?: number): Promise<AuditEntry[]> =>
+  invoke('get_audit_log', { limit: limit ?? null });
+
+// ─── Notifications ───────────────────────────────────────────────────────────
+
+export interface NotificationConfig {
+  /** Placeholders: {name} {code} {uptime} {last_error_line} {snapshot} (crash file path; appended if omitted) */
+  crash_template: string;
+  muted: boolean;              // global: no notifications at all
+  crash_sound: string;         // macOS sound name, e.g. "Basso"; "" for silent
+  projects: Record<string, ProjectAlerts>;
+  quiet_hours: QuietHours;
+  webhook: ChatWebhook | null; // crash and flapping alerts for every project
+}
+
+/** A Slack or Discord incoming webhook; alerts carry the exit code and the last log lines */
+export interface ChatWebhook {
+  service: 'slack' | 'discord';
+  url: string;
+}
+
+/** Local-time window ("HH:MM"; start after end wraps midnight) when crash and health alerts are only logged */
+export interface QuietHours {
+  enabled: boolean;
+  start: string;
+  end: string;
+}
+
+export interface ProjectAlerts {
+  banner: boolean;             // false: no notifications for this project
+  sound: boolean;              // false: banners are silent
+  crash_sound: string | null;  // null: the global crash sound
+  chat: boolean;               // false: no webhook alerts for this project
+  webhook: ChatWebhook | null; // null: the global webhook
+}
+
+export const getNotificationConfig = (): Promise<NotificationConfig> =>
+  invoke('get_notification_config');
+
+export const setNotificationConfig = (config: NotificationConfig): Promise<void> =>
+  invoke('set_notification_config', { config });
+
+/** Per-project overrides; null restores the defaults */
+export const setProjectAlerts = (name: string, alerts: ProjectAlerts | null): Promise<void> =>
+  invoke('set_project_alerts', { name, alerts });
+
+export const setNotificationsMuted = (muted: boolean): Promise<void> =>
+  invoke('set_notifications_muted', { muted });
+
+/** Render a crash template with sample values */
+export const previewCrashNotification = (template: string): Promise<string> =>
+  invoke('preview_crash_notification', { template });
+
+// ─── Presentation mode ───────────────────────────────────────────────────────
+
+export const getPresentationMode = (): Promise<boolean> =>
+  invoke('get_presentation_mode');
+
+/** Hides paths, hosts and env values and blocks stop/restart; emits `presentation-mode-changed` */
+export const setPresentationMode = (enabled: boolean): Promise<void> =>
+  invoke('set_presentation_mode', { enabled });
+
+// ─── Focus modes (macOS) ─────────────────────────────────────────────────────
+
+export interface FocusRule {
+  start: string[];       // started when this Focus turns on
+  silence: string[];     // notifications for these play no sound
+}
+
+export interface FocusConfig {
+  enabled: boolean;
+  rules: Record<string, FocusRule>;   // Focus name → rule
+}
+
+export const getFocusConfig = (): Promise<FocusConfig> =>
+  invoke('get_focus_config');
+
+export const setFocusConfig = (config: FocusConfig): Promise<void> =>
+  invoke('set_focus_config', { config });
+
+/** Current Focus name; listen for `focus-changed` to follow it */
+export const getActiveFocus = (): Promise<string | null> =>
+  invoke('get_active_focus');
+
+// ─── Run history ─────────────────────────────────────────────────────────────
+
+export interface RunRecord {
+  id: number;
+  project: string;
+  started_at: number;        // unix seconds
+  ended_at: number | null;   // null while running, or when unknown
+  duration_secs: number | null;
+  outcome: 'running' | 'stopped' | 'exited' | 'crashed' | 'unknown'; // unknown: left open by a session that didn't shut down
+  exit_code: number | null;
+  port: number | null;
+}
+
+/** Most recent runs first, from the encrypted dexhub.db; all projects when `name` is null */
+export const getRunHistory = (name: string | null, limit = 100): Promise<RunRecord[]> =>
+  invoke('get_run_history', { name, limit });
+
+/** Crashed runs of `name` started at or after `since` (unix seconds) */
+export const countCrashes = (name: string, since: number): Promise<number> =>
+  invoke('count_crashes', { name, since });
+
+export interface ProjectUsage {
+  project: string;
+  starts: number;
+  crashes: number;
+  uptime_secs: number;         // time running inside the window
+  avg_latency_ms: number | null; // null without health checks in the window
+}
+
+export interface DashboardStats {
+  since: number;               // unix secs; the window runs from here to now
+  total_uptime_secs: number;
+  total_starts: number;
+  total_crashes: number;
+  projects: ProjectUsage[];    // most uptime first
+}
+
+/** Usage over the last `days` days from the run history */
+export const getDashboardStats = (days = 7): Promise<DashboardStats> =>
+  invoke('get_dashboard_stats', { days });
+
+// ─── Event log & hooks ───────────────────────────────────────────────────────
+
+export interface EventRecord {
+  ts: number;                  // unix seconds
+  kind: string;                // "server-started" | "server-crashed" | "scan-finished" | "hook-output" | …
+  project: string | null;
+  detail: unknown;
+}
+
+/** In-memory event log (last 1000); new entries are also emitted as `dexhub-event` */
+export const getEventLog = (limit?: number): Promise<EventRecord[]> =>
+  invoke('get_event_log', { limit: limit ?? null });
+
+/** Events since `since` (unix secs) from the append-only events.jsonl, across sessions; newest `limit` kept */
+export const getEvents = (since: number, limit?: number): Promise<EventRecord[]> =>
+  invoke('get_events', { since, limit: limit ?? null });
+
+/** Executables in <app data>/hooks/, run with event JSON on stdin */
+export const listHooks = (): Promise<string[]> =>
+  invoke('list_hooks');
+
+// ─── Remote control API ──────────────────────────────────────────────────────
+
+export interface RemoteApiConfig {
+  enabled: boolean;
+  port: number;                // default 5055, bound on all interfaces
+}
+
+export interface PairedDevice {
+  public_key: string;          // hex Ed25519 public key
+  role: string;
+  enabled: boolean;
+  created_at: number;          // unix seconds
+}
+
+export const getRemoteApiConfig = (): Promise<RemoteApiConfig> =>
+  invoke('get_remote_api_config');
+
+export const setRemoteApiConfig = (config: RemoteApiConfig): Promise<void> =>
+  invoke('set_remote_api_config', { config });
+
+/** 6-digit code, valid for 5 minutes, for pairing a device via POST /pair/confirm */
+export const startRemotePairing = (): Promise<string> =>
+  invoke('start_remote_pairing');
+
+export const listPairedDevices = (): Promise<Record<string, PairedDevice>> =>
+  invoke('list_paired_devices');
+
+export const revokePairedDevice = (deviceId: string): Promise<void> =>
+  invoke('revoke_paired_device', { deviceId });
+
+/** Sign-in link for the mobile web UI at /ui; replaces the device's previous link */
+export const createRemoteUiLink = (deviceId: string): Promise<string> =>
+  invoke('create_remote_ui_link', { deviceId });
+
+// ─── Reverse proxy & wake-on-demand ──────────────────────────────────────────
+
+export interface ProxyConfig {
+  enabled: boolean;
+  port: number;          // default 8088, localhost only
+  domain: string;        // default "dex.test" → http://<project>.dex.test:8088
+  lazy: string[];        // projects started on their first request
+}
+
+export const getProxyConfig = (): Promise<ProxyConfig> =>
+  invoke('get_proxy_config');
+
+export const setProxyConfig = (config: ProxyConfig): Promise<void> =>
+  invoke('set_proxy_config', { config });
+
+export const setWakeOnDemand = (name: string, enabled: boolean): Promise<void> =>
+  invoke('set_wake_on_demand', { name, enabled });
+
+/** Proxied URL for a project, or null while the proxy is disabled */
+export const getProxyUrl = (name: string): Promise<string | null> =>
+  invoke('get_proxy_url', { name });
+
+// ─── License inventory ───────────────────────────────────────────────────────
+
+export interface PackageLicense {
+  name: string;
+  version: string;
+  license: string;
+  disallowed: boolean;
+}
+
+export interface LicenseReport {
+  project: string;
+  generated_at: number;
+  packages: PackageLicense[];
+  counts: Record<string, number>;   // license id → package count
+  disallowed: PackageLicense[];
+}
+
+export interface LicensePolicy {
+  disallowed: string[];   // SPDX ids
+}
+
+/** Cached report, or null until refreshLicenseReport has run */
+export const getLicenseReport = (name: string): Promise<LicenseReport | null> =>
+  invoke('get_license_report', { name });
+
+/** Runs in the background; listen for `license-report-ready` */
+export const refreshLicenseReport = (name: string): Promise<void> =>
+  invoke('refresh_license_report', { name });
+
+export const getLicensePolicy = (): Promise<LicensePolicy> =>
+  invoke('get_license_policy');
+
+export const setLicensePolicy = (policy: LicensePolicy): Promise<void> =>
+  invoke('set_license_policy', { policy });
+
 // ─── Favorites ───────────────────────────────────────────────────────────────
 
 export const getFavoritesFromRust = (): Promise<string[]> =>
@@ -76,23 +977,268 @@ export const getFavoritesFromRust = (): Promise<string[]> =>
 export const saveFavoritesToRust = (names: string[]): Promise<void> =>
   invoke('set_favorites', { names });
 
+export type FavoriteAction =
+  | { type: 'start'; env: Record<string, string> }  // env layered over saved overrides
+  | { type: 'restart' }
+  | { type: 'restart_clean' }
+  | { type: 'open_browser' }
+  | { type: 'open_logs' }
+  | { type: 'open_terminal' }
+  | { type: 'copy_url' };
+
+/** A pinned project (`action: null`) or a specific action on it */
+export interface Favorite {
+  project: string;
+  action: FavoriteAction | null;
+  label: string | null;
+}
+
+/** Full favorites list; the old name-only list is migrated on first read */
+export const getFavoriteEntries = (): Promise<Favorite[]> =>
+  invoke('get_favorite_entries');
+
+export const setFavoriteEntries = (entries: Favorite[]): Promise<void> =>
+  invoke('set_favorite_entries', { entries });
+
+/** Runs the favorite at `index` in the list from getFavoriteEntries */
+export const runFavorite = (index: number): Promise<void> =>
+  invoke('run_favorite_cmd', { index });
+
+/** Last 5 started projects, most recent first (also shown in the tray) */
+export const getRecentServers = (): Promise<string[]> =>
+  invoke('get_recent_servers');
+
+// ─── Tags ────────────────────────────────────────────────────────────────────
+
+/** Project name → tags, as assigned via bulkAction */
+export const getTags = (): Promise<Record<string, string[]>> =>
+  invoke('get_tags');
+
 // ─── Projects ────────────────────────────────────────────────────────────────
 
 export const refreshProjects = (): Promise<ProjectConfig[]> =>
   invoke('refresh_projects_cmd');
 
+export interface ConfigReloadReport {
+  settings_changed: string[];  // settings files whose contents changed
+  projects_added: string[];
+  projects_removed: string[];
+  projects_changed: string[];
+  restart_needed: string[];    // running servers whose launch config changed
+}
+
+/** Re-read all settings files and package.json manifests without restarting servers */
+export const reloadConfig = (): Promise<ConfigReloadReport> =>
+  invoke('reload_config_cmd');
+
 /** Return first ~8 lines of the project README.md, or null if none exists */
 export const getProjectReadme = (name: string): Promise<string | null> =>
   invoke('get_project_readme', { name });
 
+export interface ProjectReadme {
+  file: string;                // e.g. "README.md"
+  title: string | null;        // first heading; the heading stays in the markdown
+  markdown: string;            // badges stripped, relative images as file:// URLs (left relative when presenting)
+  badges_removed: number;
+}
+
+/** The whole README for a project page; getProjectReadme is the short snippet */
+export const getProjectReadmeFull = (name: string): Promise<ProjectReadme | null> =>
+  invoke('get_project_readme_full', { name });
+
 // ─── Env overrides ───────────────────────────────────────────────────────────
 
+/** Values stored in the keyring read back as this placeholder */
+export const SECRET_PLACEHOLDER = '@keyring';
+
 export const getEnvOverrides = (name: string): Promise<Record<string, string>> =>
   invoke('get_env_overrides', { name });
 
 export const setEnvOverrides = (name: string, vars: Record<string, string>): Promise<void> =>
   invoke('set_env_overrides', { name, vars });
 
+/** Stores the value in the system keyring; only resolved when the server starts */
+export const setEnvSecret = (name: string, key: string, value: string): Promise<void> =>
+  invoke('set_env_secret', { name, key, value });
+
+/** Moves an existing override into the keyring, or back out of it */
+export const markEnvSecret = (name: string, key: string, secret: boolean): Promise<void> =>
+  invoke('mark_env_secret', { name, key, secret });
+
+/** Named env sets per project; the active one is layered over the overrides above */
+export interface EnvProfiles {
+  active: string | null;
+  profiles: Record<string, Record<string, string>>;
+}
+
+export const listEnvProfiles = (name: string): Promise<EnvProfiles> =>
+  invoke('list_env_profiles', { name });
+
+/** Creates or replaces a profile */
+export const saveEnvProfile = (name: string, profile: string, vars: Record<string, string>): Promise<void> =>
+  invoke('save_env_profile', { name, profile, vars });
+
+export const deleteEnvProfile = (name: string, profile: string): Promise<void> =>
+  invoke('delete_env_profile', { name, profile });
+
+/** Pass null to use the plain overrides only */
+export const selectEnvProfile = (name: string, profile: string | null): Promise<void> =>
+  invoke('select_env_profile', { name, profile });
+
+// ─── Launch commands & hooks ─────────────────────────────────────────────────
+
+/** Run through the shell instead of the scanned dev script, e.g. { command: "make", args: ["dev"] } */
+export interface LaunchCommand {
+  command: string;
+  args: string[];
+}
+
+export const getLaunchCommand = (name: string): Promise<LaunchCommand | null> =>
+  invoke('get_launch_command', { name });
+
+/** null goes back to the scanned script; applies from the next start */
+export const setLaunchCommand = (name: string, launch: LaunchCommand | null): Promise<void> =>
+  invoke('set_launch_command', { name, launch });
+
+/** Shell lines run in the project dir; output shows in its log as `[hook] …` */
+export interface LifecycleHooks {
+  pre_start: string[];         // run in order; a failure aborts the start
+  post_stop: string[];         // run after an explicit stop, all of them even if one fails
+}
+
+export const getLifecycleHooks = (name: string): Promise<LifecycleHooks> =>
+  invoke('get_lifecycle_hooks', { name });
+
+export const setLifecycleHooks = (name: string, hooks: LifecycleHooks): Promise<void> =>
+  invoke('set_lifecycle_hooks', { name, hooks });
+
+// ─── .env files ──────────────────────────────────────────────────────────────
+
+export interface EnvFile {
+  file: string;                // ".env", ".env.local", ".env.development", ".env.development.local"
+  exists: boolean;
+  vars: Record<string, string>;
+}
+
+export interface EnvChange {
+  key: string;
+  before: string | null;       // null when added
+  after: string | null;        // null when removed
+}
+
+export interface EffectiveVar {
+  key: string;
+  value: string;
+  source: string;              // env file name, "process", "override" or "profile:<name>"
+  shadowed: string[];          // earlier sources that also set it
+}
+
+export interface EnvIssue {
+  source: string;
+  line: number | null;         // 1-based, for env files
+  key: string | null;
+  message: string;
+}
+
+export interface EffectiveEnv {
+  vars: EffectiveVar[];
+  issues: EnvIssue[];          // malformed lines, duplicate keys, invalid names, missing secrets
+}
+
+/** The project's env files in load order (later files win) */
+export const readEnvFiles = (name: string): Promise<EnvFile[]> =>
+  invoke('read_env_files', { name });
+
+/** Preview of what writeEnvFile would change */
+export const diffEnvFile = (name: string, file: string, vars: Record<string, string>): Promise<EnvChange[]> =>
+  invoke('diff_env_file', { name, file, vars });
+
+/** Replaces the file's variables, keeping its comments and ordering */
+export const writeEnvFile = (name: string, file: string, vars: Record<string, string>): Promise<EnvChange[]> =>
+  invoke('write_env_file', { name, file, vars });
+
+/** What the dev server would launch with: env files < process env < overrides < active profile */
+export const getEffectiveEnv = (name: string): Promise<EffectiveEnv> =>
+  invoke('get_effective_env', { name });
+
+// ─── Settings ────────────────────────────────────────────────────────────────
+
+export interface BrowserChoice {
+  browser: string | null;      // null for the default browser
+  profile: string | null;      // e.g. "Profile 1" for Chromium browsers, a profile name for Firefox
+}
+
+export interface HealthProbeSettings {
+  interval_secs: number | null; // null for the global interval
+  timeout_ms: number | null;    // null for the global timeout
+}
+
+export interface GeneralSettings {
+  scan_roots: string[];        // folders searched for projects, in order
+  shell: string;               // servers run as `<shell> -lc <command>`
+  browser: string | null;      // app name (macOS) or binary; null for the default browser
+  browser_profile: string | null;
+  project_browsers: Record<string, BrowserChoice>; // per-project overrides of the two above
+  health_interval_secs: number;
+  health_timeout_ms: number;   // how long a health probe waits for a connection
+  project_health: Record<string, HealthProbeSettings>; // per-project overrides of the two above
+  tailscale_interval_secs: number;
+  watchdog_interval_secs: number;
+  auto_install: boolean;       // install missing or stale dependencies before a start
+  otlp_endpoint: string | null; // OTLP/HTTP collector events are also sent to, e.g. http://localhost:4318
+}
+
+/** Everything in settings.json; the per-feature getters/setters read and write the same document */
+export interface Settings {
+  general: GeneralSettings;
+  notifications: NotificationConfig;
+  watchdog: WatchdogConfig;
+  crash_policy: CrashPolicy;
+  sleep: SleepPolicy;
+  battery: BatteryPolicy;
+  disk_guard: DiskGuardConfig;
+  editor: { editor: string | null }; // platform editor id; null for the first installed one
+  licenses: LicensePolicy;
+}
+
+export type SettingsPatch = { [K in keyof Settings]?: Partial<Settings[K]> };
+
+export const getSettings = (): Promise<Settings> =>
+  invoke('get_settings');
+
+/** Merges the patch and returns the result; every change is also emitted as `settings-changed` */
+export const updateSettings = (patch: SettingsPatch): Promise<Settings> =>
+  invoke('update_settings', { patch });
+
+/** Where the project's Open action goes; null falls back to the global browser */
+export const setProjectBrowser = (name: string, choice: BrowserChoice | null): Promise<void> =>
+  invoke('set_project_browser', { name, choice });
+
+/** A slow server's own probe interval and timeout; null reverts to the global ones */
+export const setProjectHealthProbe = (name: string, probe: HealthProbeSettings | null): Promise<void> =>
+  invoke('set_project_health_probe', { name, probe });
+
+/** Settings, favorites, port and env overrides, env profiles and tags; secrets stay as SECRET_PLACEHOLDER */
+export interface ConfigBundle {
+  version: number;
+  exported_at: number;         // unix secs
+  settings?: Settings | null;  // sections left out are left alone on import
+  favorites?: Favorite[] | null;
+  port_overrides?: Record<string, number> | null;
+  env_overrides?: Record<string, Record<string, string>> | null;
+  env_profiles?: Record<string, EnvProfiles> | null;
+  tags?: Record<string, string[]> | null;
+  launch_commands?: Record<string, LaunchCommand> | null;
+  lifecycle_hooks?: Record<string, LifecycleHooks> | null;
+}
+
+export const exportConfig = (): Promise<ConfigBundle> =>
+  invoke('export_config');
+
+/** Overwrites each included section, then reloads like reloadConfig */
+export const importConfig = (bundle: ConfigBundle): Promise<ConfigReloadReport> =>
+  invoke('import_config', { bundle });
+
 // ─── Window ──────────────────────────────────────────────────────────────────
 
 export const setPin = (pinned: boolean): Promise<void> =>
@@ -2,9 +2,13 @@ import { StrictMode } from 'react'
 import { createRoot } from 'react-dom/client'
 import './index.css'
 import App from './App.tsx'
+import { LogWindow } from './components/LogWindow'
+
+// Log windows opened from the tray load index.html?logs=<server name>
+const logsFor = new URLSearchParams(window.location.search).get('logs')
 
 createRoot(document.getElementById('root')!).render(
   <StrictMode>
-    <App />
+    {logsFor ? <LogWindow name={logsFor} /> : <App />}
   </StrictMode>,
 )